@@ -212,6 +212,7 @@ public class FfiSafetyTests
             ErrorCodes.DataLoadFailed,
             ErrorCodes.InvalidData,
             ErrorCodes.InsufficientCapital,
+            ErrorCodes.MaxOrderValueExceeded,
             ErrorCodes.ThrottleExceeded,
             ErrorCodes.PositionLimit,
             ErrorCodes.InternalPanic
@@ -238,6 +239,7 @@ public class FfiSafetyTests
                         ErrorCodes.DataLoadFailed => ex is DataLoadException,
                         ErrorCodes.InvalidData => ex is InvalidDataException,
                         ErrorCodes.InsufficientCapital => ex is InsufficientCapitalException,
+                        ErrorCodes.MaxOrderValueExceeded => ex is MaxOrderValueExceededException,
                         ErrorCodes.ThrottleExceeded => ex is ThrottleExceededException,
                         ErrorCodes.PositionLimit => ex is PositionLimitException,
                         ErrorCodes.InternalPanic => ex is EngineException,
@@ -205,6 +205,46 @@ public sealed class EngineWrapper : IDisposable
         ErrorHandler.CheckResult(result, "RunBacktest");
     }
 
+    /// <summary>
+    /// Gets the equity curve, one point per processed tick.
+    /// </summary>
+    /// <returns>Equity after each tick of the current run</returns>
+    /// <exception cref="ObjectDisposedException">Thrown if the engine has been disposed</exception>
+    public double[] GetEquityCurve()
+    {
+        ThrowIfDisposed();
+
+        unsafe
+        {
+            int count;
+
+            // SAFETY: A null buffer with length 0 only queries the curve length.
+            int result = NativeMethods.GetEquityCurve(
+                _handle!.DangerousGetHandle(),
+                null,
+                0,
+                &count);
+            if (result != ErrorCodes.BufferTooSmall)
+            {
+                ErrorHandler.CheckResult(result, "GetEquityCurve");
+            }
+
+            var curve = new double[count];
+            fixed (double* buffer = curve)
+            {
+                // SAFETY: buffer is pinned and valid for count writes.
+                result = NativeMethods.GetEquityCurve(
+                    _handle!.DangerousGetHandle(),
+                    buffer,
+                    count,
+                    &count);
+            }
+
+            ErrorHandler.CheckResult(result, "GetEquityCurve");
+            return curve;
+        }
+    }
+
     /// <summary>
     /// Throws ObjectDisposedException if the engine has been disposed.
     /// </summary>
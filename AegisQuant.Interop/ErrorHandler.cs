@@ -28,6 +28,12 @@ public static class ErrorCodes
     public const int PositionLimit = -9;
     /// <summary>File not found</summary>
     public const int FileNotFound = -10;
+    /// <summary>Caller-provided buffer is too small; the required length is still reported</summary>
+    public const int BufferTooSmall = -11;
+    /// <summary>Order notional exceeds the single-order cap</summary>
+    public const int MaxOrderValueExceeded = -12;
+    /// <summary>No position is held for the requested symbol</summary>
+    public const int PositionNotFound = -14;
     /// <summary>Internal panic (should not happen)</summary>
     public const int InternalPanic = -99;
 }
@@ -49,9 +55,11 @@ public static class ErrorHandler
     /// <exception cref="DataLoadException">Thrown for data loading errors</exception>
     /// <exception cref="InvalidDataException">Thrown for invalid data errors</exception>
     /// <exception cref="InsufficientCapitalException">Thrown for insufficient capital errors</exception>
+    /// <exception cref="MaxOrderValueExceededException">Thrown for max order value errors</exception>
     /// <exception cref="ThrottleExceededException">Thrown for throttle exceeded errors</exception>
     /// <exception cref="PositionLimitException">Thrown for position limit errors</exception>
     /// <exception cref="FileNotFoundException">Thrown for file not found errors</exception>
+    /// <exception cref="ArgumentException">Thrown for buffer too small errors</exception>
     /// <exception cref="EngineException">Thrown for unknown or internal errors</exception>
     public static void CheckResult(int errorCode, string operation)
     {
@@ -83,6 +91,9 @@ public static class ErrorHandler
             ErrorCodes.InsufficientCapital => new InsufficientCapitalException(
                 $"Insufficient capital for {operation}"),
 
+            ErrorCodes.MaxOrderValueExceeded => new MaxOrderValueExceededException(
+                $"Max order value exceeded for {operation}"),
+
             ErrorCodes.ThrottleExceeded => new ThrottleExceededException(
                 $"Order rate throttle exceeded during {operation}"),
 
@@ -92,6 +103,9 @@ public static class ErrorHandler
             ErrorCodes.FileNotFound => new FileNotFoundException(
                 $"File not found during {operation}"),
 
+            ErrorCodes.BufferTooSmall => new ArgumentException(
+                $"Buffer too small for {operation}", operation),
+
             ErrorCodes.InternalPanic => new EngineException(
                 $"Internal engine panic during {operation}. This is a bug."),
 
@@ -117,9 +131,12 @@ public static class ErrorHandler
             ErrorCodes.DataLoadFailed => "Data load failed",
             ErrorCodes.InvalidData => "Invalid data",
             ErrorCodes.InsufficientCapital => "Insufficient capital",
+            ErrorCodes.MaxOrderValueExceeded => "Max order value exceeded",
             ErrorCodes.ThrottleExceeded => "Throttle exceeded",
             ErrorCodes.PositionLimit => "Position limit exceeded",
             ErrorCodes.FileNotFound => "File not found",
+            ErrorCodes.BufferTooSmall => "Buffer too small",
+            ErrorCodes.PositionNotFound => "Position not found",
             ErrorCodes.InternalPanic => "Internal panic",
             _ => $"Unknown error ({errorCode})"
         };
@@ -50,6 +50,16 @@ public class InsufficientCapitalException : RiskRejectedException
     public InsufficientCapitalException(string message, Exception innerException) : base(message, innerException) { }
 }
 
+/// <summary>
+/// Exception thrown when an order's notional exceeds the single-order cap.
+/// </summary>
+public class MaxOrderValueExceededException : RiskRejectedException
+{
+    public MaxOrderValueExceededException() : base("Order value exceeds maximum") { }
+    public MaxOrderValueExceededException(string message) : base(message) { }
+    public MaxOrderValueExceededException(string message, Exception innerException) : base(message, innerException) { }
+}
+
 /// <summary>
 /// Exception thrown when the order rate throttle is exceeded.
 /// </summary>
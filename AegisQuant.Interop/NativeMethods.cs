@@ -10,6 +10,20 @@ namespace AegisQuant.Interop;
 [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
 public delegate void LogCallback(int level, IntPtr message);
 
+/// <summary>
+/// Warmup-complete callback delegate.
+/// </summary>
+/// <param name="firstTradableBar">Bar index at which trading becomes allowed</param>
+[UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+public delegate void WarmupCompleteCallback(ulong firstTradableBar);
+
+/// <summary>
+/// Emergency halt callback delegate.
+/// </summary>
+/// <param name="reason">Halt reason (1=Manual, 2=DailyLoss, 3=DrawdownBreach, 4=DataGap, 5=External)</param>
+[UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+public delegate void HaltCallback(int reason);
+
 /// <summary>
 /// String callback delegate for receiving strings from Rust.
 /// Used for error messages and other string data.
@@ -42,6 +56,15 @@ public static partial class NativeMethods
         StrategyParams* parameters,
         RiskConfig* riskConfig);
 
+    /// <summary>
+    /// Initialize a new backtest engine with an initial balance, slippage and commission.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "init_engine_with_costs")]
+    public static unsafe partial IntPtr InitEngineWithCosts(
+        StrategyParams* parameters,
+        RiskConfig* riskConfig,
+        EngineCostConfig* costConfig);
+
     /// <summary>
     /// Free engine resources.
     /// </summary>
@@ -79,6 +102,30 @@ public static partial class NativeMethods
     [LibraryImport(DllName, EntryPoint = "run_backtest")]
     public static partial int RunBacktest(IntPtr engine);
 
+    /// <summary>
+    /// Copy the equity curve (one point per processed tick) into a caller-provided buffer.
+    /// Always writes the full curve length to <paramref name="outCount"/>; pass a null
+    /// buffer with length 0 to query it. Returns BufferTooSmall if the buffer is short.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "get_equity_curve_ffi")]
+    public static unsafe partial int GetEquityCurve(
+        IntPtr engine,
+        double* outBuffer,
+        int bufferLen,
+        int* outCount);
+
+    /// <summary>
+    /// Copy the per-order trade log into a caller-provided buffer.
+    /// Always writes the full trade count to <paramref name="outCount"/>; pass a null
+    /// buffer with length 0 to query it. Returns BufferTooSmall if the buffer is short.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "get_trades_ffi")]
+    public static unsafe partial int GetTrades(
+        IntPtr engine,
+        TradeRecord* outBuffer,
+        int bufferLen,
+        int* outCount);
+
     /// <summary>
     /// Set the global log callback.
     /// </summary>
@@ -91,6 +138,18 @@ public static partial class NativeMethods
     [LibraryImport(DllName, EntryPoint = "clear_log_callback")]
     public static partial int ClearLogCallback();
 
+    /// <summary>
+    /// Set the callback invoked once per run when the warmup period ends.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "set_warmup_complete_callback")]
+    public static partial int SetWarmupCompleteCallback(IntPtr callback);
+
+    /// <summary>
+    /// Clear the warmup-complete callback.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "clear_warmup_complete_callback")]
+    public static partial int ClearWarmupCompleteCallback();
+
     /// <summary>
     /// Get the last error message using a callback.
     /// </summary>
@@ -143,6 +202,46 @@ public static partial class NativeMethods
     [LibraryImport(DllName, EntryPoint = "set_latency_enabled_ffi")]
     public static partial int SetLatencyEnabled(int enabled);
 
+    /// <summary>
+    /// Export latency and event bus metrics in Prometheus text format.
+    /// Pass IntPtr.Zero as eventBus to export latency metrics only.
+    /// The callback (a <see cref="StringCallback"/> function pointer) receives the text.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "export_metrics_prometheus")]
+    public static partial int ExportMetricsPrometheus(IntPtr eventBus, IntPtr callback);
+
+    // ============================================================================
+    // Optimizer FFI Functions
+    // ============================================================================
+
+    /// <summary>
+    /// Set the number of worker threads used by parameter sweeps (0 = all cores).
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "set_optimizer_threads")]
+    public static partial int SetOptimizerThreads(int threads);
+
+    /// <summary>
+    /// Get progress of the most recent parameter sweep.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "get_optimizer_progress_ffi")]
+    public static unsafe partial int GetOptimizerProgress(ulong* completed, ulong* total);
+
+    // ============================================================================
+    // Config Validation FFI Functions
+    // ============================================================================
+
+    /// <summary>
+    /// Validate an engine configuration without running it.
+    /// The callback (a <see cref="StringCallback"/> function pointer) receives a JSON array
+    /// of {"severity", "field", "message"} findings; empty means the config is consistent.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "validate_engine_config_ffi")]
+    public static unsafe partial int ValidateEngineConfig(
+        StrategyParams* parameters,
+        RiskConfig* riskConfig,
+        long tickCount,
+        IntPtr callback);
+
     // ============================================================================
     // Emergency Control FFI Functions (Requirements: 16.1, 16.2, 16.6, 16.7)
     // ============================================================================
@@ -165,6 +264,49 @@ public static partial class NativeMethods
     [LibraryImport(DllName, EntryPoint = "is_emergency_halted")]
     public static partial int IsEmergencyHalted();
 
+    /// <summary>
+    /// Trigger emergency stop with a halt reason code (see <see cref="HaltCallback"/>).
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "emergency_stop_with_reason")]
+    public static partial int EmergencyStopWithReason(int reason);
+
+    /// <summary>
+    /// Get the reason code of the active halt (0 if not halted).
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "get_halt_reason_ffi")]
+    public static partial int GetHaltReason();
+
+    /// <summary>
+    /// Halt a single symbol with a halt reason code; other symbols keep trading.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "halt_symbol_ffi", StringMarshalling = StringMarshalling.Utf8)]
+    public static partial int HaltSymbol(string symbol, int reason);
+
+    /// <summary>
+    /// Resume trading in a halted symbol.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "reset_symbol_ffi", StringMarshalling = StringMarshalling.Utf8)]
+    public static partial int ResetSymbol(string symbol);
+
+    /// <summary>
+    /// Check if a symbol is halted, either on its own or by the global halt.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "is_symbol_halted_ffi", StringMarshalling = StringMarshalling.Utf8)]
+    public static partial int IsSymbolHalted(string symbol);
+
+    /// <summary>
+    /// Set the callback invoked when an emergency halt is activated.
+    /// Pass IntPtr.Zero to unregister.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "set_halt_callback")]
+    public static partial int SetHaltCallback(IntPtr callback);
+
+    /// <summary>
+    /// Clear the halt callback.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "clear_halt_callback")]
+    public static partial int ClearHaltCallback();
+
     /// <summary>
     /// Close all positions.
     /// </summary>
@@ -174,4 +316,80 @@ public static partial class NativeMethods
         OrderRequest* orders,
         int maxOrders,
         int* orderCount);
+
+    // ============================================================================
+    // Precision FFI Functions
+    // ============================================================================
+
+    /// <summary>
+    /// Round a price to the nearest tick using banker's rounding (half-even).
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "round_price_ffi")]
+    public static partial double RoundPrice(double value, double tickSize);
+
+    /// <summary>
+    /// Round a quantity to the nearest lot using banker's rounding (half-even).
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "round_quantity_ffi")]
+    public static partial double RoundQuantity(double value, double lotSize);
+
+    /// <summary>
+    /// Compare two prices using the engine's price epsilon (1 = equal, 0 = not equal).
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "price_eq_ffi")]
+    public static partial int PriceEq(double a, double b);
+
+    /// <summary>
+    /// Compare two quantities using the engine's quantity epsilon (1 = equal, 0 = not equal).
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "quantity_eq_ffi")]
+    public static partial int QuantityEq(double a, double b);
+
+    // ============================================================================
+    // L1 Gateway FFI Functions
+    // ============================================================================
+
+    /// <summary>
+    /// Set the global gateway mode (0=Simple, 1=L1).
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "set_gateway_mode")]
+    public static partial int SetGatewayMode(int mode);
+
+    /// <summary>
+    /// Get the global gateway mode (0=Simple, 1=L1).
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "get_gateway_mode_ffi")]
+    public static partial int GetGatewayMode();
+
+    /// <summary>
+    /// Create an L1 simulated gateway with the default slippage model.
+    /// Returns IntPtr.Zero on failure; release with FreeL1Gateway.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "create_l1_gateway")]
+    public static partial IntPtr CreateL1Gateway(double initialBalance, double commissionRate);
+
+    /// <summary>
+    /// Free an L1 simulated gateway.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "free_l1_gateway")]
+    public static partial void FreeL1Gateway(IntPtr gateway);
+
+    /// <summary>
+    /// Copy an order book snapshot into an L1 gateway.
+    /// Returns ErrorCodes.InvalidParam if BidCount or AskCount exceeds the level capacity.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "update_gateway_orderbook")]
+    public static unsafe partial int UpdateGatewayOrderBook(
+        IntPtr gateway,
+        OrderBookSnapshot* snapshot);
+
+    /// <summary>
+    /// Query the full position for a symbol on an L1 gateway.
+    /// Returns ErrorCodes.PositionNotFound (outPosition untouched) if no position is held.
+    /// </summary>
+    [LibraryImport(DllName, EntryPoint = "query_position_ffi", StringMarshalling = StringMarshalling.Utf8)]
+    public static unsafe partial int QueryPosition(
+        IntPtr gateway,
+        string symbol,
+        Position* outPosition);
 }
@@ -25,6 +25,7 @@ public struct Tick
 /// - Symbol is a fixed-size array (null-terminated UTF-8)
 /// - Direction: 1 = Buy, -1 = Sell
 /// - OrderType: 0 = Market, 1 = Limit
+/// - Tif: 0 = GTC, 1 = IOC, 2 = FOK
 /// </remarks>
 [StructLayout(LayoutKind.Sequential)]
 public unsafe struct OrderRequest
@@ -39,6 +40,10 @@ public unsafe struct OrderRequest
     public int OrderType;
     /// <summary>Limit price (ignored for Market orders)</summary>
     public double LimitPrice;
+    /// <summary>Caller-assigned client order ID, echoed on fills (0 = untagged)</summary>
+    public ulong ClientId;
+    /// <summary>Time in force: 0 = GTC, 1 = IOC, 2 = FOK</summary>
+    public int Tif;
 
     /// <summary>
     /// Sets the symbol from a string.
@@ -155,6 +160,54 @@ public unsafe struct Position
     }
 }
 
+/// <summary>
+/// One executed order from the engine's trade log.
+/// Matches Rust repr(C) FfiTradeRecord struct.
+/// </summary>
+/// <remarks>
+/// Closing orders carry the realized PnL of the trades they close.
+/// </remarks>
+[StructLayout(LayoutKind.Sequential)]
+public unsafe struct TradeRecord
+{
+    /// <summary>Timestamp of the tick the order filled on</summary>
+    public long Timestamp;
+    /// <summary>Symbol as fixed-size byte array (null-terminated)</summary>
+    public fixed byte Symbol[16];
+    /// <summary>Direction: 1 = Buy, -1 = Sell</summary>
+    public int Direction;
+    /// <summary>Filled quantity</summary>
+    public double Quantity;
+    /// <summary>Volume-weighted fill price</summary>
+    public double Price;
+    /// <summary>Realized profit/loss</summary>
+    public double Pnl;
+    /// <summary>Realized profit/loss before commission</summary>
+    public double GrossPnl;
+    /// <summary>Commission paid</summary>
+    public double Commission;
+    /// <summary>Realized profit/loss after commission</summary>
+    public double NetPnl;
+    /// <summary>Entry reason code</summary>
+    public int EntryReason;
+    /// <summary>Exit reason code</summary>
+    public int ExitReason;
+
+    /// <summary>
+    /// Gets the symbol as a string.
+    /// </summary>
+    public readonly string GetSymbol()
+    {
+        fixed (byte* ptr = Symbol)
+        {
+            int len = 0;
+            while (len < 16 && ptr[len] != 0)
+                len++;
+            return System.Text.Encoding.UTF8.GetString(ptr, len);
+        }
+    }
+}
+
 /// <summary>
 /// Account status structure.
 /// Matches Rust repr(C) AccountStatus struct.
@@ -194,6 +247,12 @@ public struct StrategyParams
     public double StopLossPct;
     /// <summary>Take profit percentage (e.g., 0.05 = 5%)</summary>
     public double TakeProfitPct;
+    /// <summary>Number of bars to warm up before generating signals (the engine uses at least the longest MA period)</summary>
+    public int WarmupBars;
+    /// <summary>Moving average type of the short leg (see MaType)</summary>
+    public int ShortMaType;
+    /// <summary>Moving average type of the long leg (see MaType)</summary>
+    public int LongMaType;
 
     /// <summary>
     /// Creates default strategy parameters.
@@ -204,7 +263,10 @@ public struct StrategyParams
         LongMaPeriod = 20,
         PositionSize = 100.0,
         StopLossPct = 0.02,
-        TakeProfitPct = 0.05
+        TakeProfitPct = 0.05,
+        WarmupBars = 0,
+        ShortMaType = MaType.Sma,
+        LongMaType = MaType.Sma
     };
 }
 
@@ -224,6 +286,12 @@ public struct RiskConfig
     public double MaxOrderValue;
     /// <summary>Maximum drawdown percentage (e.g., 0.1 = 10%)</summary>
     public double MaxDrawdownPct;
+    /// <summary>Maximum number of symbols with open positions (0 = unlimited)</summary>
+    public int MaxOpenPositions;
+    /// <summary>Maximum loss from the session's starting equity before trading halts (0 = disabled)</summary>
+    public double MaxDailyLoss;
+    /// <summary>Maximum order notional traded in any 1-second window (0 = disabled)</summary>
+    public double MaxNotionalPerSecond;
 
     /// <summary>
     /// Creates default risk configuration.
@@ -233,10 +301,28 @@ public struct RiskConfig
         MaxOrderRate = 10,
         MaxPositionSize = 1000.0,
         MaxOrderValue = 100000.0,
-        MaxDrawdownPct = 0.1
+        MaxDrawdownPct = 0.1,
+        MaxOpenPositions = 0,
+        MaxDailyLoss = 0.0,
+        MaxNotionalPerSecond = 0.0
     };
 }
 
+/// <summary>
+/// Account and execution cost settings for a new engine.
+/// Matches Rust repr(C) EngineCostConfig struct.
+/// </summary>
+[StructLayout(LayoutKind.Sequential)]
+public struct EngineCostConfig
+{
+    /// <summary>Starting account balance</summary>
+    public double InitialBalance;
+    /// <summary>Slippage as a fraction of the fill price</summary>
+    public double Slippage;
+    /// <summary>Commission as a fraction of the trade value</summary>
+    public double CommissionRate;
+}
+
 /// <summary>
 /// Data quality report from data cleansing.
 /// Matches Rust repr(C) DataQualityReport struct.
@@ -256,6 +342,10 @@ public struct DataQualityReport
     public long FirstTimestamp;
     /// <summary>Last timestamp in the dataset</summary>
     public long LastTimestamp;
+    /// <summary>Downsampling stride applied to the valid ticks (1 = every tick)</summary>
+    public long Stride;
+    /// <summary>Number of quotes with bid above ask</summary>
+    public long CrossedQuotes;
 }
 
 /// <summary>
@@ -299,6 +389,16 @@ public static class OrderType
     public const int Limit = 1;
 }
 
+/// <summary>
+/// Moving average type constants for StrategyParams.
+/// </summary>
+public static class MaType
+{
+    public const int Sma = 0;
+    public const int Ema = 1;
+    public const int Wma = 2;
+}
+
 
 /// <summary>
 /// Maximum number of price levels in the order book.
@@ -403,6 +503,10 @@ public struct OrderBookStats
     public int BidLevels;
     /// <summary>Number of ask levels</summary>
     public int AskLevels;
+    /// <summary>Volume imbalance (bid - ask) / (bid + ask), in [-1, 1]; 0 if the book is empty</summary>
+    public double Imbalance;
+    /// <summary>Best bid/ask weighted by the opposite side's size; last price if a side is empty</summary>
+    public double Microprice;
 }
 
 /// <summary>
@@ -432,6 +536,26 @@ public struct IndicatorResult
     public double MacdDea;
     /// <summary>MACD histogram</summary>
     public double MacdHistogram;
+    /// <summary>14-period Average True Range</summary>
+    public double Atr14;
+    /// <summary>Slow stochastic %K (14, 3)</summary>
+    public double SlowK;
+    /// <summary>Slow stochastic %D (3-period SMA of slow %K)</summary>
+    public double SlowD;
+    /// <summary>Session volume-weighted average price (0 until volume is fed)</summary>
+    public double Vwap;
+    /// <summary>Donchian upper channel (highest high over the lookback)</summary>
+    public double DonchianUpper;
+    /// <summary>Donchian lower channel (lowest low over the lookback)</summary>
+    public double DonchianLower;
+    /// <summary>Donchian mid line ((upper + lower) / 2)</summary>
+    public double DonchianMid;
+    /// <summary>Keltner upper channel (EMA + multiplier * ATR)</summary>
+    public double KeltnerUpper;
+    /// <summary>Keltner middle line (EMA of close)</summary>
+    public double KeltnerMiddle;
+    /// <summary>Keltner lower channel (EMA - multiplier * ATR)</summary>
+    public double KeltnerLower;
 }
 
 
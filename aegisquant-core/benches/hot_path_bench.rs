@@ -5,12 +5,14 @@
 //! - Strategy signal generation
 //! - Risk manager checks
 //! - Account status retrieval
+//! - Batch moving averages (ta indicators vs single-pass slices)
 //!
 //! Target: Hot path latency < 1μs
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
 use aegisquant_core::types::*;
 use aegisquant_core::ffi::*;
+use aegisquant_core::indicators::{calculate_ema, calculate_ema_fast, calculate_sma, calculate_sma_fast};
 
 /// Benchmark: Engine initialization
 fn bench_engine_init(c: &mut Criterion) {
@@ -138,6 +140,24 @@ fn bench_struct_creation(c: &mut Criterion) {
     });
 }
 
+/// Benchmark: Batch SMA/EMA over a long series
+fn bench_batch_moving_averages(c: &mut Criterion) {
+    let prices: Vec<f64> = (0..1_000_000)
+        .map(|i| 100.0 + (i as f64 * 0.001).sin())
+        .collect();
+    let period = 50;
+
+    let mut group = c.benchmark_group("batch_moving_averages");
+    group.sample_size(20);
+
+    group.bench_function("sma_ta", |b| b.iter(|| calculate_sma(black_box(&prices), period)));
+    group.bench_function("sma_fast", |b| b.iter(|| calculate_sma_fast(black_box(&prices), period)));
+    group.bench_function("ema_ta", |b| b.iter(|| calculate_ema(black_box(&prices), period)));
+    group.bench_function("ema_fast", |b| b.iter(|| calculate_ema_fast(black_box(&prices), period)));
+
+    group.finish();
+}
+
 /// Benchmark: FFI null pointer handling
 fn bench_null_pointer_check(c: &mut Criterion) {
     c.bench_function("null_pointer_check", |b| {
@@ -159,6 +179,7 @@ criterion_group!(
     bench_tick_throughput,
     bench_struct_creation,
     bench_null_pointer_check,
+    bench_batch_moving_averages,
 );
 
 criterion_main!(benches);
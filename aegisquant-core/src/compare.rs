@@ -0,0 +1,197 @@
+//! Backtest comparison.
+//!
+//! Produces a structured diff between two backtest runs for regression
+//! analysis:
+//! - Deltas of the headline `BacktestResult` metrics
+//! - Equity curve divergence (max absolute difference, correlation) and the
+//!   first bar at which the curves differ
+//!
+//! Curves of different lengths are compared over their common prefix.
+
+use crate::precision::price_eq;
+use crate::types::BacktestResult;
+
+/// Difference between two backtest results, reported as `b - a`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ResultDiff {
+    /// Final equity delta
+    pub final_equity_delta: f64,
+    /// Total return percentage delta
+    pub total_return_pct_delta: f64,
+    /// Sharpe ratio delta
+    pub sharpe_ratio_delta: f64,
+    /// Maximum drawdown percentage delta
+    pub max_drawdown_pct_delta: f64,
+    /// Trade count delta
+    pub total_trades_delta: i32,
+    /// Equity curve comparison (None when curves were not supplied)
+    pub curve: Option<CurveDiff>,
+}
+
+/// Divergence between two equity curves over their overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CurveDiff {
+    /// Number of bars compared (the shorter curve's length)
+    pub overlap_len: usize,
+    /// Length of curve `a`
+    pub len_a: usize,
+    /// Length of curve `b`
+    pub len_b: usize,
+    /// Largest absolute equity difference over the overlap
+    pub max_abs_diff: f64,
+    /// Bar of the largest absolute difference (None if the overlap is empty)
+    pub max_abs_diff_bar: Option<usize>,
+    /// Pearson correlation over the overlap (None if either side is constant)
+    pub correlation: Option<f64>,
+    /// First bar where the curves differ by more than `PRICE_EPSILON`
+    pub first_divergence_bar: Option<usize>,
+}
+
+/// Compare the headline metrics of two backtest results.
+///
+/// The returned diff has no curve comparison; see `compare_runs`.
+pub fn compare_results(a: &BacktestResult, b: &BacktestResult) -> ResultDiff {
+    ResultDiff {
+        final_equity_delta: b.final_equity - a.final_equity,
+        total_return_pct_delta: b.total_return_pct - a.total_return_pct,
+        sharpe_ratio_delta: b.sharpe_ratio - a.sharpe_ratio,
+        max_drawdown_pct_delta: b.max_drawdown_pct - a.max_drawdown_pct,
+        total_trades_delta: b.total_trades - a.total_trades,
+        curve: None,
+    }
+}
+
+/// Compare two runs: headline metrics plus their equity curves.
+pub fn compare_runs(
+    a: &BacktestResult,
+    curve_a: &[f64],
+    b: &BacktestResult,
+    curve_b: &[f64],
+) -> ResultDiff {
+    ResultDiff {
+        curve: Some(compare_equity_curves(curve_a, curve_b)),
+        ..compare_results(a, b)
+    }
+}
+
+/// Compare two equity curves bar by bar over their common prefix.
+pub fn compare_equity_curves(a: &[f64], b: &[f64]) -> CurveDiff {
+    let (len_a, len_b) = (a.len(), b.len());
+    let overlap_len = len_a.min(len_b);
+    let a = &a[..overlap_len];
+    let b = &b[..overlap_len];
+
+    let mut max_abs_diff = 0.0;
+    let mut max_abs_diff_bar = None;
+    let mut first_divergence_bar = None;
+    for (i, (&x, &y)) in a.iter().zip(b).enumerate() {
+        let diff = (x - y).abs();
+        if max_abs_diff_bar.is_none() || diff > max_abs_diff {
+            max_abs_diff = diff;
+            max_abs_diff_bar = Some(i);
+        }
+        if first_divergence_bar.is_none() && !price_eq(x, y) {
+            first_divergence_bar = Some(i);
+        }
+    }
+
+    CurveDiff {
+        overlap_len,
+        len_a,
+        len_b,
+        max_abs_diff,
+        max_abs_diff_bar,
+        correlation: correlation(a, b),
+        first_divergence_bar,
+    }
+}
+
+/// Pearson correlation of two equal-length series.
+fn correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    let n = a.len();
+    if n < 2 {
+        return None;
+    }
+    let mean_a = a.iter().sum::<f64>() / n as f64;
+    let mean_b = b.iter().sum::<f64>() / n as f64;
+
+    let mut cov = 0.0;
+    let mut var_a = 0.0;
+    let mut var_b = 0.0;
+    for (&x, &y) in a.iter().zip(b) {
+        let dx = x - mean_a;
+        let dy = y - mean_b;
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return None;
+    }
+    Some(cov / (var_a * var_b).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(final_equity: f64, sharpe_ratio: f64, max_drawdown_pct: f64, total_trades: i32) -> BacktestResult {
+        BacktestResult {
+            final_equity,
+            total_return_pct: (final_equity - 100_000.0) / 1_000.0,
+            sharpe_ratio,
+            max_drawdown_pct,
+            total_trades,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_runs_differing_at_one_trade() {
+        // Run b skips the trade at bar 6, so equity stays flat from there
+        let curve_a = [100_000.0, 100_000.0, 100_100.0, 100_050.0, 100_200.0, 100_200.0, 100_350.0, 100_500.0, 100_450.0];
+        let mut curve_b = curve_a[..6].to_vec();
+        curve_b.extend([100_200.0, 100_200.0]);
+        let a = result(100_450.0, 1.5, 0.05, 4);
+        let b = result(100_200.0, 1.2, 0.05, 3);
+
+        let diff = compare_runs(&a, &curve_a, &b, &curve_b);
+        assert!((diff.final_equity_delta + 250.0).abs() < 1e-9);
+        assert!((diff.total_return_pct_delta + 0.25).abs() < 1e-9);
+        assert!((diff.sharpe_ratio_delta + 0.3).abs() < 1e-9);
+        assert_eq!(diff.max_drawdown_pct_delta, 0.0);
+        assert_eq!(diff.total_trades_delta, -1);
+
+        // Compared over the 8-bar overlap only
+        let curve = diff.curve.unwrap();
+        assert_eq!((curve.len_a, curve.len_b, curve.overlap_len), (9, 8, 8));
+        assert_eq!(curve.first_divergence_bar, Some(6));
+        assert_eq!(curve.max_abs_diff, 300.0);
+        assert_eq!(curve.max_abs_diff_bar, Some(7));
+        let correlation = curve.correlation.unwrap();
+        assert!(correlation > 0.5 && correlation < 1.0);
+    }
+
+    #[test]
+    fn test_identical_curves() {
+        let curve = [100.0, 101.0, 99.5, 102.0];
+        let diff = compare_equity_curves(&curve, &curve);
+        assert_eq!(diff.first_divergence_bar, None);
+        assert_eq!(diff.max_abs_diff, 0.0);
+        assert!((diff.correlation.unwrap() - 1.0).abs() < 1e-12);
+        assert!(compare_results(&BacktestResult::default(), &BacktestResult::default()).curve.is_none());
+    }
+
+    #[test]
+    fn test_empty_and_flat_curves() {
+        let diff = compare_equity_curves(&[], &[100.0, 101.0]);
+        assert_eq!(diff.overlap_len, 0);
+        assert_eq!(diff.max_abs_diff_bar, None);
+        assert_eq!(diff.correlation, None);
+
+        let diff = compare_equity_curves(&[100.0, 100.0, 100.0], &[100.0, 100.0, 101.0]);
+        assert_eq!(diff.first_divergence_bar, Some(2));
+        assert_eq!(diff.correlation, None);
+    }
+}
@@ -1,22 +1,28 @@
 //! Data loading and cleansing module using Polars.
 //!
 //! Provides high-performance data loading from CSV and Parquet files,
-//! with built-in data validation and quality reporting.
+//! with built-in data validation and quality reporting. Gzip-compressed
+//! CSV (`.csv.gz`) is supported with the `compression` feature.
 //!
 //! # Error Handling
 //! All functions use `?` operator for error propagation. No `unwrap()` calls
 //! are used in production code paths to ensure the engine never panics
 //! due to malformed input data.
 
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use polars::prelude::*;
-use std::path::Path;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 use crate::error::{EngineError, EngineResult};
-use crate::types::{DataQualityReport, Tick};
+use crate::types::{Bar, DataQualityReport, ExtendedTick, QuoteTick, Tick};
 use crate::data_pipeline::{DataPipeline, PipelineConfig};
 
 /// Result of data cleansing operation.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CleansingResult {
     /// Valid ticks after cleansing
     pub ticks: Vec<Tick>,
@@ -24,6 +30,267 @@ pub struct CleansingResult {
     pub report: DataQualityReport,
     /// Indices of anomaly ticks (price jumps)
     pub anomaly_indices: Vec<usize>,
+    /// Valid ticks with quote and open-interest data, aligned with `ticks`.
+    ///
+    /// Empty unless the source has a `bid`, `ask` or `open_interest` column.
+    pub extended_ticks: Vec<ExtendedTick>,
+    /// Uncrossed quotes, downsampled with the same stride as `ticks`.
+    ///
+    /// Empty unless the source has both a `bid` and an `ask` column.
+    pub quote_ticks: Vec<QuoteTick>,
+}
+
+/// Optional columns carried into `ExtendedTick`, matched case-insensitively.
+const EXTENDED_COLUMNS: [&str; 3] = ["bid", "ask", "open_interest"];
+
+/// Header aliases mapped to the canonical timestamp/price/volume columns.
+///
+/// Matching is case-insensitive. A header with the canonical name always
+/// wins; otherwise exactly one alias must be present in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnAliases {
+    /// Aliases for the `timestamp` column
+    pub timestamp: Vec<String>,
+    /// Aliases for the `price` column
+    pub price: Vec<String>,
+    /// Aliases for the `volume` column
+    pub volume: Vec<String>,
+}
+
+impl Default for ColumnAliases {
+    fn default() -> Self {
+        let to_strings = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+        Self {
+            timestamp: to_strings(&["ts", "time", "datetime", "date"]),
+            price: to_strings(&["close", "last", "px"]),
+            volume: to_strings(&["vol", "qty", "quantity", "size"]),
+        }
+    }
+}
+
+impl ColumnAliases {
+    /// No aliases: only the canonical column names are accepted.
+    pub fn none() -> Self {
+        Self {
+            timestamp: Vec::new(),
+            price: Vec::new(),
+            volume: Vec::new(),
+        }
+    }
+
+    /// Add an alias for a canonical column (`timestamp`, `price` or `volume`).
+    pub fn with_alias(mut self, canonical: &str, alias: &str) -> EngineResult<Self> {
+        let aliases = match canonical {
+            "timestamp" => &mut self.timestamp,
+            "price" => &mut self.price,
+            "volume" => &mut self.volume,
+            _ => {
+                return Err(EngineError::validation(format!(
+                    "Unknown canonical column: {}",
+                    canonical
+                )))
+            }
+        };
+        aliases.push(alias.to_string());
+        Ok(self)
+    }
+
+    /// Find the file header for a canonical column.
+    fn resolve<'a>(&self, canonical: &str, headers: &[&'a str]) -> EngineResult<&'a str> {
+        if let Some(header) = headers.iter().find(|h| h.trim().eq_ignore_ascii_case(canonical)) {
+            return Ok(header);
+        }
+
+        let aliases = match canonical {
+            "timestamp" => &self.timestamp,
+            "price" => &self.price,
+            _ => &self.volume,
+        };
+        let matches: Vec<&'a str> = headers
+            .iter()
+            .copied()
+            .filter(|h| aliases.iter().any(|a| h.trim().eq_ignore_ascii_case(a)))
+            .collect();
+
+        match matches.as_slice() {
+            [header] => Ok(header),
+            [] => Err(EngineError::missing_column(canonical)),
+            _ => Err(EngineError::missing_column(format!(
+                "{} (ambiguous: {})",
+                canonical,
+                matches.join(", ")
+            ))),
+        }
+    }
+}
+
+/// Explicit file headers for the canonical timestamp/price/volume columns.
+///
+/// Unlike `ColumnAliases`, a mapping names the one header to use for each
+/// column; it is applied before alias resolution and a mapped header that
+/// is missing from the file is an error. Matching is case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMapping {
+    /// Header holding the timestamp
+    pub timestamp: String,
+    /// Header holding the price
+    pub price: String,
+    /// Header holding the volume
+    pub volume: String,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self::new("timestamp", "price", "volume")
+    }
+}
+
+impl ColumnMapping {
+    /// Map the given headers to the timestamp, price and volume columns.
+    pub fn new(timestamp: &str, price: &str, volume: &str) -> Self {
+        Self {
+            timestamp: timestamp.to_string(),
+            price: price.to_string(),
+            volume: volume.to_string(),
+        }
+    }
+
+    /// Rename the mapped headers of `df` to the canonical names.
+    ///
+    /// A column already carrying a canonical name is replaced by the mapped one.
+    fn apply(&self, df: &mut DataFrame) -> EngineResult<()> {
+        for (canonical, mapped) in [
+            ("timestamp", &self.timestamp),
+            ("price", &self.price),
+            ("volume", &self.volume),
+        ] {
+            let header = df
+                .get_column_names()
+                .iter()
+                .find(|h| h.trim().eq_ignore_ascii_case(mapped.trim()))
+                .map(|h| h.to_string())
+                .ok_or_else(|| EngineError::missing_column(mapped.as_str()))?;
+            if header == canonical {
+                continue;
+            }
+            if df.column(canonical).is_ok() {
+                df.drop_in_place(canonical)
+                    .map_err(|e| EngineError::PolarsError(format!("Column drop failed: {}", e)))?;
+            }
+            df.rename(&header, canonical.into())
+                .map_err(|e| EngineError::PolarsError(format!("Column rename failed: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse an ISO-8601 timestamp string to epoch milliseconds.
+///
+/// Accepts RFC 3339 strings with an offset, naive date-times (treated as
+/// UTC) with a `T` or space separator and optional fractional seconds, and
+/// bare dates.
+fn parse_timestamp_millis(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.timestamp_millis());
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(dt.and_utc().timestamp_millis());
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis())
+}
+
+/// Hit/miss counters for the DataLoader file cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LoadCacheStats {
+    /// Loads served from the cache
+    pub hits: u64,
+    /// Loads that parsed the file
+    pub misses: u64,
+    /// Files currently cached
+    pub entries: usize,
+}
+
+/// LRU cache of parsed files keyed by path and modification time.
+#[derive(Debug)]
+struct LoadCache {
+    capacity: usize,
+    /// Least recently used first
+    entries: VecDeque<(PathBuf, SystemTime, CleansingResult)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl LoadCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Look up a file, dropping the entry if the file changed since it was cached.
+    fn get(&mut self, path: &Path, modified: SystemTime) -> Option<CleansingResult> {
+        let index = self.entries.iter().position(|(p, _, _)| p == path);
+        let hit = match index {
+            Some(i) if self.entries[i].1 == modified => self.entries.remove(i),
+            Some(i) => {
+                self.entries.remove(i);
+                None
+            }
+            None => None,
+        };
+
+        match hit {
+            Some(entry) => {
+                self.hits += 1;
+                let result = entry.2.clone();
+                self.entries.push_back(entry);
+                Some(result)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, path: PathBuf, modified: SystemTime, result: CleansingResult) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((path, modified, result));
+    }
+
+    fn stats(&self) -> LoadCacheStats {
+        LoadCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+        }
+    }
+}
+
+/// Running state of the per-tick validation checks.
+///
+/// `load_from_file_streaming` carries it across chunks, so ordering and
+/// price jump checks see the last valid tick of the previous chunk.
+#[derive(Debug, Default)]
+struct TickValidator {
+    prev_timestamp: Option<i64>,
+    prev_price: Option<f64>,
+    invalid_ticks: i64,
+    anomaly_ticks: i64,
 }
 
 /// Data loader for loading and cleansing tick data.
@@ -31,18 +298,36 @@ pub struct CleansingResult {
 pub struct DataLoader {
     /// Price jump threshold (default 10%)
     price_jump_threshold: f64,
+    /// Timestamp gap marking a market closure, in milliseconds (None = disabled)
+    session_gap_ms: Option<i64>,
     /// Advanced data pipeline for institutional-grade cleansing
     pipeline: DataPipeline,
     /// Whether to use advanced pipeline preprocessing
     use_advanced_pipeline: bool,
+    /// Optional cache of parsed files (None = disabled)
+    cache: Option<Mutex<LoadCache>>,
+    /// Header aliases for the required columns
+    column_aliases: ColumnAliases,
+    /// Explicit headers for the required columns, applied before aliases
+    column_mapping: Option<ColumnMapping>,
+    /// Ticks with volume below this are counted as invalid (default 0.0)
+    min_valid_volume: f64,
+    /// Keep every Nth valid tick (default 1 = all)
+    stride: usize,
 }
 
 impl Default for DataLoader {
     fn default() -> Self {
         Self {
             price_jump_threshold: 0.10,
+            session_gap_ms: None,
             pipeline: DataPipeline::new(PipelineConfig::default()),
             use_advanced_pipeline: false, // Disabled by default for backward compatibility
+            cache: None,
+            column_aliases: ColumnAliases::default(),
+            column_mapping: None,
+            min_valid_volume: 0.0,
+            stride: 1,
         }
     }
 }
@@ -59,6 +344,73 @@ impl DataLoader {
         self
     }
 
+    /// Set the session gap for anomaly detection.
+    ///
+    /// When the timestamp delta (epoch-millis) to the previous valid tick
+    /// exceeds this many milliseconds, the tick is treated as a session open
+    /// after a closure (weekend, holiday) and the price jump check is skipped.
+    pub fn with_session_gap_ms(mut self, gap_ms: i64) -> Self {
+        self.session_gap_ms = Some(gap_ms);
+        self
+    }
+
+    /// Set the minimum volume for a tick to be valid.
+    ///
+    /// Ticks with a finite, non-negative volume below this threshold (e.g.
+    /// zero-volume indicative quotes) are filtered and counted in
+    /// `invalid_ticks`. The default of 0.0 accepts every non-negative volume.
+    pub fn with_min_valid_volume(mut self, min_volume: f64) -> Self {
+        self.min_valid_volume = min_volume;
+        self
+    }
+
+    /// Keep only every `stride`th valid tick, for quick preview runs.
+    ///
+    /// Applied after validation: the quality report still counts the full
+    /// data and records the stride, and anomaly indices still refer to rows
+    /// of the source. A stride of 0 or 1 keeps every tick.
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = stride.max(1);
+        self
+    }
+
+    /// Enable an in-memory cache of parsed files.
+    ///
+    /// Repeated `load_from_file` calls for an unchanged file return the
+    /// cached result without re-parsing. Entries are keyed by path and
+    /// modification time, so editing the file invalidates its entry. At most
+    /// `capacity` files are kept, evicting the least recently used. The cache
+    /// is safe to use from parallel sweeps sharing one loader.
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.cache = Some(Mutex::new(LoadCache::new(capacity)));
+        self
+    }
+
+    /// Get cache hit/miss counters, or None if caching is disabled.
+    pub fn cache_stats(&self) -> Option<LoadCacheStats> {
+        self.cache
+            .as_ref()
+            .and_then(|cache| cache.lock().ok().map(|cache| cache.stats()))
+    }
+
+    /// Set the header aliases mapped to the canonical columns.
+    ///
+    /// Use `ColumnAliases::none()` to require exact column names.
+    pub fn with_column_aliases(mut self, aliases: ColumnAliases) -> Self {
+        self.column_aliases = aliases;
+        self
+    }
+
+    /// Read the required columns from explicitly named headers.
+    ///
+    /// The mapping is applied before validation and before alias
+    /// resolution, so it takes precedence over both canonical names and
+    /// aliases.
+    pub fn with_column_mapping(mut self, mapping: ColumnMapping) -> Self {
+        self.column_mapping = Some(mapping);
+        self
+    }
+
     /// Enable advanced pipeline preprocessing (Z-Score outlier detection, forward fill, etc.)
     pub fn with_advanced_pipeline(mut self, config: PipelineConfig) -> Self {
         self.pipeline = DataPipeline::new(config);
@@ -84,8 +436,10 @@ impl DataLoader {
     ///
     /// # Error Handling
     /// - Returns `FileNotFound` if the file doesn't exist
-    /// - Returns `ValidationError` for unsupported file formats
-    /// - Returns `MissingColumn` if required columns are missing
+    /// - Returns `ValidationError` for unsupported file formats, including
+    ///   `.csv.gz` without the `compression` feature
+    /// - Returns `MissingColumn` if a required column is missing or several
+    ///   aliases for it are present
     /// - Returns `ParseError` for malformed data
     pub fn load_from_file<P: AsRef<Path>>(&self, path: P) -> EngineResult<CleansingResult> {
         let path = path.as_ref();
@@ -95,6 +449,138 @@ impl DataLoader {
             return Err(EngineError::file_not_found(path.display().to_string()));
         }
 
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.parse_file(path),
+        };
+
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        if let (Some(modified), Ok(mut cache)) = (modified, cache.lock()) {
+            if let Some(result) = cache.get(&key, modified) {
+                return Ok(result);
+            }
+        }
+
+        let result = self.parse_file(path)?;
+        if let (Some(modified), Ok(mut cache)) = (modified, cache.lock()) {
+            cache.insert(key, modified, result.clone());
+        }
+        Ok(result)
+    }
+
+    /// Load and cleanse a CSV file in fixed-size chunks.
+    ///
+    /// Reads `chunk_rows` data rows at a time, so memory use is bounded by
+    /// the chunk size rather than the file size. Each chunk's valid ticks
+    /// (after the stride) are passed to `on_chunk`. Validation and anomaly
+    /// detection carry the previous valid tick across chunk boundaries, so
+    /// the returned report matches `load_from_file` on the same file.
+    ///
+    /// # Error Handling
+    /// - Returns `FileNotFound` if the file doesn't exist
+    /// - Returns `ValidationError` if `chunk_rows` is 0, the file is not CSV,
+    ///   or the advanced pipeline is enabled (it needs the whole file)
+    /// - Returns `EmptyFile` if the file has no data rows
+    pub fn load_from_file_streaming<P: AsRef<Path>>(
+        &self,
+        path: P,
+        chunk_rows: usize,
+        mut on_chunk: impl FnMut(&[Tick]),
+    ) -> EngineResult<DataQualityReport> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(EngineError::file_not_found(path.display().to_string()));
+        }
+        if chunk_rows == 0 {
+            return Err(EngineError::validation("chunk_rows must be positive"));
+        }
+        if self.use_advanced_pipeline {
+            return Err(EngineError::validation(
+                "Streaming load does not support the advanced pipeline",
+            ));
+        }
+
+        let mut reader = Self::open_csv_lines(path)?;
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        if !header.ends_with('\n') {
+            header.push('\n');
+        }
+
+        let mut validator = TickValidator::default();
+        let mut report = DataQualityReport {
+            stride: self.stride as i64,
+            ..Default::default()
+        };
+        let mut valid_count = 0usize;
+        let mut chunk_ticks = Vec::with_capacity(chunk_rows);
+        let mut line = String::new();
+
+        loop {
+            // Each chunk is parsed as its own CSV with the file's header
+            let mut buffer = header.clone().into_bytes();
+            let mut rows = 0;
+            while rows < chunk_rows {
+                line.clear();
+                if reader.read_line(&mut line)? == 0 {
+                    break;
+                }
+                if line.trim().is_empty() {
+                    continue;
+                }
+                buffer.extend_from_slice(line.as_bytes());
+                if !line.ends_with('\n') {
+                    buffer.push(b'\n');
+                }
+                rows += 1;
+            }
+            if rows == 0 {
+                break;
+            }
+
+            let df = self.normalize_columns(Self::read_csv_bytes(buffer)?)?;
+            self.validate_columns(&df)?;
+            let timestamps = self.extract_i64_column(&df, "timestamp")?;
+            let prices = self.extract_f64_column(&df, "price")?;
+            let volumes = self.extract_f64_column(&df, "volume")?;
+
+            if report.total_ticks == 0 {
+                report.first_timestamp = timestamps.first().copied().unwrap_or(0);
+            }
+            report.last_timestamp = timestamps.last().copied().unwrap_or(report.last_timestamp);
+            report.total_ticks += timestamps.len() as i64;
+            if let [Some(bids), Some(asks), _] = self.extract_extended_columns(&df)? {
+                report.crossed_quotes +=
+                    Self::collect_quotes(&timestamps, &bids, &asks, &volumes, &mut Vec::new());
+            }
+
+            chunk_ticks.clear();
+            for ((&timestamp, &price), &volume) in timestamps.iter().zip(&prices).zip(&volumes) {
+                if self.check_tick(&mut validator, timestamp, price, volume).is_none() {
+                    continue;
+                }
+                // Same stride as `downsample`, counted over the whole file
+                if self.stride <= 1 || valid_count.is_multiple_of(self.stride) {
+                    chunk_ticks.push(Tick { timestamp, price, volume });
+                }
+                valid_count += 1;
+            }
+            on_chunk(&chunk_ticks);
+        }
+
+        if report.total_ticks == 0 {
+            return Err(EngineError::empty_file(path.display().to_string()));
+        }
+
+        report.valid_ticks = valid_count as i64;
+        report.invalid_ticks = validator.invalid_ticks;
+        report.anomaly_ticks = validator.anomaly_ticks;
+        Ok(report)
+    }
+
+    /// Parse and cleanse a file that is known to exist.
+    fn parse_file(&self, path: &Path) -> EngineResult<CleansingResult> {
         // Get file extension safely without unwrap
         let extension = path.extension()
             .and_then(|e| e.to_str())
@@ -103,6 +589,7 @@ impl DataLoader {
         let df = match extension.to_lowercase().as_str() {
             "csv" => self.load_csv(path)?,
             "parquet" => self.load_parquet(path)?,
+            "gz" if Self::is_csv_gz(path) => self.load_csv_gz(path)?,
             _ => return Err(EngineError::validation(
                 format!("Unsupported file format: {}", extension)
             )),
@@ -127,6 +614,78 @@ impl DataLoader {
             .map_err(|e| EngineError::parse_error(0, format!("Failed to read CSV: {}", e)))
     }
 
+    /// Check whether a `.gz` path wraps a CSV file (`*.csv.gz`).
+    fn is_csv_gz(path: &Path) -> bool {
+        path.file_stem()
+            .map(Path::new)
+            .and_then(|stem| stem.extension())
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("csv"))
+    }
+
+    /// Load gzip-compressed CSV file by decompressing it into memory.
+    #[cfg(feature = "compression")]
+    fn load_csv_gz(&self, path: &Path) -> EngineResult<DataFrame> {
+        use std::io::Read;
+
+        let file = std::fs::File::open(path)
+            .map_err(|e| EngineError::IoError(format!("Failed to open gzip file: {}", e)))?;
+        let mut buffer = Vec::new();
+        flate2::read::MultiGzDecoder::new(file)
+            .read_to_end(&mut buffer)
+            .map_err(|e| EngineError::parse_error(0, format!("Failed to decompress gzip: {}", e)))?;
+
+        Self::read_csv_bytes(buffer)
+    }
+
+    /// Reject gzip-compressed CSV when built without the `compression` feature.
+    #[cfg(not(feature = "compression"))]
+    fn load_csv_gz(&self, _path: &Path) -> EngineResult<DataFrame> {
+        Err(EngineError::validation(
+            "Unsupported file format: csv.gz (enable the `compression` feature)",
+        ))
+    }
+
+    /// Parse in-memory CSV text, including the header row.
+    fn read_csv_bytes(buffer: Vec<u8>) -> EngineResult<DataFrame> {
+        CsvReadOptions::default()
+            .with_has_header(true)
+            .into_reader_with_file_handle(std::io::Cursor::new(buffer))
+            .finish()
+            .map_err(|e| EngineError::parse_error(0, format!("Failed to read CSV: {}", e)))
+    }
+
+    /// Open a CSV (or, with `compression`, `.csv.gz`) file for line reading.
+    fn open_csv_lines(path: &Path) -> EngineResult<Box<dyn BufRead>> {
+        let extension = path.extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        if extension != "csv" && !(extension == "gz" && Self::is_csv_gz(path)) {
+            return Err(EngineError::validation(format!(
+                "Unsupported file format for streaming: {}",
+                extension
+            )));
+        }
+
+        let file = std::fs::File::open(path)
+            .map_err(|e| EngineError::IoError(format!("Failed to open CSV file: {}", e)))?;
+        if extension == "csv" {
+            return Ok(Box::new(BufReader::new(file)));
+        }
+
+        #[cfg(feature = "compression")]
+        {
+            Ok(Box::new(BufReader::new(flate2::read::MultiGzDecoder::new(file))))
+        }
+        #[cfg(not(feature = "compression"))]
+        {
+            Err(EngineError::validation(
+                "Unsupported file format: csv.gz (enable the `compression` feature)",
+            ))
+        }
+    }
+
     /// Load Parquet file using Polars.
     fn load_parquet(&self, path: &Path) -> EngineResult<DataFrame> {
         let file = std::fs::File::open(path)
@@ -139,7 +698,8 @@ impl DataLoader {
 
     /// Process DataFrame and perform data cleansing.
     fn process_dataframe(&self, df: DataFrame) -> EngineResult<CleansingResult> {
-        // Validate required columns first
+        // Map vendor headers to the canonical names, then validate
+        let df = self.normalize_columns(df)?;
         self.validate_columns(&df)?;
 
         // Apply advanced pipeline preprocessing if enabled
@@ -155,14 +715,14 @@ impl DataLoader {
         let timestamps = self.extract_i64_column(&cleaned_df, "timestamp")?;
         let prices = self.extract_f64_column(&cleaned_df, "price")?;
         let volumes = self.extract_f64_column(&cleaned_df, "volume")?;
+        let [bids, asks, open_interest] = self.extract_extended_columns(&cleaned_df)?;
+        let has_extended = bids.is_some() || asks.is_some() || open_interest.is_some();
 
         let total_ticks = timestamps.len() as i64;
         let mut valid_ticks = Vec::with_capacity(timestamps.len());
-        let mut invalid_count = 0i64;
-        let mut anomaly_count = 0i64;
+        let mut extended_ticks = Vec::new();
+        let mut validator = TickValidator::default();
         let mut anomaly_indices = Vec::new();
-        let mut prev_timestamp: Option<i64> = None;
-        let mut prev_price: Option<f64> = None;
 
         // Use get() instead of first()/last() to avoid potential issues
         let first_timestamp = timestamps.first().copied().unwrap_or(0);
@@ -173,70 +733,250 @@ impl DataLoader {
             .zip(volumes.iter())
             .enumerate()
         {
-            // Validate price > 0
-            if price <= 0.0 || !price.is_finite() {
-                invalid_count += 1;
+            let Some(is_anomaly) = self.check_tick(&mut validator, timestamp, price, volume) else {
                 continue;
-            }
-
-            // Validate volume >= 0
-            if volume < 0.0 || !volume.is_finite() {
-                invalid_count += 1;
-                continue;
-            }
-
-            // Check timestamp order
-            if let Some(prev_ts) = prev_timestamp {
-                if timestamp <= prev_ts {
-                    invalid_count += 1;
-                    continue;
-                }
-            }
-
-            // Check price jump anomaly
-            let is_anomaly = if let Some(prev_p) = prev_price {
-                if prev_p > 0.0 {
-                    let change_pct = ((price - prev_p) / prev_p).abs();
-                    change_pct > self.price_jump_threshold
-                } else {
-                    false
-                }
-            } else {
-                false
             };
 
             if is_anomaly {
-                anomaly_count += 1;
                 anomaly_indices.push(i);
                 // Still include anomaly ticks but flag them
             }
 
-            valid_ticks.push(Tick {
+            let tick = Tick {
                 timestamp,
                 price,
                 volume,
-            });
-
-            prev_timestamp = Some(timestamp);
-            prev_price = Some(price);
+            };
+            valid_ticks.push(tick);
+
+            if has_extended {
+                let value_at = |column: &Option<Vec<f64>>| {
+                    column.as_ref().map(|values| values[i]).filter(|v| v.is_finite())
+                };
+                extended_ticks.push(ExtendedTick {
+                    tick,
+                    bid: value_at(&bids),
+                    ask: value_at(&asks),
+                    open_interest: value_at(&open_interest),
+                });
+            }
         }
 
+        let mut quote_ticks = Vec::new();
+        let crossed_quotes = match (&bids, &asks) {
+            (Some(bids), Some(asks)) => {
+                Self::collect_quotes(&timestamps, bids, asks, &volumes, &mut quote_ticks)
+            }
+            _ => 0,
+        };
+
         let report = DataQualityReport {
             total_ticks,
             valid_ticks: valid_ticks.len() as i64,
-            invalid_ticks: invalid_count,
-            anomaly_ticks: anomaly_count,
+            invalid_ticks: validator.invalid_ticks,
+            anomaly_ticks: validator.anomaly_ticks,
             first_timestamp,
             last_timestamp,
+            stride: self.stride as i64,
+            crossed_quotes,
         };
 
         Ok(CleansingResult {
-            ticks: valid_ticks,
+            ticks: self.downsample(valid_ticks),
             report,
             anomaly_indices,
+            extended_ticks: self.downsample(extended_ticks),
+            quote_ticks: self.downsample(quote_ticks),
         })
     }
 
+    /// Run the per-tick checks against the previous valid tick.
+    ///
+    /// Returns None for an invalid tick, otherwise whether it is a price
+    /// jump anomaly. Only valid ticks become the new previous tick.
+    fn check_tick(
+        &self,
+        state: &mut TickValidator,
+        timestamp: i64,
+        price: f64,
+        volume: f64,
+    ) -> Option<bool> {
+        // Validate price > 0, volume >= 0 and the minimum traded volume
+        let bad_values = price <= 0.0
+            || !price.is_finite()
+            || volume < 0.0
+            || !volume.is_finite()
+            || volume < self.min_valid_volume;
+        // Check timestamp order
+        let out_of_order = state.prev_timestamp.is_some_and(|prev_ts| timestamp <= prev_ts);
+
+        if bad_values || out_of_order {
+            state.invalid_ticks += 1;
+            return None;
+        }
+
+        // Check price jump anomaly
+        let is_anomaly = self.is_price_jump(state.prev_timestamp, state.prev_price, timestamp, price);
+        if is_anomaly {
+            state.anomaly_ticks += 1;
+        }
+
+        state.prev_timestamp = Some(timestamp);
+        state.prev_price = Some(price);
+        Some(is_anomaly)
+    }
+
+    /// Keep every `stride`th element, starting with the first.
+    fn downsample<T>(&self, items: Vec<T>) -> Vec<T> {
+        if self.stride <= 1 {
+            return items;
+        }
+        items.into_iter().step_by(self.stride).collect()
+    }
+
+    /// Extract the optional `EXTENDED_COLUMNS`, None for those not in the file.
+    fn extract_extended_columns(&self, df: &DataFrame) -> EngineResult<[Option<Vec<f64>>; 3]> {
+        let headers: Vec<String> = df.get_column_names().iter().map(|n| n.to_string()).collect();
+        let mut columns = [None, None, None];
+        for (slot, name) in columns.iter_mut().zip(EXTENDED_COLUMNS) {
+            if let Some(header) = headers.iter().find(|h| h.trim().eq_ignore_ascii_case(name)) {
+                *slot = Some(self.extract_f64_column(df, header)?);
+            }
+        }
+        Ok(columns)
+    }
+
+    /// Push the uncrossed quotes of every row with a finite bid and ask.
+    ///
+    /// Returns the number of crossed quotes (bid > ask), which are dropped.
+    fn collect_quotes(
+        timestamps: &[i64],
+        bids: &[f64],
+        asks: &[f64],
+        volumes: &[f64],
+        quotes: &mut Vec<QuoteTick>,
+    ) -> i64 {
+        let mut crossed = 0;
+        for (((&timestamp, &bid), &ask), &volume) in timestamps.iter().zip(bids).zip(asks).zip(volumes) {
+            if !bid.is_finite() || !ask.is_finite() {
+                continue;
+            }
+            if bid > ask {
+                crossed += 1;
+                continue;
+            }
+            quotes.push(QuoteTick { timestamp, bid, ask, volume });
+        }
+        crossed
+    }
+
+    /// Check whether a tick is a price jump anomaly relative to the previous valid tick.
+    ///
+    /// Ticks that open a new session after a gap longer than `session_gap_ms`
+    /// are never flagged.
+    fn is_price_jump(
+        &self,
+        prev_timestamp: Option<i64>,
+        prev_price: Option<f64>,
+        timestamp: i64,
+        price: f64,
+    ) -> bool {
+        let prev_p = match prev_price {
+            Some(p) if p > 0.0 => p,
+            _ => return false,
+        };
+
+        if let (Some(gap_ms), Some(prev_ts)) = (self.session_gap_ms, prev_timestamp) {
+            if timestamp.saturating_sub(prev_ts) > gap_ms {
+                return false;
+            }
+        }
+
+        ((price - prev_p) / prev_p).abs() > self.price_jump_threshold
+    }
+
+    /// Rename aliased columns to the canonical names and convert datetime
+    /// timestamps to epoch milliseconds.
+    fn normalize_columns(&self, mut df: DataFrame) -> EngineResult<DataFrame> {
+        if let Some(mapping) = &self.column_mapping {
+            mapping.apply(&mut df)?;
+        }
+
+        let headers: Vec<String> = df.get_column_names().iter().map(|n| n.to_string()).collect();
+        let header_refs: Vec<&str> = headers.iter().map(|h| h.as_str()).collect();
+
+        for canonical in ["timestamp", "price", "volume"] {
+            let header = match self.column_aliases.resolve(canonical, &header_refs) {
+                Ok(header) => header,
+                // Quote files without trades price their ticks at the mid
+                Err(_) if canonical == "price" && self.insert_mid_price(&mut df)? => continue,
+                Err(e) => return Err(e),
+            };
+            if header != canonical {
+                df.rename(header, canonical.into())
+                    .map_err(|e| EngineError::PolarsError(format!("Column rename failed: {}", e)))?;
+            }
+        }
+
+        let timestamps = df.column("timestamp")
+            .map_err(|_| EngineError::missing_column("timestamp"))?;
+        let millis = match timestamps.dtype() {
+            DataType::String => Some(Self::parse_timestamp_column(timestamps)?),
+            DataType::Datetime(unit, _) => {
+                let divisor = match unit {
+                    TimeUnit::Nanoseconds => 1_000_000,
+                    TimeUnit::Microseconds => 1_000,
+                    TimeUnit::Milliseconds => 1,
+                };
+                let raw = self.extract_i64_column(&df, "timestamp")?;
+                Some(raw.into_iter().map(|t| t.div_euclid(divisor)).collect())
+            }
+            DataType::Date => {
+                let days = self.extract_i64_column(&df, "timestamp")?;
+                Some(days.into_iter().map(|d| d * 86_400_000).collect())
+            }
+            _ => None,
+        };
+
+        if let Some(millis) = millis {
+            df.with_column(Series::new("timestamp".into(), millis))
+                .map_err(|e| EngineError::PolarsError(format!("Timestamp conversion failed: {}", e)))?;
+        }
+        Ok(df)
+    }
+
+    /// Add a `price` column at the bid/ask mid when both quote columns exist.
+    ///
+    /// Returns false, leaving the frame untouched, when either is missing.
+    fn insert_mid_price(&self, df: &mut DataFrame) -> EngineResult<bool> {
+        let [Some(bids), Some(asks), _] = self.extract_extended_columns(df)? else {
+            return Ok(false);
+        };
+        let mids: Vec<f64> = bids.iter().zip(&asks).map(|(bid, ask)| (bid + ask) / 2.0).collect();
+        df.with_column(Series::new("price".into(), mids))
+            .map_err(|e| EngineError::PolarsError(format!("Mid price column failed: {}", e)))?;
+        Ok(true)
+    }
+
+    /// Parse a string timestamp column to epoch milliseconds.
+    fn parse_timestamp_column(column: &Column) -> EngineResult<Vec<i64>> {
+        let strings = column.str()
+            .map_err(|_| EngineError::type_mismatch("timestamp", "str", format!("{:?}", column.dtype())))?;
+        strings
+            .into_iter()
+            .enumerate()
+            .map(|(row, value)| {
+                let value = value.unwrap_or("");
+                parse_timestamp_millis(value).ok_or_else(|| {
+                    EngineError::parse_error(
+                        row + 1,
+                        format!("Invalid timestamp '{}': expected ISO-8601", value),
+                    )
+                })
+            })
+            .collect()
+    }
+
     /// Validate that required columns exist.
     fn validate_columns(&self, df: &DataFrame) -> EngineResult<()> {
         let required = ["timestamp", "price", "volume"];
@@ -338,11 +1078,8 @@ impl DataLoader {
 
         let total_ticks = timestamps.len() as i64;
         let mut valid_ticks = Vec::with_capacity(timestamps.len());
-        let mut invalid_count = 0i64;
-        let mut anomaly_count = 0i64;
+        let mut validator = TickValidator::default();
         let mut anomaly_indices = Vec::new();
-        let mut prev_timestamp: Option<i64> = None;
-        let mut prev_price: Option<f64> = None;
 
         let first_timestamp = timestamps.first().copied().unwrap_or(0);
         let last_timestamp = timestamps.last().copied().unwrap_or(0);
@@ -352,40 +1089,10 @@ impl DataLoader {
             .zip(volumes.iter())
             .enumerate()
         {
-            // Validate price > 0 and is finite
-            if price <= 0.0 || !price.is_finite() {
-                invalid_count += 1;
-                continue;
-            }
-
-            // Validate volume >= 0 and is finite
-            if volume < 0.0 || !volume.is_finite() {
-                invalid_count += 1;
+            let Some(is_anomaly) = self.check_tick(&mut validator, timestamp, price, volume) else {
                 continue;
-            }
-
-            // Check timestamp order
-            if let Some(prev_ts) = prev_timestamp {
-                if timestamp <= prev_ts {
-                    invalid_count += 1;
-                    continue;
-                }
-            }
-
-            // Check price jump anomaly
-            let is_anomaly = if let Some(prev_p) = prev_price {
-                if prev_p > 0.0 {
-                    let change_pct = ((price - prev_p) / prev_p).abs();
-                    change_pct > self.price_jump_threshold
-                } else {
-                    false
-                }
-            } else {
-                false
             };
-
             if is_anomaly {
-                anomaly_count += 1;
                 anomaly_indices.push(i);
             }
 
@@ -394,26 +1101,60 @@ impl DataLoader {
                 price,
                 volume,
             });
-
-            prev_timestamp = Some(timestamp);
-            prev_price = Some(price);
         }
 
         let report = DataQualityReport {
             total_ticks,
             valid_ticks: valid_ticks.len() as i64,
-            invalid_ticks: invalid_count,
-            anomaly_ticks: anomaly_count,
+            invalid_ticks: validator.invalid_ticks,
+            anomaly_ticks: validator.anomaly_ticks,
             first_timestamp,
             last_timestamp,
+            stride: self.stride as i64,
+            crossed_quotes: 0,
         };
 
         Ok(CleansingResult {
-            ticks: valid_ticks,
+            ticks: self.downsample(valid_ticks),
             report,
             anomaly_indices,
+            extended_ticks: Vec::new(),
+            quote_ticks: Vec::new(),
         })
     }
+
+    /// Aggregate time-ordered ticks into OHLCV bars of `interval_ms`.
+    ///
+    /// Each tick falls in the bucket starting at its timestamp floored to an
+    /// `interval_ms` boundary, so a tick exactly on a boundary opens a new bar.
+    /// Buckets without ticks produce no bar. Returns an empty Vec if
+    /// `interval_ms` is not positive.
+    pub fn resample_to_bars(&self, ticks: &[Tick], interval_ms: i64) -> Vec<Bar> {
+        if interval_ms <= 0 {
+            return Vec::new();
+        }
+        let mut bars: Vec<Bar> = Vec::new();
+        for tick in ticks {
+            let bucket = tick.timestamp - tick.timestamp.rem_euclid(interval_ms);
+            match bars.last_mut() {
+                Some(bar) if bar.timestamp == bucket => {
+                    bar.high = bar.high.max(tick.price);
+                    bar.low = bar.low.min(tick.price);
+                    bar.close = tick.price;
+                    bar.volume += tick.volume;
+                }
+                _ => bars.push(Bar {
+                    timestamp: bucket,
+                    open: tick.price,
+                    high: tick.price,
+                    low: tick.price,
+                    close: tick.price,
+                    volume: tick.volume,
+                }),
+            }
+        }
+        bars
+    }
 }
 
 
@@ -500,6 +1241,36 @@ mod tests {
         assert_eq!(result.anomaly_indices, vec![1]);
     }
 
+    #[test]
+    fn test_session_gap_skips_price_jump() {
+        const HOUR_MS: i64 = 3_600_000;
+        let friday_close = 0;
+        let monday_open = friday_close + 64 * HOUR_MS;
+        let loader = DataLoader::new()
+            .with_price_jump_threshold(0.10)
+            .with_session_gap_ms(12 * HOUR_MS);
+
+        let result = loader.load_from_vectors(
+            vec![friday_close, monday_open, monday_open + HOUR_MS],
+            vec![100.0, 115.0, 132.25], // 15% across the weekend, then 15% intraday
+            vec![1000.0, 1000.0, 1000.0],
+        ).unwrap();
+
+        assert_eq!(result.report.anomaly_ticks, 1);
+        assert_eq!(result.anomaly_indices, vec![2]);
+
+        // Without a session gap both moves are flagged
+        let result = DataLoader::new()
+            .with_price_jump_threshold(0.10)
+            .load_from_vectors(
+                vec![friday_close, monday_open, monday_open + HOUR_MS],
+                vec![100.0, 115.0, 132.25],
+                vec![1000.0, 1000.0, 1000.0],
+            )
+            .unwrap();
+        assert_eq!(result.anomaly_indices, vec![1, 2]);
+    }
+
     #[test]
     fn test_zero_price() {
         let loader = DataLoader::new();
@@ -534,6 +1305,334 @@ mod tests {
         std::fs::remove_file(temp_file).ok();
     }
 
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_gzip_csv_matches_plain_csv() {
+        use std::io::Write;
+
+        let mut csv = String::from("timestamp,price,volume\n");
+        for i in 1..=200 {
+            csv.push_str(&format!("{},{:.2},10.0\n", i, 100.0 + (i % 9) as f64 * 0.1));
+        }
+        // One invalid row and one price jump, so validation is exercised too
+        csv.push_str("201,-1.0,10.0\n202,150.0,10.0\n");
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let plain = dir.path().join("ticks.csv");
+        std::fs::write(&plain, &csv).unwrap();
+        let gz = dir.path().join("ticks.csv.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&gz).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(csv.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let loader = DataLoader::new();
+        let expected = loader.load_from_file(&plain).unwrap();
+        let result = loader.load_from_file(&gz).unwrap();
+
+        assert_eq!(result.ticks.len(), 201);
+        assert_eq!(result.ticks, expected.ticks);
+        assert_eq!(result.anomaly_indices, expected.anomaly_indices);
+        assert_eq!(result.report.invalid_ticks, 1);
+        assert!(!result.anomaly_indices.is_empty());
+    }
+
+    #[cfg(not(feature = "compression"))]
+    #[test]
+    fn test_gzip_csv_requires_compression_feature() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("ticks.csv.gz");
+        std::fs::write(&path, "not gzip").unwrap();
+
+        let result = DataLoader::new().load_from_file(&path);
+        assert!(matches!(result, Err(EngineError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_streaming_load_matches_full_load() {
+        let mut csv = String::from("timestamp,price,volume\n");
+        for i in 1..=1000i64 {
+            let price = match i {
+                // Price jumps straddling chunk boundaries
+                100 | 101 | 501 => 150.0,
+                // Invalid rows, including a backwards timestamp
+                250 => -1.0,
+                _ => 100.0 + (i % 11) as f64 * 0.1,
+            };
+            let timestamp = if i == 300 { 5 } else { i * 1_000 };
+            csv.push_str(&format!("{},{:.2},10.0\n", timestamp, price));
+        }
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("ticks.csv");
+        std::fs::write(&path, &csv).unwrap();
+
+        for loader in [DataLoader::new(), DataLoader::new().with_stride(3)] {
+            let full = loader.load_from_file(&path).unwrap();
+            assert!(full.report.anomaly_ticks >= 3);
+            assert_eq!(full.report.invalid_ticks, 2);
+
+            for chunk_rows in [1, 7, 100, 5_000] {
+                let mut ticks = Vec::new();
+                let mut chunks = 0;
+                let report = loader
+                    .load_from_file_streaming(&path, chunk_rows, |chunk| {
+                        ticks.extend_from_slice(chunk);
+                        chunks += 1;
+                    })
+                    .unwrap();
+
+                assert_eq!(report, full.report, "chunk_rows = {}", chunk_rows);
+                assert_eq!(ticks, full.ticks, "chunk_rows = {}", chunk_rows);
+                assert_eq!(chunks, 1000usize.div_ceil(chunk_rows));
+            }
+        }
+
+        assert!(matches!(
+            DataLoader::new().load_from_file_streaming(&path, 0, |_| {}),
+            Err(EngineError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_bid_ask_columns_are_preserved() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("quotes.csv");
+        std::fs::write(
+            &path,
+            "timestamp,price,volume,Bid,ask\n1,100.0,10.0,99.9,100.1\n2,-1.0,10.0,99.0,99.2\n3,101.0,11.0,,101.2\n",
+        )
+        .unwrap();
+
+        let result = DataLoader::new().load_from_file(&path).unwrap();
+        assert_eq!(result.ticks.len(), 2);
+        assert_eq!(result.extended_ticks.len(), 2);
+
+        let first = result.extended_ticks[0];
+        assert_eq!(first.tick, result.ticks[0]);
+        assert_eq!(first.bid, Some(99.9));
+        assert_eq!(first.ask, Some(100.1));
+        assert_eq!(first.open_interest, None);
+
+        // The invalid row is skipped and the empty bid cell is None
+        let second = result.extended_ticks[1];
+        assert_eq!(second.tick.price, 101.0);
+        assert_eq!(second.bid, None);
+        assert_eq!(second.ask, Some(101.2));
+
+        // Three-column files load as before
+        let base = dir.path().join("base.csv");
+        std::fs::write(&base, "timestamp,price,volume\n1,100.0,10.0\n").unwrap();
+        assert!(DataLoader::new().load_from_file(&base).unwrap().extended_ticks.is_empty());
+    }
+
+    #[test]
+    fn test_quote_file_builds_quote_ticks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("quotes.csv");
+        std::fs::write(
+            &path,
+            "timestamp,bid,ask,volume\n1,99.9,100.1,10.0\n2,100.0,100.0,5.0\n3,100.2,100.6,7.0\n",
+        )
+        .unwrap();
+
+        let result = DataLoader::new().load_from_file(&path).unwrap();
+        assert_eq!(result.report.crossed_quotes, 0);
+        assert_eq!(
+            result.quote_ticks,
+            vec![
+                QuoteTick { timestamp: 1, bid: 99.9, ask: 100.1, volume: 10.0 },
+                QuoteTick { timestamp: 2, bid: 100.0, ask: 100.0, volume: 5.0 },
+                QuoteTick { timestamp: 3, bid: 100.2, ask: 100.6, volume: 7.0 },
+            ]
+        );
+
+        // Without a price column the trade ticks are priced at the mid
+        let prices: Vec<f64> = result.ticks.iter().map(|t| t.price).collect();
+        assert_eq!(prices.len(), 3);
+        assert!((prices[0] - 100.0).abs() < 1e-9);
+        assert!((prices[2] - 100.4).abs() < 1e-9);
+
+        // Trade files fall back to the tick path with no quotes
+        let trades = write_csv(&dir, "timestamp,price,volume\n1,100.0,10.0\n");
+        let result = DataLoader::new().load_from_file(&trades).unwrap();
+        assert!(result.quote_ticks.is_empty());
+        assert_eq!(result.report.crossed_quotes, 0);
+    }
+
+    #[test]
+    fn test_crossed_quotes_are_flagged() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_csv(
+            &dir,
+            "timestamp,price,volume,bid,ask\n1,100.0,10.0,99.9,100.1\n2,100.1,4.0,100.3,100.2\n3,100.2,6.0,100.1,100.3\n",
+        );
+
+        let result = DataLoader::new().load_from_file(&path).unwrap();
+        assert_eq!(result.report.crossed_quotes, 1);
+        let timestamps: Vec<i64> = result.quote_ticks.iter().map(|q| q.timestamp).collect();
+        assert_eq!(timestamps, vec![1, 3]);
+        // The crossed quote does not invalidate its trade tick
+        assert_eq!(result.ticks.len(), 3);
+
+        let report = DataLoader::new().load_from_file_streaming(&path, 2, |_| {}).unwrap();
+        assert_eq!(report, result.report);
+    }
+
+    #[test]
+    fn test_stride_downsamples_valid_ticks() {
+        let timestamps: Vec<i64> = (1..=1000).collect();
+        let prices: Vec<f64> = timestamps.iter().map(|&t| 100.0 + (t % 7) as f64 * 0.1).collect();
+        let volumes = vec![1000.0; 1000];
+
+        let full = DataLoader::new()
+            .with_stride(1)
+            .load_from_vectors(timestamps.clone(), prices.clone(), volumes.clone())
+            .unwrap();
+        assert_eq!(full.ticks.len(), 1000);
+        assert_eq!(full.report.stride, 1);
+
+        let preview = DataLoader::new()
+            .with_stride(10)
+            .load_from_vectors(timestamps, prices, volumes)
+            .unwrap();
+        assert_eq!(preview.ticks.len(), 100);
+        assert!(preview.ticks.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        assert_eq!(preview.ticks[1], full.ticks[10]);
+
+        // The report still describes the full data
+        assert_eq!(preview.report.valid_ticks, 1000);
+        assert_eq!(preview.report.stride, 10);
+    }
+
+    #[test]
+    fn test_resample_to_bars() {
+        let tick = |ms: i64, price: f64, volume: f64| Tick { timestamp: ms, price, volume };
+        let ticks = vec![
+            tick(1_000, 100.0, 10.0),
+            tick(1_400, 102.0, 5.0),
+            tick(1_700, 99.0, 5.0),
+            tick(1_900, 101.0, 20.0),
+            // Exactly on the 2s boundary: opens the next bar
+            tick(2_000, 103.0, 1.0),
+            tick(2_500, 104.0, 2.0),
+            // 3s-4s bucket is empty; partial final bar
+            tick(4_200, 98.0, 7.0),
+        ];
+
+        let bars = DataLoader::new().resample_to_bars(&ticks, 1_000);
+        assert_eq!(bars.len(), 3);
+
+        assert_eq!(bars[0], Bar {
+            timestamp: 1_000,
+            open: 100.0,
+            high: 102.0,
+            low: 99.0,
+            close: 101.0,
+            volume: 40.0,
+        });
+        assert_eq!(bars[1].timestamp, 2_000);
+        assert_eq!((bars[1].open, bars[1].close, bars[1].volume), (103.0, 104.0, 3.0));
+
+        assert_eq!(bars[2], Bar {
+            timestamp: 4_000,
+            open: 98.0,
+            high: 98.0,
+            low: 98.0,
+            close: 98.0,
+            volume: 7.0,
+        });
+
+        assert!(DataLoader::new().resample_to_bars(&ticks, 0).is_empty());
+    }
+
+    #[test]
+    fn test_resample_iso_loaded_ticks_to_minute_bars() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("ticks.csv");
+        std::fs::write(
+            &path,
+            "timestamp,price,volume\n\
+             2024-01-02T09:30:00Z,100.0,10.0\n\
+             2024-01-02T09:30:59.500Z,101.0,5.0\n\
+             2024-01-02T09:31:00Z,102.0,1.0\n",
+        )
+        .unwrap();
+
+        let loader = DataLoader::new();
+        let ticks = loader.load_from_file(&path).unwrap().ticks;
+        let bars = loader.resample_to_bars(&ticks, 60_000);
+
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].timestamp, ticks[0].timestamp);
+        assert_eq!((bars[0].open, bars[0].close, bars[0].volume), (100.0, 101.0, 15.0));
+        assert_eq!(bars[1].timestamp, ticks[0].timestamp + 60_000);
+    }
+
+    #[test]
+    fn test_cache_hits_unchanged_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("ticks.csv");
+        std::fs::write(&path, "timestamp,price,volume\n1,100.0,10.0\n2,101.0,11.0\n").unwrap();
+
+        let loader = DataLoader::new().with_cache(4);
+        let first = loader.load_from_file(&path).unwrap();
+        let second = loader.load_from_file(&path).unwrap();
+
+        assert_eq!(first.ticks, second.ticks);
+        let stats = loader.cache_stats().unwrap();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.entries, 1);
+
+        // Uncached loaders report no stats
+        assert!(DataLoader::new().cache_stats().is_none());
+    }
+
+    #[test]
+    fn test_cache_invalidated_on_mtime_change() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("ticks.csv");
+        std::fs::write(&path, "timestamp,price,volume\n1,100.0,10.0\n").unwrap();
+
+        let loader = DataLoader::new().with_cache(4);
+        assert_eq!(loader.load_from_file(&path).unwrap().ticks.len(), 1);
+
+        std::fs::write(&path, "timestamp,price,volume\n1,100.0,10.0\n2,101.0,11.0\n").unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(60)).unwrap();
+
+        assert_eq!(loader.load_from_file(&path).unwrap().ticks.len(), 2);
+        let stats = loader.cache_stats().unwrap();
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.hits, 0);
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let paths: Vec<PathBuf> = (0..3).map(|i| dir.path().join(format!("t{}.csv", i))).collect();
+        for path in &paths {
+            std::fs::write(path, "timestamp,price,volume\n1,100.0,10.0\n").unwrap();
+        }
+
+        let loader = DataLoader::new().with_cache(2);
+        loader.load_from_file(&paths[0]).unwrap();
+        loader.load_from_file(&paths[1]).unwrap();
+        loader.load_from_file(&paths[0]).unwrap(); // t1 is now least recently used
+        loader.load_from_file(&paths[2]).unwrap(); // evicts t1
+        loader.load_from_file(&paths[0]).unwrap();
+        loader.load_from_file(&paths[1]).unwrap();
+
+        let stats = loader.cache_stats().unwrap();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 4);
+        assert_eq!(stats.entries, 2);
+    }
+
     #[test]
     fn test_nan_price_filtered() {
         let loader = DataLoader::new();
@@ -562,6 +1661,30 @@ mod tests {
         assert_eq!(result.report.invalid_ticks, 1);
     }
 
+    #[test]
+    fn test_zero_volume_ticks_filtered_by_min_volume() {
+        let timestamps = vec![1, 2, 3, 4, 5];
+        let prices = vec![100.0, 101.0, 102.0, 103.0, 104.0];
+        let volumes = vec![1000.0, 0.0, 1200.0, 0.0, 0.5];
+
+        // Default threshold keeps zero-volume ticks
+        let result = DataLoader::new()
+            .load_from_vectors(timestamps.clone(), prices.clone(), volumes.clone())
+            .unwrap();
+        assert_eq!(result.report.valid_ticks, 5);
+        assert_eq!(result.report.invalid_ticks, 0);
+
+        let result = DataLoader::new()
+            .with_min_valid_volume(1.0)
+            .load_from_vectors(timestamps, prices, volumes)
+            .unwrap();
+        assert_eq!(result.report.total_ticks, 5);
+        assert_eq!(result.report.valid_ticks, 2);
+        assert_eq!(result.report.invalid_ticks, 3);
+        let kept: Vec<i64> = result.ticks.iter().map(|t| t.timestamp).collect();
+        assert_eq!(kept, vec![1, 3]);
+    }
+
     #[test]
     fn test_empty_vectors_error() {
         let loader = DataLoader::new();
@@ -599,4 +1722,112 @@ mod tests {
         }
         // If file doesn't exist, that's okay for this test
     }
+
+    fn write_csv(dir: &tempfile::TempDir, contents: &str) -> PathBuf {
+        let path = dir.path().join("vendor.csv");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_vendor_header_aliases() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let loader = DataLoader::new();
+
+        for header in ["ts,close,vol", "time,last,qty", "Timestamp,Price,Volume", "datetime,px,size"] {
+            let path = write_csv(&dir, &format!("{}\n1,100.0,10.0\n2,101.0,11.0\n", header));
+            let result = loader.load_from_file(&path).unwrap();
+            assert_eq!(result.ticks.len(), 2, "header: {}", header);
+            assert_eq!(result.ticks[1].timestamp, 2);
+            assert_eq!(result.ticks[1].price, 101.0);
+            assert_eq!(result.ticks[1].volume, 11.0);
+        }
+    }
+
+    #[test]
+    fn test_iso8601_timestamps_parsed_to_millis() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_csv(
+            &dir,
+            "datetime,close,volume\n\
+             2024-01-02T09:30:00Z,100.0,10.0\n\
+             2024-01-02T09:30:01.250+00:00,101.0,11.0\n\
+             2024-01-02 09:30:02,102.0,12.0\n",
+        );
+
+        let result = DataLoader::new().load_from_file(&path).unwrap();
+        let base = 1_704_187_800_000;
+        let timestamps: Vec<i64> = result.ticks.iter().map(|t| t.timestamp).collect();
+        assert_eq!(timestamps, vec![base, base + 1_250, base + 2_000]);
+    }
+
+    #[test]
+    fn test_invalid_timestamp_string_is_parse_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_csv(&dir, "time,price,volume\nnot-a-date,100.0,10.0\n");
+
+        let result = DataLoader::new().load_from_file(&path);
+        assert!(matches!(result, Err(EngineError::ParseError { .. })));
+    }
+
+    #[test]
+    fn test_ambiguous_or_missing_alias_is_missing_column() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let loader = DataLoader::new();
+
+        let path = write_csv(&dir, "ts,close,last,volume\n1,100.0,100.0,10.0\n");
+        match loader.load_from_file(&path) {
+            Err(EngineError::MissingColumn { column }) => {
+                assert!(column.starts_with("price"));
+                assert!(column.contains("ambiguous"));
+            }
+            other => panic!("expected MissingColumn, got {:?}", other),
+        }
+
+        let path = write_csv(&dir, "ts,close,turnover\n1,100.0,10.0\n");
+        match loader.load_from_file(&path) {
+            Err(EngineError::MissingColumn { column }) => assert_eq!(column, "volume"),
+            other => panic!("expected MissingColumn, got {:?}", other),
+        }
+
+        // Custom aliases resolve vendor-specific headers; none() disables aliasing
+        let custom = ColumnAliases::default().with_alias("volume", "turnover").unwrap();
+        assert_eq!(loader_with(custom).load_from_file(&path).unwrap().ticks.len(), 1);
+        let path = write_csv(&dir, "ts,price,volume\n1,100.0,10.0\n");
+        assert!(matches!(
+            loader_with(ColumnAliases::none()).load_from_file(&path),
+            Err(EngineError::MissingColumn { .. })
+        ));
+        assert!(ColumnAliases::default().with_alias("open", "o").is_err());
+    }
+
+    #[test]
+    fn test_column_mapping_loads_vendor_headers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        // `price` here is a reference price; the trade price is `Trade_Px`
+        let path = write_csv(
+            &dir,
+            "epoch,Trade_Px,shares,price\n1000,100.0,10.0,1.0\n2000,101.0,5.0,1.0\n",
+        );
+
+        let loader = DataLoader::new()
+            .with_column_aliases(ColumnAliases::none())
+            .with_column_mapping(ColumnMapping::new("epoch", "trade_px", "SHARES"));
+        let result = loader.load_from_file(&path).unwrap();
+        assert_eq!(result.ticks.len(), 2);
+        assert_eq!(result.ticks[1].timestamp, 2000);
+        assert_eq!(result.ticks[1].price, 101.0);
+        assert_eq!(result.ticks[1].volume, 5.0);
+
+        // A mapped header missing from the file is reported by name
+        let loader = DataLoader::new().with_column_mapping(ColumnMapping::new("epoch", "last", "shares"));
+        match loader.load_from_file(&path) {
+            Err(EngineError::MissingColumn { column }) => assert_eq!(column, "last"),
+            other => panic!("expected MissingColumn, got {:?}", other),
+        }
+    }
+
+    fn loader_with(aliases: ColumnAliases) -> DataLoader {
+        DataLoader::new().with_column_aliases(aliases)
+    }
 }
@@ -9,30 +9,63 @@
 use polars::prelude::*;
 use crate::error::{EngineError, EngineResult};
 
+/// Smallest rolling standard deviation, as a fraction of the window mean,
+/// used by `calculate_rolling_zscore`.
+pub const ZSCORE_MIN_RELATIVE_STD: f64 = 0.001;
+
+/// How `clean` decides which prices are outliers.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutlierMethod {
+    /// Z-Score against `outlier_zscore_threshold` (0 disables filtering)
+    #[default]
+    ZScore,
+    /// Interquartile range fences `[Q1 - k*IQR, Q3 + k*IQR]` (k <= 0 disables filtering)
+    Iqr { k: f64 },
+}
+
 /// Configuration for the data pipeline
 #[derive(Debug, Clone)]
 pub struct PipelineConfig {
+    /// Outlier detection method used by `clean`
+    pub outlier_method: OutlierMethod,
     /// Standard deviation threshold for outlier detection (e.g., 3.0)
     pub outlier_zscore_threshold: f64,
     /// Whether to fill missing values (e.g., for suspended trading days)
     pub fill_missing: bool,
     /// Whether to adjust prices if split/dividend columns exist
     pub adjust_prices: bool,
-    /// Rolling window size for Z-Score calculation
+    /// Rolling window size for Z-Score calculation in `clean` (<= 1 = global statistics)
     pub rolling_window_size: usize,
+    /// Whether `clean_audited` records the rows acted on by outlier filtering
+    pub audit_outliers: bool,
 }
 
 impl Default for PipelineConfig {
     fn default() -> Self {
         Self {
+            outlier_method: OutlierMethod::ZScore,
             outlier_zscore_threshold: 3.0,
             fill_missing: true,
             adjust_prices: true,
             rolling_window_size: 20,
+            audit_outliers: false,
         }
     }
 }
 
+/// What the pipeline did to an outlier row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlierAction {
+    /// The row was dropped from the cleaned data
+    Removed,
+}
+
+/// Outlier audit entries as (row index, original price, action).
+///
+/// Row indices refer to positions after sorting and de-duplication, before
+/// outlier filtering. A null original price is reported as NaN.
+pub type OutlierAudit = Vec<(usize, f64, OutlierAction)>;
+
 /// The main Data Pipeline processor
 #[derive(Debug)]
 pub struct DataPipeline {
@@ -62,12 +95,23 @@ impl DataPipeline {
     /// 2. Handle duplicates
     /// 3. Fill missing data (Suspension handling)
     /// 4. Adjust prices (Split/Dividend) if columns exist
-    /// 5. Filter outliers (Z-Score based on global statistics)
+    /// 5. Filter outliers per `outlier_method`: rolling Z-Score over
+    ///    `rolling_window_size` ticks (global statistics when the window is 1
+    ///    or less), or IQR fences
     pub fn clean(&self, df: DataFrame) -> EngineResult<DataFrame> {
+        self.clean_audited(df).map(|(df, _)| df)
+    }
+
+    /// Run `clean` and also return the outlier audit trail.
+    ///
+    /// The audit is `Some` only when `audit_outliers` is enabled and outlier
+    /// filtering is active; otherwise no audit is built.
+    pub fn clean_audited(&self, df: DataFrame) -> EngineResult<(DataFrame, Option<OutlierAudit>)> {
         // Convert to LazyFrame for query optimization
         let mut lf = df.lazy();
 
-        // 1. Ensure time order and remove duplicates
+        // 1. Ensure time order and remove duplicates. The stable variant keeps
+        // the sorted order that audit row indices refer to
         lf = lf
             .sort(
                 ["timestamp"],
@@ -75,7 +119,7 @@ impl DataPipeline {
                     .with_order_descending(false)
                     .with_nulls_last(true),
             )
-            .unique(None, UniqueKeepStrategy::Last);
+            .unique_stable(None, UniqueKeepStrategy::Last);
 
         // 2. Handle Missing Data (Suspension Filling)
         // Logic: Forward fill price, fill volume with 0
@@ -95,13 +139,33 @@ impl DataPipeline {
         let mut result_df = lf.collect()
             .map_err(|e| EngineError::PolarsError(format!("Pipeline execution failed: {}", e)))?;
 
-        // 4. Outlier Detection (Z-Score Method using global statistics)
-        // Filter out ticks where price deviates significantly from mean
-        if self.config.outlier_zscore_threshold > 0.0 {
-            result_df = self.filter_outliers(result_df, self.config.outlier_zscore_threshold)?;
+        // 4. Outlier Detection
+        // Z-Score: filter out ticks where price deviates significantly from its
+        // recent mean (rolling unless the window is disabled)
+        // IQR: filter out ticks outside the interquartile fences
+        let mask = match self.config.outlier_method {
+            OutlierMethod::ZScore if self.config.outlier_zscore_threshold > 0.0 => {
+                let window = self.config.rolling_window_size;
+                let z_scores = if window > 1 {
+                    self.calculate_rolling_zscore(&result_df, "price", window)?
+                } else {
+                    self.calculate_zscore(&result_df, "price")?
+                };
+                Some(Self::zscore_mask(&z_scores, self.config.outlier_zscore_threshold)?)
+            }
+            OutlierMethod::Iqr { k } if k > 0.0 => Some(self.iqr_mask(&result_df, k)?),
+            _ => None,
+        };
+
+        let mut audit = None;
+        if let Some(mask) = mask {
+            if self.config.audit_outliers {
+                audit = Some(Self::audit_removed(&result_df, &mask)?);
+            }
+            result_df = Self::apply_mask(result_df, mask)?;
         }
 
-        Ok(result_df)
+        Ok((result_df, audit))
     }
 
     /// Clean data with custom column names
@@ -194,18 +258,169 @@ impl DataPipeline {
         Ok(Series::new("z_score".into(), z_scores))
     }
 
+    /// Calculate a trailing rolling Z-Score for outlier detection.
+    ///
+    /// Each value is scored against the Polars `rolling_mean` and
+    /// `rolling_std` (population) of the `window` rows before it, so a spike
+    /// cannot inflate its own statistics. The standard deviation is floored at
+    /// `ZSCORE_MIN_RELATIVE_STD` of the mean, so a flat window does not turn
+    /// the next small move into an outlier. Rows with fewer than `window`
+    /// preceding rows score 0.0 and are never flagged; null values score NaN,
+    /// as in `calculate_zscore`, and are skipped by the window statistics.
+    pub fn calculate_rolling_zscore(
+        &self,
+        df: &DataFrame,
+        column: &str,
+        window: usize,
+    ) -> EngineResult<Series> {
+        let dtype = df
+            .column(column)
+            .map_err(|_| EngineError::MissingColumn {
+                column: column.to_string(),
+            })?
+            .dtype()
+            .clone();
+
+        let window = window.max(1);
+        let options = |fn_params| RollingOptionsFixedWindow {
+            window_size: window,
+            min_periods: 1,
+            fn_params,
+            ..Default::default()
+        };
+        let values = col(column).cast(DataType::Float64);
+        let history = values.clone().shift(lit(1));
+        let stats = df
+            .clone()
+            .lazy()
+            .select([
+                values.alias("value"),
+                history.clone().rolling_mean(options(None)).alias("mean"),
+                history
+                    .rolling_std(options(Some(RollingFnParams::Var(RollingVarParams { ddof: 0 }))))
+                    .alias("std"),
+            ])
+            .collect()
+            .map_err(|e| EngineError::PolarsError(format!("Rolling statistics failed: {}", e)))?;
+
+        let column_f64 = |name: &str| -> EngineResult<Float64Chunked> {
+            stats
+                .column(name)
+                .and_then(|c| c.f64().cloned())
+                .map_err(|_| EngineError::TypeMismatch {
+                    column: column.to_string(),
+                    expected: "f64".to_string(),
+                    actual: format!("{:?}", dtype),
+                })
+        };
+        let (values, means, stds) = (column_f64("value")?, column_f64("mean")?, column_f64("std")?);
+
+        let z_scores: Vec<f64> = values
+            .into_iter()
+            .zip(means.into_iter().zip(&stds))
+            .enumerate()
+            .map(|(i, (value, (mean, std)))| {
+                let Some(v) = value else {
+                    return f64::NAN;
+                };
+                match (mean, std) {
+                    (Some(mean), Some(std)) if i >= window => {
+                        let floor = (mean.abs() * ZSCORE_MIN_RELATIVE_STD).max(0.0001);
+                        (v - mean) / std.max(floor)
+                    }
+                    _ => 0.0,
+                }
+            })
+            .collect();
+
+        Ok(Series::new("z_score".into(), z_scores))
+    }
+
     /// Filter outliers based on Z-Score threshold
     pub fn filter_outliers(&self, df: DataFrame, threshold: f64) -> EngineResult<DataFrame> {
         let z_scores = self.calculate_zscore(&df, "price")?;
+        let mask = Self::zscore_mask(&z_scores, threshold)?;
+        Self::apply_mask(df, mask)
+    }
+
+    /// Filter outliers outside `[Q1 - k*IQR, Q3 + k*IQR]` of the price column
+    pub fn filter_outliers_iqr(&self, df: DataFrame, k: f64) -> EngineResult<DataFrame> {
+        let mask = self.iqr_mask(&df, k)?;
+        Self::apply_mask(df, mask)
+    }
+
+    /// Build the IQR keep-mask for the price column (false = outlier).
+    ///
+    /// Quartiles use linear interpolation between sorted non-null prices.
+    /// Null prices are treated as outliers, as with the Z-Score mask.
+    fn iqr_mask(&self, df: &DataFrame, k: f64) -> EngineResult<Vec<bool>> {
+        let prices = df
+            .column("price")
+            .map_err(|_| EngineError::MissingColumn {
+                column: "price".to_string(),
+            })?
+            .cast(&DataType::Float64)
+            .map_err(|e| EngineError::PolarsError(format!("Cast failed: {}", e)))?;
+        let prices = prices
+            .f64()
+            .map_err(|e| EngineError::PolarsError(e.to_string()))?;
 
+        let mut sorted: Vec<f64> = prices.into_iter().flatten().collect();
+        if sorted.is_empty() {
+            return Err(EngineError::ValidationError("No valid values for IQR calculation".to_string()));
+        }
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let quantile = |q: f64| {
+            let pos = q * (sorted.len() - 1) as f64;
+            let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+            sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+        };
+        let (q1, q3) = (quantile(0.25), quantile(0.75));
+        let iqr = q3 - q1;
+        let (lower, upper) = (q1 - k * iqr, q3 + k * iqr);
+
+        Ok(prices
+            .into_iter()
+            .map(|opt| opt.map(|p| p >= lower && p <= upper).unwrap_or(false))
+            .collect())
+    }
+
+    /// Build the keep-mask for outlier filtering (false = outlier).
+    fn zscore_mask(z_scores: &Series, threshold: f64) -> EngineResult<Vec<bool>> {
         // Create mask for non-outliers
-        let mask: Vec<bool> = z_scores
+        Ok(z_scores
             .f64()
             .map_err(|e| EngineError::PolarsError(e.to_string()))?
             .into_iter()
             .map(|opt| opt.map(|z| z.abs() < threshold).unwrap_or(false))
-            .collect();
+            .collect())
+    }
+
+    /// List the rows a keep-mask removes, with their original prices.
+    fn audit_removed(df: &DataFrame, mask: &[bool]) -> EngineResult<OutlierAudit> {
+        let prices = df
+            .column("price")
+            .map_err(|_| EngineError::MissingColumn {
+                column: "price".to_string(),
+            })?
+            .cast(&DataType::Float64)
+            .map_err(|e| EngineError::PolarsError(format!("Cast failed: {}", e)))?;
+        let prices = prices
+            .f64()
+            .map_err(|e| EngineError::PolarsError(e.to_string()))?;
+
+        Ok(prices
+            .into_iter()
+            .zip(mask)
+            .enumerate()
+            .filter(|(_, (_, &keep))| !keep)
+            .map(|(i, (price, _))| (i, price.unwrap_or(f64::NAN), OutlierAction::Removed))
+            .collect())
+    }
 
+    /// Keep only the rows where `mask` is true.
+    fn apply_mask(df: DataFrame, mask: Vec<bool>) -> EngineResult<DataFrame> {
         let mask_series = Series::new("mask".into(), mask);
         let bool_chunked = mask_series
             .bool()
@@ -259,8 +474,13 @@ pub trait MarketDataStore: Send + Sync {
     fn list_symbols(&self) -> EngineResult<Vec<String>>;
 }
 
-/// TimescaleDB Implementation (Skeleton)
-/// Requires 'sqlx' crate with 'postgres' feature in Cargo.toml
+/// TimescaleDB Implementation
+///
+/// With the `timescale` cargo feature, reads and writes a table of
+/// `(symbol TEXT, time BIGINT, price DOUBLE PRECISION, volume DOUBLE PRECISION)`,
+/// with `time` in Unix nanoseconds, through sqlx. `save_ticks` creates the
+/// table if it is missing; turning it into a hypertable is left to the
+/// operator. Without the feature the store is a stub that loads nothing.
 pub struct TimescaleDbStore {
     connection_string: String,
     table_name: String,
@@ -294,8 +514,8 @@ impl TimescaleDbStore {
     }
 }
 
-// Mock implementation to compile without sqlx dependency
-// Uncomment and add dependencies to implement fully.
+// Stub implementation used when the `timescale` feature is off
+#[cfg(not(feature = "timescale"))]
 impl MarketDataStore for TimescaleDbStore {
     fn save_ticks(&self, symbol: &str, df: &DataFrame) -> EngineResult<()> {
         // Real implementation would:
@@ -317,7 +537,7 @@ impl MarketDataStore for TimescaleDbStore {
         // 1. SELECT * FROM ticks WHERE symbol = $1 AND time BETWEEN $2 AND $3
         // 2. Convert result to Arrow/Polars DataFrame
         Err(EngineError::DatabaseError(format!(
-            "DB Loading not implemented yet for symbol {} ({} to {}). Add sqlx dependency.",
+            "DB Loading not implemented yet for symbol {} ({} to {}). Enable the `timescale` feature.",
             symbol, start_ts, end_ts
         )))
     }
@@ -333,6 +553,145 @@ impl MarketDataStore for TimescaleDbStore {
     }
 }
 
+#[cfg(feature = "timescale")]
+impl TimescaleDbStore {
+    /// Rows per INSERT statement (4 binds each, below Postgres' 65535 bind limit)
+    const INSERT_BATCH_ROWS: usize = 1000;
+
+    /// Run a query future to completion on a single-threaded runtime.
+    fn block_on<T>(
+        &self,
+        fut: impl std::future::Future<Output = Result<T, sqlx::Error>>,
+    ) -> EngineResult<T> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| EngineError::database(format!("Failed to start runtime: {}", e)))?;
+        runtime
+            .block_on(fut)
+            .map_err(|e| EngineError::database(format!("TimescaleDB query failed: {}", e)))
+    }
+
+    async fn connect(&self) -> Result<sqlx::PgConnection, sqlx::Error> {
+        use sqlx::Connection;
+        sqlx::PgConnection::connect(&self.connection_string).await
+    }
+
+    /// Table name quoted as a Postgres identifier
+    fn quoted_table(&self) -> String {
+        format!("\"{}\"", self.table_name.replace('"', "\"\""))
+    }
+}
+
+#[cfg(feature = "timescale")]
+impl MarketDataStore for TimescaleDbStore {
+    fn save_ticks(&self, symbol: &str, df: &DataFrame) -> EngineResult<()> {
+        let column = |name: &str, dtype: &DataType| {
+            df.column(name)
+                .map_err(|_| EngineError::MissingColumn {
+                    column: name.to_string(),
+                })?
+                .cast(dtype)
+                .map_err(|e| EngineError::PolarsError(format!("Cast failed: {}", e)))
+        };
+        let timestamps = column("timestamp", &DataType::Int64)?;
+        let prices = column("price", &DataType::Float64)?;
+        let volumes = column("volume", &DataType::Float64)?;
+
+        let rows: Vec<(Option<i64>, Option<f64>, Option<f64>)> = timestamps
+            .i64()
+            .map_err(|e| EngineError::PolarsError(e.to_string()))?
+            .into_iter()
+            .zip(prices.f64().map_err(|e| EngineError::PolarsError(e.to_string()))?)
+            .zip(volumes.f64().map_err(|e| EngineError::PolarsError(e.to_string()))?)
+            .map(|((t, p), v)| (t, p, v))
+            .collect();
+
+        let table = self.quoted_table();
+        self.block_on(async {
+            let mut conn = self.connect().await?;
+            sqlx::query(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (\
+                 symbol TEXT NOT NULL, time BIGINT NOT NULL, \
+                 price DOUBLE PRECISION, volume DOUBLE PRECISION)",
+                table
+            ))
+            .execute(&mut conn)
+            .await?;
+
+            let mut tx = sqlx::Connection::begin(&mut conn).await?;
+            for batch in rows.chunks(Self::INSERT_BATCH_ROWS) {
+                let mut builder = sqlx::QueryBuilder::<sqlx::Postgres>::new(format!(
+                    "INSERT INTO {} (symbol, time, price, volume) ",
+                    table
+                ));
+                builder.push_values(batch, |mut row, &(time, price, volume)| {
+                    row.push_bind(symbol)
+                        .push_bind(time)
+                        .push_bind(price)
+                        .push_bind(volume);
+                });
+                builder.build().execute(&mut *tx).await?;
+            }
+            tx.commit().await
+        })
+    }
+
+    fn load_ticks(&self, symbol: &str, start_ts: i64, end_ts: i64) -> EngineResult<DataFrame> {
+        let sql = format!(
+            "SELECT time, price, volume FROM {} \
+             WHERE symbol = $1 AND time BETWEEN $2 AND $3 ORDER BY time",
+            self.quoted_table()
+        );
+        let rows: Vec<(i64, Option<f64>, Option<f64>)> = self.block_on(async {
+            let mut conn = self.connect().await?;
+            sqlx::query_as(&sql)
+                .bind(symbol)
+                .bind(start_ts)
+                .bind(end_ts)
+                .fetch_all(&mut conn)
+                .await
+        })?;
+
+        let timestamps: Vec<i64> = rows.iter().map(|r| r.0).collect();
+        let prices: Vec<Option<f64>> = rows.iter().map(|r| r.1).collect();
+        let volumes: Vec<Option<f64>> = rows.iter().map(|r| r.2).collect();
+
+        DataFrame::new(vec![
+            Series::new("timestamp".into(), timestamps).into(),
+            Series::new("price".into(), prices).into(),
+            Series::new("volume".into(), volumes).into(),
+        ])
+        .map_err(|e| EngineError::PolarsError(format!("Failed to build DataFrame: {}", e)))
+    }
+
+    fn has_data(&self, symbol: &str) -> bool {
+        let sql = format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE symbol = $1)",
+            self.quoted_table()
+        );
+        self.block_on(async {
+            let mut conn = self.connect().await?;
+            sqlx::query_scalar(&sql)
+                .bind(symbol)
+                .fetch_one(&mut conn)
+                .await
+        })
+        .unwrap_or(false)
+    }
+
+    fn list_symbols(&self) -> EngineResult<Vec<String>> {
+        let sql = format!(
+            "SELECT DISTINCT symbol FROM {} ORDER BY symbol",
+            self.quoted_table()
+        );
+        self.block_on(async {
+            let mut conn = self.connect().await?;
+            sqlx::query_scalar(&sql).fetch_all(&mut conn).await
+        })
+    }
+}
+
 /// CSV File Store Implementation
 pub struct CsvFileStore {
     base_path: String,
@@ -561,6 +920,7 @@ mod tests {
         assert!(config.fill_missing);
         assert!(config.adjust_prices);
         assert_eq!(config.rolling_window_size, 20);
+        assert_eq!(config.outlier_method, OutlierMethod::ZScore);
     }
 
     #[test]
@@ -572,10 +932,12 @@ mod tests {
     #[test]
     fn test_clean_basic() {
         let pipeline = DataPipeline::new(PipelineConfig {
+            outlier_method: OutlierMethod::ZScore,
             outlier_zscore_threshold: 0.0, // Disable outlier filtering
             fill_missing: false,
             adjust_prices: false,
             rolling_window_size: 20,
+            audit_outliers: false,
         });
 
         let df = create_test_dataframe();
@@ -590,10 +952,12 @@ mod tests {
     #[test]
     fn test_clean_sorts_data() {
         let pipeline = DataPipeline::new(PipelineConfig {
+            outlier_method: OutlierMethod::ZScore,
             outlier_zscore_threshold: 0.0,
             fill_missing: false,
             adjust_prices: false,
             rolling_window_size: 20,
+            audit_outliers: false,
         });
 
         let df = create_unsorted_dataframe();
@@ -700,10 +1064,12 @@ mod tests {
     #[test]
     fn test_clean_with_outlier_filtering() {
         let pipeline = DataPipeline::new(PipelineConfig {
+            outlier_method: OutlierMethod::ZScore,
             outlier_zscore_threshold: 2.0, // Enable outlier filtering
             fill_missing: false,
             adjust_prices: false,
-            rolling_window_size: 5,
+            rolling_window_size: 1, // Global statistics
+            audit_outliers: false,
         });
 
         let df = create_dataframe_with_outlier();
@@ -723,6 +1089,156 @@ mod tests {
         assert!(!prices.contains(&500.0));
     }
 
+    #[test]
+    fn test_clean_audited_lists_removed_outliers() {
+        let config = PipelineConfig {
+            outlier_method: OutlierMethod::ZScore,
+            outlier_zscore_threshold: 2.0,
+            fill_missing: false,
+            adjust_prices: false,
+            rolling_window_size: 1,
+            audit_outliers: true,
+        };
+        let pipeline = DataPipeline::new(config.clone());
+
+        // Outlier at timestamp 4 sits at row 0 before sorting, row 3 after
+        let df = df! {
+            "timestamp" => &[4i64, 1, 2, 3, 5, 6, 7, 8, 9, 10],
+            "price" => &[500.0, 100.0, 101.0, 102.0, 104.0, 105.0, 106.0, 107.0, 108.0, 109.0],
+            "volume" => &[1300.0, 1000.0, 1100.0, 1200.0, 1400.0, 1500.0, 1600.0, 1700.0, 1800.0, 1900.0]
+        }
+        .unwrap();
+
+        let (cleaned, audit) = pipeline.clean_audited(df.clone()).unwrap();
+        assert_eq!(cleaned.height(), 9);
+        assert_eq!(audit, Some(vec![(3, 500.0, OutlierAction::Removed)]));
+
+        // Disabled by default, and the cleaned data is unchanged
+        let pipeline = DataPipeline::new(PipelineConfig {
+            audit_outliers: false,
+            ..config
+        });
+        let (unaudited, audit) = pipeline.clean_audited(df).unwrap();
+        assert!(audit.is_none());
+        assert!(unaudited.equals(&cleaned));
+    }
+
+    #[test]
+    fn test_rolling_zscore_keeps_trend_and_drops_spike() {
+        let pipeline = DataPipeline::new(PipelineConfig {
+            outlier_method: OutlierMethod::ZScore,
+            outlier_zscore_threshold: 3.0,
+            fill_missing: false,
+            adjust_prices: false,
+            rolling_window_size: 10,
+            audit_outliers: true,
+        });
+
+        // Slow uptrend with a single spike late in the series
+        let timestamps: Vec<i64> = (1..=60).collect();
+        let mut prices: Vec<f64> = (0..60).map(|i| 100.0 + i as f64 * 0.5).collect();
+        prices[45] = 132.0;
+        let volumes = vec![1000.0; 60];
+        let df = df! {
+            "timestamp" => &timestamps,
+            "price" => &prices,
+            "volume" => &volumes
+        }
+        .unwrap();
+
+        // Global statistics see the trend's spread and miss the spike
+        let global = pipeline.calculate_zscore(&df, "price").unwrap();
+        assert!(global.f64().unwrap().get(45).unwrap() < 3.0);
+
+        // Warmup rows are never flagged
+        let rolling = pipeline.calculate_rolling_zscore(&df, "price", 10).unwrap();
+        let rolling = rolling.f64().unwrap();
+        assert!((0..10).all(|i| rolling.get(i) == Some(0.0)));
+
+        let (cleaned, audit) = pipeline.clean_audited(df).unwrap();
+        assert_eq!(cleaned.height(), 59);
+        assert_eq!(audit, Some(vec![(45, 132.0, OutlierAction::Removed)]));
+    }
+
+    #[test]
+    fn test_rolling_zscore_keeps_flat_then_step() {
+        let pipeline = DataPipeline::with_defaults();
+
+        // 20 flat ticks, then a small level shift that holds
+        let timestamps: Vec<i64> = (1..=40).collect();
+        let mut prices = vec![100.0; 20];
+        prices.extend([100.2; 20]);
+        let volumes = vec![1000.0; 40];
+        let df = df! {
+            "timestamp" => &timestamps,
+            "price" => &prices,
+            "volume" => &volumes
+        }
+        .unwrap();
+        assert_eq!(pipeline.clean(df).unwrap().height(), 40);
+
+        // A bad print on the same flat tape is still removed
+        let mut prices = vec![100.0; 40];
+        prices[30] = 110.0;
+        let df = df! {
+            "timestamp" => &timestamps,
+            "price" => &prices,
+            "volume" => &volumes
+        }
+        .unwrap();
+        let cleaned = pipeline.clean(df).unwrap();
+        assert_eq!(cleaned.height(), 39);
+        assert!(cleaned.column("price").unwrap().f64().unwrap().into_iter().all(|p| p == Some(100.0)));
+    }
+
+    #[test]
+    fn test_iqr_catches_clustered_upside_outliers() {
+        // Tight range around 100-102 with a cluster of upside outliers
+        let mut prices: Vec<f64> = (0..40).map(|i| 100.0 + (i % 5) as f64 * 0.5).collect();
+        prices.extend([120.0, 125.0, 130.0, 135.0]);
+        let timestamps: Vec<i64> = (1..=prices.len() as i64).collect();
+        let volumes = vec![1000.0; prices.len()];
+        let df = df! {
+            "timestamp" => &timestamps,
+            "price" => &prices,
+            "volume" => &volumes
+        }
+        .unwrap();
+
+        let config = PipelineConfig {
+            outlier_method: OutlierMethod::ZScore,
+            outlier_zscore_threshold: 3.0,
+            fill_missing: false,
+            adjust_prices: false,
+            rolling_window_size: 1,
+            audit_outliers: true,
+        };
+        let max_price = |df: &DataFrame| {
+            df.column("price").unwrap().f64().unwrap().max().unwrap()
+        };
+
+        // The cluster inflates the standard deviation and masks its smaller members
+        let (zscore, audit) = DataPipeline::new(config.clone()).clean_audited(df.clone()).unwrap();
+        assert!(zscore.height() > 40);
+        assert!(max_price(&zscore) >= 120.0);
+        assert!(audit.unwrap().len() < 4);
+
+        let (iqr, audit) = DataPipeline::new(PipelineConfig {
+            outlier_method: OutlierMethod::Iqr { k: 1.5 },
+            ..config
+        })
+        .clean_audited(df.clone())
+        .unwrap();
+        assert_eq!(iqr.height(), 40);
+        assert!(max_price(&iqr) <= 102.0);
+        let removed: Vec<usize> = audit.unwrap().iter().map(|(i, _, _)| *i).collect();
+        assert_eq!(removed, vec![40, 41, 42, 43]);
+
+        // The standalone filter agrees with clean()
+        let filtered = DataPipeline::with_defaults().filter_outliers_iqr(df, 1.5).unwrap();
+        assert!(filtered.equals(&iqr));
+    }
+
     #[test]
     fn test_adjust_prices_with_split() {
         let pipeline = DataPipeline::with_defaults();
@@ -1,14 +1,20 @@
 //! Emergency Stop Module
 //!
 //! Provides global emergency halt functionality for the trading engine.
-//! When activated, all trading operations are immediately stopped.
+//! When activated, all trading operations are immediately stopped. Each halt
+//! records a `HaltReason` and notifies the host through the halt callback.
+//! Individual symbols can also be halted while the rest of the book keeps
+//! trading; the global halt overrides them.
 //!
 //! Requirements: 16.1, 16.2, 16.6, 16.7
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicPtr, Ordering};
+use std::sync::Mutex;
 
 use crate::error::{EngineError, EngineResult};
-use crate::ffi::{ERR_NULL_POINTER, ERR_SUCCESS};
+use crate::ffi::{ERR_INVALID_PARAM, ERR_NULL_POINTER, ERR_SUCCESS};
 use crate::logger::{log, LogLevel};
 use crate::types::{OrderRequest, Position};
 
@@ -17,6 +23,68 @@ use crate::types::{OrderRequest, Position};
 /// When set to true, all trading operations should be blocked.
 static EMERGENCY_HALT: AtomicBool = AtomicBool::new(false);
 
+/// Code of the `HaltReason` for the active halt (0 when not halted).
+static HALT_REASON: AtomicI32 = AtomicI32::new(0);
+
+lazy_static::lazy_static! {
+    /// Halted symbols and the reason each was halted.
+    static ref SYMBOL_HALTS: Mutex<HashMap<String, HaltReason>> = Mutex::new(HashMap::new());
+}
+
+/// Why trading was halted.
+///
+/// Passed across FFI as its `i32` code. `None` is only reported while no
+/// halt is active.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HaltReason {
+    #[default]
+    None = 0,
+    /// Operator pressed the emergency stop
+    Manual = 1,
+    /// Session loss exceeded `max_daily_loss`
+    DailyLoss = 2,
+    /// Drawdown exceeded its limit
+    DrawdownBreach = 3,
+    /// Market data stopped arriving
+    DataGap = 4,
+    /// Requested by an external system (e.g. the C# host or a broker)
+    External = 5,
+}
+
+impl HaltReason {
+    /// Reason for a code. Unknown codes map to `None`.
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            1 => HaltReason::Manual,
+            2 => HaltReason::DailyLoss,
+            3 => HaltReason::DrawdownBreach,
+            4 => HaltReason::DataGap,
+            5 => HaltReason::External,
+            _ => HaltReason::None,
+        }
+    }
+}
+
+/// Callback invoked when an emergency halt is activated, with the
+/// `HaltReason` code.
+pub type HaltCallback = extern "C" fn(reason: i32);
+
+/// Global halt callback (null = none).
+///
+/// SAFETY: The pointer is either null or a valid `HaltCallback`.
+static HALT_CALLBACK: AtomicPtr<()> = AtomicPtr::new(std::ptr::null_mut());
+
+/// Invoke the halt callback, if one is set.
+fn notify_halt(reason: HaltReason) {
+    let ptr = HALT_CALLBACK.load(Ordering::SeqCst);
+    if !ptr.is_null() {
+        // SAFETY: Only valid HaltCallback pointers are stored
+        let callback: HaltCallback = unsafe { std::mem::transmute(ptr) };
+        callback(reason as i32);
+    }
+}
+
 /// Check if the system is in emergency halt state.
 #[inline]
 pub fn is_halted() -> bool {
@@ -26,10 +94,72 @@ pub fn is_halted() -> bool {
 /// Activate emergency stop.
 ///
 /// This immediately sets the global halt flag, preventing any new
-/// trading operations from being executed.
-pub fn activate_emergency_stop() {
-    EMERGENCY_HALT.store(true, Ordering::SeqCst);
-    log(LogLevel::Error, "EMERGENCY STOP ACTIVATED - All trading halted");
+/// trading operations from being executed. Only the first activation of a
+/// halt records its reason and fires the halt callback; activating again
+/// while halted keeps the original reason.
+pub fn activate_emergency_stop(reason: HaltReason) {
+    if EMERGENCY_HALT.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    HALT_REASON.store(reason as i32, Ordering::SeqCst);
+    log(
+        LogLevel::Error,
+        &format!("EMERGENCY STOP ACTIVATED ({:?}) - All trading halted", reason),
+    );
+    notify_halt(reason);
+}
+
+/// Reason for the active halt, or `HaltReason::None` if not halted.
+pub fn get_halt_reason() -> HaltReason {
+    HaltReason::from_code(HALT_REASON.load(Ordering::SeqCst))
+}
+
+/// Halt trading in a single symbol.
+///
+/// Other symbols keep trading. Halting an already halted symbol keeps its
+/// original reason.
+pub fn halt_symbol(symbol: &str, reason: HaltReason) {
+    let mut halts = SYMBOL_HALTS.lock().unwrap_or_else(|e| e.into_inner());
+    if halts.contains_key(symbol) {
+        return;
+    }
+    halts.insert(symbol.to_string(), reason);
+    log(
+        LogLevel::Error,
+        &format!("Symbol {} halted ({:?})", symbol, reason),
+    );
+}
+
+/// Resume trading in a halted symbol.
+///
+/// Has no effect on the global halt.
+pub fn reset_symbol(symbol: &str) {
+    let removed = SYMBOL_HALTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(symbol)
+        .is_some();
+    if removed {
+        log(LogLevel::Info, &format!("Symbol {} halt reset", symbol));
+    }
+}
+
+/// Check if a symbol is halted on its own (ignores the global halt).
+pub fn is_symbol_halted(symbol: &str) -> bool {
+    SYMBOL_HALTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains_key(symbol)
+}
+
+/// Reason a symbol was halted, or `HaltReason::None` if it is not.
+pub fn get_symbol_halt_reason(symbol: &str) -> HaltReason {
+    SYMBOL_HALTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(symbol)
+        .copied()
+        .unwrap_or_default()
 }
 
 /// Reset emergency stop.
@@ -37,6 +167,7 @@ pub fn activate_emergency_stop() {
 /// This clears the global halt flag, allowing trading operations to resume.
 /// Should only be called after the emergency situation has been resolved.
 pub fn reset_emergency_stop() {
+    HALT_REASON.store(HaltReason::None as i32, Ordering::SeqCst);
     EMERGENCY_HALT.store(false, Ordering::SeqCst);
     log(LogLevel::Info, "Emergency stop reset - Trading can resume");
 }
@@ -73,12 +204,15 @@ pub fn generate_close_all_orders(positions: &[Position]) -> Vec<OrderRequest> {
     orders
 }
 
-/// Check if an operation should be blocked due to emergency halt.
+/// Check if an operation on `symbol` should be blocked due to emergency halt.
 ///
-/// Returns an error if the system is halted, otherwise Ok(()).
-pub fn check_halt() -> EngineResult<()> {
+/// Returns an error if the system is halted globally or `symbol` is halted,
+/// otherwise Ok(()).
+pub fn check_halt(symbol: &str) -> EngineResult<()> {
     if is_halted() {
         Err(EngineError::risk_rejected("Emergency halt is active"))
+    } else if is_symbol_halted(symbol) {
+        Err(EngineError::risk_rejected(format!("Symbol {} is halted", symbol)))
     } else {
         Ok(())
     }
@@ -88,13 +222,125 @@ pub fn check_halt() -> EngineResult<()> {
 // FFI Functions
 // ============================================================================
 
-/// Activate emergency stop via FFI.
+/// Activate emergency stop via FFI with reason `Manual`.
 ///
 /// # Returns
 /// - ERR_SUCCESS on success
 #[no_mangle]
 pub extern "C" fn emergency_stop() -> i32 {
-    activate_emergency_stop();
+    activate_emergency_stop(HaltReason::Manual);
+    ERR_SUCCESS
+}
+
+/// Activate emergency stop via FFI with a `HaltReason` code.
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_INVALID_PARAM if `reason` is not a known halt reason
+#[no_mangle]
+pub extern "C" fn emergency_stop_with_reason(reason: i32) -> i32 {
+    let reason = HaltReason::from_code(reason);
+    if reason == HaltReason::None {
+        return ERR_INVALID_PARAM;
+    }
+    activate_emergency_stop(reason);
+    ERR_SUCCESS
+}
+
+/// Get the `HaltReason` code of the active halt via FFI.
+///
+/// # Returns
+/// - The reason code, or 0 if not halted
+#[no_mangle]
+pub extern "C" fn get_halt_reason_ffi() -> i32 {
+    get_halt_reason() as i32
+}
+
+/// Halt a single symbol via FFI with a `HaltReason` code.
+///
+/// # Safety
+/// - `symbol` must be a valid null-terminated UTF-8 string
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if symbol is null
+/// - ERR_INVALID_PARAM if symbol is not UTF-8 or `reason` is unknown
+#[no_mangle]
+pub unsafe extern "C" fn halt_symbol_ffi(symbol: *const c_char, reason: i32) -> i32 {
+    if symbol.is_null() {
+        return ERR_NULL_POINTER;
+    }
+    let Ok(symbol) = CStr::from_ptr(symbol).to_str() else {
+        return ERR_INVALID_PARAM;
+    };
+    let reason = HaltReason::from_code(reason);
+    if reason == HaltReason::None {
+        return ERR_INVALID_PARAM;
+    }
+    halt_symbol(symbol, reason);
+    ERR_SUCCESS
+}
+
+/// Resume trading in a halted symbol via FFI.
+///
+/// # Safety
+/// - `symbol` must be a valid null-terminated UTF-8 string
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if symbol is null
+/// - ERR_INVALID_PARAM if symbol is not UTF-8
+#[no_mangle]
+pub unsafe extern "C" fn reset_symbol_ffi(symbol: *const c_char) -> i32 {
+    if symbol.is_null() {
+        return ERR_NULL_POINTER;
+    }
+    let Ok(symbol) = CStr::from_ptr(symbol).to_str() else {
+        return ERR_INVALID_PARAM;
+    };
+    reset_symbol(symbol);
+    ERR_SUCCESS
+}
+
+/// Check if a symbol can't trade via FFI, either because it is halted or
+/// because the global halt is active.
+///
+/// # Safety
+/// - `symbol` must be a valid null-terminated UTF-8 string
+///
+/// # Returns
+/// - 1 if halted, 0 if not halted
+/// - ERR_NULL_POINTER if symbol is null
+/// - ERR_INVALID_PARAM if symbol is not UTF-8
+#[no_mangle]
+pub unsafe extern "C" fn is_symbol_halted_ffi(symbol: *const c_char) -> i32 {
+    if symbol.is_null() {
+        return ERR_NULL_POINTER;
+    }
+    let Ok(symbol) = CStr::from_ptr(symbol).to_str() else {
+        return ERR_INVALID_PARAM;
+    };
+    if check_halt(symbol).is_err() { 1 } else { 0 }
+}
+
+/// Set the callback invoked when an emergency halt is activated.
+///
+/// Passing `None` (a null pointer from C) unregisters the callback, like
+/// `clear_halt_callback`.
+///
+/// # Safety
+/// The callback must remain valid until it is cleared.
+#[no_mangle]
+pub unsafe extern "C" fn set_halt_callback(callback: Option<HaltCallback>) -> i32 {
+    let ptr = callback.map_or(std::ptr::null_mut(), |callback| callback as *mut ());
+    HALT_CALLBACK.store(ptr, Ordering::SeqCst);
+    ERR_SUCCESS
+}
+
+/// Clear the halt callback.
+#[no_mangle]
+pub extern "C" fn clear_halt_callback() -> i32 {
+    HALT_CALLBACK.store(std::ptr::null_mut(), Ordering::SeqCst);
     ERR_SUCCESS
 }
 
@@ -161,49 +407,179 @@ pub unsafe extern "C" fn close_all_positions(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
 
     fn reset_state() {
         EMERGENCY_HALT.store(false, Ordering::SeqCst);
+        HALT_REASON.store(0, Ordering::SeqCst);
+        SYMBOL_HALTS.lock().unwrap().clear();
     }
 
     #[test]
+    #[serial]
     fn test_emergency_halt_default() {
         reset_state();
         assert!(!is_halted());
     }
 
     #[test]
+    #[serial]
     fn test_activate_emergency_stop() {
         reset_state();
-        activate_emergency_stop();
+        activate_emergency_stop(HaltReason::Manual);
         assert!(is_halted());
         reset_state();
     }
 
     #[test]
+    #[serial]
     fn test_reset_emergency_stop() {
         reset_state();
-        activate_emergency_stop();
+        activate_emergency_stop(HaltReason::Manual);
         assert!(is_halted());
         reset_emergency_stop();
         assert!(!is_halted());
     }
 
     #[test]
+    #[serial]
+    fn test_halt_reason_is_recorded() {
+        reset_state();
+        assert_eq!(get_halt_reason(), HaltReason::None);
+
+        activate_emergency_stop(HaltReason::DataGap);
+        assert_eq!(get_halt_reason(), HaltReason::DataGap);
+        assert_eq!(get_halt_reason_ffi(), 4);
+
+        // A second activation while halted keeps the original reason
+        activate_emergency_stop(HaltReason::Manual);
+        assert_eq!(get_halt_reason(), HaltReason::DataGap);
+
+        reset_emergency_stop();
+        assert_eq!(get_halt_reason(), HaltReason::None);
+    }
+
+    static HALT_CALLS: AtomicI32 = AtomicI32::new(0);
+    static LAST_HALT_REASON: AtomicI32 = AtomicI32::new(0);
+
+    extern "C" fn record_halt(reason: i32) {
+        HALT_CALLS.fetch_add(1, Ordering::SeqCst);
+        LAST_HALT_REASON.store(reason, Ordering::SeqCst);
+    }
+
+    #[test]
+    #[serial]
+    fn test_halt_callback_fires_once_per_halt() {
+        reset_state();
+        HALT_CALLS.store(0, Ordering::SeqCst);
+        unsafe { set_halt_callback(Some(record_halt)) };
+
+        activate_emergency_stop(HaltReason::DailyLoss);
+        activate_emergency_stop(HaltReason::External);
+        assert_eq!(HALT_CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(LAST_HALT_REASON.load(Ordering::SeqCst), HaltReason::DailyLoss as i32);
+
+        // After a reset the next halt notifies again
+        reset_emergency_stop();
+        assert_eq!(emergency_stop_with_reason(HaltReason::External as i32), ERR_SUCCESS);
+        clear_halt_callback();
+        assert_eq!(HALT_CALLS.load(Ordering::SeqCst), 2);
+        assert_eq!(LAST_HALT_REASON.load(Ordering::SeqCst), HaltReason::External as i32);
+
+        // Setting None unregisters the callback
+        reset_emergency_stop();
+        unsafe {
+            set_halt_callback(Some(record_halt));
+            set_halt_callback(None);
+        }
+        activate_emergency_stop(HaltReason::Manual);
+        assert_eq!(HALT_CALLS.load(Ordering::SeqCst), 2);
+
+        reset_state();
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_emergency_stop_rejects_unknown_reason() {
+        reset_state();
+        assert_eq!(emergency_stop_with_reason(0), ERR_INVALID_PARAM);
+        assert_eq!(emergency_stop_with_reason(42), ERR_INVALID_PARAM);
+        assert!(!is_halted());
+    }
+
+    #[test]
+    #[serial]
     fn test_check_halt_when_not_halted() {
         reset_state();
-        assert!(check_halt().is_ok());
+        assert!(check_halt("BTCUSDT").is_ok());
     }
 
     #[test]
+    #[serial]
     fn test_check_halt_when_halted() {
         reset_state();
-        activate_emergency_stop();
-        assert!(check_halt().is_err());
+        activate_emergency_stop(HaltReason::Manual);
+        assert!(check_halt("BTCUSDT").is_err());
+        reset_state();
+    }
+
+    #[test]
+    #[serial]
+    fn test_symbol_halt_leaves_other_symbols_trading() {
+        reset_state();
+        halt_symbol("ETHUSDT", HaltReason::DataGap);
+
+        assert!(check_halt("BTCUSDT").is_ok());
+        assert!(check_halt("ETHUSDT").is_err());
+        assert!(is_symbol_halted("ETHUSDT"));
+        assert!(!is_halted());
+        assert_eq!(get_symbol_halt_reason("ETHUSDT"), HaltReason::DataGap);
+        assert_eq!(get_symbol_halt_reason("BTCUSDT"), HaltReason::None);
+
+        reset_symbol("ETHUSDT");
+        assert!(check_halt("ETHUSDT").is_ok());
+        reset_state();
+    }
+
+    #[test]
+    #[serial]
+    fn test_global_halt_supersedes_symbol_halts() {
+        reset_state();
+        halt_symbol("ETHUSDT", HaltReason::External);
+        activate_emergency_stop(HaltReason::Manual);
+
+        assert!(check_halt("BTCUSDT").is_err());
+        assert!(check_halt("ETHUSDT").is_err());
+        assert!(!is_symbol_halted("BTCUSDT"));
+
+        // Clearing the global halt leaves the symbol halt in place
+        reset_emergency_stop();
+        assert!(check_halt("BTCUSDT").is_ok());
+        assert!(check_halt("ETHUSDT").is_err());
+        reset_state();
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_symbol_halt() {
+        reset_state();
+        let eth = std::ffi::CString::new("ETHUSDT").unwrap();
+        let btc = std::ffi::CString::new("BTCUSDT").unwrap();
+
+        unsafe {
+            assert_eq!(halt_symbol_ffi(eth.as_ptr(), 0), ERR_INVALID_PARAM);
+            assert_eq!(halt_symbol_ffi(eth.as_ptr(), HaltReason::DataGap as i32), ERR_SUCCESS);
+            assert_eq!(is_symbol_halted_ffi(eth.as_ptr()), 1);
+            assert_eq!(is_symbol_halted_ffi(btc.as_ptr()), 0);
+            assert_eq!(reset_symbol_ffi(eth.as_ptr()), ERR_SUCCESS);
+            assert_eq!(is_symbol_halted_ffi(eth.as_ptr()), 0);
+            assert_eq!(is_symbol_halted_ffi(std::ptr::null()), ERR_NULL_POINTER);
+        }
         reset_state();
     }
 
     #[test]
+    #[serial]
     fn test_generate_close_orders_long_position() {
         let mut position = Position::with_symbol("BTCUSDT");
         position.quantity = 10.0;
@@ -219,6 +595,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_generate_close_orders_short_position() {
         let mut position = Position::with_symbol("ETHUSDT");
         position.quantity = -5.0;
@@ -233,6 +610,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_generate_close_orders_zero_position() {
         let position = Position::with_symbol("BTCUSDT");
         // quantity is 0 by default
@@ -243,6 +621,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_generate_close_orders_multiple_positions() {
         let mut pos1 = Position::with_symbol("BTCUSDT");
         pos1.quantity = 10.0;
@@ -259,6 +638,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_ffi_emergency_stop() {
         reset_state();
         let result = emergency_stop();
@@ -268,6 +648,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_ffi_reset_emergency_stop() {
         reset_state();
         emergency_stop();
@@ -277,6 +658,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_ffi_is_emergency_halted() {
         reset_state();
         assert_eq!(is_emergency_halted(), 0);
@@ -286,6 +668,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_ffi_close_all_positions() {
         let mut pos = Position::with_symbol("BTCUSDT");
         pos.quantity = 10.0;
@@ -311,6 +694,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_ffi_close_all_positions_null_pointers() {
         let mut order_count: i32 = 0;
 
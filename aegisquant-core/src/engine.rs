@@ -5,17 +5,150 @@
 
 use rust_decimal::prelude::*;
 use rust_decimal::Decimal;
+use std::any::Any;
+use std::collections::VecDeque;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::data_loader::DataLoader;
 use crate::error::{EngineError, EngineResult};
-use crate::gateway::{Gateway, SimulatedGateway};
+use crate::event_bus::{Event, OrderStatus, SharedEventBus};
+use crate::gateway::{Fill, Gateway, SimulatedGateway, VolumeTieredFee};
+use crate::indicators::AtrIndicator;
+use crate::optimizer::{Optimizer, ParameterRange};
+use crate::persistence::{AccountSnapshot, EntryReason, ExitReason, PersistenceManager, TradeRecord};
+use crate::precision::QUANTITY_EPSILON;
 use crate::risk::RiskManager;
-use crate::strategy::{DualMAStrategy, Signal, Strategy};
+use crate::strategy::{DualMAStrategy, MaType, RegimeFilter, Signal, Strategy};
+use crate::validation::{validate_config, ValidationWarning};
+use crate::warmup::WarmupManager;
 use crate::types::{
-    AccountStatus, BacktestResult, DataQualityReport, RiskConfig, StrategyParams, Tick,
+    AccountStatus, BacktestResult, DataQualityReport, EngineCostConfig, ExtendedTick, OrderRequest,
+    RiskConfig, StrategyParams, Tick, DIRECTION_BUY, DIRECTION_SELL, ORDER_TYPE_MARKET,
 };
 
+/// Bars per year used to annualize Sharpe and de-annualize risk-free rates.
+const BARS_PER_YEAR: f64 = 252.0;
+
+/// Session under which `checkpoint()` stores the `trades()` order log.
+fn order_log_session(session: &str) -> String {
+    format!("{}/orders", session)
+}
+
+/// Warmup bars for a run: the configured warmup, but at least the longest MA period.
+fn warmup_period(params: &StrategyParams) -> i32 {
+    params
+        .warmup_bars
+        .max(params.long_ma_period)
+        .max(params.short_ma_period)
+}
+
+/// Milliseconds per day for session time-of-day calculations.
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Client order ID tagging orders that flatten positions at the session end.
+pub const SESSION_CLOSE_CLIENT_ID: u64 = u64::MAX;
+
+/// Longest single sleep in realtime replay before re-checking for cancellation.
+const REALTIME_CANCEL_POLL: Duration = Duration::from_millis(20);
+
+/// Number of recent ticks kept for re-warming indicators after a parameter update.
+pub const RECENT_TICK_CAPACITY: usize = 512;
+
+/// Callback invoked once per run when the warmup period ends.
+pub type WarmupCompleteCallback = extern "C" fn(first_tradable_bar: u64);
+
+/// Global warmup-complete callback (null = none).
+///
+/// SAFETY: The pointer is either null or a valid `WarmupCompleteCallback`.
+static WARMUP_COMPLETE_CALLBACK: AtomicPtr<()> = AtomicPtr::new(std::ptr::null_mut());
+
+/// Set the callback invoked when the engine's warmup period ends.
+///
+/// # Safety
+/// The callback must remain valid until it is cleared.
+#[no_mangle]
+pub unsafe extern "C" fn set_warmup_complete_callback(callback: WarmupCompleteCallback) -> i32 {
+    WARMUP_COMPLETE_CALLBACK.store(callback as *mut (), AtomicOrdering::SeqCst);
+    crate::ffi::ERR_SUCCESS
+}
+
+/// Clear the warmup-complete callback.
+#[no_mangle]
+pub extern "C" fn clear_warmup_complete_callback() -> i32 {
+    WARMUP_COMPLETE_CALLBACK.store(std::ptr::null_mut(), AtomicOrdering::SeqCst);
+    crate::ffi::ERR_SUCCESS
+}
+
+/// Invoke the warmup-complete callback, if one is set.
+fn notify_warmup_complete(first_tradable_bar: u64) {
+    let ptr = WARMUP_COMPLETE_CALLBACK.load(AtomicOrdering::SeqCst);
+    if !ptr.is_null() {
+        // SAFETY: Only valid WarmupCompleteCallback pointers are stored
+        let callback: WarmupCompleteCallback = unsafe { std::mem::transmute(ptr) };
+        callback(first_tradable_bar);
+    }
+}
+
+/// Open quantity and its accumulated entry cost, for trade attribution.
+#[derive(Debug, Clone, Copy, Default)]
+struct OpenLot {
+    /// Signed open quantity (positive = long)
+    quantity: f64,
+    /// Sum of entry price * quantity over the open quantity
+    cost: f64,
+    /// Entry commission not yet attributed to a closing trade
+    commission: f64,
+    /// Why the open quantity was entered
+    entry_reason: EntryReason,
+    /// Stop distance fixed at entry by an ATR stop (0 = none)
+    stop_distance: f64,
+    /// Ticks seen since the lot was opened from flat
+    bars_held: usize,
+}
+
+/// How the engine sizes the strategy's orders.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SizingMode {
+    /// Trade `StrategyParams::position_size` units per order
+    #[default]
+    Fixed,
+    /// Size entries to `equity * target_exposure / price`, so gross exposure
+    /// stays a constant fraction of current equity
+    EquityExposure {
+        /// Target gross exposure as a fraction of equity (e.g. 0.5 = 50%)
+        target_exposure: f64,
+    },
+}
+
+/// How the protective stop is placed relative to the entry price.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StopMode {
+    /// `StrategyParams::stop_loss_pct` from the average entry price
+    #[default]
+    Percent,
+    /// `multiplier` times the ATR as of the entry bar, over `atr_period` bars
+    AtrMultiple {
+        /// ATR lookback in bars
+        atr_period: usize,
+        /// Stop distance in ATRs
+        multiplier: f64,
+    },
+}
+
+/// Online walk-forward settings for `BacktestEngine::with_adaptive`.
+#[derive(Debug, Clone)]
+pub struct AdaptiveConfig {
+    /// Re-optimize before every bar whose index is a multiple of this (0 = disabled)
+    pub reoptimize_every_bars: usize,
+    /// Number of trailing bars each grid search runs on
+    pub lookback_bars: usize,
+    /// Parameter grid searched at each re-optimization
+    pub param_grid: ParameterRange,
+}
+
 /// Backtest Engine for running strategy simulations.
 ///
 /// Integrates all components:
@@ -26,30 +159,34 @@ use crate::types::{
 /// - Account and equity tracking
 #[derive(Debug)]
 pub struct BacktestEngine {
-    /// Strategy parameters (kept for potential future use in parameter reporting)
-    #[allow(dead_code)]
+    /// Strategy parameters
     params: StrategyParams,
-    /// Risk configuration (kept for potential future use in config reporting)
-    #[allow(dead_code)]
+    /// Risk configuration (used for config validation)
     risk_config: RiskConfig,
     /// Account balance (using Decimal for precision)
     balance: Decimal,
     /// Initial balance for PnL calculation
     initial_balance: Decimal,
-    /// Strategy instance
-    strategy: DualMAStrategy,
+    /// Strategy instance (DualMA unless replaced with `with_strategy`)
+    strategy: Box<dyn Strategy>,
     /// Risk manager
     risk_manager: RiskManager,
     /// Gateway for order execution
     gateway: SimulatedGateway,
     /// Loaded tick data
     ticks: Vec<Tick>,
+    /// Quote and open-interest data aligned with `ticks` (empty if the file had none)
+    extended_ticks: Vec<ExtendedTick>,
     /// Data quality report
     data_report: Option<DataQualityReport>,
     /// Current tick index
     current_index: usize,
     /// Equity curve (for tracking performance)
     equity_curve: Vec<f64>,
+    /// Whether to record equity at ticks that produce fills
+    record_trade_marked_equity: bool,
+    /// Equity after each tick that produced fills, as (timestamp, equity)
+    trade_marked_equity: Vec<(i64, f64)>,
     /// Peak equity for drawdown calculation
     peak_equity: Decimal,
     /// Trading symbol
@@ -62,6 +199,64 @@ pub struct BacktestEngine {
     winning_trades: i32,
     /// Losing trades
     losing_trades: i32,
+    /// Equity floor below which the run is halted and flattened (0 = disabled)
+    min_equity: f64,
+    /// Ring buffer of recent ticks for re-warming a rebuilt strategy
+    recent_ticks: VecDeque<Tick>,
+    /// Bars during which new entries are suppressed after a trade (0 = disabled)
+    min_bars_between_trades: usize,
+    /// Bars processed since the last executed order (None = no trade yet)
+    bars_since_trade: Option<usize>,
+    /// Constant annual risk-free rate used when no series is set
+    risk_free_rate: f64,
+    /// Time-varying annual risk-free rates as (timestamp, rate), sorted by timestamp
+    risk_free_series: Vec<(i64, f64)>,
+    /// Fills produced by the most recently processed tick
+    last_fills: Vec<Fill>,
+    /// Position being built up by entry fills
+    open_lot: OpenLot,
+    /// Closing trades of the current run with PnL and costs attributed
+    trade_records: Vec<TradeRecord>,
+    /// One record per strategy order executed in the current run
+    trades: Vec<TradeRecord>,
+    /// Whether stop loss / take profit exits from the strategy params are applied
+    protective_exits: bool,
+    /// Stop placement used by the protective exits
+    stop_mode: StopMode,
+    /// Ticks after which an open position is closed (0 = disabled)
+    max_holding_bars: usize,
+    /// ATR over tick prices for `StopMode::AtrMultiple` (None = other modes)
+    atr: Option<AtrIndicator>,
+    /// ATR as of the latest tick (None until a full period has been seen)
+    last_atr: Option<f64>,
+    /// Early termination flag for the current run
+    blown_up: i32,
+    /// Bar index at which the current run halted (-1 if not halted)
+    stopped_at_bar: i32,
+    /// Timestamp of the first order accepted by the gateway in the current run
+    first_trade_timestamp: Option<i64>,
+    /// Cancellation flag for realtime replay
+    realtime_cancel: Arc<AtomicBool>,
+    /// Bollinger regime gate applied to the strategy's orders (None = disabled)
+    regime_filter: Option<RegimeFilter>,
+    /// Decimal places for reported results (None = full precision)
+    display_decimals: Option<u32>,
+    /// Session end as milliseconds after local midnight (None = disabled)
+    session_end_ms_of_day: Option<i64>,
+    /// Offset from UTC to the session's local time in milliseconds
+    session_utc_offset_ms: i64,
+    /// Index of the session the previous tick belonged to
+    last_session_index: Option<i64>,
+    /// Warmup period during which the strategy sees ticks but cannot trade
+    warmup: WarmupManager,
+    /// Whether the warmup-complete callback has fired in the current run
+    warmup_notified: bool,
+    /// Periodic re-optimization during `run()` (None = disabled)
+    adaptive: Option<AdaptiveConfig>,
+    /// Order sizing applied to the strategy's orders
+    sizing: SizingMode,
+    /// Parameter swaps made by re-optimization in the current run, as (bar, params)
+    adaptive_history: Vec<(usize, StrategyParams)>,
 }
 
 impl BacktestEngine {
@@ -74,19 +269,51 @@ impl BacktestEngine {
             risk_config,
             balance: initial_balance,
             initial_balance,
-            strategy: DualMAStrategy::new(params),
+            strategy: Box::new(DualMAStrategy::new(params)),
             risk_manager: RiskManager::new(risk_config),
             gateway: SimulatedGateway::new(100_000.0, 0.001, 0.0001),
             ticks: Vec::new(),
+            extended_ticks: Vec::new(),
             data_report: None,
             current_index: 0,
             equity_curve: Vec::new(),
+            record_trade_marked_equity: false,
+            trade_marked_equity: Vec::new(),
             peak_equity: initial_balance,
             symbol: "BTCUSDT".to_string(),
             initialized: true,
             total_trades: 0,
             winning_trades: 0,
             losing_trades: 0,
+            min_equity: 0.0,
+            recent_ticks: VecDeque::with_capacity(RECENT_TICK_CAPACITY),
+            min_bars_between_trades: 0,
+            bars_since_trade: None,
+            risk_free_rate: 0.0,
+            risk_free_series: Vec::new(),
+            last_fills: Vec::new(),
+            open_lot: OpenLot::default(),
+            trade_records: Vec::new(),
+            trades: Vec::new(),
+            protective_exits: false,
+            max_holding_bars: 0,
+            stop_mode: StopMode::Percent,
+            atr: None,
+            last_atr: None,
+            blown_up: 0,
+            stopped_at_bar: -1,
+            first_trade_timestamp: None,
+            realtime_cancel: Arc::new(AtomicBool::new(false)),
+            regime_filter: None,
+            display_decimals: None,
+            session_end_ms_of_day: None,
+            session_utc_offset_ms: 0,
+            last_session_index: None,
+            warmup: WarmupManager::new(warmup_period(&params)),
+            warmup_notified: false,
+            adaptive: None,
+            sizing: SizingMode::Fixed,
+            adaptive_history: Vec::new(),
         }
     }
 
@@ -94,22 +321,296 @@ impl BacktestEngine {
     pub fn with_initial_balance(mut self, balance: f64) -> Self {
         self.balance = Decimal::from_f64(balance).unwrap_or(Decimal::from(100_000));
         self.initial_balance = self.balance;
-        self.gateway = SimulatedGateway::new(balance, 0.001, 0.0001);
+        self.gateway.set_initial_balance(balance);
+        self
+    }
+
+    /// Create engine with a slippage fraction applied to every fill.
+    ///
+    /// Builder form of `set_slippage`; other gateway settings are kept.
+    pub fn with_slippage(mut self, slippage: f64) -> Self {
+        self.set_slippage(slippage);
+        self
+    }
+
+    /// Create engine trading a margin account at `leverage`.
+    ///
+    /// Builder form of `set_margin_mode(true)` and `set_leverage`.
+    pub fn with_leverage(mut self, leverage: f64) -> Self {
+        self.set_margin_mode(true);
+        self.set_leverage(leverage);
+        self
+    }
+
+    /// Create engine with a commission rate applied to every fill.
+    ///
+    /// Builder form of `set_commission`; other gateway settings are kept.
+    pub fn with_commission(mut self, commission_rate: f64) -> Self {
+        self.set_commission(commission_rate);
         self
     }
 
+    /// Create engine with the balance and costs of `config`.
+    pub fn with_cost_config(self, config: EngineCostConfig) -> Self {
+        self.with_initial_balance(config.initial_balance)
+            .with_slippage(config.slippage)
+            .with_commission(config.commission_rate)
+    }
+
     /// Set the trading symbol.
     pub fn with_symbol(mut self, symbol: &str) -> Self {
         self.symbol = symbol.to_string();
         self
     }
 
+    /// Periodically re-optimize parameters on trailing data during `run()`.
+    ///
+    /// Before bar `i` (a positive multiple of `reoptimize_every_bars` with at
+    /// least `lookback_bars` of history), the grid is searched on ticks
+    /// `i - lookback_bars .. i` and the best parameters by Sharpe ratio are
+    /// applied through `update_strategy_params`, so open positions are kept.
+    /// The search never sees bar `i` or later. The engine keeps the last
+    /// chosen parameters after the run.
+    pub fn with_adaptive(mut self, config: AdaptiveConfig) -> Self {
+        self.adaptive = Some(config);
+        self
+    }
+
+    /// Apply the strategy's `stop_loss_pct` and `take_profit_pct` exits.
+    ///
+    /// Before the strategy sees each tick, an open position whose move from
+    /// its average entry price reaches either threshold is flattened at the
+    /// tick price, recording `ExitReason::Stop` or `ExitReason::Target`.
+    /// A threshold of 0 disables that exit.
+    ///
+    /// Takes precedence over `DualMAStrategy::with_protective_exits`: while
+    /// enabled, the built-in strategy's own stop/target exits are switched
+    /// off so a position is never exited twice.
+    pub fn with_protective_exits(mut self, enabled: bool) -> Self {
+        self.protective_exits = enabled;
+        self.disable_strategy_exits();
+        self
+    }
+
+    /// Close positions held for `bars` ticks.
+    ///
+    /// Before the strategy sees each tick, a position opened from flat at
+    /// least `bars` ticks earlier is flattened at the tick price, recording
+    /// `ExitReason::Time`. Stop and target exits on the same tick take
+    /// precedence. 0 disables the time exit.
+    pub fn with_max_holding_bars(mut self, bars: usize) -> Self {
+        self.max_holding_bars = bars;
+        self
+    }
+
+    /// Set how the protective stop is placed.
+    ///
+    /// With `StopMode::AtrMultiple`, the stop distance is `multiplier` times
+    /// the ATR of tick prices (each tick is a bar with high = low = close)
+    /// as of the bar the position is entered, and stays fixed until the
+    /// position is closed. Entries before the ATR has `atr_period` bars get
+    /// no stop. Stops only apply with `with_protective_exits(true)`.
+    pub fn with_stop_mode(mut self, mode: StopMode) -> Self {
+        self.stop_mode = mode;
+        self.atr = match mode {
+            StopMode::AtrMultiple { atr_period, .. } => AtrIndicator::new(atr_period),
+            StopMode::Percent => None,
+        };
+        self
+    }
+
+    /// Set the minimum equity floor.
+    ///
+    /// When equity falls below this value, `run()` flattens open positions
+    /// and stops processing further ticks. A value of 0 disables the floor.
+    pub fn with_min_equity(mut self, min_equity: f64) -> Self {
+        self.min_equity = min_equity.max(0.0);
+        self
+    }
+
+    /// Limit the absolute position in `symbol`, overriding `max_position_size`.
+    ///
+    /// Entries that would take the position past the limit are rejected;
+    /// see `RiskManager::with_symbol_limit`.
+    pub fn with_symbol_limit(mut self, symbol: &str, max_position: f64) -> Self {
+        self.risk_manager.set_symbol_limit(symbol, max_position);
+        self
+    }
+
+    /// Only act on crossovers in the given Bollinger band regime.
+    ///
+    /// See `DualMAStrategy::with_regime_filter`. The filter is kept when
+    /// strategy parameters are updated.
+    pub fn with_regime_filter(mut self, filter: RegimeFilter) -> Self {
+        self.regime_filter = Some(filter);
+        if self.dual_ma_strategy().is_some() {
+            self.strategy = Box::new(DualMAStrategy::new(self.params).with_regime_filter(filter));
+        }
+        self
+    }
+
+    /// Run a custom strategy instead of the default DualMA.
+    ///
+    /// `StrategyParams` still set the warmup length, `position_size` for
+    /// exits and the protective exit thresholds. The regime filter and
+    /// `update_strategy_params` only apply to the built-in DualMA strategy.
+    /// A DualMA strategy's own protective exits are switched off if the
+    /// engine's are enabled; see `with_protective_exits`.
+    pub fn with_strategy(mut self, strategy: Box<dyn Strategy>) -> Self {
+        self.strategy = strategy;
+        self.disable_strategy_exits();
+        self
+    }
+
+    /// Switch off the DualMA strategy's exits while the engine's are enabled.
+    fn disable_strategy_exits(&mut self) {
+        if !self.protective_exits {
+            return;
+        }
+        if let Some(strategy) = (self.strategy.as_mut() as &mut dyn Any).downcast_mut::<DualMAStrategy>() {
+            strategy.set_protective_exits(false);
+        }
+    }
+
+    /// Get the built-in DualMA strategy, or None if a custom one is set.
+    pub fn dual_ma_strategy(&self) -> Option<&DualMAStrategy> {
+        (self.strategy.as_ref() as &dyn Any).downcast_ref()
+    }
+
+    /// Round reported results to a number of decimal places.
+    ///
+    /// Only the returned `BacktestResult` is rounded; accounting, the equity
+    /// curve and metric calculations keep full precision.
+    pub fn with_display_decimals(mut self, decimals: u32) -> Self {
+        self.display_decimals = Some(decimals);
+        self
+    }
+
+    /// Record equity at fill events.
+    ///
+    /// When enabled, every tick that produces fills adds a
+    /// `(timestamp, equity)` point taken right after the fills, available
+    /// from `trade_marked_equity()`. The per-tick equity curve is kept as well.
+    pub fn with_trade_marked_equity(mut self, enabled: bool) -> Self {
+        self.record_trade_marked_equity = enabled;
+        self
+    }
+
+    /// Set the trade cooldown in bars.
+    ///
+    /// After any order executes, entry signals are ignored until this many
+    /// bars have been processed. Orders that reduce an open position are
+    /// always allowed. A value of 0 disables the cooldown.
+    pub fn with_min_bars_between_trades(mut self, bars: usize) -> Self {
+        self.min_bars_between_trades = bars;
+        self
+    }
+
+    /// Set how the strategy's orders are sized.
+    ///
+    /// With `SizingMode::EquityExposure`, each entry's quantity is computed
+    /// from the gateway's equity at the signal tick, marked at that tick's
+    /// price, and exits close the whole open position. Resized orders go
+    /// through the usual risk checks, so `max_position_size` and
+    /// `max_order_value` still cap them.
+    pub fn with_sizing(mut self, sizing: SizingMode) -> Self {
+        self.sizing = sizing;
+        self
+    }
+
+    /// Flatten all positions at a daily session end.
+    ///
+    /// `ms_of_day` is the session end in milliseconds after local midnight,
+    /// and `utc_offset_ms` converts tick timestamps (UTC epoch-millis) to local
+    /// time. The first tick at or past each session end closes open positions
+    /// with market orders tagged `SESSION_CLOSE_CLIENT_ID` before the strategy
+    /// sees the tick, then re-baselines the risk manager's daily loss limit at
+    /// the flat equity. Without a session end, the daily loss is measured from
+    /// the start of the run.
+    pub fn with_session_end(mut self, ms_of_day: i64, utc_offset_ms: i64) -> Self {
+        self.session_end_ms_of_day = Some(ms_of_day.rem_euclid(MS_PER_DAY));
+        self.session_utc_offset_ms = utc_offset_ms;
+        self
+    }
+
+    /// Set a constant annual risk-free rate for the Sharpe ratio.
+    pub fn with_risk_free_rate(mut self, rate: f64) -> Self {
+        self.risk_free_rate = rate;
+        self
+    }
+
+    /// Set a time-varying annual risk-free rate series of (timestamp, rate).
+    ///
+    /// Each bar uses the latest entry at or before its timestamp; bars before
+    /// the first entry use the first rate. An empty series falls back to the
+    /// constant rate.
+    pub fn with_risk_free_series(mut self, mut series: Vec<(i64, f64)>) -> Self {
+        series.sort_by_key(|&(timestamp, _)| timestamp);
+        self.risk_free_series = series;
+        self
+    }
+
+    /// Set the slippage fraction used by the gateway.
+    ///
+    /// Reconfigures the existing gateway in place, so it can be changed
+    /// between `run()` calls for cost sensitivity sweeps.
+    pub fn set_slippage(&mut self, slippage: f64) {
+        self.gateway.set_slippage(slippage);
+    }
+
+    /// Set the commission rate fraction used by the gateway.
+    pub fn set_commission(&mut self, commission_rate: f64) {
+        self.gateway.set_commission_rate(commission_rate);
+    }
+
+    /// Charge commission from a volume-tiered schedule (None = flat rate).
+    ///
+    /// Session volume restarts with each run.
+    pub fn set_volume_tiered_fee(&mut self, fee: Option<VolumeTieredFee>) {
+        self.gateway.set_volume_tiered_fee(fee);
+    }
+
+    /// Trade a leveraged margin account instead of a cash account.
+    ///
+    /// See `SimulatedGateway::set_margin_mode`. The risk manager's capital
+    /// check uses the leverage from `set_leverage` while margin mode is on.
+    pub fn set_margin_mode(&mut self, margin_mode: bool) {
+        self.gateway.set_margin_mode(margin_mode);
+        self.sync_risk_leverage();
+    }
+
+    /// Set the margin mode leverage. Values below 1 are treated as 1.
+    pub fn set_leverage(&mut self, leverage: f64) {
+        self.gateway.set_leverage(leverage);
+        self.sync_risk_leverage();
+    }
+
+    /// Give the risk manager the gateway's leverage, or 1 for a cash account.
+    fn sync_risk_leverage(&mut self) {
+        let leverage = if self.gateway.margin_mode() { self.gateway.leverage() } else { 1.0 };
+        self.risk_manager.set_leverage(leverage);
+    }
+
+    /// Get the configured slippage fraction.
+    pub fn slippage(&self) -> f64 {
+        self.gateway.slippage()
+    }
+
+    /// Get the configured commission rate fraction.
+    pub fn commission(&self) -> f64 {
+        self.gateway.commission_rate()
+    }
+
     /// Load data from a file.
+    ///
+    /// If the file has bid, ask or open-interest columns, runs pass each
+    /// tick to the strategy through `Strategy::on_extended_tick`.
     pub fn load_data<P: AsRef<Path>>(&mut self, path: P) -> EngineResult<DataQualityReport> {
         let loader = DataLoader::new();
         let result = loader.load_from_file(path)?;
         
         self.ticks = result.ticks;
+        self.extended_ticks = result.extended_ticks;
         self.data_report = Some(result.report);
         self.current_index = 0;
         
@@ -130,6 +631,7 @@ impl BacktestEngine {
         let result = loader.load_from_vectors(timestamps, prices, volumes)?;
         
         self.ticks = result.ticks;
+        self.extended_ticks = result.extended_ticks;
         self.data_report = Some(result.report);
         self.current_index = 0;
         
@@ -139,49 +641,194 @@ impl BacktestEngine {
         Ok(result.report)
     }
 
+    /// Replace the strategy parameters mid-run.
+    ///
+    /// Rebuilds the strategy's indicators with the new periods and re-warms
+    /// them from the recent tick buffer. Signals produced while re-warming are
+    /// discarded, so the swap itself never trades. Gateway positions, balance
+    /// and the equity curve are left untouched. Fails if a custom strategy
+    /// is set.
+    pub fn update_strategy_params(&mut self, params: StrategyParams) -> EngineResult<()> {
+        let Some(current) = self.dual_ma_strategy() else {
+            return Err(EngineError::invalid_param(
+                "strategy",
+                "parameters can only be updated on the built-in DualMA strategy",
+            ));
+        };
+        let current_position = current.current_position();
+        if params.short_ma_period <= 0 {
+            return Err(EngineError::invalid_param("short_ma_period", "must be positive"));
+        }
+        if params.long_ma_period <= 0 {
+            return Err(EngineError::invalid_param("long_ma_period", "must be positive"));
+        }
+        if MaType::from_code(params.short_ma_type).is_none() {
+            return Err(EngineError::invalid_param("short_ma_type", "unknown MA type"));
+        }
+        if MaType::from_code(params.long_ma_type).is_none() {
+            return Err(EngineError::invalid_param("long_ma_type", "unknown MA type"));
+        }
+
+        let mut strategy = DualMAStrategy::new(params);
+        if let Some(filter) = self.regime_filter {
+            strategy = strategy.with_regime_filter(filter);
+        }
+        for tick in &self.recent_ticks {
+            strategy.on_tick(tick);
+        }
+        strategy.set_current_position(current_position);
+
+        self.strategy = Box::new(strategy);
+        self.params = params;
+        Ok(())
+    }
+
+    /// Get the current strategy parameters.
+    pub fn strategy_params(&self) -> StrategyParams {
+        self.params
+    }
+
+    /// Check the configuration against the loaded data without running.
+    ///
+    /// Besides `validate_config`, flags a first order that would already
+    /// exceed `max_order_value` at the opening price.
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let mut findings = validate_config(&self.params, &self.risk_config, self.ticks.len());
+
+        if let Some(first) = self.ticks.first() {
+            let order_value = self.params.position_size * first.price;
+            if self.risk_config.max_order_value > 0.0 && order_value > self.risk_config.max_order_value {
+                findings.push(ValidationWarning::error(
+                    "max_order_value",
+                    format!(
+                        "order value {:.2} at the first price exceeds max order value {:.2}",
+                        order_value, self.risk_config.max_order_value
+                    ),
+                ));
+            }
+        }
+
+        findings
+    }
+
     /// Process a single tick.
     pub fn process_tick(&mut self, tick: &Tick) -> EngineResult<Option<Signal>> {
+        self.process_tick_with(tick, None)
+    }
+
+    /// Process a single tick carrying quote and open-interest data.
+    ///
+    /// Same as `process_tick`, except that the strategy sees the tick
+    /// through `Strategy::on_extended_tick`.
+    pub fn process_extended_tick(&mut self, tick: &ExtendedTick) -> EngineResult<Option<Signal>> {
+        self.process_tick_with(&tick.tick, Some(tick))
+    }
+
+    fn process_tick_with(
+        &mut self,
+        tick: &Tick,
+        extended: Option<&ExtendedTick>,
+    ) -> EngineResult<Option<Signal>> {
         if !self.initialized {
             return Err(EngineError::EngineNotInitialized);
         }
 
-        // Update gateway price
-        self.gateway.update_price(&self.symbol, tick.price);
-        self.gateway.set_timestamp(tick.timestamp);
+        self.last_fills.clear();
+
+        // Update gateway price, volume and timestamp
+        self.gateway.update_tick(&self.symbol, tick);
+
+        // Close positions once the tick crosses a session end
+        if let Some(session_index) = self.session_index(tick.timestamp) {
+            if self.last_session_index.is_some_and(|last| session_index > last) {
+                self.flatten_position(tick.price, SESSION_CLOSE_CLIENT_ID, ExitReason::Session);
+                self.strategy.set_current_position(0);
+                self.risk_manager.reset_session(self.gateway.query_account().equity);
+            }
+            self.last_session_index = Some(session_index);
+        }
+
+        if let Some(atr) = self.atr.as_mut() {
+            self.last_atr = atr.next(tick.price, tick.price, tick.price);
+        }
+
+        if self.open_lot.quantity.abs() > QUANTITY_EPSILON {
+            self.open_lot.bars_held += 1;
+        }
+
+        if self.protective_exits {
+            self.apply_protective_exits(tick.price);
+        }
+
+        if self.max_holding_bars > 0 && self.open_lot.bars_held >= self.max_holding_bars {
+            self.flatten_position(tick.price, 0, ExitReason::Time);
+            self.strategy.set_current_position(0);
+        }
+
+        if self.recent_ticks.len() == RECENT_TICK_CAPACITY {
+            self.recent_ticks.pop_front();
+        }
+        self.recent_ticks.push_back(*tick);
+
+        // Trading is allowed once the warmup bars have all been seen
+        let tradable = self.warmup.is_warmed_up();
+        self.warmup.tick(tick.timestamp);
+        if tradable && !self.warmup_notified {
+            self.warmup_notified = true;
+            notify_warmup_complete(self.current_index as u64);
+        }
 
         // Get strategy signal
-        let signal = self.strategy.on_tick(tick);
+        let signal = match extended {
+            Some(extended) => self.strategy.on_extended_tick(extended),
+            None => self.strategy.on_tick(tick),
+        };
+
+        if let Some(bars) = self.bars_since_trade.as_mut() {
+            *bars += 1;
+        }
+
+        // Strategy exits record why the strategy closed the position
+        let exit_reason = if signal == Signal::Exit {
+            self.strategy.exit_reason()
+        } else {
+            ExitReason::Signal
+        };
 
         // If signal, try to execute order
-        if signal != Signal::None {
-            if let Some(order) = self.strategy.generate_order(signal, &self.symbol, tick.price) {
+        if signal != Signal::None && tradable {
+            if let Some(order) = self
+                .strategy
+                .generate_order(signal, &self.symbol, tick.price)
+                .filter(|order| !self.in_cooldown() || self.is_exit(order))
+                .map(|order| self.size_order(order, tick.price))
+            {
                 // Get current account status for risk check
                 let account = self.get_account_status();
 
-                // Risk check
-                match self.risk_manager.check(&order, &account, tick.price) {
+                // Risk check against the real position in the order's symbol
+                let positions = self.gateway.query_positions().unwrap_or_default();
+                let current_qty = positions
+                    .iter()
+                    .find(|p| p.symbol_str() == order.symbol_str())
+                    .map_or(0.0, |p| p.quantity);
+                let risk_check = self
+                    .risk_manager
+                    .check_with_position(&order, &account, tick.price, current_qty)
+                    .and_then(|()| self.risk_manager.check_max_positions(&order, &positions));
+                match risk_check {
                     Ok(()) => {
                         // Execute order through gateway
                         match self.gateway.submit_order(&order, tick.price) {
                             Ok(_order_id) => {
-                                self.total_trades += 1;
-                                
-                                // Check fills for PnL tracking
+                                self.bars_since_trade = Some(0);
+
                                 let fills = self.gateway.get_fills();
-                                for fill in fills {
-                                    // Simple win/loss tracking based on direction
-                                    // In a real system, this would track actual PnL
-                                    if fill.direction == crate::types::DIRECTION_SELL {
-                                        // Closing a position - check if profitable
-                                        if let Some(pos) = self.gateway.query_position(&self.symbol) {
-                                            if pos.realized_pnl > 0.0 {
-                                                self.winning_trades += 1;
-                                            } else if pos.realized_pnl < 0.0 {
-                                                self.losing_trades += 1;
-                                            }
-                                        }
-                                    }
-                                }
+                                let closed_before = self.trade_records.len();
+                                self.attribute_fills(&fills, exit_reason);
+                                self.record_trade(tick.timestamp, order.direction, &fills, closed_before);
+                                self.count_trade(self.trades.len() - 1);
+                                self.last_fills.extend(fills);
                             }
                             Err(_) => {
                                 // Order rejected by gateway
@@ -198,6 +845,7 @@ impl BacktestEngine {
         // Update equity curve
         let account = self.gateway.query_account();
         self.equity_curve.push(account.equity);
+        self.mark_trade_equity(tick.timestamp, account.equity);
 
         // Update balance from gateway
         self.balance = Decimal::from_f64(account.balance).unwrap_or(self.balance);
@@ -216,26 +864,348 @@ impl BacktestEngine {
 
     /// Run the complete backtest.
     pub fn run(&mut self) -> EngineResult<BacktestResult> {
+        self.run_bars(self.ticks.len())
+    }
+
+    /// Run the backtest over the first `bars` loaded ticks only.
+    ///
+    /// Used with `checkpoint()` to stop a run part way; the result covers
+    /// the ticks processed so far.
+    pub fn run_bars(&mut self, bars: usize) -> EngineResult<BacktestResult> {
+        self.begin_run()?;
+        self.continue_run(bars)
+    }
+
+    /// Process ticks from `current_index` up to `end`, halting early if
+    /// equity breaches the floor.
+    fn continue_run(&mut self, end: usize) -> EngineResult<BacktestResult> {
+        let ticks = self.ticks.clone();
+        let end = end.min(ticks.len());
+        for bar in self.current_index..end {
+            self.reoptimize(bar, &ticks[..bar])?;
+            if self.step(&ticks[bar])? {
+                break;
+            }
+        }
+
+        Ok(self.finish_run())
+    }
+
+    /// Save the state of a partial run under `session`.
+    ///
+    /// Stores the balance and the last processed tick's timestamp as an
+    /// account snapshot, the open positions, the closed trades and the
+    /// equity curve (one point per processed tick, which also records the
+    /// index to resume from). The `trades()` order log goes to the companion
+    /// session `<session>/orders`. Any earlier checkpoint of the session is
+    /// replaced in the same transaction, so a failed write leaves it intact.
+    pub fn checkpoint(&self, pm: &PersistenceManager, session: &str) -> EngineResult<()> {
+        let processed = self.equity_curve.len();
+        let Some(last_tick) = processed.checked_sub(1).and_then(|i| self.ticks.get(i)) else {
+            return Err(EngineError::validation("No ticks processed to checkpoint"));
+        };
+
+        let account = self.gateway.query_account();
+        let snapshot = AccountSnapshot {
+            timestamp: last_tick.timestamp,
+            balance: Decimal::from_f64(account.balance).unwrap_or(self.balance),
+            equity: Decimal::from_f64(account.equity).unwrap_or(self.balance),
+            position_count: account.position_count,
+        };
+        let points: Vec<(i64, f64)> = self
+            .ticks
+            .iter()
+            .zip(&self.equity_curve)
+            .map(|(tick, &equity)| (tick.timestamp, equity))
+            .collect();
+
+        pm.transaction(|pm| {
+            pm.clear_session(session)?;
+            pm.save_account_snapshot(&snapshot, session)?;
+            for position in self.gateway.query_positions().unwrap_or_default() {
+                if position.quantity.abs() > QUANTITY_EPSILON {
+                    pm.save_position_struct(&position, session)?;
+                }
+            }
+            pm.save_trades_batch(&self.trade_records, session)?;
+            pm.clear_session(&order_log_session(session))?;
+            pm.save_trades_batch(&self.trades, &order_log_session(session))?;
+            pm.save_equity_curve(session, &points)
+        })
+    }
+
+    /// Restore a `checkpoint()` of `session` and run the remaining ticks.
+    ///
+    /// The same ticks must be loaded as in the checkpointed run. Strategy,
+    /// warmup and ATR state are rebuilt by replaying the ticks before the
+    /// saved index without trading. The `trades()` log is restored and the
+    /// trade counters are rebuilt from it. The cooldown and the entry
+    /// commission of open positions are not persisted, so they restart at
+    /// the resume point.
+    pub fn resume(&mut self, pm: &PersistenceManager, session: &str) -> EngineResult<BacktestResult> {
+        let state = pm.recover_state(session)?;
+        let Some(snapshot) = state.snapshot else {
+            return Err(EngineError::validation(format!("No checkpoint for session {}", session)));
+        };
+        let curve = pm.load_equity_curve(session)?;
+        let index = curve.len();
+        if index == 0 || self.ticks.get(index - 1).map(|t| t.timestamp) != Some(snapshot.timestamp) {
+            return Err(EngineError::validation(format!(
+                "Checkpoint for session {} does not match the loaded ticks",
+                session
+            )));
+        }
+
+        self.begin_run()?;
+        let ticks = self.ticks.clone();
+        for (i, tick) in ticks[..index].iter().enumerate() {
+            let extended = self.extended_tick_at(i, tick);
+            self.replay_tick(tick, extended);
+        }
+
+        self.balance = snapshot.balance;
+        self.gateway.set_balance(snapshot.balance.to_f64().unwrap_or(0.0));
+        self.open_lot = OpenLot::default();
+        for position in &state.positions {
+            self.gateway
+                .restore_position(&position.symbol, position.quantity, position.average_price)
+                .map_err(|e| EngineError::internal(format!("Failed to restore position: {}", e)))?;
+            if position.symbol == self.symbol {
+                self.open_lot.quantity = position.quantity;
+                self.open_lot.cost = position.quantity * position.average_price;
+            }
+        }
+        self.trade_records = state.trades;
+        self.trades = pm.get_trades(&order_log_session(session))?;
+        for index in 0..self.trades.len() {
+            self.count_trade(index);
+        }
+
+        for &(_, equity) in &curve {
+            self.equity_curve.push(equity);
+            self.risk_manager.update_equity(equity);
+            let equity = Decimal::from_f64(equity).unwrap_or(self.peak_equity);
+            if equity > self.peak_equity {
+                self.peak_equity = equity;
+            }
+        }
+        self.current_index = index;
+
+        self.continue_run(ticks.len())
+    }
+
+    /// Feed a tick processed before a checkpoint back into the indicator,
+    /// strategy and warmup state without trading.
+    fn replay_tick(&mut self, tick: &Tick, extended: Option<ExtendedTick>) {
+        self.gateway.update_tick(&self.symbol, tick);
+        if let Some(session_index) = self.session_index(tick.timestamp) {
+            if self.last_session_index.is_some_and(|last| session_index > last) {
+                self.strategy.set_current_position(0);
+            }
+            self.last_session_index = Some(session_index);
+        }
+        if let Some(atr) = self.atr.as_mut() {
+            self.last_atr = atr.next(tick.price, tick.price, tick.price);
+        }
+        if self.recent_ticks.len() == RECENT_TICK_CAPACITY {
+            self.recent_ticks.pop_front();
+        }
+        self.recent_ticks.push_back(*tick);
+        if self.warmup.is_warmed_up() {
+            self.warmup_notified = true;
+        }
+        self.warmup.tick(tick.timestamp);
+        match extended {
+            Some(extended) => self.strategy.on_extended_tick(&extended),
+            None => self.strategy.on_tick(tick),
+        };
+    }
+
+    /// Extended data for the loaded tick at `index`, if the file had any.
+    fn extended_tick_at(&self, index: usize, tick: &Tick) -> Option<ExtendedTick> {
+        self.extended_ticks.get(index).copied().filter(|extended| extended.tick == *tick)
+    }
+
+    /// Parameter swaps made by adaptive re-optimization in the last run.
+    ///
+    /// Each entry is the bar before which the new parameters took effect.
+    pub fn adaptive_history(&self) -> &[(usize, StrategyParams)] {
+        &self.adaptive_history
+    }
+
+    /// Re-optimize on `history` (the ticks before `bar`) if one is due.
+    fn reoptimize(&mut self, bar: usize, history: &[Tick]) -> EngineResult<()> {
+        let Some(config) = &self.adaptive else {
+            return Ok(());
+        };
+        let every = config.reoptimize_every_bars;
+        let lookback = config.lookback_bars;
+        if every == 0 || lookback == 0 || bar == 0 || !bar.is_multiple_of(every) || history.len() < lookback {
+            return Ok(());
+        }
+
+        let window = &history[history.len() - lookback..];
+        let best = Optimizer::new(self.risk_config)
+            .with_initial_balance(self.initial_balance.to_f64().unwrap_or(100_000.0))
+            .with_symbol(&self.symbol)
+            .optimize_window(window, &config.param_grid, &self.params);
+
+        if let Some(best) = best {
+            if best.params != self.params {
+                self.update_strategy_params(best.params)?;
+                self.adaptive_history.push((bar, best.params));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replay the loaded ticks paced to their timestamps, publishing events.
+    ///
+    /// Sleeps between ticks for the timestamp delta (milliseconds) divided by
+    /// `speed_multiplier`; a multiplier of 0 or less replays at full speed.
+    /// Each tick is published as a tick event, followed by an order update
+    /// for every fill it produced. Playback stops early when the handle from
+    /// `realtime_cancel_handle()` is set, returning results up to that point;
+    /// a cancel set before the call stops it before the first tick. The run
+    /// it stops clears the handle. Adaptive re-optimization runs on the same
+    /// bars as in `run()`, so apart from pacing the results match it.
+    pub fn run_realtime(
+        &mut self,
+        speed_multiplier: f64,
+        bus: &SharedEventBus,
+    ) -> EngineResult<BacktestResult> {
+        self.begin_run()?;
+
+        let ticks = self.ticks.clone();
+        let mut prev_timestamp: Option<i64> = None;
+        for (bar, tick) in ticks.iter().enumerate() {
+            if let Some(prev) = prev_timestamp {
+                if speed_multiplier > 0.0 {
+                    let delay_ms = (tick.timestamp - prev).max(0) as f64 / speed_multiplier;
+                    self.sleep_unless_cancelled(Duration::from_secs_f64(delay_ms / 1000.0));
+                }
+            }
+            prev_timestamp = Some(tick.timestamp);
+
+            if self.realtime_cancel.load(AtomicOrdering::SeqCst) {
+                break;
+            }
+
+            self.reoptimize(bar, &ticks[..bar])?;
+            let halted = self.step(tick)?;
+
+            if let Ok(mut bus) = bus.lock() {
+                bus.publish(Event::tick(*tick));
+                for fill in &self.last_fills {
+                    bus.publish(Event::order_update(
+                        fill.order_id,
+                        OrderStatus::Filled,
+                        fill.quantity,
+                        fill.price,
+                    ));
+                }
+            }
+
+            if halted {
+                break;
+            }
+        }
+
+        self.realtime_cancel.store(false, AtomicOrdering::SeqCst);
+        Ok(self.finish_run())
+    }
+
+    /// Get a handle that stops `run_realtime()` playback when set to true.
+    pub fn realtime_cancel_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.realtime_cancel)
+    }
+
+    /// Sleep for `duration`, waking early if playback is cancelled.
+    fn sleep_unless_cancelled(&self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        while !self.realtime_cancel.load(AtomicOrdering::SeqCst) {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            std::thread::sleep((deadline - now).min(REALTIME_CANCEL_POLL));
+        }
+    }
+
+    /// Reset run state before processing ticks.
+    fn begin_run(&mut self) -> EngineResult<()> {
         if self.ticks.is_empty() {
             return Err(EngineError::validation("No data loaded"));
         }
 
-        // Reset state
         self.current_index = 0;
         self.equity_curve.clear();
+        self.trade_marked_equity.clear();
+        self.recent_ticks.clear();
+        self.bars_since_trade = None;
         self.strategy.reset();
+        self.gateway.reset();
+        self.balance = self.initial_balance;
+        self.peak_equity = self.initial_balance;
+        self.risk_manager.initialize(self.initial_balance.to_f64().unwrap_or(100_000.0));
+        self.risk_manager.clear_throttle_history();
         self.total_trades = 0;
         self.winning_trades = 0;
         self.losing_trades = 0;
+        self.blown_up = 0;
+        self.stopped_at_bar = -1;
+        self.first_trade_timestamp = None;
+        self.last_session_index = None;
+        self.adaptive_history.clear();
+        self.warmup = WarmupManager::new(warmup_period(&self.params));
+        self.warmup_notified = false;
+        self.open_lot = OpenLot::default();
+        self.trade_records.clear();
+        self.trades.clear();
+        if let StopMode::AtrMultiple { atr_period, .. } = self.stop_mode {
+            self.atr = AtrIndicator::new(atr_period);
+        }
+        self.last_atr = None;
+        Ok(())
+    }
 
-        // Process all ticks
-        let ticks = self.ticks.clone();
-        for tick in &ticks {
-            self.process_tick(tick)?;
-            self.current_index += 1;
+    /// Record the post-fill equity if the current tick produced fills.
+    ///
+    /// Repeated calls for the same tick update its point in place.
+    fn mark_trade_equity(&mut self, timestamp: i64, equity: f64) {
+        if !self.record_trade_marked_equity || self.last_fills.is_empty() {
+            return;
+        }
+        match self.trade_marked_equity.last_mut() {
+            Some(last) if last.0 == timestamp => last.1 = equity,
+            _ => self.trade_marked_equity.push((timestamp, equity)),
+        }
+    }
+
+    /// Process one tick of a run. Returns true if the run must halt.
+    fn step(&mut self, tick: &Tick) -> EngineResult<bool> {
+        let extended = self.extended_tick_at(self.current_index, tick);
+        self.process_tick_with(tick, extended.as_ref())?;
+
+        if self.min_equity > 0.0 && self.gateway.query_account().equity < self.min_equity {
+            self.flatten_position(tick.price, 0, ExitReason::Emergency);
+            let account = self.gateway.query_account();
+            if let Some(last) = self.equity_curve.last_mut() {
+                *last = account.equity;
+            }
+            self.mark_trade_equity(tick.timestamp, account.equity);
+            self.balance = Decimal::from_f64(account.balance).unwrap_or(self.balance);
+            self.blown_up = 1;
+            self.stopped_at_bar = self.current_index as i32;
+            return Ok(true);
         }
 
-        // Calculate results
+        self.current_index += 1;
+        Ok(false)
+    }
+
+    /// Compute the result of the ticks processed so far.
+    fn finish_run(&self) -> BacktestResult {
         let final_account = self.gateway.query_account();
         let final_equity = final_account.equity;
         let initial = self.initial_balance.to_f64().unwrap_or(100_000.0);
@@ -247,7 +1217,14 @@ impl BacktestEngine {
         // Calculate Sharpe ratio (simplified)
         let sharpe_ratio = self.calculate_sharpe_ratio();
 
-        Ok(BacktestResult {
+        // Benchmark over the same ticks the strategy processed
+        let last_bar = self.equity_curve.len().saturating_sub(1);
+        let buy_and_hold_return_pct = self.calculate_buy_and_hold_return(0, last_bar);
+        // Fair benchmark: hold only from the first bar the strategy could trade
+        let actual_start_bar = self.warmup.actual_start_bar();
+        let benchmark_return_pct = self.calculate_buy_and_hold_return(actual_start_bar, last_bar);
+
+        let result = BacktestResult {
             final_equity,
             total_return_pct,
             max_drawdown_pct,
@@ -255,9 +1232,19 @@ impl BacktestEngine {
             total_trades: self.total_trades,
             winning_trades: self.winning_trades,
             losing_trades: self.losing_trades,
-            actual_start_bar: 0, // TODO: Integrate with WarmupManager
-            first_trade_timestamp: 0, // TODO: Track first trade timestamp
-        })
+            actual_start_bar: actual_start_bar as i32,
+            first_trade_timestamp: self.first_trade_timestamp.unwrap_or(0),
+            blown_up: self.blown_up,
+            stopped_at_bar: self.stopped_at_bar,
+            buy_and_hold_return_pct,
+            benchmark_return_pct,
+            alpha_pct: total_return_pct - benchmark_return_pct,
+        };
+
+        match self.display_decimals {
+            Some(decimals) => result.rounded(decimals),
+            None => result,
+        }
     }
 
     /// Get current account status.
@@ -270,13 +1257,146 @@ impl BacktestEngine {
         &self.equity_curve
     }
 
-    /// Get the data quality report.
-    pub fn data_report(&self) -> Option<&DataQualityReport> {
-        self.data_report.as_ref()
+    /// Get the equity points recorded at fill events as (timestamp, equity).
+    ///
+    /// Empty unless enabled with `with_trade_marked_equity`.
+    pub fn trade_marked_equity(&self) -> &[(i64, f64)] {
+        &self.trade_marked_equity
     }
 
-    /// Get current tick index.
-    pub fn current_index(&self) -> usize {
+    /// Get the closing trades of the last run.
+    ///
+    /// Each record carries the gross PnL of the closed quantity against its
+    /// average entry price, the entry and exit commission attributable to
+    /// it, `net_pnl = gross_pnl - commission`, and why the position was
+    /// opened and closed.
+    pub fn trade_records(&self) -> &[TradeRecord] {
+        &self.trade_records
+    }
+
+    /// Get every strategy order executed in the last run, one record each.
+    ///
+    /// There is one record per order counted in `total_trades`; orders that
+    /// close a position carry its realized PnL as in `trade_records()`.
+    /// Session, protective and emergency exits appear only in
+    /// `trade_records()`.
+    pub fn trades(&self) -> &[TradeRecord] {
+        &self.trades
+    }
+
+    /// Update the open lot from fills, recording a trade for each close.
+    ///
+    /// Entry commission is attributed to closes pro rata by quantity. A fill
+    /// that reverses the position closes the old side and opens the rest.
+    /// Closes are tagged with `exit`; entries only come from strategy
+    /// signals.
+    fn attribute_fills(&mut self, fills: &[Fill], exit: ExitReason) {
+        let stop_distance = match (self.stop_mode, self.last_atr) {
+            (StopMode::AtrMultiple { multiplier, .. }, Some(atr)) => atr * multiplier,
+            _ => 0.0,
+        };
+
+        for fill in fills {
+            let signed = fill.quantity * fill.direction as f64;
+            let lot = &mut self.open_lot;
+
+            let closing = if lot.quantity * signed < 0.0 {
+                fill.quantity.min(lot.quantity.abs())
+            } else {
+                0.0
+            };
+            let opening = fill.quantity - closing;
+            let exit_commission = fill.commission * closing / fill.quantity;
+
+            if closing > QUANTITY_EPSILON {
+                let fraction = closing / lot.quantity.abs();
+                let entry_price = lot.cost / lot.quantity;
+                let entry_commission = lot.commission * fraction;
+                let gross_pnl = (fill.price - entry_price) * closing * lot.quantity.signum();
+                let entry_reason = lot.entry_reason;
+
+                lot.cost -= lot.cost * fraction;
+                lot.commission -= entry_commission;
+                lot.quantity -= closing * lot.quantity.signum();
+                if lot.quantity.abs() <= QUANTITY_EPSILON {
+                    *lot = OpenLot::default();
+                }
+
+                self.trade_records.push(TradeRecord::closing(
+                    fill.timestamp,
+                    &self.symbol,
+                    fill.direction,
+                    closing,
+                    fill.price,
+                    gross_pnl,
+                    entry_commission + exit_commission,
+                ).with_reasons(entry_reason, exit));
+            }
+
+            if opening > QUANTITY_EPSILON {
+                let lot = &mut self.open_lot;
+                if lot.quantity.abs() <= QUANTITY_EPSILON {
+                    lot.stop_distance = stop_distance;
+                }
+                lot.quantity += opening * fill.direction as f64;
+                lot.cost += fill.price * opening * fill.direction as f64;
+                lot.commission += fill.commission - exit_commission;
+                lot.entry_reason = EntryReason::Signal;
+            }
+        }
+    }
+
+    /// Update the trade counters for the order logged at `index` in `trades`.
+    ///
+    /// An order wins or loses by the net PnL of the lots it closed; opening
+    /// orders count towards `total_trades` only.
+    fn count_trade(&mut self, index: usize) {
+        let trade = &self.trades[index];
+        self.total_trades += 1;
+        self.first_trade_timestamp.get_or_insert(trade.timestamp);
+        if trade.net_pnl > 0.0 {
+            self.winning_trades += 1;
+        } else if trade.net_pnl < 0.0 {
+            self.losing_trades += 1;
+        }
+    }
+
+    /// Log an executed strategy order as one trade.
+    ///
+    /// Quantity and price are the filled total and its volume-weighted
+    /// price (0 if nothing filled). The PnL fields sum the closing trades
+    /// attributed from index `closed_from` on, so opening orders carry zero PnL.
+    fn record_trade(&mut self, timestamp: i64, direction: i32, fills: &[Fill], closed_from: usize) {
+        let quantity: f64 = fills.iter().map(|f| f.quantity).sum();
+        let notional: f64 = fills.iter().map(|f| f.quantity * f.price).sum();
+
+        let closed = &self.trade_records[closed_from..];
+        let mut trade = TradeRecord {
+            timestamp,
+            symbol: self.symbol.clone(),
+            direction,
+            quantity,
+            price: if quantity > 0.0 { notional / quantity } else { 0.0 },
+            ..Default::default()
+        };
+        if let Some(first) = closed.first() {
+            trade.gross_pnl = closed.iter().map(|t| t.gross_pnl).sum();
+            trade.commission = closed.iter().map(|t| t.commission).sum();
+            trade.net_pnl = closed.iter().map(|t| t.net_pnl).sum();
+            trade.pnl = trade.gross_pnl;
+            trade.entry_reason = first.entry_reason;
+            trade.exit_reason = first.exit_reason;
+        }
+        self.trades.push(trade);
+    }
+
+    /// Get the data quality report.
+    pub fn data_report(&self) -> Option<&DataQualityReport> {
+        self.data_report.as_ref()
+    }
+
+    /// Get current tick index.
+    pub fn current_index(&self) -> usize {
         self.current_index
     }
 
@@ -285,26 +1405,150 @@ impl BacktestEngine {
         self.ticks.len()
     }
 
+    /// Whether new entries are currently suppressed by the trade cooldown.
+    fn in_cooldown(&self) -> bool {
+        self.bars_since_trade
+            .is_some_and(|bars| bars < self.min_bars_between_trades)
+    }
+
+    /// Apply the sizing mode to a strategy order.
+    fn size_order(&self, mut order: OrderRequest, price: f64) -> OrderRequest {
+        let SizingMode::EquityExposure { target_exposure } = self.sizing else {
+            return order;
+        };
+
+        if self.is_exit(&order) {
+            if let Some(pos) = self.gateway.query_position(&self.symbol) {
+                order.quantity = pos.quantity.abs();
+            }
+        } else if price > 0.0 {
+            order.quantity = self.gateway.query_account().equity * target_exposure / price;
+        }
+        order
+    }
+
+    /// Whether the order reduces the current position.
+    fn is_exit(&self, order: &OrderRequest) -> bool {
+        match self.gateway.query_position(&self.symbol) {
+            Some(pos) if pos.quantity > QUANTITY_EPSILON => order.direction == DIRECTION_SELL,
+            Some(pos) if pos.quantity < -QUANTITY_EPSILON => order.direction == DIRECTION_BUY,
+            _ => false,
+        }
+    }
+
+    /// Number of session ends between the epoch and a timestamp (epoch-millis),
+    /// or None if no session end is configured.
+    fn session_index(&self, timestamp: i64) -> Option<i64> {
+        let session_end = self.session_end_ms_of_day?;
+        let local_ms = timestamp + self.session_utc_offset_ms;
+        Some((local_ms - session_end).div_euclid(MS_PER_DAY))
+    }
+
+    /// Stop price of the open position under the current stop mode.
+    ///
+    /// None when flat, when the stop is disabled, or when an ATR stop had no
+    /// ATR at entry.
+    pub fn stop_price(&self) -> Option<f64> {
+        let lot = self.open_lot;
+        if lot.quantity.abs() <= QUANTITY_EPSILON {
+            return None;
+        }
+
+        let entry_price = lot.cost / lot.quantity;
+        let distance = match self.stop_mode {
+            StopMode::Percent if self.params.stop_loss_pct > 0.0 => {
+                entry_price * self.params.stop_loss_pct
+            }
+            StopMode::AtrMultiple { .. } if lot.stop_distance > 0.0 => lot.stop_distance,
+            _ => return None,
+        };
+        Some(entry_price - distance * lot.quantity.signum())
+    }
+
+    /// Close the open position if it has hit its stop loss or take profit.
+    fn apply_protective_exits(&mut self, price: f64) {
+        let lot = self.open_lot;
+        if lot.quantity.abs() <= QUANTITY_EPSILON {
+            return;
+        }
+
+        let direction = lot.quantity.signum();
+        let entry_price = lot.cost / lot.quantity;
+        let change = (price - entry_price) / entry_price * direction;
+        let reason = if self.stop_price().is_some_and(|stop| (price - stop) * direction <= 0.0) {
+            ExitReason::Stop
+        } else if self.params.take_profit_pct > 0.0 && change >= self.params.take_profit_pct {
+            ExitReason::Target
+        } else {
+            return;
+        };
+
+        self.flatten_position(price, 0, reason);
+        self.strategy.set_current_position(0);
+    }
+
+    /// Close any open position at the given price, bypassing risk checks.
+    ///
+    /// The closing order carries `client_id`, its fills are added to the
+    /// current tick's fills, and the closing trade records `reason`.
+    fn flatten_position(&mut self, price: f64, client_id: u64, reason: ExitReason) {
+        let quantity = match self.gateway.query_position(&self.symbol) {
+            Some(pos) if pos.quantity.abs() > QUANTITY_EPSILON => pos.quantity,
+            _ => return,
+        };
+
+        let mut order = OrderRequest::with_symbol(&self.symbol);
+        order.quantity = quantity.abs();
+        order.direction = if quantity > 0.0 { DIRECTION_SELL } else { DIRECTION_BUY };
+        order.order_type = ORDER_TYPE_MARKET;
+        order.limit_price = price;
+        order.client_id = client_id;
+
+        if self.gateway.submit_order(&order, price).is_ok() {
+            let fills = self.gateway.get_fills();
+            self.attribute_fills(&fills, reason);
+            self.last_fills.extend(fills);
+        }
+    }
+
     /// Calculate maximum drawdown from equity curve.
     fn calculate_max_drawdown(&self) -> f64 {
-        if self.equity_curve.is_empty() {
-            return 0.0;
+        max_drawdown_pct(&self.equity_curve)
+    }
+
+    /// Number of bars from the maximum drawdown trough back to the prior peak.
+    ///
+    /// Returns `Some(0)` if the equity curve never draws down, and `None` if
+    /// there is no equity curve or the worst drawdown has not recovered by
+    /// the end of the run.
+    pub fn max_drawdown_recovery_bars(&self) -> Option<usize> {
+        let worst = max_drawdown_point(&self.equity_curve)?;
+        if worst.drawdown == 0.0 {
+            return Some(0);
         }
 
-        let mut peak = self.equity_curve[0];
-        let mut max_drawdown = 0.0;
+        self.equity_curve[worst.trough_index..]
+            .iter()
+            .position(|&equity| equity >= worst.peak)
+    }
 
-        for &equity in &self.equity_curve {
-            if equity > peak {
-                peak = equity;
-            }
-            let drawdown = (peak - equity) / peak;
-            if drawdown > max_drawdown {
-                max_drawdown = drawdown;
-            }
+    /// Calculate the buy-and-hold return from `first_bar` to `last_bar` as a percentage.
+    ///
+    /// Invests the full initial balance at the price of `first_bar` and sells
+    /// at the price of `last_bar`, paying the gateway commission rate on both
+    /// legs. Returns 0 if `first_bar` is past `last_bar`.
+    fn calculate_buy_and_hold_return(&self, first_bar: usize, last_bar: usize) -> f64 {
+        if first_bar > last_bar {
+            return 0.0;
         }
+        let (first, last) = match (self.ticks.get(first_bar), self.ticks.get(last_bar)) {
+            (Some(first), Some(last)) if first.price > 0.0 => (first.price, last.price),
+            _ => return 0.0,
+        };
 
-        max_drawdown * 100.0 // Return as percentage
+        let commission = self.gateway.commission_rate();
+        let growth = (last / first) * (1.0 - commission) / (1.0 + commission);
+        (growth - 1.0) * 100.0
     }
 
     /// Calculate Sharpe ratio (simplified version).
@@ -313,36 +1557,99 @@ impl BacktestEngine {
             return 0.0;
         }
 
-        // Calculate returns
-        let mut returns = Vec::with_capacity(self.equity_curve.len() - 1);
-        for i in 1..self.equity_curve.len() {
+        // Calculate returns in excess of the per-bar risk-free rate,
+        // starting after the warmup bars where no trades can fire
+        let start = self.warmup.actual_start_bar().max(1);
+        let mut returns = Vec::with_capacity(self.equity_curve.len().saturating_sub(start));
+        let mut rate_index = 0;
+        for i in start..self.equity_curve.len() {
             let ret = (self.equity_curve[i] - self.equity_curve[i - 1]) / self.equity_curve[i - 1];
-            returns.push(ret);
+            let annual_rate = match self.ticks.get(i) {
+                Some(tick) if !self.risk_free_series.is_empty() => {
+                    // Series and ticks are both sorted, so the index only moves forward
+                    while rate_index + 1 < self.risk_free_series.len()
+                        && self.risk_free_series[rate_index + 1].0 <= tick.timestamp
+                    {
+                        rate_index += 1;
+                    }
+                    self.risk_free_series[rate_index].1
+                }
+                _ => self.risk_free_rate,
+            };
+            returns.push(ret - annual_rate / BARS_PER_YEAR);
         }
 
-        if returns.is_empty() {
-            return 0.0;
-        }
+        annualized_sharpe(&returns)
+    }
+}
 
-        // Calculate mean return
-        let mean_return: f64 = returns.iter().sum::<f64>() / returns.len() as f64;
+/// Worst peak-to-trough drawdown of an equity curve.
+struct DrawdownPoint {
+    /// Drawdown as a fraction of the peak
+    drawdown: f64,
+    /// Running peak before the trough
+    peak: f64,
+    /// Bar index of the trough
+    trough_index: usize,
+}
 
-        // Calculate standard deviation
-        let variance: f64 = returns
-            .iter()
-            .map(|r| (r - mean_return).powi(2))
-            .sum::<f64>()
-            / returns.len() as f64;
-        let std_dev = variance.sqrt();
+/// Find the worst drawdown of an equity curve, or None if it is empty.
+fn max_drawdown_point(equity_curve: &[f64]) -> Option<DrawdownPoint> {
+    let &first = equity_curve.first()?;
 
-        if std_dev == 0.0 {
-            return 0.0;
+    let mut peak = first;
+    let mut worst = DrawdownPoint {
+        drawdown: 0.0,
+        peak,
+        trough_index: 0,
+    };
+
+    for (i, &equity) in equity_curve.iter().enumerate() {
+        if equity > peak {
+            peak = equity;
         }
+        let drawdown = (peak - equity) / peak;
+        if drawdown > worst.drawdown {
+            worst = DrawdownPoint {
+                drawdown,
+                peak,
+                trough_index: i,
+            };
+        }
+    }
+
+    Some(worst)
+}
 
-        // Annualized Sharpe (assuming daily data, 252 trading days)
-        // Simplified: just return mean/std for now
-        mean_return / std_dev * (252.0_f64).sqrt()
+/// Maximum peak-to-trough drawdown of an equity curve as a percentage.
+pub(crate) fn max_drawdown_pct(equity_curve: &[f64]) -> f64 {
+    max_drawdown_point(equity_curve).map_or(0.0, |worst| worst.drawdown * 100.0)
+}
+
+/// Annualized Sharpe ratio of per-bar (excess) returns.
+pub(crate) fn annualized_sharpe(returns: &[f64]) -> f64 {
+    if returns.is_empty() {
+        return 0.0;
+    }
+
+    // Calculate mean return
+    let mean_return: f64 = returns.iter().sum::<f64>() / returns.len() as f64;
+
+    // Calculate standard deviation
+    let variance: f64 = returns
+        .iter()
+        .map(|r| (r - mean_return).powi(2))
+        .sum::<f64>()
+        / returns.len() as f64;
+    let std_dev = variance.sqrt();
+
+    if std_dev == 0.0 {
+        return 0.0;
     }
+
+    // Annualized Sharpe (assuming daily data, 252 trading days)
+    // Simplified: just return mean/std for now
+    mean_return / std_dev * BARS_PER_YEAR.sqrt()
 }
 
 impl Default for BacktestEngine {
@@ -354,6 +1661,7 @@ impl Default for BacktestEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::precision::round_to_decimals;
 
     fn create_test_data() -> (Vec<i64>, Vec<f64>, Vec<f64>) {
         let timestamps: Vec<i64> = (0..100).collect();
@@ -385,6 +1693,41 @@ mod tests {
         assert_eq!(engine.tick_count(), 100);
     }
 
+    #[test]
+    fn test_validate_flags_misconfiguration() {
+        let (timestamps, prices, volumes) = create_test_data();
+
+        let mut engine = BacktestEngine::default();
+        engine.load_data_from_vectors(timestamps.clone(), prices.clone(), volumes.clone()).unwrap();
+        assert!(engine.validate().is_empty());
+
+        // Long MA longer than the data and a zero position size
+        let params = StrategyParams {
+            long_ma_period: 200,
+            position_size: 0.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, RiskConfig::default());
+        engine.load_data_from_vectors(timestamps.clone(), prices.clone(), volumes.clone()).unwrap();
+        let fields: Vec<&str> = engine.validate().iter().map(|w| w.field).collect();
+        assert_eq!(fields, vec!["position_size", "long_ma_period"]);
+
+        // First order already breaches the order value limit
+        let risk = RiskConfig {
+            max_order_value: 5_000.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(StrategyParams::default(), risk);
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        let findings = engine.validate();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].field, "max_order_value");
+        assert!(findings[0].is_error());
+
+        // Nothing loaded
+        assert!(BacktestEngine::default().validate().iter().any(|w| w.field == "ticks"));
+    }
+
     #[test]
     fn test_run_backtest() {
         let params = StrategyParams {
@@ -406,6 +1749,83 @@ mod tests {
         assert!(result.final_equity > 0.0);
     }
 
+    #[test]
+    fn test_checkpoint_resume_matches_straight_run() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let new_engine = || {
+            let mut engine = BacktestEngine::new(params, RiskConfig::default());
+            let (timestamps, prices, volumes) = create_test_data();
+            engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+            engine
+        };
+
+        let mut straight = new_engine();
+        let expected = straight.run().unwrap();
+
+        let pm = PersistenceManager::in_memory().unwrap();
+        let mut first_half = new_engine();
+        first_half.run_bars(50).unwrap();
+        assert_eq!(first_half.equity_curve().len(), 50);
+        assert!(!first_half.trade_records().is_empty());
+        first_half.checkpoint(&pm, "2024-01-01").unwrap();
+
+        let mut resumed = new_engine();
+        let result = resumed.resume(&pm, "2024-01-01").unwrap();
+
+        assert_eq!(resumed.current_index(), 100);
+        assert_eq!(resumed.equity_curve().len(), 100);
+        for (a, b) in resumed.equity_curve().iter().zip(straight.equity_curve()) {
+            assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+        }
+        assert!((result.final_equity - expected.final_equity).abs() < 1e-6);
+        assert!((result.max_drawdown_pct - expected.max_drawdown_pct).abs() < 1e-9);
+        let balance = resumed.get_account_status().balance;
+        assert!((balance - straight.get_account_status().balance).abs() < 1e-6);
+        assert_eq!(resumed.trade_records().len(), straight.trade_records().len());
+        assert_eq!(resumed.trades().len(), straight.trades().len());
+        assert!(expected.winning_trades + expected.losing_trades > 0);
+        assert_eq!(result.total_trades, expected.total_trades);
+        assert_eq!(result.winning_trades, expected.winning_trades);
+        assert_eq!(result.losing_trades, expected.losing_trades);
+        assert_eq!(result.first_trade_timestamp, expected.first_trade_timestamp);
+
+        // A session without a checkpoint cannot be resumed
+        assert!(new_engine().resume(&pm, "2024-01-02").is_err());
+    }
+
+    #[test]
+    fn test_trade_log_matches_total_trades() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, RiskConfig::default());
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+
+        let result = engine.run().unwrap();
+        assert!(result.total_trades > 0);
+        assert_eq!(engine.trades().len(), result.total_trades as usize);
+
+        // Closing orders carry the realized PnL of their closing trades
+        let logged: f64 = engine.trades().iter().map(|t| t.net_pnl).sum();
+        let closed: f64 = engine.trade_records().iter().map(|t| t.net_pnl).sum();
+        assert!((logged - closed).abs() < 1e-9);
+        assert!(engine.trades().iter().any(|t| t.pnl != 0.0));
+        assert!(engine.trades().iter().all(|t| t.quantity > 0.0 && t.price > 0.0));
+
+        // A second run starts a fresh log
+        engine.run().unwrap();
+        assert_eq!(engine.trades().len(), result.total_trades as usize);
+    }
+
     #[test]
     fn test_equity_curve_tracking() {
         let mut engine = BacktestEngine::default();
@@ -434,6 +1854,146 @@ mod tests {
         assert!((status.balance - 50_000.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_slippage_sweep_decreases_returns() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let risk_config = RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, risk_config);
+        assert_eq!(engine.slippage(), 0.001);
+        assert_eq!(engine.commission(), 0.0001);
+
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+
+        let mut returns = Vec::new();
+        for slippage in [0.0, 0.001, 0.005, 0.01] {
+            engine.set_slippage(slippage);
+            let result = engine.run().unwrap();
+            assert!(result.total_trades > 0);
+            returns.push(result.total_return_pct);
+        }
+
+        for pair in returns.windows(2) {
+            assert!(pair[1] < pair[0], "returns not decreasing: {:?}", returns);
+        }
+    }
+
+    #[test]
+    fn test_slippage_builder_reduces_final_equity() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let run = |slippage: f64| {
+            let mut engine = BacktestEngine::new(params, RiskConfig::default())
+                .with_slippage(slippage)
+                .with_initial_balance(50_000.0)
+                .with_commission(0.0);
+            assert_eq!(engine.slippage(), slippage);
+            assert_eq!(engine.commission(), 0.0);
+            assert_eq!(engine.get_account_status().balance, 50_000.0);
+            let (timestamps, prices, volumes) = create_test_data();
+            engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+            engine.run().unwrap()
+        };
+
+        let cheap = run(0.0);
+        let costly = run(0.01);
+        assert!(cheap.total_trades > 0);
+        assert!(costly.final_equity < cheap.final_equity);
+
+        let config = EngineCostConfig {
+            initial_balance: 50_000.0,
+            slippage: 0.01,
+            commission_rate: 0.0,
+        };
+        let engine = BacktestEngine::new(params, RiskConfig::default()).with_cost_config(config);
+        assert_eq!(engine.slippage(), 0.01);
+        assert_eq!(engine.get_account_status().balance, 50_000.0);
+    }
+
+    #[test]
+    fn test_cost_builders_keep_tiered_fee() {
+        let mut engine = BacktestEngine::default();
+        engine.set_volume_tiered_fee(Some(VolumeTieredFee::new(0.001, vec![(1_000_000.0, 0.0005)])));
+
+        let engine = engine
+            .with_slippage(0.002)
+            .with_commission(0.0003)
+            .with_cost_config(EngineCostConfig::default());
+        assert_eq!(engine.slippage(), 0.001);
+        assert_eq!(engine.commission(), 0.0001);
+        assert!(engine.gateway.volume_tiered_fee().is_some());
+    }
+
+    #[test]
+    fn test_commission_change_between_runs() {
+        let mut engine = BacktestEngine::default();
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+
+        let cheap = engine.run().unwrap();
+        engine.set_commission(0.01);
+        let expensive = engine.run().unwrap();
+
+        assert!(expensive.final_equity < cheap.final_equity);
+    }
+
+    #[test]
+    fn test_min_equity_halts_run() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 100.0,
+            ..Default::default()
+        };
+        // Tight drawdown guard blocks the exit, so the long rides the collapse
+        let risk_config = RiskConfig {
+            max_drawdown_pct: 0.05,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, risk_config).with_min_equity(85_000.0);
+
+        // Flat, then a rally that triggers a buy, then a steady collapse
+        let mut prices: Vec<f64> = vec![100.0; 20];
+        prices.extend((1..=10).map(|i| 100.0 + i as f64));
+        prices.extend((1..=20).map(|i| 110.0 - 5.0 * i as f64));
+        let timestamps: Vec<i64> = (0..prices.len() as i64).collect();
+        let volumes = vec![1000.0; prices.len()];
+        engine.load_data_from_vectors(timestamps, prices.clone(), volumes).unwrap();
+
+        let result = engine.run().unwrap();
+
+        assert_eq!(result.blown_up, 1);
+        assert!(result.stopped_at_bar > 30);
+        assert!((result.stopped_at_bar as usize) < prices.len() - 1);
+        assert_eq!(engine.equity_curve().len(), result.stopped_at_bar as usize + 1);
+
+        let position = engine.gateway.query_position("BTCUSDT").unwrap();
+        assert!(position.quantity.abs() < QUANTITY_EPSILON);
+    }
+
+    #[test]
+    fn test_min_equity_disabled_runs_to_completion() {
+        let mut engine = BacktestEngine::default();
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+
+        let result = engine.run().unwrap();
+        assert_eq!(result.blown_up, 0);
+        assert_eq!(result.stopped_at_bar, -1);
+    }
+
     #[test]
     fn test_max_drawdown_calculation() {
         let engine = BacktestEngine {
@@ -445,4 +2005,1209 @@ mod tests {
         // Max drawdown from 110 to 95 = 13.6%
         assert!((max_dd - 13.636).abs() < 0.1);
     }
+
+    #[test]
+    fn test_max_drawdown_recovery_bars() {
+        // Peak 110 at bar 1, trough 95 at bar 3, back to 110 at bar 6
+        let engine = BacktestEngine {
+            equity_curve: vec![100.0, 110.0, 105.0, 95.0, 100.0, 108.0, 110.0, 115.0],
+            ..Default::default()
+        };
+        assert_eq!(engine.max_drawdown_recovery_bars(), Some(3));
+
+        // A shallower drawdown that recovers does not mask the unrecovered worst one
+        let engine = BacktestEngine {
+            equity_curve: vec![100.0, 98.0, 101.0, 120.0, 90.0, 110.0, 119.0],
+            ..Default::default()
+        };
+        assert_eq!(engine.max_drawdown_recovery_bars(), None);
+
+        let engine = BacktestEngine {
+            equity_curve: vec![100.0, 101.0, 102.0],
+            ..Default::default()
+        };
+        assert_eq!(engine.max_drawdown_recovery_bars(), Some(0));
+
+        assert_eq!(BacktestEngine::default().max_drawdown_recovery_bars(), None);
+    }
+
+    #[test]
+    fn test_update_strategy_params_preserves_positions() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let risk_config = RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, risk_config);
+
+        let (timestamps, prices, volumes) = create_test_data();
+        let ticks: Vec<Tick> = (0..timestamps.len())
+            .map(|i| Tick {
+                timestamp: timestamps[i],
+                price: prices[i],
+                volume: volumes[i],
+            })
+            .collect();
+
+        // Run until a position is open
+        let mut processed = 0;
+        for tick in &ticks {
+            engine.process_tick(tick).unwrap();
+            processed += 1;
+            if engine.gateway.query_position("BTCUSDT").is_some_and(|p| p.quantity != 0.0) {
+                break;
+            }
+        }
+        assert!(processed < ticks.len(), "test data never opened a position");
+
+        let position_before = engine.gateway.query_position("BTCUSDT").unwrap();
+        let account_before = engine.get_account_status();
+        let curve_len = engine.equity_curve().len();
+        let trades_before = engine.total_trades;
+        let strategy_position = engine.dual_ma_strategy().unwrap().current_position();
+
+        let new_params = StrategyParams {
+            short_ma_period: 3,
+            long_ma_period: 8,
+            ..params
+        };
+        engine.update_strategy_params(new_params).unwrap();
+
+        assert_eq!(engine.strategy_params(), new_params);
+        assert_eq!(engine.dual_ma_strategy().unwrap().short_ma_period(), 3);
+        assert_eq!(engine.dual_ma_strategy().unwrap().long_ma_period(), 8);
+        assert_eq!(engine.dual_ma_strategy().unwrap().price_count(), processed);
+        assert!(engine.dual_ma_strategy().unwrap().current_long_ma().is_some());
+        assert_eq!(engine.dual_ma_strategy().unwrap().current_position(), strategy_position);
+
+        let position_after = engine.gateway.query_position("BTCUSDT").unwrap();
+        assert_eq!(position_after.quantity, position_before.quantity);
+        assert_eq!(position_after.average_price, position_before.average_price);
+        assert_eq!(engine.get_account_status().balance, account_before.balance);
+        assert_eq!(engine.equity_curve().len(), curve_len);
+        assert_eq!(engine.total_trades, trades_before);
+
+        // Processing continues normally with the new indicators
+        for tick in &ticks[processed..] {
+            engine.process_tick(tick).unwrap();
+        }
+        assert_eq!(engine.equity_curve().len(), ticks.len());
+    }
+
+    fn adaptive_config() -> AdaptiveConfig {
+        AdaptiveConfig {
+            reoptimize_every_bars: 20,
+            lookback_bars: 20,
+            param_grid: ParameterRange {
+                short_ma_range: (2, 4, 1),
+                long_ma_range: (6, 10, 2),
+                position_size_range: None,
+            },
+        }
+    }
+
+    fn run_adaptive(prices: Vec<f64>) -> BacktestEngine {
+        let params = StrategyParams {
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let risk_config = RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, risk_config).with_adaptive(adaptive_config());
+        let n = prices.len();
+        engine
+            .load_data_from_vectors((0..n as i64).collect(), prices, vec![1000.0; n])
+            .unwrap();
+        engine.run().unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_adaptive_reoptimizes_on_schedule() {
+        let (_, prices, _) = create_test_data();
+        let engine = run_adaptive(prices);
+
+        // The default 5/20 periods are outside the grid, so bar 20 must swap
+        let history = engine.adaptive_history();
+        assert_eq!(history[0].0, 20);
+        assert!(history.iter().all(|(bar, _)| bar.is_multiple_of(20) && *bar < 100));
+        for (_, params) in history {
+            assert!((2..=4).contains(&params.short_ma_period));
+            assert!((6..=10).contains(&params.long_ma_period));
+            assert_eq!(params.position_size, 10.0);
+        }
+        assert_eq!(engine.strategy_params(), history.last().unwrap().1);
+        assert_eq!(engine.equity_curve().len(), 100);
+    }
+
+    #[test]
+    fn test_adaptive_uses_only_past_data() {
+        // Identical through bar 59, wildly different from bar 60 on
+        let (_, prices, _) = create_test_data();
+        let mut shocked = prices.clone();
+        for (i, price) in shocked.iter_mut().enumerate().skip(60) {
+            *price = if i % 2 == 0 { 150.0 } else { 60.0 };
+        }
+
+        let a = run_adaptive(prices);
+        let b = run_adaptive(shocked);
+
+        // Choices up to and including bar 60 only saw bars 0..60
+        let before = |engine: &BacktestEngine| -> Vec<(usize, StrategyParams)> {
+            engine.adaptive_history().iter().copied().filter(|(bar, _)| *bar <= 60).collect()
+        };
+        assert!(!before(&a).is_empty());
+        assert_eq!(before(&a), before(&b));
+        assert_eq!(a.equity_curve()[..60], b.equity_curve()[..60]);
+    }
+
+    #[test]
+    fn test_adaptive_swap_preserves_position() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, RiskConfig::default()).with_adaptive(adaptive_config());
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        engine.begin_run().unwrap();
+
+        // Stop on a re-optimization bar with a position open
+        let ticks = engine.ticks.clone();
+        let bar = (0..ticks.len())
+            .find(|&bar| {
+                let open = engine.gateway.query_position("BTCUSDT").is_some_and(|p| p.quantity != 0.0);
+                if bar > 0 && bar.is_multiple_of(20) && open {
+                    return true;
+                }
+                engine.step(&ticks[bar]).unwrap();
+                false
+            })
+            .expect("no position open at a re-optimization bar");
+
+        let position_before = engine.gateway.query_position("BTCUSDT").unwrap();
+        let strategy_position = engine.dual_ma_strategy().unwrap().current_position();
+        engine.reoptimize(bar, &ticks[..bar]).unwrap();
+
+        assert_eq!(engine.adaptive_history().last().unwrap().0, bar);
+        let position_after = engine.gateway.query_position("BTCUSDT").unwrap();
+        assert_eq!(position_after.quantity, position_before.quantity);
+        assert_eq!(position_after.average_price, position_before.average_price);
+        assert_eq!(engine.dual_ma_strategy().unwrap().current_position(), strategy_position);
+    }
+
+    #[test]
+    fn test_round_trip_trade_attribution() {
+        let mut engine = BacktestEngine::default();
+        let fill = |direction: i32, quantity: f64, price: f64, commission: f64| Fill {
+            order_id: 0,
+            symbol: [0u8; 16],
+            quantity,
+            price,
+            commission,
+            direction,
+            timestamp: 0,
+            client_id: 0,
+        };
+
+        // Buy 10 @ 100 in two lots, then sell 10 @ 110
+        engine.attribute_fills(
+            &[fill(DIRECTION_BUY, 4.0, 100.0, 0.4), fill(DIRECTION_BUY, 6.0, 100.0, 0.6)],
+            ExitReason::Signal,
+        );
+        assert!(engine.trade_records().is_empty());
+        engine.attribute_fills(&[fill(DIRECTION_SELL, 10.0, 110.0, 1.1)], ExitReason::Signal);
+
+        let trade = &engine.trade_records()[0];
+        assert_eq!(trade.quantity, 10.0);
+        assert!((trade.gross_pnl - 100.0).abs() < 1e-9);
+        assert!((trade.commission - 2.1).abs() < 1e-9);
+        assert_eq!(trade.net_pnl, trade.gross_pnl - trade.commission);
+
+        // From flat, selling 5 opens a short; buying 8 covers it and opens 3 long
+        engine.attribute_fills(&[fill(DIRECTION_SELL, 5.0, 110.0, 0.5)], ExitReason::Signal);
+        engine.attribute_fills(&[fill(DIRECTION_BUY, 8.0, 100.0, 0.8)], ExitReason::Signal);
+        let cover = &engine.trade_records()[1];
+        assert_eq!(cover.quantity, 5.0);
+        assert!((cover.gross_pnl - 50.0).abs() < 1e-9);
+        assert!((cover.commission - 1.0).abs() < 1e-9);
+        assert_eq!(engine.open_lot.quantity, 3.0);
+    }
+
+    #[test]
+    fn test_run_records_trade_costs() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, RiskConfig::default());
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        engine.run().unwrap();
+
+        assert!(!engine.trade_records().is_empty());
+        for trade in engine.trade_records() {
+            assert!(trade.commission > 0.0);
+            assert_eq!(trade.net_pnl, trade.gross_pnl - trade.commission);
+        }
+    }
+
+    #[test]
+    fn test_exit_reasons_recorded_and_counted() {
+        let params = StrategyParams {
+            short_ma_period: 2,
+            long_ma_period: 4,
+            position_size: 10.0,
+            stop_loss_pct: 0.03,
+            take_profit_pct: 0.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, RiskConfig::default()).with_protective_exits(true);
+
+        // Golden cross buys at 101; the gap to 97 hits the 3% stop before the
+        // death cross opens a short, which the golden cross at 99 covers
+        let prices = vec![100.0, 100.0, 100.0, 100.0, 101.0, 97.0, 97.0, 98.5, 99.0];
+        let timestamps = (0..prices.len() as i64).collect();
+        let volumes = vec![1000.0; prices.len()];
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        engine.run().unwrap();
+
+        let reasons: Vec<_> = engine
+            .trade_records()
+            .iter()
+            .map(|t| (t.entry_reason, t.exit_reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (EntryReason::Signal, ExitReason::Stop),
+                (EntryReason::Signal, ExitReason::Signal),
+            ]
+        );
+
+        let manager = crate::persistence::PersistenceManager::in_memory().unwrap();
+        for trade in engine.trade_records() {
+            manager.save_trade(trade, "2024-01-01").unwrap();
+        }
+        let counts = manager.get_exit_reason_counts("2024-01-01").unwrap();
+        assert_eq!(counts[ExitReason::Stop as usize], 1);
+        assert_eq!(counts[ExitReason::Signal as usize], 1);
+        assert_eq!(counts.iter().sum::<i64>(), 2);
+    }
+
+    #[test]
+    fn test_max_holding_bars_records_time_exit() {
+        let params = StrategyParams {
+            short_ma_period: 2,
+            long_ma_period: 4,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, RiskConfig::default()).with_max_holding_bars(3);
+
+        // Golden cross buys at 101 on bar 4; flat prices never cross back
+        let prices = vec![100.0, 100.0, 100.0, 100.0, 101.0, 101.0, 101.0, 101.0, 101.0];
+        let timestamps = (0..prices.len() as i64).collect();
+        let volumes = vec![1000.0; prices.len()];
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        engine.run().unwrap();
+
+        let records = engine.trade_records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].exit_reason, ExitReason::Time);
+        assert_eq!(records[0].timestamp, 7);
+        assert!(engine.gateway.query_position(&engine.symbol).is_none_or(|p| p.quantity == 0.0));
+    }
+
+    #[test]
+    fn test_strategy_exits_record_stop_and_are_exclusive() {
+        let params = StrategyParams {
+            short_ma_period: 2,
+            long_ma_period: 4,
+            position_size: 10.0,
+            stop_loss_pct: 0.01,
+            take_profit_pct: 0.0,
+            ..Default::default()
+        };
+        // Golden cross buys at 101; 99.5 breaches the 1% stop while the
+        // short MA is still above the long
+        let prices = vec![100.0, 100.0, 100.0, 100.0, 101.0, 104.0, 99.5, 99.5];
+        let run = |engine: &mut BacktestEngine| {
+            let timestamps = (0..prices.len() as i64).collect();
+            let volumes = vec![1000.0; prices.len()];
+            engine.load_data_from_vectors(timestamps, prices.clone(), volumes).unwrap();
+            engine.run().unwrap();
+            engine.trade_records().iter().map(|t| t.exit_reason).collect::<Vec<_>>()
+        };
+
+        // Strategy-side stop is recorded as a stop, not a signal exit
+        let strategy = DualMAStrategy::new(params).with_protective_exits(true);
+        let mut engine = BacktestEngine::new(params, RiskConfig::default()).with_strategy(Box::new(strategy));
+        assert_eq!(run(&mut engine), vec![ExitReason::Stop]);
+
+        // With both enabled the engine's exits win, in either builder order
+        let strategy = DualMAStrategy::new(params).with_protective_exits(true);
+        let mut engine = BacktestEngine::new(params, RiskConfig::default())
+            .with_protective_exits(true)
+            .with_strategy(Box::new(strategy));
+        assert!(!engine.dual_ma_strategy().unwrap().protective_exits());
+        assert_eq!(run(&mut engine), vec![ExitReason::Stop]);
+
+        let strategy = DualMAStrategy::new(params).with_protective_exits(true);
+        let engine = BacktestEngine::new(params, RiskConfig::default())
+            .with_strategy(Box::new(strategy))
+            .with_protective_exits(true);
+        assert!(!engine.dual_ma_strategy().unwrap().protective_exits());
+    }
+
+    #[test]
+    fn test_equity_exposure_sizing_compounds() {
+        let params = StrategyParams {
+            short_ma_period: 2,
+            long_ma_period: 4,
+            ..Default::default()
+        };
+        // The simulated gateway's equity excludes the cost of open longs, so
+        // allow the drawdown that a 30% position shows
+        let risk = RiskConfig {
+            max_drawdown_pct: 0.9,
+            ..Default::default()
+        };
+        let sizing = SizingMode::EquityExposure { target_exposure: 0.3 };
+        let mut engine = BacktestEngine::new(params, risk).with_sizing(sizing);
+
+        // Buy at 101, sell into the rally at 110, buy again at 101
+        let prices = [100.0, 100.0, 100.0, 100.0, 101.0, 110.0, 120.0, 130.0, 125.0, 110.0, 100.0, 95.0, 95.0, 101.0];
+        let mut fills = Vec::new();
+        for (i, &price) in prices.iter().enumerate() {
+            let tick = Tick { timestamp: i as i64, price, volume: 1000.0 };
+            engine.process_tick(&tick).unwrap();
+            fills.extend(engine.last_fills.iter().map(|f| (f.direction, f.quantity)));
+        }
+
+        assert_eq!(fills.len(), 3);
+        assert_eq!(fills[0], (DIRECTION_BUY, 100_000.0 * 0.3 / 101.0));
+        assert_eq!(fills[1], (DIRECTION_SELL, fills[0].1));
+        assert_eq!(fills[2].0, DIRECTION_BUY);
+        assert!(fills[2].1 > fills[0].1);
+
+        // Entries beyond max_position_size are still rejected
+        let risk = RiskConfig {
+            max_position_size: 200.0,
+            ..risk
+        };
+        let mut engine = BacktestEngine::new(params, risk).with_sizing(sizing);
+        for (i, &price) in prices[..5].iter().enumerate() {
+            engine.process_tick(&Tick { timestamp: i as i64, price, volume: 1000.0 }).unwrap();
+        }
+        assert!(engine.last_fills.is_empty());
+        assert!(engine.gateway.query_position("BTCUSDT").is_none_or(|p| p.quantity == 0.0));
+    }
+
+    #[test]
+    fn test_atr_stop_widens_with_volatility() {
+        let params = StrategyParams {
+            short_ma_period: 2,
+            long_ma_period: 4,
+            position_size: 10.0,
+            warmup_bars: 6,
+            ..Default::default()
+        };
+        let stop_mode = StopMode::AtrMultiple { atr_period: 5, multiplier: 2.0 };
+        let stop_distance = |prices: &[f64]| {
+            let mut engine = BacktestEngine::new(params, RiskConfig::default())
+                .with_protective_exits(true)
+                .with_stop_mode(stop_mode);
+            let timestamps = (0..prices.len() as i64).collect();
+            engine.load_data_from_vectors(timestamps, prices.to_vec(), vec![1000.0; prices.len()]).unwrap();
+            engine.run().unwrap();
+
+            let entry_price = engine.open_lot.cost / engine.open_lot.quantity;
+            assert!(engine.open_lot.quantity > 0.0);
+            entry_price - engine.stop_price().unwrap()
+        };
+
+        // Both series end in a single long entry after the warmup
+        let calm = stop_distance(&[100.0, 100.5, 100.0, 100.5, 100.0, 100.0, 100.0, 100.0, 101.0]);
+        let volatile = stop_distance(&[100.0, 106.0, 94.0, 106.0, 94.0, 100.0, 100.0, 100.0, 101.0]);
+        assert!(calm > 0.0);
+        assert!(volatile > 2.0 * calm);
+    }
+
+    #[test]
+    fn test_long_ma_period_sets_warmup() {
+        let params = StrategyParams {
+            short_ma_period: 10,
+            long_ma_period: 60,
+            position_size: 10.0,
+            warmup_bars: 0,
+            ..Default::default()
+        };
+        let timestamps: Vec<i64> = (0..300).collect();
+        let prices: Vec<f64> = (0..300)
+            .map(|i| 100.0 + (i as f64 / 20.0 * std::f64::consts::PI).sin() * 10.0)
+            .collect();
+        let volumes = vec![1000.0; 300];
+
+        let mut engine = BacktestEngine::new(params, RiskConfig::default());
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        engine.begin_run().unwrap();
+        let ticks = engine.ticks.clone();
+        for tick in &ticks[..60] {
+            engine.step(tick).unwrap();
+            assert!(engine.last_fills.is_empty());
+        }
+
+        let result = engine.run().unwrap();
+        assert_eq!(result.actual_start_bar, 60);
+        assert!(result.total_trades > 0);
+    }
+
+    /// Buys one unit on every tick.
+    struct AlwaysBuy;
+
+    impl Strategy for AlwaysBuy {
+        fn on_tick(&mut self, _tick: &Tick) -> Signal {
+            Signal::Buy
+        }
+
+        fn generate_order(&self, signal: Signal, symbol: &str, price: f64) -> Option<OrderRequest> {
+            (signal == Signal::Buy).then(|| {
+                let mut order = OrderRequest::with_symbol(symbol);
+                order.quantity = 1.0;
+                order.direction = DIRECTION_BUY;
+                order.limit_price = price;
+                order
+            })
+        }
+
+        fn reset(&mut self) {}
+
+        fn name(&self) -> &str {
+            "AlwaysBuy"
+        }
+    }
+
+    /// Records the bid of every tick it receives through `on_extended_tick`.
+    struct BidRecorder {
+        bids: Arc<std::sync::Mutex<Vec<Option<f64>>>>,
+    }
+
+    impl Strategy for BidRecorder {
+        fn on_tick(&mut self, _tick: &Tick) -> Signal {
+            Signal::None
+        }
+
+        fn on_extended_tick(&mut self, tick: &ExtendedTick) -> Signal {
+            self.bids.lock().unwrap().push(tick.bid);
+            Signal::None
+        }
+
+        fn generate_order(&self, _signal: Signal, _symbol: &str, _price: f64) -> Option<OrderRequest> {
+            None
+        }
+
+        fn reset(&mut self) {}
+
+        fn name(&self) -> &str {
+            "BidRecorder"
+        }
+    }
+
+    #[test]
+    fn test_extended_ticks_dispatched_to_strategy() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("quotes.csv");
+        std::fs::write(
+            &path,
+            "timestamp,price,volume,bid,ask\n1,100.0,10.0,99.5,100.5\n2,101.0,10.0,,101.5\n3,102.0,10.0,101.5,102.5\n",
+        )
+        .unwrap();
+
+        let bids = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let strategy = BidRecorder { bids: Arc::clone(&bids) };
+        let mut engine = BacktestEngine::default().with_strategy(Box::new(strategy));
+        engine.load_data(&path).unwrap();
+        engine.run().unwrap();
+        assert_eq!(*bids.lock().unwrap(), vec![Some(99.5), None, Some(101.5)]);
+
+        // Streaming callers opt in per tick
+        let quote = ExtendedTick {
+            bid: Some(102.0),
+            ..ExtendedTick::from(Tick { timestamp: 4, price: 102.5, volume: 1.0 })
+        };
+        engine.process_extended_tick(&quote).unwrap();
+        engine.process_tick(&quote.tick).unwrap();
+        assert_eq!(bids.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_custom_strategy_dispatch() {
+        let params = StrategyParams {
+            short_ma_period: 1,
+            long_ma_period: 2,
+            ..Default::default()
+        };
+        // The order throttle is wall-clock based, so lift it for a buy every tick
+        let risk = RiskConfig {
+            max_order_rate: 100,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, risk).with_strategy(Box::new(AlwaysBuy));
+        assert!(engine.dual_ma_strategy().is_none());
+        assert!(engine.update_strategy_params(params).is_err());
+
+        let timestamps: Vec<i64> = (0..20).collect();
+        engine
+            .load_data_from_vectors(timestamps, vec![100.0; 20], vec![1000.0; 20])
+            .unwrap();
+        let result = engine.run().unwrap();
+
+        // Every tick after the 2-bar warmup buys one unit
+        assert_eq!(result.total_trades, 18);
+        let position = engine.gateway.query_position(&engine.symbol).unwrap();
+        assert_eq!(position.quantity, 18.0);
+
+        // The default engine still runs DualMA
+        let engine = BacktestEngine::new(params, RiskConfig::default());
+        assert_eq!(engine.dual_ma_strategy().unwrap().name(), "DualMA");
+    }
+
+    #[test]
+    fn test_margin_mode_allows_leveraged_entries() {
+        // 1500 units at ~100 is more notional than the 100k balance
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 1500.0,
+            ..Default::default()
+        };
+        let risk = RiskConfig {
+            max_position_size: 1e6,
+            max_order_value: 1e9,
+            ..Default::default()
+        };
+        let (timestamps, prices, volumes) = create_test_data();
+
+        let mut cash = BacktestEngine::new(params, risk);
+        cash.load_data_from_vectors(timestamps.clone(), prices.clone(), volumes.clone()).unwrap();
+        assert_eq!(cash.run().unwrap().total_trades, 0);
+
+        let mut margin = BacktestEngine::new(params, risk).with_leverage(5.0);
+        assert_eq!(margin.risk_manager.leverage(), 5.0);
+        margin.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        let result = margin.run().unwrap();
+        assert!(result.total_trades > 0);
+        assert!(margin.gateway.margin_mode());
+
+        // Back to a cash account, the capital check is unleveraged again
+        margin.set_margin_mode(false);
+        assert_eq!(margin.risk_manager.leverage(), 1.0);
+    }
+
+    #[test]
+    fn test_symbol_limit_caps_position() {
+        let params = StrategyParams {
+            short_ma_period: 1,
+            long_ma_period: 2,
+            ..Default::default()
+        };
+        let risk = RiskConfig {
+            max_order_rate: 100,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, risk)
+            .with_strategy(Box::new(AlwaysBuy))
+            .with_symbol_limit("BTCUSDT", 5.0);
+        let timestamps: Vec<i64> = (0..20).collect();
+        engine
+            .load_data_from_vectors(timestamps, vec![100.0; 20], vec![1000.0; 20])
+            .unwrap();
+        let result = engine.run().unwrap();
+
+        // Buys stop once the position reaches the symbol's limit
+        assert_eq!(result.total_trades, 5);
+        assert_eq!(engine.gateway.query_position("BTCUSDT").unwrap().quantity, 5.0);
+    }
+
+    #[test]
+    fn test_first_trade_timestamp_matches_first_accepted_order() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let (timestamps, prices, volumes) = create_test_data();
+        let timestamps: Vec<i64> = timestamps.iter().map(|t| 1_000_000 + t * 60_000).collect();
+
+        let mut engine = BacktestEngine::new(params, RiskConfig::default());
+        engine.load_data_from_vectors(timestamps, prices, volumes.clone()).unwrap();
+        engine.begin_run().unwrap();
+        let ticks = engine.ticks.clone();
+        let mut first_fill = None;
+        for tick in &ticks {
+            engine.step(tick).unwrap();
+            if first_fill.is_none() && !engine.last_fills.is_empty() {
+                first_fill = Some(tick.timestamp);
+            }
+        }
+        let first_fill = first_fill.expect("crossover data should trade");
+        assert_eq!(engine.finish_run().first_trade_timestamp, first_fill);
+
+        let result = engine.run().unwrap();
+        assert_eq!(result.first_trade_timestamp, first_fill);
+
+        // Stays 0 when nothing trades
+        let mut engine = BacktestEngine::new(params, RiskConfig::default());
+        let flat: Vec<i64> = (0..100).map(|t| 1_000_000 + t * 60_000).collect();
+        engine.load_data_from_vectors(flat, vec![100.0; 100], volumes).unwrap();
+        let result = engine.run().unwrap();
+        assert_eq!(result.total_trades, 0);
+        assert_eq!(result.first_trade_timestamp, 0);
+    }
+
+    /// Calls reporting bar 37; other engine tests running in parallel warm
+    /// up for their MA periods and report other bars.
+    static WARMUP_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    extern "C" fn record_warmup_complete(first_tradable_bar: u64) {
+        if first_tradable_bar == 37 {
+            WARMUP_CALLS.fetch_add(1, AtomicOrdering::SeqCst);
+        }
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_warmup_complete_callback_fires_once_per_run() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            warmup_bars: 37,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, RiskConfig::default());
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+
+        WARMUP_CALLS.store(0, AtomicOrdering::SeqCst);
+        unsafe { set_warmup_complete_callback(record_warmup_complete) };
+
+        let result = engine.run().unwrap();
+        assert_eq!(WARMUP_CALLS.load(AtomicOrdering::SeqCst), 1);
+        assert_eq!(result.actual_start_bar, 37);
+
+        // Re-running resets the warmup, so it fires again at the same bar
+        engine.run().unwrap();
+        clear_warmup_complete_callback();
+        assert_eq!(WARMUP_CALLS.load(AtomicOrdering::SeqCst), 2);
+
+        // No trades during warmup
+        let mut engine = BacktestEngine::new(params, RiskConfig::default());
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        engine.begin_run().unwrap();
+        let ticks = engine.ticks.clone();
+        for tick in &ticks[..37] {
+            engine.step(tick).unwrap();
+            assert!(engine.last_fills.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_update_strategy_params_rejects_invalid_periods() {
+        let mut engine = BacktestEngine::default();
+        let params = StrategyParams {
+            short_ma_period: 0,
+            ..Default::default()
+        };
+        assert!(engine.update_strategy_params(params).is_err());
+        let params = StrategyParams {
+            long_ma_type: 9,
+            ..Default::default()
+        };
+        assert!(engine.update_strategy_params(params).is_err());
+        assert_eq!(engine.strategy_params(), StrategyParams::default());
+    }
+
+    #[test]
+    fn test_flat_strategy_underperforms_rising_buy_and_hold() {
+        // A steady uptrend never produces a crossover, so the strategy stays flat
+        let mut engine = BacktestEngine::default();
+        engine.set_commission(0.001);
+        let timestamps: Vec<i64> = (0..50).collect();
+        let prices: Vec<f64> = (0..50).map(|i| 100.0 + i as f64).collect();
+        let volumes = vec![1000.0; 50];
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+
+        let result = engine.run().unwrap();
+        assert_eq!(result.total_trades, 0);
+        assert!(result.total_return_pct.abs() < 1e-9);
+
+        let expected = (149.0 / 100.0 * 0.999 / 1.001 - 1.0) * 100.0;
+        assert!((result.buy_and_hold_return_pct - expected).abs() < 1e-9);
+        assert!(result.buy_and_hold_return_pct > result.total_return_pct);
+    }
+
+    #[test]
+    fn test_benchmark_starts_after_warmup() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, RiskConfig::default());
+        engine.set_commission(0.0);
+        // Flat for 20 bars, then rising: the MA strategy enters after the crossover
+        let timestamps: Vec<i64> = (0..100).collect();
+        let prices: Vec<f64> = (0..100).map(|i| 100.0 + (i as f64 - 20.0).max(0.0)).collect();
+        let volumes = vec![1000.0; 100];
+        engine.load_data_from_vectors(timestamps, prices.clone(), volumes).unwrap();
+
+        let result = engine.run().unwrap();
+        assert!(result.total_trades > 0);
+        assert_eq!(result.actual_start_bar, 10);
+
+        let start_price = prices[result.actual_start_bar as usize];
+        let expected = (179.0 / start_price - 1.0) * 100.0;
+        assert!((result.benchmark_return_pct - expected).abs() < 1e-9);
+        assert!(result.benchmark_return_pct > result.total_return_pct);
+        assert!(result.alpha_pct < 0.0);
+        assert!((result.alpha_pct - (result.total_return_pct - result.benchmark_return_pct)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trade_cooldown_reduces_whipsaw_trades() {
+        let params = StrategyParams {
+            short_ma_period: 2,
+            long_ma_period: 4,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let risk_config = RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        };
+
+        // Price chops around 100 so the MAs cross every few bars
+        let timestamps: Vec<i64> = (0..200).collect();
+        let prices: Vec<f64> = (0..200)
+            .map(|i| 100.0 + (i as f64 * std::f64::consts::PI / 3.0).sin() * 2.0)
+            .collect();
+        let volumes = vec![1000.0; 200];
+
+        let mut baseline = BacktestEngine::new(params, risk_config);
+        baseline
+            .load_data_from_vectors(timestamps.clone(), prices.clone(), volumes.clone())
+            .unwrap();
+        let baseline_trades = baseline.run().unwrap().total_trades;
+
+        let mut cooled = BacktestEngine::new(params, risk_config).with_min_bars_between_trades(20);
+        cooled.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        let cooled_trades = cooled.run().unwrap().total_trades;
+
+        assert!(baseline_trades > 10, "baseline_trades = {}", baseline_trades);
+        assert!(cooled_trades > 0);
+        assert!(
+            cooled_trades < baseline_trades,
+            "cooled {} vs baseline {}",
+            cooled_trades,
+            baseline_trades
+        );
+    }
+
+    #[test]
+    fn test_rising_risk_free_rates_lower_sharpe() {
+        let equity_curve: Vec<f64> = (0..252)
+            .map(|i| 100_000.0 * (1.0 + 0.0004 * i as f64 + (i as f64).sin() * 0.002))
+            .collect();
+        let ticks: Vec<Tick> = (0..252)
+            .map(|i| Tick {
+                timestamp: i as i64,
+                price: 100.0,
+                volume: 1000.0,
+            })
+            .collect();
+
+        let baseline = BacktestEngine {
+            equity_curve: equity_curve.clone(),
+            ticks: ticks.clone(),
+            ..Default::default()
+        };
+        let rising = BacktestEngine {
+            equity_curve,
+            ticks,
+            ..Default::default()
+        }
+        .with_risk_free_series(vec![(100, 0.02), (50, 0.01), (200, 0.05)]);
+
+        let baseline_sharpe = baseline.calculate_sharpe_ratio();
+        let rising_sharpe = rising.calculate_sharpe_ratio();
+        assert!(baseline_sharpe > 0.0);
+        assert!(
+            rising_sharpe < baseline_sharpe - 0.1,
+            "rising {} vs baseline {}",
+            rising_sharpe,
+            baseline_sharpe
+        );
+
+        // A flat series equals the same constant rate
+        let flat_series = BacktestEngine {
+            equity_curve: baseline.equity_curve.clone(),
+            ticks: baseline.ticks.clone(),
+            ..Default::default()
+        }
+        .with_risk_free_series(vec![(10_000, 0.03)]);
+        let constant = BacktestEngine {
+            equity_curve: baseline.equity_curve.clone(),
+            ticks: baseline.ticks.clone(),
+            ..Default::default()
+        }
+        .with_risk_free_rate(0.03);
+        assert!((flat_series.calculate_sharpe_ratio() - constant.calculate_sharpe_ratio()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_run_realtime_matches_run_and_emits_events() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let risk_config = RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, risk_config);
+        let (timestamps, prices, volumes) = create_test_data();
+        // One second apart in milliseconds
+        let timestamps: Vec<i64> = timestamps.iter().map(|t| t * 1_000).collect();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+
+        let expected = engine.run().unwrap();
+        let expected_curve = engine.equity_curve().to_vec();
+
+        let bus = crate::event_bus::create_shared_event_bus(1000);
+        let ticks_sub = bus.lock().unwrap().subscribe(crate::event_bus::EventFilter::tick_only());
+        let orders_sub = bus.lock().unwrap().subscribe(crate::event_bus::EventFilter::orders_only());
+
+        // 99 seconds of data replayed at 10_000x takes about 10ms
+        let start = Instant::now();
+        let result = engine.run_realtime(10_000.0, &bus).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(9));
+
+        assert_eq!(result, expected);
+        assert_eq!(engine.equity_curve(), expected_curve.as_slice());
+        assert_eq!(ticks_sub.len(), 100);
+        assert_eq!(orders_sub.len(), result.total_trades as usize);
+    }
+
+    #[test]
+    fn test_run_realtime_matches_run_with_adaptive() {
+        let (_, prices, _) = create_test_data();
+        let expected_engine = run_adaptive(prices.clone());
+        let expected = expected_engine.finish_run();
+        assert!(!expected_engine.adaptive_history().is_empty());
+
+        let params = StrategyParams {
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let risk_config = RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, risk_config).with_adaptive(adaptive_config());
+        engine
+            .load_data_from_vectors((0..100).collect(), prices, vec![1000.0; 100])
+            .unwrap();
+        let bus = crate::event_bus::create_shared_event_bus(1000);
+        let result = engine.run_realtime(0.0, &bus).unwrap();
+
+        assert_eq!(result, expected);
+        assert_eq!(engine.adaptive_history(), expected_engine.adaptive_history());
+        assert_eq!(engine.equity_curve(), expected_engine.equity_curve());
+    }
+
+    #[test]
+    fn test_run_realtime_cancellation() {
+        let mut engine = BacktestEngine::default();
+        let timestamps: Vec<i64> = (0..10).map(|i| i * 1_000).collect();
+        engine
+            .load_data_from_vectors(timestamps, vec![100.0; 10], vec![1000.0; 10])
+            .unwrap();
+
+        let cancel = engine.realtime_cancel_handle();
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            cancel.store(true, AtomicOrdering::SeqCst);
+        });
+
+        // Unscaled this would take 9 seconds
+        let bus = crate::event_bus::create_shared_event_bus(100);
+        let start = Instant::now();
+        engine.run_realtime(1.0, &bus).unwrap();
+        canceller.join().unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(engine.equity_curve().len() < 10);
+
+        // A cancel requested before the call stops it before the first tick
+        engine.realtime_cancel_handle().store(true, AtomicOrdering::SeqCst);
+        engine.run_realtime(1.0, &bus).unwrap();
+        assert!(engine.equity_curve().is_empty());
+
+        // Each cancel stops one run; the next plays in full
+        engine.run_realtime(0.0, &bus).unwrap();
+        assert_eq!(engine.equity_curve().len(), 10);
+    }
+
+    #[test]
+    fn test_session_end_flattens_positions_once() {
+        const HOUR_MS: i64 = 3_600_000;
+        // Session ends at 16:00 local time, UTC+8
+        let mut engine = BacktestEngine::default().with_session_end(16 * HOUR_MS, 8 * HOUR_MS);
+        let utc_ms = |local_hour: i64, minute: i64| {
+            local_hour * HOUR_MS + minute * 60_000 - 8 * HOUR_MS
+        };
+        let tick = |timestamp| Tick {
+            timestamp,
+            price: 100.0,
+            volume: 1000.0,
+        };
+
+        engine.process_tick(&tick(utc_ms(15, 58))).unwrap();
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 10.0;
+        order.direction = DIRECTION_BUY;
+        engine.gateway.submit_order(&order, 100.0).unwrap();
+        engine.gateway.get_fills();
+
+        engine.process_tick(&tick(utc_ms(15, 59))).unwrap();
+        assert_eq!(engine.gateway.query_position("BTCUSDT").unwrap().quantity, 10.0);
+        assert!(engine.last_fills.is_empty());
+
+        // First tick past 16:00 closes the position with a tagged order
+        engine.process_tick(&tick(utc_ms(16, 1))).unwrap();
+        assert_eq!(engine.gateway.query_position("BTCUSDT").unwrap().quantity, 0.0);
+        assert_eq!(engine.last_fills.len(), 1);
+        assert_eq!(engine.last_fills[0].client_id, SESSION_CLOSE_CLIENT_ID);
+        assert_eq!(engine.last_fills[0].direction, DIRECTION_SELL);
+
+        // A new position after the boundary is not closed again the same session
+        engine.gateway.submit_order(&order, 100.0).unwrap();
+        engine.gateway.get_fills();
+        engine.process_tick(&tick(utc_ms(17, 0))).unwrap();
+        assert_eq!(engine.gateway.query_position("BTCUSDT").unwrap().quantity, 10.0);
+
+        // Next morning is still the same session
+        engine.process_tick(&tick(utc_ms(24 + 9, 30))).unwrap();
+        assert_eq!(engine.gateway.query_position("BTCUSDT").unwrap().quantity, 10.0);
+
+        // A tick that jumps past the next day's session end closes it
+        engine.process_tick(&tick(utc_ms(24 + 17, 0))).unwrap();
+        assert_eq!(engine.gateway.query_position("BTCUSDT").unwrap().quantity, 0.0);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_daily_loss_limit_resets_each_session() {
+        use crate::emergency::{is_halted, reset_emergency_stop};
+
+        const HOUR_MS: i64 = 3_600_000;
+        reset_emergency_stop();
+        let params = StrategyParams {
+            short_ma_period: 1,
+            long_ma_period: 2,
+            ..Default::default()
+        };
+        let risk = RiskConfig {
+            max_order_rate: 100,
+            max_daily_loss: 130.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, risk)
+            .with_strategy(Box::new(AlwaysBuy))
+            .with_session_end(16 * HOUR_MS, 0);
+
+        // Day one buys two units at 100 before the limit trips, then sells
+        // off to 60. Day two starts from the flattened equity, so both buys
+        // pass; measured from the start of the run the second would not
+        let day_one: Vec<i64> = (0..10).map(|m| 10 * HOUR_MS + m * 60_000).collect();
+        let day_two: Vec<i64> = (0..2).map(|m| 34 * HOUR_MS + m * 60_000).collect();
+        let timestamps = [day_one, day_two].concat();
+        let prices = vec![100.0, 100.0, 100.0, 100.0, 92.0, 85.0, 78.0, 72.0, 66.0, 60.0, 60.0, 60.0];
+        engine.load_data_from_vectors(timestamps, prices, vec![1000.0; 12]).unwrap();
+        let result = engine.run().unwrap();
+
+        assert!(is_halted());
+        assert_eq!(engine.trade_records()[0].exit_reason, ExitReason::Session);
+        assert!(engine.risk_manager.session_start_equity() < 99_950.0);
+        assert_eq!(result.total_trades, 4);
+        let position = engine.gateway.query_position(&engine.symbol).unwrap();
+        assert_eq!(position.quantity, 2.0);
+
+        reset_emergency_stop();
+    }
+
+    #[test]
+    fn test_session_end_on_loaded_iso_timestamps() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("ticks.csv");
+        std::fs::write(
+            &path,
+            "timestamp,price,volume\n\
+             2024-01-02T15:58:00Z,100.0,10.0\n\
+             2024-01-02T15:59:00Z,100.0,10.0\n\
+             2024-01-02T16:01:00Z,100.0,10.0\n",
+        )
+        .unwrap();
+        let ticks = DataLoader::new().load_from_file(&path).unwrap().ticks;
+
+        // Session ends at 16:00 UTC on the loader's epoch-millis timestamps
+        let mut engine = BacktestEngine::default().with_session_end(16 * 3_600_000, 0);
+        engine.process_tick(&ticks[0]).unwrap();
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 10.0;
+        order.direction = DIRECTION_BUY;
+        engine.gateway.submit_order(&order, 100.0).unwrap();
+        engine.gateway.get_fills();
+
+        engine.process_tick(&ticks[1]).unwrap();
+        assert_eq!(engine.gateway.query_position("BTCUSDT").unwrap().quantity, 10.0);
+
+        engine.process_tick(&ticks[2]).unwrap();
+        assert_eq!(engine.gateway.query_position("BTCUSDT").unwrap().quantity, 0.0);
+        assert_eq!(engine.last_fills[0].client_id, SESSION_CLOSE_CLIENT_ID);
+    }
+
+    #[test]
+    fn test_trade_marked_equity_aligns_with_fills() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let mut engine = BacktestEngine::new(params, RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        })
+        .with_trade_marked_equity(true);
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+
+        let ticks = engine.ticks.clone();
+        let mut expected = Vec::new();
+        for tick in &ticks {
+            engine.process_tick(tick).unwrap();
+            if let Some(fill) = engine.last_fills.first() {
+                assert_eq!(fill.timestamp, tick.timestamp);
+                expected.push((fill.timestamp, *engine.equity_curve().last().unwrap()));
+            }
+        }
+
+        assert!(expected.len() >= 3);
+        assert_eq!(engine.trade_marked_equity(), expected.as_slice());
+        // The full per-tick curve is still recorded
+        assert_eq!(engine.equity_curve().len(), ticks.len());
+
+        // A fresh run rebuilds the same points
+        engine.run().unwrap();
+        assert_eq!(engine.trade_marked_equity(), expected.as_slice());
+
+        // Disabled by default
+        let mut engine = BacktestEngine::new(params, RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        });
+        let (timestamps, prices, volumes) = create_test_data();
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        engine.run().unwrap();
+        assert!(engine.trade_marked_equity().is_empty());
+    }
+
+    #[test]
+    fn test_display_decimals_round_reported_result_only() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 3.3,
+            ..Default::default()
+        };
+        let risk = RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        };
+        let (timestamps, prices, volumes) = create_test_data();
+
+        let mut full = BacktestEngine::new(params, risk);
+        full.load_data_from_vectors(timestamps.clone(), prices.clone(), volumes.clone()).unwrap();
+        let full_result = full.run().unwrap();
+
+        let mut engine = BacktestEngine::new(params, risk).with_display_decimals(2);
+        engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+        let result = engine.run().unwrap();
+
+        // Internal accounting is unaffected by display rounding
+        let account = engine.get_account_status();
+        assert_eq!(account.equity, full_result.final_equity);
+        assert_eq!(engine.equity_curve(), full.equity_curve());
+        assert!(result.total_trades > 0);
+        assert_ne!(account.equity, result.final_equity);
+
+        assert_eq!(result.final_equity, round_to_decimals(account.equity, 2));
+        assert_eq!(result.final_equity, (result.final_equity * 100.0).round() / 100.0);
+        assert_eq!(result.total_return_pct, round_to_decimals(full_result.total_return_pct, 2));
+        assert_eq!(result.max_drawdown_pct, round_to_decimals(full_result.max_drawdown_pct, 2));
+        assert_eq!(result.total_trades, full_result.total_trades);
+    }
+
+    #[test]
+    fn test_regime_filter_reduces_engine_trades() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let risk = RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        };
+        let run = |filter: Option<RegimeFilter>| {
+            let mut engine = BacktestEngine::new(params, risk);
+            if let Some(filter) = filter {
+                engine = engine.with_regime_filter(filter);
+            }
+            let (timestamps, prices, volumes) = create_test_data();
+            engine.load_data_from_vectors(timestamps, prices, volumes).unwrap();
+            let result = engine.run().unwrap();
+            (engine, result)
+        };
+
+        let (_, plain) = run(None);
+        // Smooth cycles cross over inside the bands, so breakouts are rare
+        let (mut filtered, breakout) = run(Some(RegimeFilter::Breakout));
+        assert!(plain.total_trades > 0);
+        assert!(breakout.total_trades < plain.total_trades);
+
+        // Updating parameters keeps the filter
+        filtered.update_strategy_params(params).unwrap();
+        assert_eq!(filtered.dual_ma_strategy().unwrap().regime_filter(), Some(RegimeFilter::Breakout));
+    }
 }
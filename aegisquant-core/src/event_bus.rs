@@ -9,11 +9,14 @@
 //! - Requirement 6.3: Strategy trait supports subscribing to multiple event types
 //! - Requirement 6.6: Provide subscribe_event FFI function
 
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use crossbeam_channel::{bounded, unbounded, Receiver, Sender, TryRecvError, TrySendError};
 
+use crate::error::EngineError;
 use crate::types::{AccountStatus, OrderRequest, Tick};
 
 /// Unique identifier for event subscriptions.
@@ -105,6 +108,18 @@ impl Event {
         }
     }
     
+    /// Get the event type as an `EVENT_TYPE_*` code.
+    pub fn type_code(&self) -> i32 {
+        match self {
+            Event::Tick(_) => EVENT_TYPE_TICK,
+            Event::Timer { .. } => EVENT_TYPE_TIMER,
+            Event::OrderUpdate { .. } => EVENT_TYPE_ORDER_UPDATE,
+            Event::AccountUpdate(_) => EVENT_TYPE_ACCOUNT_UPDATE,
+            Event::Signal { .. } => EVENT_TYPE_SIGNAL,
+            Event::Custom { .. } => EVENT_TYPE_CUSTOM,
+        }
+    }
+
     /// Create a new tick event.
     pub fn tick(tick: Tick) -> Self {
         Event::Tick(tick)
@@ -140,6 +155,19 @@ impl Event {
     }
 }
 
+/// Event tagged with the bus sequence number assigned at publish time.
+///
+/// Sequence numbers start at 1 and increase by one per published event, so
+/// a subscriber to every event type can detect drops as gaps. Subscribers
+/// with a narrower filter also see gaps for the events filtered out.
+#[derive(Debug, Clone)]
+pub struct SequencedEvent {
+    /// Bus-wide publish sequence number
+    pub seq: u64,
+    /// The published event
+    pub event: Event,
+}
+
 /// Event filter for selective subscription.
 #[derive(Debug, Clone, Default)]
 pub struct EventFilter {
@@ -199,13 +227,73 @@ impl EventFilter {
     }
 }
 
+/// Default channel capacity per event type.
+///
+/// Used by `EventBus::subscribe` to size a subscription's channel when no
+/// explicit capacity is given. A filter covering several event types gets
+/// the largest capacity among the selected types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventCapacities {
+    /// Capacity for tick subscriptions
+    pub tick: usize,
+    /// Capacity for timer subscriptions
+    pub timer: usize,
+    /// Capacity for order update subscriptions
+    pub order_update: usize,
+    /// Capacity for account update subscriptions
+    pub account_update: usize,
+    /// Capacity for signal subscriptions
+    pub signal: usize,
+    /// Capacity for custom event subscriptions
+    pub custom: usize,
+}
+
+impl Default for EventCapacities {
+    fn default() -> Self {
+        Self::uniform(1000)
+    }
+}
+
+impl EventCapacities {
+    /// Use the same capacity for every event type.
+    pub fn uniform(capacity: usize) -> Self {
+        Self {
+            tick: capacity,
+            timer: capacity,
+            order_update: capacity,
+            account_update: capacity,
+            signal: capacity,
+            custom: capacity,
+        }
+    }
+
+    /// Get the channel capacity for a filter.
+    ///
+    /// Returns the largest capacity among the event types selected by the
+    /// filter, or `None` if the filter selects no types.
+    pub fn capacity_for(&self, filter: &EventFilter) -> Option<usize> {
+        [
+            (filter.tick, self.tick),
+            (filter.timer, self.timer),
+            (filter.order_update, self.order_update),
+            (filter.account_update, self.account_update),
+            (filter.signal, self.signal),
+            (filter.custom, self.custom),
+        ]
+        .iter()
+        .filter(|(selected, _)| *selected)
+        .map(|(_, capacity)| *capacity)
+        .max()
+    }
+}
+
 /// Subscription handle for receiving events.
 #[derive(Debug)]
 pub struct Subscription {
     /// Unique subscription ID
     pub id: SubscriptionId,
     /// Event receiver channel
-    receiver: Receiver<Event>,
+    receiver: Receiver<SequencedEvent>,
     /// Event filter
     filter: EventFilter,
 }
@@ -213,17 +301,27 @@ pub struct Subscription {
 impl Subscription {
     /// Try to receive an event without blocking.
     pub fn try_recv(&self) -> Result<Event, TryRecvError> {
-        self.receiver.try_recv()
+        self.try_recv_sequenced().map(|e| e.event)
     }
     
     /// Receive an event, blocking until one is available.
     pub fn recv(&self) -> Result<Event, crossbeam_channel::RecvError> {
-        self.receiver.recv()
+        self.recv_sequenced().map(|e| e.event)
     }
     
     /// Receive an event with a timeout.
     pub fn recv_timeout(&self, timeout: std::time::Duration) -> Result<Event, crossbeam_channel::RecvTimeoutError> {
-        self.receiver.recv_timeout(timeout)
+        self.receiver.recv_timeout(timeout).map(|e| e.event)
+    }
+
+    /// Try to receive an event with its sequence number without blocking.
+    pub fn try_recv_sequenced(&self) -> Result<SequencedEvent, TryRecvError> {
+        self.receiver.try_recv()
+    }
+
+    /// Receive an event with its sequence number, blocking until one is available.
+    pub fn recv_sequenced(&self) -> Result<SequencedEvent, crossbeam_channel::RecvError> {
+        self.receiver.recv()
     }
     
     /// Check if there are pending events.
@@ -235,6 +333,11 @@ impl Subscription {
     pub fn len(&self) -> usize {
         self.receiver.len()
     }
+
+    /// Get the channel capacity (`None` for unbounded subscriptions).
+    pub fn capacity(&self) -> Option<usize> {
+        self.receiver.capacity()
+    }
     
     /// Get the event filter for this subscription.
     pub fn filter(&self) -> &EventFilter {
@@ -246,27 +349,52 @@ impl Subscription {
 #[derive(Debug)]
 struct SubscriberEntry {
     id: SubscriptionId,
-    sender: Sender<Event>,
+    sender: Sender<SequencedEvent>,
     filter: EventFilter,
 }
 
+/// Callback invoked synchronously for each matching event.
+pub type EventHandler = Box<dyn FnMut(&Event) + Send>;
+
+/// Internal callback subscriber entry.
+struct CallbackEntry {
+    id: SubscriptionId,
+    callback: EventHandler,
+    filter: EventFilter,
+}
+
+impl fmt::Debug for CallbackEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackEntry")
+            .field("id", &self.id)
+            .field("filter", &self.filter)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Event bus for publish-subscribe communication.
 ///
 /// The event bus allows components to publish events and subscribe to
 /// receive events of interest. It uses crossbeam-channel for efficient
-/// multi-threaded event delivery.
+/// multi-threaded event delivery, or calls in-process callbacks directly.
 #[derive(Debug)]
 pub struct EventBus {
     /// Subscribers list
     subscribers: Vec<SubscriberEntry>,
+    /// Callback subscribers, invoked inside `publish`
+    callbacks: Vec<CallbackEntry>,
     /// Default channel capacity for bounded subscriptions
     default_capacity: usize,
+    /// Per-event-type default capacities
+    capacities: EventCapacities,
     /// Statistics: total events published
     events_published: u64,
     /// Statistics: total events delivered
     events_delivered: u64,
     /// Statistics: events dropped due to full channels
     events_dropped: u64,
+    /// Statistics broken down by event type, indexed by `EVENT_TYPE_*`
+    type_stats: [EventTypeStats; EVENT_TYPE_COUNT],
 }
 
 impl Default for EventBus {
@@ -280,16 +408,44 @@ impl EventBus {
     pub fn new(default_capacity: usize) -> Self {
         Self {
             subscribers: Vec::new(),
+            callbacks: Vec::new(),
             default_capacity,
+            capacities: EventCapacities::uniform(default_capacity),
             events_published: 0,
             events_delivered: 0,
             events_dropped: 0,
+            type_stats: std::array::from_fn(|code| EventTypeStats {
+                event_type: code as i32,
+                ..Default::default()
+            }),
         }
     }
     
-    /// Subscribe to events with the default capacity and filter.
+    /// Set per-event-type default capacities.
+    pub fn with_capacities(mut self, capacities: EventCapacities) -> Self {
+        self.capacities = capacities;
+        self
+    }
+
+    /// Set per-event-type default capacities.
+    pub fn set_capacities(&mut self, capacities: EventCapacities) {
+        self.capacities = capacities;
+    }
+
+    /// Get the per-event-type default capacities.
+    pub fn capacities(&self) -> &EventCapacities {
+        &self.capacities
+    }
+
+    /// Subscribe to events with the filter's per-type default capacity.
+    ///
+    /// Falls back to the bus-wide default capacity if the filter selects no types.
     pub fn subscribe(&mut self, filter: EventFilter) -> Subscription {
-        self.subscribe_with_capacity(filter, self.default_capacity)
+        let capacity = self
+            .capacities
+            .capacity_for(&filter)
+            .unwrap_or(self.default_capacity);
+        self.subscribe_with_capacity(filter, capacity)
     }
     
     /// Subscribe to events with a specific channel capacity.
@@ -328,29 +484,73 @@ impl EventBus {
         }
     }
     
-    /// Unsubscribe from events.
+    /// Subscribe a callback that `publish` invokes for each matching event.
+    ///
+    /// The callback runs synchronously on the publishing thread, so no
+    /// polling is needed. A callback that panics is counted as a dropped
+    /// delivery and stays subscribed.
+    pub fn subscribe_fn(&mut self, filter: EventFilter, callback: EventHandler) -> SubscriptionId {
+        let id = next_subscription_id();
+        self.callbacks.push(CallbackEntry {
+            id,
+            callback,
+            filter,
+        });
+        id
+    }
+
+    /// Unsubscribe from events (channel or callback subscription).
     pub fn unsubscribe(&mut self, subscription_id: SubscriptionId) -> bool {
-        let initial_len = self.subscribers.len();
+        let initial_len = self.subscriber_count();
         self.subscribers.retain(|s| s.id != subscription_id);
-        self.subscribers.len() < initial_len
+        self.callbacks.retain(|c| c.id != subscription_id);
+        self.subscriber_count() < initial_len
+    }
+
+    /// Invoke matching callbacks for an already-counted publish.
+    ///
+    /// Returns the number of callbacks that completed without panicking.
+    fn deliver_to_callbacks(&mut self, event: &Event) -> usize {
+        let type_stats = &mut self.type_stats[event.type_code() as usize];
+        let mut delivered = 0;
+
+        for entry in self.callbacks.iter_mut().filter(|c| c.filter.matches(event)) {
+            if catch_unwind(AssertUnwindSafe(|| (entry.callback)(event))).is_ok() {
+                delivered += 1;
+                self.events_delivered += 1;
+                type_stats.events_delivered += 1;
+            } else {
+                self.events_dropped += 1;
+                type_stats.events_dropped += 1;
+            }
+        }
+
+        delivered
     }
     
     /// Publish an event to all matching subscribers.
     ///
-    /// Returns the number of subscribers that received the event.
+    /// The event is assigned the next sequence number. Returns the number of
+    /// subscribers that received the event.
     pub fn publish(&mut self, event: Event) -> usize {
+        let type_stats = &mut self.type_stats[event.type_code() as usize];
         self.events_published += 1;
+        type_stats.events_published += 1;
+        let seq = self.events_published;
         let mut delivered = 0;
         
         for subscriber in &self.subscribers {
             if subscriber.filter.matches(&event) {
-                match subscriber.sender.try_send(event.clone()) {
+                let sequenced = SequencedEvent { seq, event: event.clone() };
+                match subscriber.sender.try_send(sequenced) {
                     Ok(()) => {
                         delivered += 1;
                         self.events_delivered += 1;
+                        type_stats.events_delivered += 1;
                     }
                     Err(TrySendError::Full(_)) => {
                         self.events_dropped += 1;
+                        type_stats.events_dropped += 1;
                     }
                     Err(TrySendError::Disconnected(_)) => {
                         // Subscriber disconnected, will be cleaned up later
@@ -359,31 +559,36 @@ impl EventBus {
             }
         }
         
-        delivered
+        delivered + self.deliver_to_callbacks(&event)
     }
     
     /// Publish an event, blocking if channels are full.
     ///
-    /// Returns the number of subscribers that received the event.
+    /// The event is assigned the next sequence number. Returns the number of
+    /// subscribers that received the event.
     pub fn publish_blocking(&mut self, event: Event) -> usize {
+        let type_stats = &mut self.type_stats[event.type_code() as usize];
         self.events_published += 1;
+        type_stats.events_published += 1;
+        let seq = self.events_published;
         let mut delivered = 0;
         
         for subscriber in &self.subscribers {
             if subscriber.filter.matches(&event)
-                && subscriber.sender.send(event.clone()).is_ok()
+                && subscriber.sender.send(SequencedEvent { seq, event: event.clone() }).is_ok()
             {
                 delivered += 1;
                 self.events_delivered += 1;
+                type_stats.events_delivered += 1;
             }
         }
         
-        delivered
+        delivered + self.deliver_to_callbacks(&event)
     }
     
-    /// Get the number of active subscribers.
+    /// Get the number of active subscribers, including callbacks.
     pub fn subscriber_count(&self) -> usize {
-        self.subscribers.len()
+        self.subscribers.len() + self.callbacks.len()
     }
     
     /// Clean up disconnected subscribers.
@@ -394,12 +599,21 @@ impl EventBus {
     /// Get statistics about the event bus.
     pub fn stats(&self) -> EventBusStats {
         EventBusStats {
-            subscriber_count: self.subscribers.len(),
+            subscriber_count: self.subscriber_count(),
             events_published: self.events_published,
             events_delivered: self.events_delivered,
             events_dropped: self.events_dropped,
+            last_seq: self.events_published,
         }
     }
+
+    /// Get statistics per event type, indexed by `EVENT_TYPE_*`.
+    ///
+    /// Each counter summed over all types equals the matching aggregate in
+    /// `stats()`.
+    pub fn stats_by_type(&self) -> &[EventTypeStats; EVENT_TYPE_COUNT] {
+        &self.type_stats
+    }
 }
 
 /// Statistics about the event bus.
@@ -414,6 +628,22 @@ pub struct EventBusStats {
     pub events_delivered: u64,
     /// Events dropped due to full channels
     pub events_dropped: u64,
+    /// Sequence number of the last published event (0 if none)
+    pub last_seq: u64,
+}
+
+/// Statistics for a single event type.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EventTypeStats {
+    /// Event type (EVENT_TYPE_*)
+    pub event_type: i32,
+    /// Events of this type published
+    pub events_published: u64,
+    /// Events of this type delivered
+    pub events_delivered: u64,
+    /// Events of this type dropped due to full channels
+    pub events_dropped: u64,
 }
 
 /// Trait for event-driven strategies.
@@ -464,6 +694,8 @@ pub const EVENT_TYPE_ORDER_UPDATE: i32 = 2;
 pub const EVENT_TYPE_ACCOUNT_UPDATE: i32 = 3;
 pub const EVENT_TYPE_SIGNAL: i32 = 4;
 pub const EVENT_TYPE_CUSTOM: i32 = 5;
+/// Number of event types.
+pub const EVENT_TYPE_COUNT: usize = 6;
 
 /// FFI-safe event callback type.
 pub type EventCallback = extern "C" fn(event_type: i32, data: *const c_void, data_len: usize);
@@ -580,6 +812,42 @@ pub unsafe extern "C" fn get_event_bus_stats(
     ERR_SUCCESS
 }
 
+/// Get event bus statistics broken down by event type.
+///
+/// Writes one entry per event type, in `EVENT_TYPE_*` order, up to `len`
+/// entries.
+///
+/// # Safety
+/// - `event_bus` must be a valid pointer
+/// - `out_array` must be valid for `len` writes
+///
+/// # Returns
+/// - ERR_SUCCESS if all `EVENT_TYPE_COUNT` entries were written
+/// - ERR_BUFFER_TOO_SMALL if `len` is less than `EVENT_TYPE_COUNT`
+/// - ERR_NULL_POINTER if either pointer is null
+#[no_mangle]
+pub unsafe extern "C" fn get_event_bus_stats_by_type(
+    event_bus: *const EventBus,
+    out_array: *mut EventTypeStats,
+    len: usize,
+) -> i32 {
+    use crate::ffi::{ERR_BUFFER_TOO_SMALL, ERR_NULL_POINTER, ERR_SUCCESS};
+
+    if event_bus.is_null() || out_array.is_null() {
+        return ERR_NULL_POINTER;
+    }
+
+    let stats = (*event_bus).stats_by_type();
+    let copy_len = stats.len().min(len);
+    std::ptr::copy_nonoverlapping(stats.as_ptr(), out_array, copy_len);
+
+    if copy_len < stats.len() {
+        ERR_BUFFER_TOO_SMALL
+    } else {
+        ERR_SUCCESS
+    }
+}
+
 // ============================================================================
 // Timer Management
 // ============================================================================
@@ -595,6 +863,110 @@ fn next_timer_id() -> TimerId {
     NEXT_TIMER_ID.fetch_add(1, Ordering::SeqCst)
 }
 
+/// Milliseconds per minute, the resolution of cron schedules.
+const MS_PER_MINUTE: i64 = 60_000;
+
+/// Minute/hour/day-of-week schedule for `TimerManager::schedule_cron`.
+///
+/// Parsed from `"minute hour day-of-week"`. Each field is `*`, a value, a
+/// range `a-b`, a step `*/n`, `a/n` or `a-b/n`, or a comma-separated list of
+/// those. Days of week run 0-6 from Sunday (7 is also Sunday). Times are
+/// epoch milliseconds in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronSchedule {
+    /// Bit per matching minute (0-59)
+    minutes: u64,
+    /// Bit per matching hour (0-23)
+    hours: u64,
+    /// Bit per matching day of week (0 = Sunday)
+    days_of_week: u64,
+}
+
+impl CronSchedule {
+    /// Parse a `"minute hour day-of-week"` expression.
+    pub fn parse(expr: &str) -> Result<Self, EngineError> {
+        let invalid = |reason: String| {
+            EngineError::invalid_param("cron", format!("'{}': {}", expr, reason))
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day_of_week] = fields.as_slice() else {
+            return Err(invalid("expected 3 fields: minute hour day-of-week".to_string()));
+        };
+
+        let mut days_of_week = Self::parse_field(day_of_week, 7).map_err(invalid)?;
+        // 7 is an alias for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: Self::parse_field(minute, 59).map_err(invalid)?,
+            hours: Self::parse_field(hour, 23).map_err(invalid)?,
+            days_of_week,
+        })
+    }
+
+    /// Parse one field into a bitmask of values in `0..=max`.
+    fn parse_field(field: &str, max: u32) -> Result<u64, String> {
+        let parse_value = |value: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("invalid value '{}'", value))
+        };
+
+        let mut mask = 0u64;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => match step.parse::<usize>() {
+                    Ok(step) if step > 0 => (range, step),
+                    _ => return Err(format!("invalid step '{}'", step)),
+                },
+                None => (part, 1),
+            };
+            let (start, end) = if range == "*" {
+                (0, max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (parse_value(start)?, parse_value(end)?)
+            } else {
+                // A single value with a step runs to the end of the range
+                let start = parse_value(range)?;
+                (start, if step > 1 { max } else { start })
+            };
+            if start > end || end > max {
+                return Err(format!("'{}' outside 0-{}", part, max));
+            }
+            for value in (start..=end).step_by(step) {
+                mask |= 1 << value;
+            }
+        }
+        Ok(mask)
+    }
+
+    /// Check whether the minute containing `time_ms` matches the schedule.
+    pub fn matches(&self, time_ms: i64) -> bool {
+        let minutes = time_ms.div_euclid(MS_PER_MINUTE);
+        let minute = minutes.rem_euclid(60);
+        let hour = minutes.div_euclid(60).rem_euclid(24);
+        // 1970-01-01 was a Thursday
+        let day_of_week = (minutes.div_euclid(24 * 60) + 4).rem_euclid(7);
+
+        self.minutes & (1 << minute) != 0
+            && self.hours & (1 << hour) != 0
+            && self.days_of_week & (1 << day_of_week) != 0
+    }
+
+    /// Get the first matching minute boundary strictly after `after_ms`.
+    pub fn next_after(&self, after_ms: i64) -> i64 {
+        let start = (after_ms.div_euclid(MS_PER_MINUTE) + 1) * MS_PER_MINUTE;
+        // Every field matches at least one value, so some minute of any
+        // 7-day window matches
+        (0..7 * 24 * 60)
+            .map(|i| start + i * MS_PER_MINUTE)
+            .find(|&t| self.matches(t))
+            .unwrap_or(start)
+    }
+}
+
 /// Timer entry for scheduled callbacks.
 #[derive(Debug, Clone)]
 pub struct TimerEntry {
@@ -608,6 +980,10 @@ pub struct TimerEntry {
     pub active: bool,
     /// Whether this is a repeating timer
     pub repeating: bool,
+    /// Event delivered instead of a timer event when this timer fires
+    pub payload: Option<Event>,
+    /// Cron schedule that sets the next trigger instead of `interval_ms`
+    pub cron: Option<CronSchedule>,
 }
 
 impl TimerEntry {
@@ -619,6 +995,16 @@ impl TimerEntry {
             next_trigger_ms: trigger_at_ms,
             active: true,
             repeating: false,
+            payload: None,
+            cron: None,
+        }
+    }
+
+    /// Create a one-shot timer that delivers `event` when it fires.
+    pub fn delayed_event(trigger_at_ms: i64, event: Event) -> Self {
+        Self {
+            payload: Some(event),
+            ..Self::one_shot(trigger_at_ms)
         }
     }
     
@@ -630,6 +1016,21 @@ impl TimerEntry {
             next_trigger_ms: start_at_ms + interval_ms as i64,
             active: true,
             repeating: true,
+            payload: None,
+            cron: None,
+        }
+    }
+
+    /// Create a timer that fires at each boundary of `schedule` after `start_at_ms`.
+    pub fn cron(schedule: CronSchedule, start_at_ms: i64) -> Self {
+        Self {
+            id: next_timer_id(),
+            interval_ms: 0,
+            next_trigger_ms: schedule.next_after(start_at_ms),
+            active: true,
+            repeating: true,
+            payload: None,
+            cron: Some(schedule),
         }
     }
     
@@ -640,7 +1041,9 @@ impl TimerEntry {
     
     /// Advance the timer to the next trigger time (for repeating timers).
     pub fn advance(&mut self) {
-        if self.repeating && self.interval_ms > 0 {
+        if let Some(schedule) = self.cron {
+            self.next_trigger_ms = schedule.next_after(self.next_trigger_ms);
+        } else if self.repeating && self.interval_ms > 0 {
             self.next_trigger_ms += self.interval_ms as i64;
         } else {
             self.active = false;
@@ -684,6 +1087,17 @@ impl TimerManager {
         id
     }
     
+    /// Schedule an event to be returned by `process` after a delay.
+    ///
+    /// Events due in the same `process` call are returned in the order
+    /// they were scheduled.
+    pub fn schedule_event(&mut self, delay_ms: u64, event: Event) -> TimerId {
+        let timer = TimerEntry::delayed_event(self.current_time_ms + delay_ms as i64, event);
+        let id = timer.id;
+        self.timers.push(timer);
+        id
+    }
+
     /// Schedule a repeating timer.
     pub fn schedule_repeating(&mut self, interval_ms: u64) -> TimerId {
         let timer = TimerEntry::repeating(interval_ms, self.current_time_ms);
@@ -692,6 +1106,19 @@ impl TimerManager {
         id
     }
     
+    /// Schedule a repeating timer from a cron expression.
+    ///
+    /// The expression is `"minute hour day-of-week"` (see `CronSchedule`),
+    /// evaluated against the manager's time as UTC epoch milliseconds. The
+    /// first trigger is the next matching minute after the current time.
+    pub fn schedule_cron(&mut self, expr: &str) -> Result<TimerId, EngineError> {
+        let schedule = CronSchedule::parse(expr)?;
+        let timer = TimerEntry::cron(schedule, self.current_time_ms);
+        let id = timer.id;
+        self.timers.push(timer);
+        Ok(id)
+    }
+
     /// Cancel a timer.
     pub fn cancel(&mut self, timer_id: TimerId) -> bool {
         if let Some(timer) = self.timers.iter_mut().find(|t| t.id == timer_id) {
@@ -709,7 +1136,10 @@ impl TimerManager {
         
         for timer in &mut self.timers {
             if timer.should_fire(current_time_ms) {
-                events.push(Event::timer(timer.id, current_time_ms));
+                match timer.payload.take() {
+                    Some(event) => events.push(event),
+                    None => events.push(Event::timer(timer.id, current_time_ms)),
+                }
                 timer.advance();
             }
         }
@@ -736,19 +1166,80 @@ impl TimerManager {
 // ============================================================================
 
 /// Helper struct for sending order update events.
+///
+/// By default updates are published immediately. With a confirmation delay,
+/// updates are scheduled on a `TimerManager` and published by
+/// `deliver_due` once the simulated clock reaches them, keeping the order in
+/// which they were sent.
 pub struct OrderUpdateSender<'a> {
     event_bus: &'a mut EventBus,
+    /// Timer manager holding delayed updates (None = synchronous)
+    timers: Option<&'a mut TimerManager>,
+    /// Confirmation delay in simulated milliseconds
+    delay_ms: u64,
 }
 
 impl<'a> OrderUpdateSender<'a> {
     /// Create a new order update sender.
     pub fn new(event_bus: &'a mut EventBus) -> Self {
-        Self { event_bus }
+        Self {
+            event_bus,
+            timers: None,
+            delay_ms: 0,
+        }
+    }
+
+    /// Create a sender that delays updates by `delay_ms` simulated millis.
+    ///
+    /// A delay of 0 publishes synchronously, like `new`.
+    pub fn with_delay(
+        event_bus: &'a mut EventBus,
+        timers: &'a mut TimerManager,
+        delay_ms: u64,
+    ) -> Self {
+        Self {
+            event_bus,
+            timers: Some(timers),
+            delay_ms,
+        }
+    }
+
+    /// Get the confirmation delay in milliseconds.
+    pub fn delay_ms(&self) -> u64 {
+        self.delay_ms
+    }
+
+    /// Process the timer manager and publish every event now due.
+    ///
+    /// Returns the number of events published. Plain timer events due at
+    /// the same time are published too, as `process_timers` does.
+    pub fn deliver_due(&mut self, current_time_ms: i64) -> usize {
+        let events = match self.timers.as_mut() {
+            Some(timers) => timers.process(current_time_ms),
+            None => return 0,
+        };
+        let count = events.len();
+        for event in events {
+            self.event_bus.publish(event);
+        }
+        count
+    }
+
+    /// Publish an update now, or schedule it if a delay is configured.
+    fn send(&mut self, event: Event) {
+        match self.timers.as_mut() {
+            Some(timers) if self.delay_ms > 0 => {
+                timers.schedule_event(self.delay_ms, event);
+            }
+            _ => {
+                self.event_bus.publish(event);
+            }
+        }
     }
     
     /// Send an order submitted event.
     pub fn order_submitted(&mut self, order_id: u64) {
-        self.event_bus.publish(Event::order_update(
+        self.send(Event::order_update(
             order_id,
             OrderStatus::Pending,
             0.0,
@@ -758,7 +1249,7 @@ impl<'a> OrderUpdateSender<'a> {
     
     /// Send an order partially filled event.
     pub fn order_partially_filled(&mut self, order_id: u64, filled_quantity: f64, fill_price: f64) {
-        self.event_bus.publish(Event::order_update(
+        self.send(Event::order_update(
             order_id,
             OrderStatus::PartiallyFilled,
             filled_quantity,
@@ -768,7 +1259,7 @@ impl<'a> OrderUpdateSender<'a> {
     
     /// Send an order filled event.
     pub fn order_filled(&mut self, order_id: u64, filled_quantity: f64, fill_price: f64) {
-        self.event_bus.publish(Event::order_update(
+        self.send(Event::order_update(
             order_id,
             OrderStatus::Filled,
             filled_quantity,
@@ -778,7 +1269,7 @@ impl<'a> OrderUpdateSender<'a> {
     
     /// Send an order cancelled event.
     pub fn order_cancelled(&mut self, order_id: u64) {
-        self.event_bus.publish(Event::order_update(
+        self.send(Event::order_update(
             order_id,
             OrderStatus::Cancelled,
             0.0,
@@ -788,7 +1279,7 @@ impl<'a> OrderUpdateSender<'a> {
     
     /// Send an order rejected event.
     pub fn order_rejected(&mut self, order_id: u64) {
-        self.event_bus.publish(Event::order_update(
+        self.send(Event::order_update(
             order_id,
             OrderStatus::Rejected,
             0.0,
@@ -984,6 +1475,68 @@ mod tests {
         assert!(!removed);
     }
     
+    #[test]
+    fn test_callback_subscription_delivery() {
+        let mut bus = EventBus::new(100);
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let sink = Arc::clone(&seen);
+        let id = bus.subscribe_fn(
+            EventFilter::tick_only(),
+            Box::new(move |event| {
+                if let Event::Tick(tick) = event {
+                    sink.lock().unwrap().push(tick.price);
+                }
+            }),
+        );
+        let sub = bus.subscribe(EventFilter::all());
+        assert_eq!(bus.subscriber_count(), 2);
+
+        let tick = Tick { price: 101.5, ..Default::default() };
+        // Channel subscriber plus callback
+        assert_eq!(bus.publish(Event::tick(tick)), 2);
+        // Filtered out for the callback
+        assert_eq!(bus.publish(Event::timer(1, 0)), 1);
+        assert_eq!(bus.publish_blocking(Event::tick(tick)), 2);
+
+        assert_eq!(*seen.lock().unwrap(), vec![101.5, 101.5]);
+        assert_eq!(sub.len(), 3);
+        assert_eq!(bus.stats().events_delivered, 5);
+
+        assert!(bus.unsubscribe(id));
+        assert!(!bus.unsubscribe(id));
+        assert_eq!(bus.publish(Event::tick(tick)), 1);
+        assert_eq!(seen.lock().unwrap().len(), 2);
+        assert_eq!(bus.subscriber_count(), 1);
+    }
+
+    #[test]
+    fn test_panicking_callback_counts_as_dropped() {
+        let mut bus = EventBus::new(100);
+        let calls = Arc::new(AtomicU64::new(0));
+
+        bus.subscribe_fn(EventFilter::all(), Box::new(|_| panic!("strategy bug")));
+        let counter = Arc::clone(&calls);
+        bus.subscribe_fn(
+            EventFilter::all(),
+            Box::new(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+        );
+
+        // The bus stays usable and later callbacks still run
+        for _ in 0..2 {
+            assert_eq!(bus.publish(Event::timer(1, 0)), 1);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let stats = bus.stats();
+        assert_eq!(stats.events_delivered, 2);
+        assert_eq!(stats.events_dropped, 2);
+        assert_eq!(bus.stats_by_type()[EVENT_TYPE_TIMER as usize].events_dropped, 2);
+        assert_eq!(bus.subscriber_count(), 2);
+    }
+
     #[test]
     fn test_subscription_receive() {
         let mut bus = EventBus::new(100);
@@ -1031,7 +1584,111 @@ mod tests {
         assert_eq!(stats.events_delivered, 2);
         assert_eq!(stats.events_dropped, 1);
     }
-    
+
+    #[test]
+    fn test_sequence_gap_matches_drop_count() {
+        let mut bus = EventBus::new(3);
+        let sub = bus.subscribe(EventFilter::all());
+
+        // 3 fit, the next 4 are dropped
+        for _ in 0..7 {
+            bus.publish(Event::tick(Tick::default()));
+        }
+        let first: Vec<u64> = std::iter::from_fn(|| sub.try_recv_sequenced().ok())
+            .map(|e| e.seq)
+            .collect();
+        assert_eq!(first, vec![1, 2, 3]);
+
+        // After draining, delivery resumes with the current sequence
+        bus.publish(Event::timer(1, 0));
+        let next = sub.try_recv_sequenced().unwrap();
+        assert_eq!(next.event.event_type(), "Timer");
+
+        let stats = bus.stats();
+        assert_eq!(stats.last_seq, 8);
+        assert_eq!(next.seq, stats.last_seq);
+        let gap = next.seq - first.last().unwrap() - 1;
+        assert_eq!(gap, stats.events_dropped);
+        assert_eq!(gap, 4);
+    }
+
+    #[test]
+    fn test_stats_by_type_breaks_down_drops() {
+        let mut bus = EventBus::new(3);
+        let _sub = bus.subscribe(EventFilter::all());
+
+        // Three events fill the channel; everything after is dropped
+        bus.publish(Event::tick(Tick::default()));
+        bus.publish(Event::order_update(1, OrderStatus::Filled, 1.0, 100.0));
+        bus.publish(Event::tick(Tick::default()));
+        for _ in 0..4 {
+            bus.publish(Event::tick(Tick::default()));
+        }
+        bus.publish(Event::order_update(2, OrderStatus::Filled, 1.0, 100.0));
+
+        let by_type = bus.stats_by_type();
+        let ticks = by_type[EVENT_TYPE_TICK as usize];
+        assert_eq!((ticks.events_published, ticks.events_delivered, ticks.events_dropped), (6, 2, 4));
+        let orders = by_type[EVENT_TYPE_ORDER_UPDATE as usize];
+        assert_eq!((orders.events_published, orders.events_delivered, orders.events_dropped), (2, 1, 1));
+        assert_eq!(by_type[EVENT_TYPE_TIMER as usize], EventTypeStats {
+            event_type: EVENT_TYPE_TIMER,
+            ..Default::default()
+        });
+
+        // Per-type counters sum to the aggregate
+        let stats = bus.stats();
+        assert_eq!(by_type.iter().map(|s| s.events_published).sum::<u64>(), stats.events_published);
+        assert_eq!(by_type.iter().map(|s| s.events_delivered).sum::<u64>(), stats.events_delivered);
+        assert_eq!(by_type.iter().map(|s| s.events_dropped).sum::<u64>(), stats.events_dropped);
+
+        // FFI copy reports a short buffer
+        let mut out = [EventTypeStats::default(); EVENT_TYPE_COUNT];
+        let code = unsafe { get_event_bus_stats_by_type(&bus, out.as_mut_ptr(), 2) };
+        assert_eq!(code, crate::ffi::ERR_BUFFER_TOO_SMALL);
+        assert_eq!(out[0], ticks);
+        assert_eq!(out[2], EventTypeStats::default());
+        let code = unsafe { get_event_bus_stats_by_type(&bus, out.as_mut_ptr(), out.len()) };
+        assert_eq!(code, crate::ffi::ERR_SUCCESS);
+        assert_eq!(&out, by_type);
+    }
+
+    #[test]
+    fn test_per_type_capacities() {
+        let capacities = EventCapacities {
+            tick: 10_000,
+            account_update: 16,
+            ..EventCapacities::uniform(100)
+        };
+        let mut bus = EventBus::new(100).with_capacities(capacities);
+
+        let ticks = bus.subscribe(EventFilter::tick_only());
+        let accounts = bus.subscribe(EventFilter {
+            account_update: true,
+            ..Default::default()
+        });
+        let orders = bus.subscribe(EventFilter::orders_only());
+        let everything = bus.subscribe(EventFilter::all());
+        let nothing = bus.subscribe(EventFilter::default());
+
+        assert_eq!(ticks.capacity(), Some(10_000));
+        assert_eq!(accounts.capacity(), Some(16));
+        assert_eq!(orders.capacity(), Some(100));
+        assert_eq!(everything.capacity(), Some(10_000));
+        assert_eq!(nothing.capacity(), Some(100));
+    }
+
+    #[test]
+    fn test_explicit_capacity_overrides_per_type() {
+        let mut bus = EventBus::new(100).with_capacities(EventCapacities {
+            tick: 10_000,
+            ..EventCapacities::uniform(100)
+        });
+
+        let sub = bus.subscribe_with_capacity(EventFilter::tick_only(), 5);
+        assert_eq!(sub.capacity(), Some(5));
+    }
+
     #[test]
     fn test_shared_event_bus() {
         let bus = create_shared_event_bus(100);
@@ -1089,6 +1746,63 @@ mod tests {
         assert_eq!(manager.active_count(), 1);
     }
     
+    /// 2024-01-01 00:00 UTC, a Monday.
+    const MONDAY_MS: i64 = 1_704_067_200_000;
+    const DAY_MS: i64 = 86_400_000;
+    const NINE_THIRTY_MS: i64 = (9 * 60 + 30) * MS_PER_MINUTE;
+
+    #[test]
+    fn test_cron_daily_crosses_day_boundary() {
+        let mut manager = TimerManager::new();
+        manager.set_time(MONDAY_MS + 12 * 3_600_000);
+        let id = manager.schedule_cron("30 9 *").unwrap();
+
+        // Monday's 09:30 has passed; the next trigger is Tuesday 09:30
+        let tuesday = MONDAY_MS + DAY_MS + NINE_THIRTY_MS;
+        assert!(manager.process(tuesday - 1).is_empty());
+        let events = manager.process(tuesday);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Event::Timer { id: fired, timestamp } if fired == id && timestamp == tuesday));
+
+        assert!(manager.process(tuesday + DAY_MS - 1).is_empty());
+        assert_eq!(manager.process(tuesday + DAY_MS).len(), 1);
+        assert_eq!(manager.active_count(), 1);
+    }
+
+    #[test]
+    fn test_cron_weekdays_skip_weekend() {
+        let mut manager = TimerManager::new();
+        // Thursday 10:00
+        manager.set_time(MONDAY_MS + 3 * DAY_MS + 10 * 3_600_000);
+        manager.schedule_cron("30 9 1-5").unwrap();
+
+        let friday = MONDAY_MS + 4 * DAY_MS + NINE_THIRTY_MS;
+        assert_eq!(manager.process(friday).len(), 1);
+        for weekend_day in [friday + DAY_MS, friday + 2 * DAY_MS] {
+            assert!(manager.process(weekend_day).is_empty());
+        }
+        assert_eq!(manager.process(friday + 3 * DAY_MS).len(), 1);
+    }
+
+    #[test]
+    fn test_cron_parse() {
+        let schedule = CronSchedule::parse("*/15 9-16 1,3,7").unwrap();
+        // Monday 09:45 and Sunday (7) 16:00 match; Tuesday and 09:50 do not
+        assert!(schedule.matches(MONDAY_MS + NINE_THIRTY_MS + 15 * MS_PER_MINUTE));
+        assert!(schedule.matches(MONDAY_MS - DAY_MS + 16 * 3_600_000));
+        assert!(!schedule.matches(MONDAY_MS + DAY_MS + NINE_THIRTY_MS));
+        assert!(!schedule.matches(MONDAY_MS + NINE_THIRTY_MS + 20 * MS_PER_MINUTE));
+
+        for expr in ["30 9", "60 9 *", "30 24 *", "*/0 * *", "5-3 * *", "x 9 *", "30 9 8"] {
+            assert!(
+                matches!(CronSchedule::parse(expr), Err(EngineError::InvalidParameter { .. })),
+                "{}",
+                expr
+            );
+        }
+        assert!(TimerManager::new().schedule_cron("bad").is_err());
+    }
+
     #[test]
     fn test_timer_cancel() {
         let mut manager = TimerManager::new();
@@ -1137,4 +1851,56 @@ mod tests {
             panic!("Expected OrderUpdate event");
         }
     }
+
+    #[test]
+    fn test_order_update_sender_delayed_delivery() {
+        let mut bus = EventBus::new(100);
+        let sub = bus.subscribe(EventFilter::orders_only());
+        let mut timers = TimerManager::new();
+        timers.set_time(1000);
+
+        {
+            let mut sender = OrderUpdateSender::with_delay(&mut bus, &mut timers, 50);
+            sender.order_submitted(7);
+            sender.order_filled(7, 10.0, 99.5);
+
+            // Nothing is published before the delay elapses
+            assert_eq!(sender.deliver_due(1049), 0);
+        }
+        assert!(sub.is_empty());
+        assert_eq!(timers.active_count(), 2);
+
+        {
+            let mut sender = OrderUpdateSender::with_delay(&mut bus, &mut timers, 50);
+            assert_eq!(sender.deliver_due(1050), 2);
+        }
+        assert_eq!(timers.active_count(), 0);
+
+        let statuses: Vec<OrderStatus> = std::iter::from_fn(|| sub.try_recv().ok())
+            .map(|event| match event {
+                Event::OrderUpdate { order_id, status, .. } => {
+                    assert_eq!(order_id, 7);
+                    status
+                }
+                _ => panic!("Expected OrderUpdate event"),
+            })
+            .collect();
+        assert_eq!(statuses, vec![OrderStatus::Pending, OrderStatus::Filled]);
+    }
+
+    #[test]
+    fn test_order_update_sender_zero_delay_is_synchronous() {
+        let mut bus = EventBus::new(100);
+        let sub = bus.subscribe(EventFilter::orders_only());
+        let mut timers = TimerManager::new();
+
+        {
+            let mut sender = OrderUpdateSender::with_delay(&mut bus, &mut timers, 0);
+            sender.order_submitted(1);
+            sender.order_filled(1, 5.0, 10.0);
+        }
+
+        assert_eq!(sub.len(), 2);
+        assert_eq!(timers.active_count(), 0);
+    }
 }
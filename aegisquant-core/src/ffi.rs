@@ -4,8 +4,10 @@
 //! Error handling uses return codes instead of panics to ensure FFI safety.
 
 use std::ffi::c_char;
-use std::panic::catch_unwind;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 
+use crate::engine::BacktestEngine;
+use crate::persistence::FfiTradeRecord;
 use crate::types::*;
 
 // ============================================================================
@@ -34,37 +36,39 @@ pub const ERR_THROTTLE_EXCEEDED: i32 = -8;
 pub const ERR_POSITION_LIMIT: i32 = -9;
 /// File not found
 pub const ERR_FILE_NOT_FOUND: i32 = -10;
+/// Caller-provided buffer is too small; the required length is still reported
+pub const ERR_BUFFER_TOO_SMALL: i32 = -11;
+/// Order notional exceeds the single-order cap
+pub const ERR_MAX_ORDER_VALUE: i32 = -12;
+/// No position is held for the requested symbol
+pub const ERR_POSITION_NOT_FOUND: i32 = -14;
 /// Internal panic (should not happen)
 pub const ERR_INTERNAL_PANIC: i32 = -99;
 
 // ============================================================================
-// Engine Handle (Placeholder - will be implemented in Phase 2)
+// Engine Handle
 // ============================================================================
 
 /// Opaque engine handle for FFI.
-/// This is a placeholder that will be replaced with BacktestEngine in Phase 2.
+///
+/// Wraps a `BacktestEngine`: ticks streamed with `process_tick` and runs
+/// started with `run_backtest` update the same account, equity curve and
+/// trade log.
 pub struct EngineHandle {
-    pub params: StrategyParams,
-    pub risk_config: RiskConfig,
-    pub account: AccountStatus,
-    pub initialized: bool,
+    engine: BacktestEngine,
 }
 
 impl EngineHandle {
-    fn new(params: StrategyParams, risk_config: RiskConfig) -> Self {
+    fn new(params: StrategyParams, risk_config: RiskConfig, cost_config: EngineCostConfig) -> Self {
         Self {
-            params,
-            risk_config,
-            account: AccountStatus {
-                balance: 100_000.0,
-                equity: 100_000.0,
-                available: 100_000.0,
-                position_count: 0,
-                total_pnl: 0.0,
-            },
-            initialized: true,
+            engine: BacktestEngine::new(params, risk_config).with_cost_config(cost_config),
         }
     }
+
+    /// Get the wrapped engine.
+    pub fn engine(&self) -> &BacktestEngine {
+        &self.engine
+    }
 }
 
 // ============================================================================
@@ -73,6 +77,8 @@ impl EngineHandle {
 
 /// Initialize a new backtest engine.
 ///
+/// Uses the default balance and costs; see `init_engine_with_costs`.
+///
 /// # Safety
 /// - `params` must be a valid pointer to StrategyParams or null (uses defaults)
 /// - `risk_config` must be a valid pointer to RiskConfig or null (uses defaults)
@@ -85,6 +91,27 @@ impl EngineHandle {
 pub unsafe extern "C" fn init_engine(
     params: *const StrategyParams,
     risk_config: *const RiskConfig,
+) -> *mut EngineHandle {
+    init_engine_with_costs(params, risk_config, std::ptr::null())
+}
+
+/// Initialize a new backtest engine with an initial balance, slippage and commission.
+///
+/// # Safety
+/// - `params` must be a valid pointer to StrategyParams or null (uses defaults)
+/// - `risk_config` must be a valid pointer to RiskConfig or null (uses defaults)
+/// - `cost_config` must be a valid pointer to EngineCostConfig or null (uses defaults)
+/// - Caller must call `free_engine` to release the returned pointer
+///
+/// # Returns
+/// - Valid engine pointer on success
+/// - Null pointer on failure or if `cost_config` has a non-positive balance
+///   or a negative rate
+#[no_mangle]
+pub unsafe extern "C" fn init_engine_with_costs(
+    params: *const StrategyParams,
+    risk_config: *const RiskConfig,
+    cost_config: *const EngineCostConfig,
 ) -> *mut EngineHandle {
     let result = catch_unwind(|| {
         let strategy_params = if params.is_null() {
@@ -101,7 +128,17 @@ pub unsafe extern "C" fn init_engine(
             *risk_config
         };
 
-        let engine = Box::new(EngineHandle::new(strategy_params, risk_cfg));
+        let costs = if cost_config.is_null() {
+            EngineCostConfig::default()
+        } else {
+            // SAFETY: Caller guarantees cost_config is valid
+            *cost_config
+        };
+        if !costs.is_valid() {
+            return std::ptr::null_mut();
+        }
+
+        let engine = Box::new(EngineHandle::new(strategy_params, risk_cfg, costs));
         Box::into_raw(engine)
     });
 
@@ -123,10 +160,10 @@ pub unsafe extern "C" fn free_engine(engine: *mut EngineHandle) {
         return;
     }
 
-    let _ = catch_unwind(|| {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
         // SAFETY: Caller guarantees engine is valid and this is called only once
         let _ = Box::from_raw(engine);
-    });
+    }));
 }
 
 /// Process a single tick.
@@ -139,6 +176,7 @@ pub unsafe extern "C" fn free_engine(engine: *mut EngineHandle) {
 /// - ERR_SUCCESS on success
 /// - ERR_NULL_POINTER if engine or tick is null
 /// - ERR_INVALID_DATA if tick data is invalid
+/// - The engine's error code if processing fails
 #[no_mangle]
 pub unsafe extern "C" fn process_tick(
     engine: *mut EngineHandle,
@@ -152,9 +190,9 @@ pub unsafe extern "C" fn process_tick(
         return ERR_NULL_POINTER;
     }
 
-    let result = catch_unwind(|| {
+    let result = catch_unwind(AssertUnwindSafe(|| {
         // SAFETY: Validated above
-        let _engine = &mut *engine;
+        let engine_ref = &mut *engine;
         let tick_data = &*tick;
 
         // Validate tick data
@@ -165,9 +203,11 @@ pub unsafe extern "C" fn process_tick(
             return ERR_INVALID_DATA;
         }
 
-        // TODO: Implement actual tick processing in Phase 2
-        ERR_SUCCESS
-    });
+        match engine_ref.engine.process_tick(tick_data) {
+            Ok(_) => ERR_SUCCESS,
+            Err(e) => e.set_and_return_code(),
+        }
+    }));
 
     match result {
         Ok(code) => code,
@@ -197,14 +237,14 @@ pub unsafe extern "C" fn get_account_status(
         return ERR_NULL_POINTER;
     }
 
-    let result = catch_unwind(|| {
+    let result = catch_unwind(AssertUnwindSafe(|| {
         // SAFETY: Validated above
         let engine_ref = &*engine;
         let status_ref = &mut *status;
 
-        *status_ref = engine_ref.account;
+        *status_ref = engine_ref.engine.get_account_status();
         ERR_SUCCESS
-    });
+    }));
 
     match result {
         Ok(code) => code,
@@ -212,7 +252,9 @@ pub unsafe extern "C" fn get_account_status(
     }
 }
 
-/// Load data from file (placeholder).
+/// Load tick data from a file into the engine.
+///
+/// Replaces any previously loaded data; `run_backtest` runs over it.
 ///
 /// # Safety
 /// - `engine` must be a valid engine pointer
@@ -223,6 +265,7 @@ pub unsafe extern "C" fn get_account_status(
 /// - ERR_SUCCESS on success
 /// - ERR_NULL_POINTER if any pointer is null
 /// - ERR_FILE_NOT_FOUND if file doesn't exist
+/// - ERR_DATA_LOAD_FAILED or ERR_INVALID_DATA if the file can't be loaded
 #[no_mangle]
 pub unsafe extern "C" fn load_data_from_file(
     engine: *mut EngineHandle,
@@ -240,23 +283,26 @@ pub unsafe extern "C" fn load_data_from_file(
         return ERR_NULL_POINTER;
     }
 
-    let result = catch_unwind(|| {
+    let result = catch_unwind(AssertUnwindSafe(|| {
         // SAFETY: Validated above
-        let _engine_ref = &mut *engine;
+        let engine_ref = &mut *engine;
         let report_ref = &mut *report;
 
         // Convert C string to Rust string
         let path_cstr = std::ffi::CStr::from_ptr(file_path);
-        let _path = match path_cstr.to_str() {
+        let path = match path_cstr.to_str() {
             Ok(s) => s,
             Err(_) => return ERR_INVALID_PARAM,
         };
 
-        // TODO: Implement actual data loading with Polars in Phase 2
-        // For now, return a placeholder report
-        *report_ref = DataQualityReport::default();
-        ERR_SUCCESS
-    });
+        match engine_ref.engine.load_data(path) {
+            Ok(loaded) => {
+                *report_ref = loaded;
+                ERR_SUCCESS
+            }
+            Err(e) => e.set_and_return_code(),
+        }
+    }));
 
     match result {
         Ok(code) => code,
@@ -264,7 +310,10 @@ pub unsafe extern "C" fn load_data_from_file(
     }
 }
 
-/// Run complete backtest (placeholder).
+/// Run a complete backtest over the loaded data.
+///
+/// Restarts the account, equity curve and trade log, so state left by
+/// streamed `process_tick` calls is discarded.
 ///
 /// # Safety
 /// - `engine` must be a valid engine pointer
@@ -272,19 +321,134 @@ pub unsafe extern "C" fn load_data_from_file(
 /// # Returns
 /// - ERR_SUCCESS on success
 /// - ERR_NULL_POINTER if engine is null
+/// - ERR_INVALID_DATA if no data has been loaded
 #[no_mangle]
 pub unsafe extern "C" fn run_backtest(engine: *mut EngineHandle) -> i32 {
     if engine.is_null() {
         return ERR_NULL_POINTER;
     }
 
-    let result = catch_unwind(|| {
+    let result = catch_unwind(AssertUnwindSafe(|| {
         // SAFETY: Validated above
-        let _engine_ref = &mut *engine;
+        let engine_ref = &mut *engine;
 
-        // TODO: Implement actual backtest execution in Phase 2
-        ERR_SUCCESS
-    });
+        match engine_ref.engine.run() {
+            Ok(_) => ERR_SUCCESS,
+            Err(e) => e.set_and_return_code(),
+        }
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => ERR_INTERNAL_PANIC,
+    }
+}
+
+/// Copy the equity curve into a caller-provided buffer.
+///
+/// Copies at most `buffer_len` values and always writes the full curve
+/// length to `out_count`. Passing a null `out_buffer` with `buffer_len = 0`
+/// queries the length without copying.
+///
+/// # Safety
+/// - `engine` must be a valid engine pointer from `init_engine`
+/// - `out_buffer` must be valid for `buffer_len` f64 writes (may be null if `buffer_len` is 0)
+/// - `out_count` must be a valid pointer to write the curve length
+///
+/// # Returns
+/// - ERR_SUCCESS if the whole curve was copied
+/// - ERR_BUFFER_TOO_SMALL if `buffer_len` is less than the curve length
+/// - ERR_NULL_POINTER if engine or out_count is null, or out_buffer is null with buffer_len > 0
+/// - ERR_INVALID_PARAM if buffer_len is negative
+#[no_mangle]
+pub unsafe extern "C" fn get_equity_curve_ffi(
+    engine: *const EngineHandle,
+    out_buffer: *mut f64,
+    buffer_len: i32,
+    out_count: *mut i32,
+) -> i32 {
+    if engine.is_null() || out_count.is_null() {
+        return ERR_NULL_POINTER;
+    }
+    if buffer_len < 0 {
+        return ERR_INVALID_PARAM;
+    }
+    if out_buffer.is_null() && buffer_len > 0 {
+        return ERR_NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: Validated above
+        let curve = (*engine).engine.equity_curve();
+        *out_count = curve.len() as i32;
+
+        let copy_len = curve.len().min(buffer_len as usize);
+        if copy_len > 0 {
+            std::ptr::copy_nonoverlapping(curve.as_ptr(), out_buffer, copy_len);
+        }
+
+        if copy_len < curve.len() {
+            ERR_BUFFER_TOO_SMALL
+        } else {
+            ERR_SUCCESS
+        }
+    }));
+
+    match result {
+        Ok(code) => code,
+        Err(_) => ERR_INTERNAL_PANIC,
+    }
+}
+
+/// Copy the per-order trade log into a caller-provided buffer.
+///
+/// Same contract as `get_equity_curve_ffi`: copies at most `buffer_len`
+/// records and always writes the full trade count to `out_count`. Closing
+/// trades carry their realized PnL.
+///
+/// # Safety
+/// - `engine` must be a valid engine pointer from `init_engine`
+/// - `out_buffer` must be valid for `buffer_len` FfiTradeRecord writes (may be null if `buffer_len` is 0)
+/// - `out_count` must be a valid pointer to write the trade count
+///
+/// # Returns
+/// - ERR_SUCCESS if every trade was copied
+/// - ERR_BUFFER_TOO_SMALL if `buffer_len` is less than the trade count
+/// - ERR_NULL_POINTER if engine or out_count is null, or out_buffer is null with buffer_len > 0
+/// - ERR_INVALID_PARAM if buffer_len is negative
+#[no_mangle]
+pub unsafe extern "C" fn get_trades_ffi(
+    engine: *const EngineHandle,
+    out_buffer: *mut FfiTradeRecord,
+    buffer_len: i32,
+    out_count: *mut i32,
+) -> i32 {
+    if engine.is_null() || out_count.is_null() {
+        return ERR_NULL_POINTER;
+    }
+    if buffer_len < 0 {
+        return ERR_INVALID_PARAM;
+    }
+    if out_buffer.is_null() && buffer_len > 0 {
+        return ERR_NULL_POINTER;
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: Validated above
+        let trades = (*engine).engine.trades();
+        *out_count = trades.len() as i32;
+
+        let copy_len = trades.len().min(buffer_len as usize);
+        for (i, trade) in trades.iter().take(copy_len).enumerate() {
+            out_buffer.add(i).write(FfiTradeRecord::from(trade));
+        }
+
+        if copy_len < trades.len() {
+            ERR_BUFFER_TOO_SMALL
+        } else {
+            ERR_SUCCESS
+        }
+    }));
 
     match result {
         Ok(code) => code,
@@ -353,6 +517,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_init_engine_with_costs() {
+        unsafe {
+            let costs = EngineCostConfig {
+                initial_balance: 25_000.0,
+                slippage: 0.002,
+                commission_rate: 0.0005,
+            };
+            let engine = init_engine_with_costs(std::ptr::null(), std::ptr::null(), &costs);
+            assert!(!engine.is_null());
+            let engine_ref = (*engine).engine();
+            assert_eq!(engine_ref.slippage(), 0.002);
+            assert_eq!(engine_ref.commission(), 0.0005);
+            assert_eq!(engine_ref.get_account_status().balance, 25_000.0);
+            free_engine(engine);
+
+            let invalid = EngineCostConfig { slippage: -0.1, ..costs };
+            assert!(init_engine_with_costs(std::ptr::null(), std::ptr::null(), &invalid).is_null());
+        }
+    }
+
+    #[test]
+    fn test_cost_config_applies_to_fills() {
+        let params = StrategyParams {
+            short_ma_period: 5,
+            long_ma_period: 10,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        let final_equity = |commission_rate: f64| unsafe {
+            let costs = EngineCostConfig {
+                commission_rate,
+                ..Default::default()
+            };
+            let engine = init_engine_with_costs(&params, std::ptr::null(), &costs);
+            for i in 0..100 {
+                let tick = Tick {
+                    timestamp: i as i64,
+                    price: cycle_price(i),
+                    volume: 1000.0,
+                };
+                process_tick(engine, &tick);
+            }
+            assert!(!(*engine).engine().trades().is_empty());
+            let mut status = AccountStatus::default();
+            get_account_status(engine, &mut status);
+            free_engine(engine);
+            status.equity
+        };
+
+        assert!(final_equity(0.01) < final_equity(0.0));
+    }
+
     #[test]
     fn test_init_with_params() {
         unsafe {
@@ -363,15 +580,17 @@ mod tests {
                 stop_loss_pct: 0.03,
                 take_profit_pct: 0.06,
                 warmup_bars: 0,
+                short_ma_type: MA_TYPE_SMA,
+                long_ma_type: MA_TYPE_SMA,
             };
             let risk = RiskConfig::default();
 
             let engine = init_engine(&params, &risk);
             assert!(!engine.is_null());
 
-            let engine_ref = &*engine;
-            assert_eq!(engine_ref.params.short_ma_period, 10);
-            assert_eq!(engine_ref.params.long_ma_period, 30);
+            let engine_params = (*engine).engine().strategy_params();
+            assert_eq!(engine_params.short_ma_period, 10);
+            assert_eq!(engine_params.long_ma_period, 30);
 
             free_engine(engine);
         }
@@ -456,4 +675,206 @@ mod tests {
             free_engine(engine);
         }
     }
+
+    /// Crossing price cycle that makes the default strategy trade.
+    fn cycle_price(i: usize) -> f64 {
+        100.0 + (i as f64 / 20.0 * std::f64::consts::PI).sin() * 10.0
+    }
+
+    /// An engine that has streamed `len` ticks with `position_size` 10.
+    fn engine_with_curve(len: usize) -> *mut EngineHandle {
+        unsafe {
+            let params = StrategyParams {
+                short_ma_period: 5,
+                long_ma_period: 10,
+                position_size: 10.0,
+                ..Default::default()
+            };
+            let engine = init_engine(&params, std::ptr::null());
+            for i in 0..len {
+                let tick = Tick {
+                    timestamp: i as i64,
+                    price: cycle_price(i),
+                    volume: 1000.0,
+                };
+                assert_eq!(process_tick(engine, &tick), ERR_SUCCESS);
+            }
+            engine
+        }
+    }
+
+    #[test]
+    fn test_get_equity_curve_query_length() {
+        unsafe {
+            let engine = engine_with_curve(5);
+            let mut count = -1;
+            let result = get_equity_curve_ffi(engine, std::ptr::null_mut(), 0, &mut count);
+            assert_eq!(result, ERR_BUFFER_TOO_SMALL);
+            assert_eq!(count, 5);
+            free_engine(engine);
+
+            let empty = engine_with_curve(0);
+            let result = get_equity_curve_ffi(empty, std::ptr::null_mut(), 0, &mut count);
+            assert_eq!(result, ERR_SUCCESS);
+            assert_eq!(count, 0);
+            free_engine(empty);
+        }
+    }
+
+    #[test]
+    fn test_get_equity_curve_too_small_buffer() {
+        unsafe {
+            let engine = engine_with_curve(5);
+            // Sentinel past the advertised length must not be touched
+            let mut buffer = [-1.0; 4];
+            let mut count = 0;
+            let result = get_equity_curve_ffi(engine, buffer.as_mut_ptr(), 3, &mut count);
+            assert_eq!(result, ERR_BUFFER_TOO_SMALL);
+            assert_eq!(count, 5);
+            assert_eq!(buffer[..3], (*engine).engine().equity_curve()[..3]);
+            assert_eq!(buffer[3], -1.0);
+            free_engine(engine);
+        }
+    }
+
+    #[test]
+    fn test_get_trades_copies_streamed_trades() {
+        unsafe {
+            let engine = engine_with_curve(100);
+            let expected = (*engine).engine().trades().to_vec();
+            assert!(expected.len() >= 2);
+
+            let mut count = 0;
+            let mut buffer = [FfiTradeRecord::default(); 1];
+            let result = get_trades_ffi(engine, buffer.as_mut_ptr(), 1, &mut count);
+            assert_eq!(result, ERR_BUFFER_TOO_SMALL);
+            assert_eq!(count, expected.len() as i32);
+            assert_eq!(buffer[0].timestamp, expected[0].timestamp);
+
+            let mut buffer = vec![FfiTradeRecord::default(); expected.len()];
+            let result = get_trades_ffi(engine, buffer.as_mut_ptr(), count, &mut count);
+            assert_eq!(result, ERR_SUCCESS);
+            assert_eq!(&buffer[0].symbol[..8], b"BTCUSDT\0");
+            for (copied, trade) in buffer.iter().zip(&expected) {
+                assert_eq!(copied.direction, trade.direction);
+                assert_eq!(copied.price, trade.price);
+                assert_eq!(copied.net_pnl, trade.net_pnl);
+            }
+            assert!(buffer.iter().any(|t| t.pnl != 0.0));
+
+            assert_eq!(get_trades_ffi(engine, std::ptr::null_mut(), 1, &mut count), ERR_NULL_POINTER);
+            assert_eq!(get_trades_ffi(engine, std::ptr::null_mut(), -1, &mut count), ERR_INVALID_PARAM);
+            free_engine(engine);
+        }
+    }
+
+    #[test]
+    fn test_run_backtest_over_loaded_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("ticks.csv");
+        let mut csv = String::from("timestamp,price,volume\n");
+        for i in 0..100 {
+            csv.push_str(&format!("{},{},1000.0\n", i, cycle_price(i)));
+        }
+        std::fs::write(&path, csv).unwrap();
+        let path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+
+        unsafe {
+            let streamed = engine_with_curve(100);
+            let engine = engine_with_curve(0);
+            assert_eq!(run_backtest(engine), ERR_INVALID_DATA);
+
+            let mut report = DataQualityReport::default();
+            assert_eq!(load_data_from_file(engine, path.as_ptr(), &mut report), ERR_SUCCESS);
+            assert_eq!(report.valid_ticks, 100);
+            assert_eq!(run_backtest(engine), ERR_SUCCESS);
+
+            // A file run trades exactly like streaming the same ticks
+            let mut count = 0;
+            get_trades_ffi(engine, std::ptr::null_mut(), 0, &mut count);
+            assert!(count > 0);
+            assert_eq!(count as usize, (*streamed).engine().trades().len());
+            assert_eq!((*engine).engine().equity_curve(), (*streamed).engine().equity_curve());
+
+            free_engine(streamed);
+            free_engine(engine);
+        }
+    }
+
+    #[test]
+    fn test_get_equity_curve_exact_fit() {
+        unsafe {
+            let engine = engine_with_curve(3);
+            let mut buffer = [0.0; 3];
+            let mut count = 0;
+            let result = get_equity_curve_ffi(engine, buffer.as_mut_ptr(), 3, &mut count);
+            assert_eq!(result, ERR_SUCCESS);
+            assert_eq!(count, 3);
+            assert_eq!(buffer[..], *(*engine).engine().equity_curve());
+            free_engine(engine);
+        }
+    }
+
+    #[test]
+    fn test_get_equity_curve_tracks_engine_equity() {
+        unsafe {
+            let engine = engine_with_curve(100);
+            assert!(!(*engine).engine().trades().is_empty());
+
+            let mut buffer = vec![0.0; 100];
+            let mut count = 0;
+            let result = get_equity_curve_ffi(engine, buffer.as_mut_ptr(), 100, &mut count);
+            assert_eq!(result, ERR_SUCCESS);
+            assert_eq!(count, 100);
+
+            // Open positions and costs move equity away from the start
+            assert!(buffer.iter().any(|&equity| equity != buffer[0]));
+            let mut status = AccountStatus::default();
+            get_account_status(engine, &mut status);
+            assert_eq!(buffer[99], status.equity);
+            free_engine(engine);
+        }
+    }
+
+    #[test]
+    fn test_get_equity_curve_invalid_args() {
+        unsafe {
+            let engine = engine_with_curve(2);
+            let mut count = 0;
+            assert_eq!(
+                get_equity_curve_ffi(std::ptr::null(), std::ptr::null_mut(), 0, &mut count),
+                ERR_NULL_POINTER
+            );
+            assert_eq!(
+                get_equity_curve_ffi(engine, std::ptr::null_mut(), 0, std::ptr::null_mut()),
+                ERR_NULL_POINTER
+            );
+            assert_eq!(
+                get_equity_curve_ffi(engine, std::ptr::null_mut(), 2, &mut count),
+                ERR_NULL_POINTER
+            );
+            let mut buffer = [0.0; 2];
+            assert_eq!(
+                get_equity_curve_ffi(engine, buffer.as_mut_ptr(), -1, &mut count),
+                ERR_INVALID_PARAM
+            );
+            free_engine(engine);
+        }
+    }
+
+    #[test]
+    fn test_process_tick_records_equity() {
+        unsafe {
+            let engine = init_engine(std::ptr::null(), std::ptr::null());
+            let tick = Tick {
+                timestamp: 1,
+                price: 100.0,
+                volume: 10.0,
+            };
+            process_tick(engine, &tick);
+            process_tick(engine, &tick);
+            assert_eq!((*engine).engine().equity_curve(), [100_000.0, 100_000.0]);
+            free_engine(engine);
+        }
+    }
 }
@@ -3,10 +3,15 @@
 //! Provides a unified interface for both simulated (backtest) and live trading.
 //! The Gateway trait abstracts order submission, cancellation, and account queries.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use thiserror::Error;
 
-use crate::types::{AccountStatus, OrderRequest, Position, DIRECTION_BUY, DIRECTION_SELL};
+use crate::event_bus::{TimerId, TimerManager};
+use crate::precision::QUANTITY_EPSILON;
+use crate::types::{
+    AccountStatus, OrderRequest, Position, Tick, DIRECTION_BUY, DIRECTION_SELL, ORDER_TYPE_LIMIT,
+    TIF_FOK, TIF_GTC, TIF_IOC,
+};
 
 /// Unique identifier for orders.
 pub type OrderId = u64;
@@ -28,6 +33,8 @@ pub struct Fill {
     pub direction: i32,
     /// Timestamp of fill
     pub timestamp: i64,
+    /// Client order ID copied from the order request (0 = untagged)
+    pub client_id: u64,
 }
 
 /// Gateway error types.
@@ -48,6 +55,9 @@ pub enum GatewayError {
     #[error("Order already cancelled: {0}")]
     AlreadyCancelled(OrderId),
 
+    #[error("Not supported by this gateway: {0}")]
+    NotSupported(&'static str),
+
     #[error("Gateway error: {0}")]
     Other(String),
 }
@@ -88,6 +98,30 @@ pub trait Gateway: Send + Sync {
     /// * `None` - If no position for symbol
     fn query_position(&self, symbol: &str) -> Option<Position>;
 
+    /// Query all positions held by the gateway.
+    ///
+    /// # Returns
+    /// One entry per symbol the gateway tracks, including flat positions.
+    /// The default returns `GatewayError::NotSupported`, for gateways that
+    /// cannot enumerate them.
+    fn query_positions(&self) -> Result<Vec<Position>, GatewayError> {
+        Err(GatewayError::NotSupported("query_positions"))
+    }
+
+    /// Overwrite the position for a symbol, e.g. when restoring persisted state.
+    ///
+    /// Balance and realized PnL are left unchanged. A zero quantity removes
+    /// the position. The default returns `GatewayError::NotSupported`, for
+    /// gateways whose positions are owned by the venue.
+    ///
+    /// # Arguments
+    /// * `symbol` - The symbol to overwrite
+    /// * `quantity` - Position quantity (positive = long, negative = short)
+    /// * `average_price` - Average entry price
+    fn restore_position(&mut self, _symbol: &str, _quantity: f64, _average_price: f64) -> Result<(), GatewayError> {
+        Err(GatewayError::NotSupported("restore_position"))
+    }
+
     /// Query current account status.
     ///
     /// # Returns
@@ -108,10 +142,247 @@ pub trait Gateway: Send + Sync {
     fn update_price(&mut self, symbol: &str, price: f64);
 }
 
+/// Handling of the unfilled remainder when a fill is capped by tick volume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RemainderPolicy {
+    /// Discard the unfilled quantity
+    #[default]
+    Drop,
+    /// Keep the unfilled quantity and fill it on subsequent ticks
+    CarryForward,
+}
+
+/// Handling of a sell that exceeds the long position when shorting is disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OversellPolicy {
+    /// Reduce the sell to the long position, leaving the account flat
+    #[default]
+    Clamp,
+    /// Reject the whole order
+    Reject,
+}
+
+/// How realized PnL is measured when a position is reduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CostBasisMethod {
+    /// Match closes against the position's average entry price
+    #[default]
+    Average,
+    /// Match closes against the oldest entry lots first
+    Fifo,
+    /// Match closes against the newest entry lots first
+    Lifo,
+}
+
+/// Open entry lots of one position, oldest first.
+///
+/// Only populated under FIFO/LIFO cost basis; under `Average` every call is
+/// a no-op and closes are matched at the average price. Quantities are
+/// unsigned, the position's sign gives the side.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EntryLots {
+    /// (quantity, price) per entry fill
+    lots: VecDeque<(f64, f64)>,
+}
+
+impl EntryLots {
+    /// Add an entry lot.
+    pub(crate) fn open(&mut self, method: CostBasisMethod, quantity: f64, price: f64) {
+        if method != CostBasisMethod::Average && quantity > QUANTITY_EPSILON {
+            self.lots.push_back((quantity, price));
+        }
+    }
+
+    /// Replace all lots with a single lot (none when `quantity` is zero).
+    pub(crate) fn reset(&mut self, method: CostBasisMethod, quantity: f64, price: f64) {
+        self.lots.clear();
+        self.open(method, quantity, price);
+    }
+
+    /// Remove `quantity` from the lots in `method` order and return the
+    /// average entry price it was matched against.
+    ///
+    /// Under `Average`, or for quantity beyond the tracked lots,
+    /// `average_price` is used.
+    pub(crate) fn close(&mut self, method: CostBasisMethod, quantity: f64, average_price: f64) -> f64 {
+        if method == CostBasisMethod::Average || quantity <= 0.0 {
+            return average_price;
+        }
+
+        let mut remaining = quantity;
+        let mut cost = 0.0;
+        while remaining > QUANTITY_EPSILON {
+            let lot = if method == CostBasisMethod::Lifo {
+                self.lots.back_mut()
+            } else {
+                self.lots.front_mut()
+            };
+            let Some(lot) = lot else {
+                break;
+            };
+            let matched = remaining.min(lot.0);
+            cost += matched * lot.1;
+            lot.0 -= matched;
+            remaining -= matched;
+            if lot.0 <= QUANTITY_EPSILON {
+                if method == CostBasisMethod::Lifo {
+                    self.lots.pop_back();
+                } else {
+                    self.lots.pop_front();
+                }
+            }
+        }
+        cost += remaining.max(0.0) * average_price;
+        cost / quantity
+    }
+
+    /// Quantity-weighted average price of the open lots, if any.
+    pub(crate) fn average_price(&self) -> Option<f64> {
+        let quantity: f64 = self.lots.iter().map(|(q, _)| q).sum();
+        if quantity <= QUANTITY_EPSILON {
+            return None;
+        }
+        Some(self.lots.iter().map(|(q, p)| q * p).sum::<f64>() / quantity)
+    }
+}
+
+/// Commission schedule that lowers the rate as session volume accrues.
+///
+/// Tracks cumulative traded notional since the last `reset_volume` and
+/// charges the rate of the highest tier whose threshold has been reached.
+/// The tier is chosen from volume traded before the current trade, so a
+/// trade that crosses a boundary pays the old rate and only later trades
+/// get the new one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeTieredFee {
+    /// Rate charged below the first tier threshold
+    base_rate: f64,
+    /// (minimum cumulative notional, rate), sorted by threshold
+    tiers: Vec<(f64, f64)>,
+    /// Notional traded in the current session
+    cumulative_notional: f64,
+}
+
+impl VolumeTieredFee {
+    /// Create a tiered fee.
+    ///
+    /// # Arguments
+    /// * `base_rate` - Commission rate fraction below the first tier
+    /// * `tiers` - (cumulative notional threshold, commission rate fraction) pairs, in any order
+    pub fn new(base_rate: f64, mut tiers: Vec<(f64, f64)>) -> Self {
+        tiers.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        Self {
+            base_rate: base_rate.max(0.0),
+            tiers: tiers.into_iter().map(|(threshold, rate)| (threshold, rate.max(0.0))).collect(),
+            cumulative_notional: 0.0,
+        }
+    }
+
+    /// Rate that applies to the next trade.
+    pub fn current_rate(&self) -> f64 {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|(threshold, _)| self.cumulative_notional >= *threshold)
+            .map(|&(_, rate)| rate)
+            .unwrap_or(self.base_rate)
+    }
+
+    /// Commission for a trade of the given notional at the current rate.
+    pub fn commission(&self, trade_value: f64) -> f64 {
+        trade_value * self.current_rate()
+    }
+
+    /// Add a filled trade's notional to the session volume.
+    pub fn record_trade(&mut self, trade_value: f64) {
+        self.cumulative_notional += trade_value.abs();
+    }
+
+    /// Get the notional traded in the current session.
+    pub fn cumulative_notional(&self) -> f64 {
+        self.cumulative_notional
+    }
+
+    /// Start a new session, returning to the base rate.
+    pub fn reset_volume(&mut self) {
+        self.cumulative_notional = 0.0;
+    }
+}
+
+/// Standard perpetual-swap funding interval (8 hours) in milliseconds.
+pub const FUNDING_INTERVAL_8H_MS: u64 = 8 * 60 * 60 * 1000;
+
+/// One funding settlement applied to a position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundingPayment {
+    /// Symbol the funding was charged on
+    pub symbol: String,
+    /// Settlement timestamp
+    pub timestamp: i64,
+    /// Funding rate for the period (e.g., 0.0001 = 0.01%)
+    pub rate: f64,
+    /// Price the position was valued at
+    pub mark_price: f64,
+    /// Amount paid by the account (negative when funding was received)
+    pub amount: f64,
+}
+
+/// Schedule a repeating funding timer on `timers`.
+///
+/// The returned id identifies the `Event::Timer` events that mark each
+/// settlement; call `SimulatedGateway::apply_funding` for every perpetual
+/// position when one fires.
+pub fn schedule_funding(timers: &mut TimerManager, interval_ms: u64) -> TimerId {
+    timers.schedule_repeating(interval_ms)
+}
+
+/// Check that an order's time in force is one of the `TIF_*` codes.
+pub(crate) fn validate_tif(order: &OrderRequest) -> Result<(), GatewayError> {
+    if matches!(order.tif, TIF_GTC | TIF_IOC | TIF_FOK) {
+        Ok(())
+    } else {
+        Err(GatewayError::InvalidOrder(format!("Invalid time in force: {}", order.tif)))
+    }
+}
+
+/// Error for a fill-or-kill order that cannot fill in full.
+pub(crate) fn fok_rejected(requested: f64, available: f64) -> GatewayError {
+    GatewayError::InvalidOrder(format!(
+        "FOK order for {} cannot be filled in full (available {})",
+        requested, available
+    ))
+}
+
 /// Simulated gateway for backtesting.
 ///
 /// Executes orders immediately with configurable slippage and commission.
 /// Maintains internal position and account state.
+///
+/// Limit orders (`ORDER_TYPE_LIMIT`) that are not marketable on submission
+/// rest in the gateway and fill on a later price update that reaches their
+/// limit. Limit fills never execute beyond the limit price after slippage.
+///
+/// Time in force (`OrderRequest::tif`) controls what happens to quantity
+/// that cannot fill on submission: GTC limit orders rest and GTC remainders
+/// follow the remainder policy, IOC orders cancel it, and FOK orders are
+/// rejected unless the full quantity fills.
+///
+/// Optionally caps each fill at a fraction of the current tick's traded
+/// volume (`set_max_fill_fraction_of_volume`), modelling liquidity limits
+/// without a full order book.
+///
+/// In margin mode (`set_margin_mode`) the account trades like a leveraged
+/// futures account: fills move the balance only by realized PnL and
+/// commission, each position ties up `notional / leverage` of margin at the
+/// current price, and orders that would push used margin above equity are
+/// rejected.
+///
+/// Realized PnL uses average-price accounting unless a FIFO or LIFO
+/// `CostBasisMethod` is set, in which case entry lots are tracked per
+/// symbol and closes are matched against them in that order.
+///
+/// Perpetual-swap funding is settled with `apply_funding`, usually driven
+/// by a `schedule_funding` timer.
 #[derive(Debug)]
 pub struct SimulatedGateway {
     /// Slippage as a fraction (e.g., 0.001 = 0.1%)
@@ -120,12 +391,11 @@ pub struct SimulatedGateway {
     commission_rate: f64,
     /// Current market prices by symbol
     current_prices: HashMap<String, f64>,
-    /// Positions by symbol
-    positions: HashMap<String, PositionInternal>,
+    /// Positions by symbol, ordered so PnL sums are deterministic
+    positions: BTreeMap<String, PositionInternal>,
     /// Account balance
     balance: f64,
-    /// Initial balance for PnL calculation (kept for potential future reporting)
-    #[allow(dead_code)]
+    /// Initial balance for PnL calculation and reset
     initial_balance: f64,
     /// Next order ID
     next_order_id: OrderId,
@@ -133,6 +403,30 @@ pub struct SimulatedGateway {
     pending_fills: Vec<Fill>,
     /// Current timestamp for fills
     current_timestamp: i64,
+    /// Maximum fraction of tick volume a fill may consume (None = unlimited)
+    max_fill_fraction_of_volume: Option<f64>,
+    /// What to do with quantity left unfilled by the volume cap
+    remainder_policy: RemainderPolicy,
+    /// Liquidity left in the current tick by symbol
+    available_volume: HashMap<String, f64>,
+    /// Remainders carried to subsequent ticks, in submission order
+    carried_orders: Vec<(OrderId, OrderRequest)>,
+    /// Resting limit orders, in submission order
+    pending_orders: Vec<(OrderId, OrderRequest)>,
+    /// Whether sells may take a position below flat
+    allow_short: bool,
+    /// What to do with a sell larger than the long position when shorting is disabled
+    oversell_policy: OversellPolicy,
+    /// Volume-tiered commission replacing `commission_rate` (None = flat rate)
+    tiered_fee: Option<VolumeTieredFee>,
+    /// Whether positions are margined instead of paid for in full
+    margin_mode: bool,
+    /// Leverage applied to position notional in margin mode
+    leverage: f64,
+    /// Funding settlements applied, in order
+    funding_payments: Vec<FundingPayment>,
+    /// Lot matching used for realized PnL
+    cost_basis_method: CostBasisMethod,
 }
 
 /// Internal position representation with more detail.
@@ -142,6 +436,7 @@ struct PositionInternal {
     quantity: f64,
     average_price: f64,
     realized_pnl: f64,
+    lots: EntryLots,
 }
 
 impl SimulatedGateway {
@@ -156,12 +451,24 @@ impl SimulatedGateway {
             slippage,
             commission_rate,
             current_prices: HashMap::new(),
-            positions: HashMap::new(),
+            positions: BTreeMap::new(),
             balance: initial_balance,
             initial_balance,
             next_order_id: 1,
             pending_fills: Vec::new(),
             current_timestamp: 0,
+            max_fill_fraction_of_volume: None,
+            remainder_policy: RemainderPolicy::Drop,
+            available_volume: HashMap::new(),
+            carried_orders: Vec::new(),
+            pending_orders: Vec::new(),
+            allow_short: true,
+            oversell_policy: OversellPolicy::Clamp,
+            tiered_fee: None,
+            margin_mode: false,
+            leverage: 1.0,
+            funding_payments: Vec::new(),
+            cost_basis_method: CostBasisMethod::Average,
         }
     }
 
@@ -170,94 +477,298 @@ impl SimulatedGateway {
         self.current_timestamp = timestamp;
     }
 
-    /// Calculate fill price with slippage.
-    fn calculate_fill_price(&self, base_price: f64, direction: i32) -> f64 {
-        let slippage_amount = base_price * self.slippage;
-        if direction == DIRECTION_BUY {
-            base_price + slippage_amount // Buy at higher price
-        } else {
-            base_price - slippage_amount // Sell at lower price
-        }
+    /// Cap each fill at a fraction of the current tick's volume.
+    ///
+    /// `None` disables the cap (every order fills in full).
+    pub fn set_max_fill_fraction_of_volume(&mut self, fraction: Option<f64>) {
+        self.max_fill_fraction_of_volume = fraction.map(|f| f.max(0.0));
     }
 
-    /// Calculate commission for a trade.
-    fn calculate_commission(&self, trade_value: f64) -> f64 {
-        trade_value * self.commission_rate
+    /// Get the volume fill cap.
+    pub fn max_fill_fraction_of_volume(&self) -> Option<f64> {
+        self.max_fill_fraction_of_volume
     }
 
-    /// Calculate unrealized PnL for a position.
-    fn calculate_unrealized_pnl(&self, position: &PositionInternal) -> f64 {
-        if let Some(&current_price) = self.current_prices.get(&position.symbol) {
-            (current_price - position.average_price) * position.quantity
-        } else {
-            0.0
-        }
+    /// Set how quantity left unfilled by the volume cap is handled.
+    pub fn set_remainder_policy(&mut self, policy: RemainderPolicy) {
+        self.remainder_policy = policy;
     }
 
-    /// Get total unrealized PnL across all positions.
-    fn total_unrealized_pnl(&self) -> f64 {
+    /// Get the remainder policy.
+    pub fn remainder_policy(&self) -> RemainderPolicy {
+        self.remainder_policy
+    }
+
+    /// Allow or forbid short positions.
+    ///
+    /// With shorting disabled, sells against a flat account are rejected and
+    /// sells larger than the long position are handled per the oversell policy.
+    pub fn set_allow_short(&mut self, allow_short: bool) {
+        self.allow_short = allow_short;
+    }
+
+    /// Check whether short positions are allowed.
+    pub fn allow_short(&self) -> bool {
+        self.allow_short
+    }
+
+    /// Set how sells beyond the long position are handled when shorting is disabled.
+    pub fn set_oversell_policy(&mut self, policy: OversellPolicy) {
+        self.oversell_policy = policy;
+    }
+
+    /// Get the oversell policy.
+    pub fn oversell_policy(&self) -> OversellPolicy {
+        self.oversell_policy
+    }
+
+    /// Charge commission from a volume-tiered schedule instead of the flat rate.
+    ///
+    /// `None` restores the flat `commission_rate`. Session volume is cleared
+    /// on `reset`.
+    pub fn set_volume_tiered_fee(&mut self, fee: Option<VolumeTieredFee>) {
+        self.tiered_fee = fee;
+    }
+
+    /// Get the volume-tiered fee, if any.
+    pub fn volume_tiered_fee(&self) -> Option<&VolumeTieredFee> {
+        self.tiered_fee.as_ref()
+    }
+
+    /// Set how realized PnL matches closes against entries.
+    ///
+    /// Change this only while flat: lots are tracked from the first fill
+    /// after switching to FIFO or LIFO.
+    pub fn set_cost_basis_method(&mut self, method: CostBasisMethod) {
+        self.cost_basis_method = method;
+    }
+
+    /// Get the cost basis method.
+    pub fn cost_basis_method(&self) -> CostBasisMethod {
+        self.cost_basis_method
+    }
+
+    /// Switch between a fully funded cash account and a margin account.
+    ///
+    /// Change this only while flat: positions opened in one mode are not
+    /// re-booked in the other.
+    pub fn set_margin_mode(&mut self, margin_mode: bool) {
+        self.margin_mode = margin_mode;
+    }
+
+    /// Check whether the account is in margin mode.
+    pub fn margin_mode(&self) -> bool {
+        self.margin_mode
+    }
+
+    /// Set the margin mode leverage. Values below 1 are treated as 1.
+    pub fn set_leverage(&mut self, leverage: f64) {
+        self.leverage = if leverage.is_finite() { leverage.max(1.0) } else { 1.0 };
+    }
+
+    /// Get the margin mode leverage.
+    pub fn leverage(&self) -> f64 {
+        self.leverage
+    }
+
+    /// Margin tied up by open positions at current prices (0 outside margin mode).
+    pub fn used_margin(&self) -> f64 {
+        if !self.margin_mode {
+            return 0.0;
+        }
         self.positions
             .values()
-            .map(|p| self.calculate_unrealized_pnl(p))
+            .map(|p| p.quantity.abs() * self.mark_price(p) / self.leverage)
             .sum()
     }
 
-    /// Get total realized PnL across all positions.
-    fn total_realized_pnl(&self) -> f64 {
-        self.positions.values().map(|p| p.realized_pnl).sum()
+    /// Current price of a position, or its entry price before any update.
+    fn mark_price(&self, position: &PositionInternal) -> f64 {
+        self.current_prices
+            .get(&position.symbol)
+            .copied()
+            .unwrap_or(position.average_price)
     }
 
-    /// Get slippage configuration.
-    pub fn slippage(&self) -> f64 {
-        self.slippage
+    /// Settle one funding period for `symbol` at `rate`.
+    ///
+    /// The payment is `position_qty * mark_price * rate`, taken from the
+    /// balance and booked as realized PnL. Sign convention: with a positive
+    /// rate longs pay and shorts receive; with a negative rate the reverse.
+    /// Returns the amount paid (negative when received, 0 when flat).
+    pub fn apply_funding(&mut self, symbol: &str, rate: f64, timestamp: i64) -> f64 {
+        let Some(position) = self.positions.get(symbol) else {
+            return 0.0;
+        };
+        if position.quantity.abs() < QUANTITY_EPSILON {
+            return 0.0;
+        }
+
+        let mark_price = self.mark_price(position);
+        let amount = position.quantity * mark_price * rate;
+        if let Some(position) = self.positions.get_mut(symbol) {
+            position.realized_pnl -= amount;
+        }
+        self.balance -= amount;
+        self.funding_payments.push(FundingPayment {
+            symbol: symbol.to_string(),
+            timestamp,
+            rate,
+            mark_price,
+            amount,
+        });
+        amount
     }
 
-    /// Get commission rate configuration.
-    pub fn commission_rate(&self) -> f64 {
-        self.commission_rate
+    /// Funding settlements applied since the last reset.
+    pub fn funding_payments(&self) -> &[FundingPayment] {
+        &self.funding_payments
     }
-}
 
-impl Default for SimulatedGateway {
-    fn default() -> Self {
-        Self::new(100_000.0, 0.001, 0.0001)
+    /// Set the traded volume of the current tick for a symbol.
+    ///
+    /// Resets the liquidity available to fills for that symbol. Symbols
+    /// without a volume update are treated as having unlimited liquidity.
+    pub fn update_volume(&mut self, symbol: &str, volume: f64) {
+        self.available_volume.insert(symbol.to_string(), volume.max(0.0));
     }
-}
 
-impl Gateway for SimulatedGateway {
-    fn submit_order(&mut self, order: &OrderRequest, current_price: f64) -> Result<OrderId, GatewayError> {
-        // Validate order
-        if order.quantity <= 0.0 {
-            return Err(GatewayError::InvalidOrder("Quantity must be positive".to_string()));
+    /// Apply a new market tick: price, volume and timestamp.
+    ///
+    /// Resting limit orders the new price reaches and any carried remainders
+    /// for the symbol are filled against the new tick.
+    pub fn update_tick(&mut self, symbol: &str, tick: &Tick) {
+        self.update_volume(symbol, tick.volume);
+        self.set_timestamp(tick.timestamp);
+        self.update_price(symbol, tick.price);
+        self.process_carried_orders(symbol, tick.price);
+    }
+
+    /// Get the resting limit orders, in submission order.
+    pub fn pending_orders(&self) -> &[(OrderId, OrderRequest)] {
+        &self.pending_orders
+    }
+
+    /// Get the total carried (unfilled) quantity for a symbol.
+    pub fn carried_quantity(&self, symbol: &str) -> f64 {
+        self.carried_orders
+            .iter()
+            .filter(|(_, order)| order.symbol_str() == symbol)
+            .map(|(_, order)| order.quantity)
+            .sum()
+    }
+
+    /// Largest quantity that can be sold without going short (unlimited if shorting is allowed).
+    fn sellable_quantity(&self, symbol: &str) -> f64 {
+        if self.allow_short {
+            return f64::INFINITY;
         }
-        if order.direction != DIRECTION_BUY && order.direction != DIRECTION_SELL {
-            return Err(GatewayError::InvalidOrder("Invalid direction".to_string()));
+        self.positions.get(symbol).map(|p| p.quantity.max(0.0)).unwrap_or(0.0)
+    }
+
+    /// Order quantity after applying the no-short restriction.
+    fn short_limited_quantity(&self, symbol: &str, order: &OrderRequest) -> Result<f64, GatewayError> {
+        if order.direction != DIRECTION_SELL {
+            return Ok(order.quantity);
         }
 
-        let symbol = order.symbol_str().to_string();
-        
-        // Calculate fill price with slippage
-        let fill_price = self.calculate_fill_price(current_price, order.direction);
-        let trade_value = order.quantity * fill_price;
-        let commission = self.calculate_commission(trade_value);
+        let sellable = self.sellable_quantity(symbol);
+        if order.quantity <= sellable + QUANTITY_EPSILON {
+            return Ok(order.quantity);
+        }
+        if sellable <= QUANTITY_EPSILON {
+            return Err(GatewayError::InvalidOrder(
+                "Short selling is disabled and there is no long position".to_string(),
+            ));
+        }
 
-        // Check if we have sufficient funds for buy orders (opening new long or covering short)
-        if order.direction == DIRECTION_BUY {
-            // Check if we're covering a short position
-            let current_position = self.positions.get(&symbol).map(|p| p.quantity).unwrap_or(0.0);
-            if current_position >= 0.0 {
-                // Opening or adding to long position - need funds
-                let total_cost = trade_value + commission;
-                if total_cost > self.balance {
-                    return Err(GatewayError::InsufficientFunds);
-                }
+        match self.oversell_policy {
+            OversellPolicy::Clamp => Ok(sellable),
+            OversellPolicy::Reject => Err(GatewayError::InvalidOrder(format!(
+                "Sell quantity {} exceeds long position {} with short selling disabled",
+                order.quantity, sellable
+            ))),
+        }
+    }
+
+    /// Quantity that can fill now for a symbol, given the volume cap.
+    fn fillable_quantity(&self, symbol: &str, requested: f64) -> f64 {
+        match (self.max_fill_fraction_of_volume, self.available_volume.get(symbol)) {
+            (Some(fraction), Some(&volume)) => requested.min(volume * fraction).max(0.0),
+            _ => requested,
+        }
+    }
+
+    /// Check that the account can pay for a fill.
+    fn check_funds(
+        &self,
+        symbol: &str,
+        direction: i32,
+        quantity: f64,
+        fill_price: f64,
+        commission: f64,
+    ) -> Result<(), GatewayError> {
+        if self.margin_mode {
+            return self.check_margin(symbol, direction, quantity, fill_price, commission);
+        }
+
+        // Opening or adding to a long needs funds; covering a short does not
+        let trade_value = quantity * fill_price;
+        if direction == DIRECTION_BUY {
+            let current_position = self.positions.get(symbol).map(|p| p.quantity).unwrap_or(0.0);
+            if current_position >= 0.0 && trade_value + commission > self.balance {
+                return Err(GatewayError::InsufficientFunds);
             }
-            // If covering short, we don't need additional funds (we're closing a position)
+        }
+        Ok(())
+    }
+
+    /// Check that a margin account can carry the position after a fill.
+    ///
+    /// Fills that reduce exposure are always allowed.
+    fn check_margin(
+        &self,
+        symbol: &str,
+        direction: i32,
+        quantity: f64,
+        fill_price: f64,
+        commission: f64,
+    ) -> Result<(), GatewayError> {
+        let current = self.positions.get(symbol).map(|p| p.quantity).unwrap_or(0.0);
+        let signed = if direction == DIRECTION_BUY { quantity } else { -quantity };
+        let new_quantity = current + signed;
+        if new_quantity.abs() <= current.abs() + QUANTITY_EPSILON {
+            return Ok(());
         }
 
-        // Generate order ID
-        let order_id = self.next_order_id;
-        self.next_order_id += 1;
+        let other_margin: f64 = self
+            .positions
+            .values()
+            .filter(|p| p.symbol != symbol)
+            .map(|p| p.quantity.abs() * self.mark_price(p) / self.leverage)
+            .sum();
+        let required = other_margin + new_quantity.abs() * fill_price / self.leverage;
+        let equity = self.balance + self.total_unrealized_pnl() - commission;
+        if required > equity {
+            return Err(GatewayError::InsufficientFunds);
+        }
+        Ok(())
+    }
+
+    /// Apply a fill to positions and balance, and record it.
+    fn apply_fill(
+        &mut self,
+        order_id: OrderId,
+        order: &OrderRequest,
+        quantity: f64,
+        fill_price: f64,
+        commission: f64,
+        current_price: f64,
+    ) {
+        let symbol = order.symbol_str().to_string();
+        let trade_value = quantity * fill_price;
+        let realized_before = self.positions.get(&symbol).map_or(0.0, |p| p.realized_pnl);
+        let method = self.cost_basis_method;
 
         // Update position
         let position = self.positions.entry(symbol.clone()).or_insert(PositionInternal {
@@ -265,110 +776,462 @@ impl Gateway for SimulatedGateway {
             quantity: 0.0,
             average_price: 0.0,
             realized_pnl: 0.0,
+            lots: EntryLots::default(),
         });
 
         if order.direction == DIRECTION_BUY {
             // Buying: increase position
-            let new_quantity = position.quantity + order.quantity;
+            let new_quantity = position.quantity + quantity;
             if position.quantity > 0.0 {
                 // Average up existing long position
-                position.average_price = (position.average_price * position.quantity + fill_price * order.quantity) / new_quantity;
+                position.average_price = (position.average_price * position.quantity + fill_price * quantity) / new_quantity;
+                position.lots.open(method, quantity, fill_price);
             } else if position.quantity < 0.0 {
                 // Covering short position
-                let cover_quantity = order.quantity.min(-position.quantity);
-                let pnl = (position.average_price - fill_price) * cover_quantity;
+                let cover_quantity = quantity.min(-position.quantity);
+                let entry_price = position.lots.close(method, cover_quantity, position.average_price);
+                let pnl = (entry_price - fill_price) * cover_quantity;
                 position.realized_pnl += pnl;
                 
-                if order.quantity > -position.quantity {
+                if quantity > -position.quantity {
                     // Flipping from short to long
                     position.average_price = fill_price;
+                    position.lots.reset(method, quantity - cover_quantity, fill_price);
+                } else if let Some(average_price) = position.lots.average_price() {
+                    position.average_price = average_price;
                 }
             } else {
                 // New position
                 position.average_price = fill_price;
+                position.lots.reset(method, quantity, fill_price);
             }
             position.quantity = new_quantity;
-            self.balance -= trade_value + commission;
+            if !self.margin_mode {
+                self.balance -= trade_value + commission;
+            }
         } else {
             // Selling: decrease position
-            let new_quantity = position.quantity - order.quantity;
+            let new_quantity = position.quantity - quantity;
             if position.quantity > 0.0 {
                 // Closing long position
-                let close_quantity = order.quantity.min(position.quantity);
-                let pnl = (fill_price - position.average_price) * close_quantity;
+                let close_quantity = quantity.min(position.quantity);
+                let entry_price = position.lots.close(method, close_quantity, position.average_price);
+                let pnl = (fill_price - entry_price) * close_quantity;
                 position.realized_pnl += pnl;
                 
-                if order.quantity > position.quantity {
+                if quantity > position.quantity {
                     // Flipping from long to short
                     position.average_price = fill_price;
+                    position.lots.reset(method, quantity - close_quantity, fill_price);
+                } else if let Some(average_price) = position.lots.average_price() {
+                    position.average_price = average_price;
                 }
             } else if position.quantity < 0.0 {
                 // Adding to short position
-                position.average_price = (position.average_price * (-position.quantity) + fill_price * order.quantity) / (-new_quantity);
+                position.average_price = (position.average_price * (-position.quantity) + fill_price * quantity) / (-new_quantity);
+                position.lots.open(method, quantity, fill_price);
             } else {
                 // New short position
                 position.average_price = fill_price;
+                position.lots.reset(method, quantity, fill_price);
             }
             position.quantity = new_quantity;
-            self.balance += trade_value - commission;
+            if !self.margin_mode {
+                self.balance += trade_value - commission;
+            }
+        }
+
+        // Margin accounts only settle realized PnL and fees
+        if self.margin_mode {
+            self.balance += position.realized_pnl - realized_before - commission;
         }
 
         // Update current price
         self.current_prices.insert(symbol.clone(), current_price);
 
+        // Accrue session volume after charging, so this trade's tier excludes it
+        if let Some(fee) = &mut self.tiered_fee {
+            fee.record_trade(trade_value);
+        }
+
+        // Consume tick liquidity
+        if let Some(volume) = self.available_volume.get_mut(&symbol) {
+            if let Some(fraction) = self.max_fill_fraction_of_volume {
+                if fraction > 0.0 {
+                    *volume = (*volume - quantity / fraction).max(0.0);
+                }
+            }
+        }
+
         // Record fill
         let fill = Fill {
             order_id,
             symbol: order.symbol,
-            quantity: order.quantity,
+            quantity,
             price: fill_price,
             commission,
             direction: order.direction,
             timestamp: self.current_timestamp,
+            client_id: order.client_id,
         };
         self.pending_fills.push(fill);
-
-        Ok(order_id)
     }
 
-    fn cancel_order(&mut self, order_id: OrderId) -> Result<(), GatewayError> {
-        // In simulated gateway, orders are filled immediately, so cancellation is not applicable
-        Err(GatewayError::OrderNotFound(order_id))
-    }
+    /// Fill carried remainders for a symbol against the current tick.
+    ///
+    /// Limit remainders keep waiting while the price is past their limit and
+    /// never fill at a worse price. Remainders that can no longer be funded
+    /// are dropped.
+    fn process_carried_orders(&mut self, symbol: &str, current_price: f64) {
+        if self.carried_orders.is_empty() {
+            return;
+        }
 
-    fn query_position(&self, symbol: &str) -> Option<Position> {
-        self.positions.get(symbol).map(|p| {
-            let mut pos = Position::with_symbol(symbol);
-            pos.quantity = p.quantity;
-            pos.average_price = p.average_price;
-            pos.unrealized_pnl = self.calculate_unrealized_pnl(p);
-            pos.realized_pnl = p.realized_pnl;
-            pos
-        })
-    }
+        let carried = std::mem::take(&mut self.carried_orders);
+        for (order_id, mut order) in carried {
+            if order.symbol_str() != symbol || !is_marketable(&order, current_price) {
+                self.carried_orders.push((order_id, order));
+                continue;
+            }
 
-    fn query_account(&self) -> AccountStatus {
-        let unrealized_pnl = self.total_unrealized_pnl();
-        let realized_pnl = self.total_realized_pnl();
-        let equity = self.balance + unrealized_pnl;
-        
-        AccountStatus {
-            balance: self.balance,
-            equity,
-            available: self.balance, // Simplified: available = balance
-            position_count: self.positions.values().filter(|p| p.quantity.abs() > 0.0001).count() as i32,
-            total_pnl: realized_pnl + unrealized_pnl,
+            // Earlier fills may have shrunk the long position since submission
+            if order.direction == DIRECTION_SELL {
+                order.quantity = order.quantity.min(self.sellable_quantity(symbol));
+            }
+
+            let quantity = self.fillable_quantity(symbol, order.quantity);
+            if quantity > QUANTITY_EPSILON {
+                let fill_price = self.limit_fill_price(&order, current_price);
+                let commission = self.calculate_commission(quantity * fill_price);
+                if self
+                    .check_funds(symbol, order.direction, quantity, fill_price, commission)
+                    .is_err()
+                {
+                    continue;
+                }
+                self.apply_fill(order_id, &order, quantity, fill_price, commission, current_price);
+                order.quantity -= quantity;
+            }
+
+            if order.quantity > QUANTITY_EPSILON {
+                self.carried_orders.push((order_id, order));
+            }
         }
     }
 
-    fn get_fills(&mut self) -> Vec<Fill> {
-        std::mem::take(&mut self.pending_fills)
+    /// Fill resting limit orders for a symbol that the current price reaches.
+    ///
+    /// Fills are capped by the volume cap, leaving the rest resting. Orders
+    /// that can no longer be funded or sold are dropped.
+    fn process_pending_orders(&mut self, symbol: &str, current_price: f64) {
+        if self.pending_orders.is_empty() {
+            return;
+        }
+
+        let pending = std::mem::take(&mut self.pending_orders);
+        for (order_id, mut order) in pending {
+            if order.symbol_str() != symbol || !is_marketable(&order, current_price) {
+                self.pending_orders.push((order_id, order));
+                continue;
+            }
+
+            let quantity = match self.short_limited_quantity(symbol, &order) {
+                Ok(quantity) => self.fillable_quantity(symbol, quantity),
+                Err(_) => continue,
+            };
+            if quantity > QUANTITY_EPSILON {
+                let fill_price = self.limit_fill_price(&order, current_price);
+                let commission = self.calculate_commission(quantity * fill_price);
+                if self
+                    .check_funds(symbol, order.direction, quantity, fill_price, commission)
+                    .is_err()
+                {
+                    continue;
+                }
+                self.apply_fill(order_id, &order, quantity, fill_price, commission, current_price);
+                order.quantity -= quantity;
+            }
+
+            if order.quantity > QUANTITY_EPSILON {
+                self.pending_orders.push((order_id, order));
+            }
+        }
     }
 
-    fn update_price(&mut self, symbol: &str, price: f64) {
-        self.current_prices.insert(symbol.to_string(), price);
+    /// Fill price for an order, capped at the limit for limit orders.
+    fn limit_fill_price(&self, order: &OrderRequest, current_price: f64) -> f64 {
+        let fill_price = self.calculate_fill_price(current_price, order.direction);
+        if order.order_type != ORDER_TYPE_LIMIT {
+            fill_price
+        } else if order.direction == DIRECTION_BUY {
+            fill_price.min(order.limit_price)
+        } else {
+            fill_price.max(order.limit_price)
+        }
     }
-}
+
+    /// Calculate fill price with slippage.
+    fn calculate_fill_price(&self, base_price: f64, direction: i32) -> f64 {
+        let slippage_amount = base_price * self.slippage;
+        if direction == DIRECTION_BUY {
+            base_price + slippage_amount // Buy at higher price
+        } else {
+            base_price - slippage_amount // Sell at lower price
+        }
+    }
+
+    /// Calculate commission for a trade.
+    fn calculate_commission(&self, trade_value: f64) -> f64 {
+        match &self.tiered_fee {
+            Some(fee) => fee.commission(trade_value),
+            None => trade_value * self.commission_rate,
+        }
+    }
+
+    /// Calculate unrealized PnL for a position.
+    fn calculate_unrealized_pnl(&self, position: &PositionInternal) -> f64 {
+        if let Some(&current_price) = self.current_prices.get(&position.symbol) {
+            (current_price - position.average_price) * position.quantity
+        } else {
+            0.0
+        }
+    }
+
+    /// Get total unrealized PnL across all positions.
+    fn total_unrealized_pnl(&self) -> f64 {
+        self.positions
+            .values()
+            .map(|p| self.calculate_unrealized_pnl(p))
+            .sum()
+    }
+
+    /// Get total realized PnL across all positions.
+    fn total_realized_pnl(&self) -> f64 {
+        self.positions.values().map(|p| p.realized_pnl).sum()
+    }
+
+    /// Get slippage configuration.
+    pub fn slippage(&self) -> f64 {
+        self.slippage
+    }
+
+    /// Get commission rate configuration.
+    pub fn commission_rate(&self) -> f64 {
+        self.commission_rate
+    }
+
+    /// Set slippage fraction. Applies to all subsequent fills.
+    pub fn set_slippage(&mut self, slippage: f64) {
+        self.slippage = slippage.max(0.0);
+    }
+
+    /// Set commission rate fraction. Applies to all subsequent fills.
+    pub fn set_commission_rate(&mut self, commission_rate: f64) {
+        self.commission_rate = commission_rate.max(0.0);
+    }
+
+    /// Set the starting balance and reset the cash balance to it.
+    ///
+    /// Other settings (costs, fill model, fee schedule) are kept.
+    pub fn set_initial_balance(&mut self, balance: f64) {
+        self.initial_balance = balance;
+        self.balance = balance;
+    }
+
+    /// Overwrite the cash balance, e.g. when resuming from a checkpoint.
+    pub fn set_balance(&mut self, balance: f64) {
+        self.balance = balance;
+    }
+
+    /// Reset account state to the initial balance.
+    ///
+    /// Clears positions, prices, and pending fills while keeping the
+    /// configured slippage and commission.
+    pub fn reset(&mut self) {
+        self.current_prices.clear();
+        self.positions.clear();
+        self.balance = self.initial_balance;
+        self.next_order_id = 1;
+        self.pending_fills.clear();
+        self.current_timestamp = 0;
+        self.available_volume.clear();
+        self.carried_orders.clear();
+        self.pending_orders.clear();
+        self.funding_payments.clear();
+        if let Some(fee) = &mut self.tiered_fee {
+            fee.reset_volume();
+        }
+    }
+}
+
+impl Default for SimulatedGateway {
+    fn default() -> Self {
+        Self::new(100_000.0, 0.001, 0.0001)
+    }
+}
+
+/// Whether an order can execute at the current price.
+///
+/// Market orders always can; a buy limit needs the price at or below its
+/// limit and a sell limit at or above it.
+fn is_marketable(order: &OrderRequest, current_price: f64) -> bool {
+    if order.order_type != ORDER_TYPE_LIMIT {
+        return true;
+    }
+    if order.direction == DIRECTION_BUY {
+        current_price <= order.limit_price
+    } else {
+        current_price >= order.limit_price
+    }
+}
+
+impl Gateway for SimulatedGateway {
+    fn submit_order(&mut self, order: &OrderRequest, current_price: f64) -> Result<OrderId, GatewayError> {
+        // Validate order
+        if order.quantity <= 0.0 {
+            return Err(GatewayError::InvalidOrder("Quantity must be positive".to_string()));
+        }
+        if order.direction != DIRECTION_BUY && order.direction != DIRECTION_SELL {
+            return Err(GatewayError::InvalidOrder("Invalid direction".to_string()));
+        }
+        validate_tif(order)?;
+
+        let symbol = order.symbol_str().to_string();
+
+        // Sells cannot pass flat when shorting is disabled
+        let order_quantity = self.short_limited_quantity(&symbol, order)?;
+
+        // Rest GTC limit orders the current price does not reach; IOC
+        // orders are cancelled unfilled and FOK orders rejected
+        if !is_marketable(order, current_price) {
+            if order.tif == TIF_FOK {
+                return Err(fok_rejected(order.quantity, 0.0));
+            }
+            let order_id = self.next_order_id;
+            self.next_order_id += 1;
+            if order.tif == TIF_GTC {
+                self.pending_orders.push((order_id, *order));
+            }
+            self.current_prices.insert(symbol, current_price);
+            return Ok(order_id);
+        }
+
+        // Quantity that can fill now under the volume cap (full order if disabled)
+        let fill_quantity = self.fillable_quantity(&symbol, order_quantity);
+        if order.tif == TIF_FOK && fill_quantity < order.quantity - QUANTITY_EPSILON {
+            return Err(fok_rejected(order.quantity, fill_quantity));
+        }
+
+        // Calculate fill price with slippage, capped at any limit
+        let fill_price = self.limit_fill_price(order, current_price);
+        let trade_value = fill_quantity * fill_price;
+        let commission = self.calculate_commission(trade_value);
+
+        // Check if we have sufficient funds for buy orders (opening new long or covering short)
+        self.check_funds(&symbol, order.direction, fill_quantity, fill_price, commission)?;
+
+        // Generate order ID
+        let order_id = self.next_order_id;
+        self.next_order_id += 1;
+
+        if fill_quantity > QUANTITY_EPSILON {
+            self.apply_fill(order_id, order, fill_quantity, fill_price, commission, current_price);
+        } else {
+            self.current_prices.insert(symbol, current_price);
+        }
+
+        // Carry the unfilled GTC remainder if configured; IOC cancels it
+        let remainder = order_quantity - fill_quantity;
+        if remainder > QUANTITY_EPSILON
+            && order.tif == TIF_GTC
+            && self.remainder_policy == RemainderPolicy::CarryForward
+        {
+            let mut carried = *order;
+            carried.quantity = remainder;
+            self.carried_orders.push((order_id, carried));
+        }
+
+        Ok(order_id)
+    }
+
+    fn cancel_order(&mut self, order_id: OrderId) -> Result<(), GatewayError> {
+        // Only resting limit orders and carried remainders can be cancelled;
+        // everything else fills immediately
+        let before = self.carried_orders.len() + self.pending_orders.len();
+        self.carried_orders.retain(|(id, _)| *id != order_id);
+        self.pending_orders.retain(|(id, _)| *id != order_id);
+        if self.carried_orders.len() + self.pending_orders.len() < before {
+            Ok(())
+        } else {
+            Err(GatewayError::OrderNotFound(order_id))
+        }
+    }
+
+    fn query_position(&self, symbol: &str) -> Option<Position> {
+        self.positions.get(symbol).map(|p| {
+            let mut pos = Position::with_symbol(symbol);
+            pos.quantity = p.quantity;
+            pos.average_price = p.average_price;
+            pos.unrealized_pnl = self.calculate_unrealized_pnl(p);
+            pos.realized_pnl = p.realized_pnl;
+            pos
+        })
+    }
+
+    fn query_positions(&self) -> Result<Vec<Position>, GatewayError> {
+        Ok(self
+            .positions
+            .keys()
+            .filter_map(|symbol| self.query_position(symbol))
+            .collect())
+    }
+
+    fn restore_position(&mut self, symbol: &str, quantity: f64, average_price: f64) -> Result<(), GatewayError> {
+        if quantity.abs() < QUANTITY_EPSILON {
+            self.positions.remove(symbol);
+            return Ok(());
+        }
+        let position = self.positions.entry(symbol.to_string()).or_insert(PositionInternal {
+            symbol: symbol.to_string(),
+            quantity: 0.0,
+            average_price: 0.0,
+            realized_pnl: 0.0,
+            lots: EntryLots::default(),
+        });
+        position.quantity = quantity;
+        position.average_price = average_price;
+        position.lots.reset(self.cost_basis_method, quantity.abs(), average_price);
+        Ok(())
+    }
+
+    fn query_account(&self) -> AccountStatus {
+        let unrealized_pnl = self.total_unrealized_pnl();
+        let realized_pnl = self.total_realized_pnl();
+        let equity = self.balance + unrealized_pnl;
+        let available = if self.margin_mode {
+            equity - self.used_margin()
+        } else {
+            self.balance // Simplified: available = balance
+        };
+        
+        AccountStatus {
+            balance: self.balance,
+            equity,
+            available,
+            position_count: self.positions.values().filter(|p| p.quantity.abs() > 0.0001).count() as i32,
+            total_pnl: realized_pnl + unrealized_pnl,
+        }
+    }
+
+    fn get_fills(&mut self) -> Vec<Fill> {
+        std::mem::take(&mut self.pending_fills)
+    }
+
+    fn update_price(&mut self, symbol: &str, price: f64) {
+        self.current_prices.insert(symbol.to_string(), price);
+        self.process_pending_orders(symbol, price);
+    }
+}
 
 /// Extension trait for live trading gateways (placeholder for future implementation).
 pub trait LiveGateway: Gateway {
@@ -385,6 +1248,7 @@ pub trait LiveGateway: Gateway {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event_bus::Event;
 
     #[test]
     fn test_simulated_gateway_creation() {
@@ -442,6 +1306,177 @@ mod tests {
         assert!(matches!(result, Err(GatewayError::InsufficientFunds)));
     }
 
+    fn market_order(direction: i32, quantity: f64) -> OrderRequest {
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = quantity;
+        order.direction = direction;
+        order
+    }
+
+    fn margin_gateway(leverage: f64) -> SimulatedGateway {
+        let mut gateway = SimulatedGateway::new(10_000.0, 0.0, 0.0);
+        gateway.set_margin_mode(true);
+        gateway.set_leverage(leverage);
+        gateway
+    }
+
+    /// Realized PnL and remaining average price after buying 10 @ 100,
+    /// buying 10 @ 110 and selling 10 @ 120.
+    fn realized_after_partial_close(method: CostBasisMethod) -> (f64, f64) {
+        let mut gateway = SimulatedGateway::new(100_000.0, 0.0, 0.0);
+        gateway.set_cost_basis_method(method);
+        gateway.submit_order(&market_order(DIRECTION_BUY, 10.0), 100.0).unwrap();
+        gateway.submit_order(&market_order(DIRECTION_BUY, 10.0), 110.0).unwrap();
+        gateway.submit_order(&market_order(DIRECTION_SELL, 10.0), 120.0).unwrap();
+        let position = gateway.query_position("BTCUSDT").unwrap();
+        (position.realized_pnl, position.average_price)
+    }
+
+    #[test]
+    fn test_fifo_and_lifo_realize_different_pnl() {
+        assert_eq!(SimulatedGateway::default().cost_basis_method(), CostBasisMethod::Average);
+
+        let (average_pnl, average_price) = realized_after_partial_close(CostBasisMethod::Average);
+        assert!((average_pnl - 150.0).abs() < 1e-9);
+        assert!((average_price - 105.0).abs() < 1e-9);
+
+        // FIFO closes the 100 lot, leaving the 110 lot open
+        let (fifo_pnl, fifo_price) = realized_after_partial_close(CostBasisMethod::Fifo);
+        assert!((fifo_pnl - 200.0).abs() < 1e-9);
+        assert!((fifo_price - 110.0).abs() < 1e-9);
+
+        // LIFO closes the 110 lot, leaving the 100 lot open
+        let (lifo_pnl, lifo_price) = realized_after_partial_close(CostBasisMethod::Lifo);
+        assert!((lifo_pnl - 100.0).abs() < 1e-9);
+        assert!((lifo_price - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fifo_short_cover_spans_lots_and_flips() {
+        let mut gateway = SimulatedGateway::new(100_000.0, 0.0, 0.0);
+        gateway.set_cost_basis_method(CostBasisMethod::Fifo);
+        gateway.submit_order(&market_order(DIRECTION_SELL, 5.0), 100.0).unwrap();
+        gateway.submit_order(&market_order(DIRECTION_SELL, 5.0), 90.0).unwrap();
+
+        // Covering all 10 closes the 100 lot, then the 90 lot
+        gateway.submit_order(&market_order(DIRECTION_BUY, 10.0), 80.0).unwrap();
+        let position = gateway.query_position("BTCUSDT").unwrap();
+        assert!((position.realized_pnl - (5.0 * 20.0 + 5.0 * 10.0)).abs() < 1e-9);
+        assert!((position.quantity - 0.0).abs() < 1e-9);
+
+        gateway.submit_order(&market_order(DIRECTION_SELL, 2.0), 80.0).unwrap();
+        gateway.submit_order(&market_order(DIRECTION_SELL, 3.0), 90.0).unwrap();
+        gateway.submit_order(&market_order(DIRECTION_BUY, 4.0), 85.0).unwrap();
+        let position = gateway.query_position("BTCUSDT").unwrap();
+        // FIFO covers 2 @ 80 at a loss of 5 each and 2 of the 90 lot at a gain of 5 each
+        assert!((position.realized_pnl - 150.0).abs() < 1e-9);
+        assert!((position.quantity + 1.0).abs() < 1e-9);
+        assert!((position.average_price - 90.0).abs() < 1e-9);
+
+        // Covering the last 90 lot gains 20, then the fill flips to 2 long @ 70
+        gateway.submit_order(&market_order(DIRECTION_BUY, 3.0), 70.0).unwrap();
+        let position = gateway.query_position("BTCUSDT").unwrap();
+        assert!((position.realized_pnl - 170.0).abs() < 1e-9);
+        assert!((position.quantity - 2.0).abs() < 1e-9);
+        assert!((position.average_price - 70.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_eight_hour_funding_cycle_charges_long() {
+        let mut gateway = margin_gateway(5.0);
+        gateway.submit_order(&market_order(DIRECTION_BUY, 10.0), 100.0).unwrap();
+        gateway.update_price("BTCUSDT", 100.0);
+        let equity_before = gateway.query_account().equity;
+
+        let mut timers = TimerManager::new();
+        let funding_timer = schedule_funding(&mut timers, FUNDING_INTERVAL_8H_MS);
+        let rate = 0.0001;
+
+        // Nothing settles before the first 8 hours elapse
+        assert!(timers.process(FUNDING_INTERVAL_8H_MS as i64 - 1).is_empty());
+
+        for event in timers.process(FUNDING_INTERVAL_8H_MS as i64) {
+            if let Event::Timer { id, timestamp } = event {
+                if id == funding_timer {
+                    gateway.apply_funding("BTCUSDT", rate, timestamp);
+                }
+            }
+        }
+
+        // Long 10 @ 100 pays 10 * 100 * 0.0001 = 0.1
+        let account = gateway.query_account();
+        assert!((equity_before - account.equity - 0.1).abs() < 1e-9);
+        assert!((account.total_pnl + 0.1).abs() < 1e-9);
+        let payments = gateway.funding_payments();
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].timestamp, FUNDING_INTERVAL_8H_MS as i64);
+        assert!((payments[0].amount - 0.1).abs() < 1e-12);
+
+        // Shorts receive positive funding; flat symbols pay nothing
+        gateway.submit_order(&market_order(DIRECTION_SELL, 20.0), 100.0).unwrap();
+        assert!((gateway.apply_funding("BTCUSDT", rate, 0) + 0.1).abs() < 1e-12);
+        assert_eq!(gateway.apply_funding("ETHUSDT", rate, 0), 0.0);
+    }
+
+    #[test]
+    fn test_margin_at_1x_matches_cash_affordability() {
+        for margin_mode in [false, true] {
+            let mut gateway = SimulatedGateway::new(10_000.0, 0.0, 0.0);
+            gateway.set_margin_mode(margin_mode);
+            assert!(matches!(
+                gateway.submit_order(&market_order(DIRECTION_BUY, 101.0), 100.0),
+                Err(GatewayError::InsufficientFunds)
+            ));
+            assert!(gateway.submit_order(&market_order(DIRECTION_BUY, 100.0), 100.0).is_ok());
+            assert!(gateway.submit_order(&market_order(DIRECTION_BUY, 1.0), 100.0).is_err());
+            assert!((gateway.query_account().available).abs() < 1e-9);
+        }
+
+        // Reducing a fully margined position is always allowed
+        let mut gateway = margin_gateway(1.0);
+        gateway.submit_order(&market_order(DIRECTION_BUY, 100.0), 100.0).unwrap();
+        assert!(gateway.submit_order(&market_order(DIRECTION_SELL, 50.0), 100.0).is_ok());
+        assert!((gateway.query_account().available - 5_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_margin_at_5x_affords_five_times_the_position() {
+        let mut gateway = margin_gateway(5.0);
+        assert!(matches!(
+            gateway.submit_order(&market_order(DIRECTION_BUY, 501.0), 100.0),
+            Err(GatewayError::InsufficientFunds)
+        ));
+        gateway.submit_order(&market_order(DIRECTION_BUY, 500.0), 100.0).unwrap();
+
+        // Only fees and realized PnL touch the balance
+        let account = gateway.query_account();
+        assert_eq!(account.balance, 10_000.0);
+        assert!(account.available.abs() < 1e-9);
+        assert!((gateway.used_margin() - 10_000.0).abs() < 1e-9);
+
+        // A 10% rally adds 5,000 of equity against 1,000 more margin
+        gateway.update_price("BTCUSDT", 110.0);
+        let account = gateway.query_account();
+        assert!((account.equity - 15_000.0).abs() < 1e-9);
+        assert!((account.available - 4_000.0).abs() < 1e-9);
+
+        gateway.submit_order(&market_order(DIRECTION_SELL, 500.0), 110.0).unwrap();
+        let account = gateway.query_account();
+        assert!((account.balance - 15_000.0).abs() < 1e-9);
+        assert!((account.available - 15_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_margin_shorts_use_margin_too() {
+        let mut gateway = margin_gateway(5.0);
+        gateway.set_commission_rate(0.001);
+
+        // 500 short needs 10,000 margin plus 50 commission
+        assert!(gateway.submit_order(&market_order(DIRECTION_SELL, 500.0), 100.0).is_err());
+        gateway.submit_order(&market_order(DIRECTION_SELL, 400.0), 100.0).unwrap();
+        assert!((gateway.query_account().balance - 9_960.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_slippage_applied() {
         let mut gateway = SimulatedGateway::new(100_000.0, 0.01, 0.0); // 1% slippage
@@ -474,6 +1509,56 @@ mod tests {
         assert!((fills[0].commission - 50.0).abs() < 0.01); // 50000 * 0.001 = 50
     }
 
+    #[test]
+    fn test_equity_independent_of_position_insertion_order() {
+        // Magnitudes chosen so float summation order changes the result
+        let positions = [("AAA", 1e8, 1.0), ("BBB", 1.0, 0.1), ("CCC", -1e8, 1.0), ("DDD", 3.0, 0.7)];
+        let equity_for = |order: &[usize]| {
+            let mut gateway = SimulatedGateway::new(100_000.0, 0.0, 0.0);
+            for &i in order {
+                let (symbol, quantity, price_move) = positions[i];
+                gateway.restore_position(symbol, quantity, 100.0).unwrap();
+                gateway.update_price(symbol, 100.0 + price_move);
+            }
+            gateway.query_account().equity
+        };
+
+        let forward = equity_for(&[0, 1, 2, 3]);
+        for order in [[3, 2, 1, 0], [2, 0, 3, 1], [1, 3, 0, 2]] {
+            assert_eq!(equity_for(&order).to_bits(), forward.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_volume_tiered_fee_crosses_tier() {
+        let mut gateway = SimulatedGateway::new(100_000.0, 0.0, 0.001);
+        gateway.set_volume_tiered_fee(Some(VolumeTieredFee::new(
+            0.001,
+            vec![(200_000.0, 0.0002), (100_000.0, 0.0005)],
+        )));
+
+        // 40k notional per trade: the third trade crosses 100k at the base rate
+        let mut commissions = Vec::new();
+        for i in 0..4 {
+            let mut order = OrderRequest::with_symbol("BTCUSDT");
+            order.quantity = 1.0;
+            order.direction = if i % 2 == 0 { DIRECTION_BUY } else { DIRECTION_SELL };
+            gateway.submit_order(&order, 40_000.0).unwrap();
+            commissions.push(gateway.get_fills()[0].commission);
+        }
+
+        assert_eq!(commissions.len(), 4);
+        assert!((commissions[0] - 40.0).abs() < 1e-9);
+        assert!((commissions[2] - 40.0).abs() < 1e-9);
+        assert!((commissions[3] - 20.0).abs() < 1e-9);
+        let fee = gateway.volume_tiered_fee().unwrap();
+        assert!((fee.cumulative_notional() - 160_000.0).abs() < 1e-6);
+
+        // A new session starts back at the base rate
+        gateway.reset();
+        assert_eq!(gateway.volume_tiered_fee().unwrap().current_rate(), 0.001);
+    }
+
     #[test]
     fn test_account_status() {
         let mut gateway = SimulatedGateway::new(100_000.0, 0.0, 0.0);
@@ -503,4 +1588,369 @@ mod tests {
         let result = gateway.submit_order(&order, 50_000.0);
         assert!(matches!(result, Err(GatewayError::InvalidOrder(_))));
     }
+
+    fn volume_tick(price: f64, volume: f64, timestamp: i64) -> Tick {
+        Tick {
+            timestamp,
+            price,
+            volume,
+        }
+    }
+
+    #[test]
+    fn test_client_id_echoed_on_fills() {
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+        gateway.set_max_fill_fraction_of_volume(Some(0.1));
+        gateway.set_remainder_policy(RemainderPolicy::CarryForward);
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 100.0, 1));
+
+        let mut first = OrderRequest::with_symbol("BTCUSDT");
+        first.quantity = 15.0;
+        first.direction = DIRECTION_BUY;
+        first.client_id = 1001;
+        gateway.submit_order(&first, 100.0).unwrap();
+
+        let untagged = OrderRequest {
+            quantity: 1.0,
+            direction: DIRECTION_BUY,
+            ..OrderRequest::with_symbol("BTCUSDT")
+        };
+        assert_eq!(untagged.client_id, 0);
+
+        // The carried remainder keeps its tag when it fills on the next tick
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 100.0, 2));
+        gateway.submit_order(&untagged, 100.0).unwrap();
+
+        let tags: Vec<u64> = gateway.get_fills().iter().map(|f| f.client_id).collect();
+        assert_eq!(tags, vec![1001, 1001, 0]);
+    }
+
+    #[test]
+    fn test_volume_cap_disabled_fills_fully() {
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 10.0, 1));
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 500.0;
+        order.direction = DIRECTION_BUY;
+        gateway.submit_order(&order, 100.0).unwrap();
+
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].quantity, 500.0);
+    }
+
+    #[test]
+    fn test_volume_cap_partial_fill_drop() {
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+        gateway.set_max_fill_fraction_of_volume(Some(0.1));
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 200.0, 1));
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 50.0;
+        order.direction = DIRECTION_BUY;
+        gateway.submit_order(&order, 100.0).unwrap();
+
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert!((fills[0].quantity - 20.0).abs() < 1e-9);
+        assert_eq!(gateway.carried_quantity("BTCUSDT"), 0.0);
+        assert!((gateway.query_position("BTCUSDT").unwrap().quantity - 20.0).abs() < 1e-9);
+
+        // Liquidity is shared within the tick
+        gateway.submit_order(&order, 100.0).unwrap();
+        assert!(gateway.get_fills().is_empty());
+
+        // Remainder was dropped, so the next tick fills nothing
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 200.0, 2));
+        assert!(gateway.get_fills().is_empty());
+    }
+
+    #[test]
+    fn test_fok_rejects_when_volume_cap_limits_fill() {
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+        gateway.set_max_fill_fraction_of_volume(Some(0.1));
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 200.0, 1));
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 50.0;
+        order.direction = DIRECTION_BUY;
+        order.tif = TIF_FOK;
+        let result = gateway.submit_order(&order, 100.0);
+        assert!(matches!(result, Err(GatewayError::InvalidOrder(_))));
+        assert!(gateway.get_fills().is_empty());
+        assert!(gateway.query_position("BTCUSDT").is_none());
+
+        // Within the available 20 the order fills in full
+        order.quantity = 20.0;
+        gateway.submit_order(&order, 100.0).unwrap();
+        assert!((gateway.get_fills()[0].quantity - 20.0).abs() < 1e-9);
+
+        // A non-marketable FOK limit cannot fill now either
+        order.order_type = ORDER_TYPE_LIMIT;
+        order.limit_price = 90.0;
+        assert!(gateway.submit_order(&order, 100.0).is_err());
+        assert!(gateway.pending_orders().is_empty());
+    }
+
+    #[test]
+    fn test_ioc_fills_available_and_cancels_remainder() {
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+        gateway.set_max_fill_fraction_of_volume(Some(0.1));
+        gateway.set_remainder_policy(RemainderPolicy::CarryForward);
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 200.0, 1));
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 50.0;
+        order.direction = DIRECTION_BUY;
+        order.tif = TIF_IOC;
+        let order_id = gateway.submit_order(&order, 100.0).unwrap();
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert!((fills[0].quantity - 20.0).abs() < 1e-9);
+
+        // The remainder is cancelled, not carried to the next tick
+        assert_eq!(gateway.carried_quantity("BTCUSDT"), 0.0);
+        gateway.update_tick("BTCUSDT", &volume_tick(101.0, 1000.0, 2));
+        assert!(gateway.get_fills().is_empty());
+        assert!(matches!(gateway.cancel_order(order_id), Err(GatewayError::OrderNotFound(_))));
+
+        // A non-marketable IOC limit is cancelled instead of resting
+        order.order_type = ORDER_TYPE_LIMIT;
+        order.limit_price = 90.0;
+        gateway.submit_order(&order, 101.0).unwrap();
+        assert!(gateway.pending_orders().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_tif_rejected() {
+        let mut gateway = SimulatedGateway::default();
+        let mut order = market_order(DIRECTION_BUY, 1.0);
+        order.tif = 7;
+        assert!(matches!(gateway.submit_order(&order, 100.0), Err(GatewayError::InvalidOrder(_))));
+    }
+
+    #[test]
+    fn test_volume_cap_carry_forward() {
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+        gateway.set_max_fill_fraction_of_volume(Some(0.1));
+        gateway.set_remainder_policy(RemainderPolicy::CarryForward);
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 200.0, 1));
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 50.0;
+        order.direction = DIRECTION_BUY;
+        let order_id = gateway.submit_order(&order, 100.0).unwrap();
+        assert_eq!(gateway.get_fills().len(), 1);
+        assert!((gateway.carried_quantity("BTCUSDT") - 30.0).abs() < 1e-9);
+
+        gateway.update_tick("BTCUSDT", &volume_tick(101.0, 200.0, 2));
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].order_id, order_id);
+        assert_eq!(fills[0].timestamp, 2);
+        assert!((fills[0].quantity - 20.0).abs() < 1e-9);
+
+        gateway.update_tick("BTCUSDT", &volume_tick(102.0, 1000.0, 3));
+        let fills = gateway.get_fills();
+        assert!((fills[0].quantity - 10.0).abs() < 1e-9);
+        assert_eq!(gateway.carried_quantity("BTCUSDT"), 0.0);
+        assert!((gateway.query_position("BTCUSDT").unwrap().quantity - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_carried_limit_remainder_respects_limit() {
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.001, 0.0);
+        gateway.set_max_fill_fraction_of_volume(Some(0.1));
+        gateway.set_remainder_policy(RemainderPolicy::CarryForward);
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 200.0, 1));
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 50.0;
+        order.direction = DIRECTION_BUY;
+        order.order_type = ORDER_TYPE_LIMIT;
+        order.limit_price = 100.05;
+        gateway.submit_order(&order, 100.0).unwrap();
+        gateway.get_fills();
+        assert!((gateway.carried_quantity("BTCUSDT") - 30.0).abs() < 1e-9);
+
+        // Past the limit the remainder waits instead of filling at market
+        gateway.update_tick("BTCUSDT", &volume_tick(102.0, 200.0, 2));
+        assert!(gateway.get_fills().is_empty());
+        assert!((gateway.carried_quantity("BTCUSDT") - 30.0).abs() < 1e-9);
+
+        // Back at the limit, slippage is capped at the limit price
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 200.0, 3));
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert!((fills[0].quantity - 20.0).abs() < 1e-9);
+        assert!((fills[0].price - 100.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cancel_carried_remainder() {
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+        gateway.set_max_fill_fraction_of_volume(Some(0.5));
+        gateway.set_remainder_policy(RemainderPolicy::CarryForward);
+        gateway.update_tick("BTCUSDT", &volume_tick(100.0, 10.0, 1));
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 20.0;
+        order.direction = DIRECTION_BUY;
+        let order_id = gateway.submit_order(&order, 100.0).unwrap();
+
+        assert!(gateway.cancel_order(order_id).is_ok());
+        assert_eq!(gateway.carried_quantity("BTCUSDT"), 0.0);
+        assert!(matches!(gateway.cancel_order(order_id), Err(GatewayError::OrderNotFound(_))));
+    }
+
+    fn buy_limit(quantity: f64, limit_price: f64) -> OrderRequest {
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = quantity;
+        order.direction = DIRECTION_BUY;
+        order.order_type = ORDER_TYPE_LIMIT;
+        order.limit_price = limit_price;
+        order
+    }
+
+    #[test]
+    fn test_buy_limit_rests_until_price_drops() {
+        let mut gateway = SimulatedGateway::new(100_000.0, 0.0, 0.0);
+        let order_id = gateway.submit_order(&buy_limit(10.0, 95.0), 100.0).unwrap();
+
+        assert!(gateway.get_fills().is_empty());
+        assert_eq!(gateway.pending_orders().len(), 1);
+
+        gateway.update_price("BTCUSDT", 97.0);
+        assert!(gateway.get_fills().is_empty());
+
+        gateway.update_price("BTCUSDT", 94.0);
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].order_id, order_id);
+        assert_eq!(fills[0].quantity, 10.0);
+        assert_eq!(fills[0].price, 94.0);
+        assert!(gateway.pending_orders().is_empty());
+        assert_eq!(gateway.query_position("BTCUSDT").unwrap().quantity, 10.0);
+    }
+
+    #[test]
+    fn test_cancel_resting_limit_prevents_fill() {
+        let mut gateway = SimulatedGateway::new(100_000.0, 0.0, 0.0);
+        let order_id = gateway.submit_order(&buy_limit(10.0, 95.0), 100.0).unwrap();
+
+        assert!(gateway.cancel_order(order_id).is_ok());
+        gateway.update_price("BTCUSDT", 90.0);
+
+        assert!(gateway.get_fills().is_empty());
+        assert!(gateway.query_position("BTCUSDT").is_none());
+        assert!(matches!(gateway.cancel_order(order_id), Err(GatewayError::OrderNotFound(_))));
+    }
+
+    fn long_gateway(quantity: f64) -> SimulatedGateway {
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+        gateway.set_allow_short(false);
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = quantity;
+        order.direction = DIRECTION_BUY;
+        gateway.submit_order(&order, 100.0).unwrap();
+        gateway.get_fills();
+        gateway
+    }
+
+    fn sell_order(quantity: f64) -> OrderRequest {
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = quantity;
+        order.direction = DIRECTION_SELL;
+        order
+    }
+
+    #[test]
+    fn test_no_short_clamps_oversell_to_flat() {
+        let mut gateway = long_gateway(3.0);
+        assert_eq!(gateway.oversell_policy(), OversellPolicy::Clamp);
+
+        gateway.submit_order(&sell_order(5.0), 100.0).unwrap();
+
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].quantity, 3.0);
+        assert_eq!(gateway.query_position("BTCUSDT").unwrap().quantity, 0.0);
+    }
+
+    #[test]
+    fn test_no_short_rejects_oversell() {
+        let mut gateway = long_gateway(3.0);
+        gateway.set_oversell_policy(OversellPolicy::Reject);
+
+        let result = gateway.submit_order(&sell_order(5.0), 100.0);
+        assert!(matches!(result, Err(GatewayError::InvalidOrder(_))));
+        assert!(gateway.get_fills().is_empty());
+        assert_eq!(gateway.query_position("BTCUSDT").unwrap().quantity, 3.0);
+
+        // A sell within the position still fills
+        gateway.submit_order(&sell_order(3.0), 100.0).unwrap();
+        assert_eq!(gateway.query_position("BTCUSDT").unwrap().quantity, 0.0);
+    }
+
+    #[test]
+    fn test_no_short_forbids_opening_short() {
+        for policy in [OversellPolicy::Clamp, OversellPolicy::Reject] {
+            let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+            gateway.set_allow_short(false);
+            gateway.set_oversell_policy(policy);
+
+            let result = gateway.submit_order(&sell_order(1.0), 100.0);
+            assert!(matches!(result, Err(GatewayError::InvalidOrder(_))));
+            assert!(gateway.query_position("BTCUSDT").is_none());
+        }
+
+        // Shorting is allowed by default
+        let mut gateway = SimulatedGateway::new(1_000_000.0, 0.0, 0.0);
+        assert!(gateway.allow_short());
+        gateway.submit_order(&sell_order(1.0), 100.0).unwrap();
+        assert_eq!(gateway.query_position("BTCUSDT").unwrap().quantity, -1.0);
+    }
+
+    /// Gateway implementing only the required methods.
+    struct MinimalGateway;
+
+    impl Gateway for MinimalGateway {
+        fn submit_order(&mut self, _order: &OrderRequest, _current_price: f64) -> Result<OrderId, GatewayError> {
+            Err(GatewayError::NotConnected)
+        }
+
+        fn cancel_order(&mut self, order_id: OrderId) -> Result<(), GatewayError> {
+            Err(GatewayError::OrderNotFound(order_id))
+        }
+
+        fn query_position(&self, _symbol: &str) -> Option<Position> {
+            None
+        }
+
+        fn query_account(&self) -> AccountStatus {
+            AccountStatus::default()
+        }
+
+        fn get_fills(&mut self) -> Vec<Fill> {
+            Vec::new()
+        }
+
+        fn update_price(&mut self, _symbol: &str, _price: f64) {}
+    }
+
+    #[test]
+    fn test_position_methods_default_to_not_supported() {
+        use crate::persistence::{reconcile, reconcile_and_restore, RecoveredState};
+
+        let mut gateway = MinimalGateway;
+        let not_supported = GatewayError::NotSupported;
+        assert_eq!(gateway.restore_position("BTCUSDT", 1.0, 100.0), Err(not_supported("restore_position")));
+        assert_eq!(gateway.query_positions(), Err(not_supported("query_positions")));
+
+        // Reconciliation reports the gap instead of finding nothing to fix
+        let recovered = RecoveredState::default();
+        assert_eq!(reconcile(&mut gateway, &recovered), Err(not_supported("query_positions")));
+        assert_eq!(reconcile_and_restore(&mut gateway, &recovered), Err(not_supported("query_positions")));
+    }
 }
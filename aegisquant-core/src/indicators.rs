@@ -4,16 +4,29 @@
 //! - Moving Averages (MA5, MA10, MA20, MA60)
 //! - Bollinger Bands
 //! - MACD (Moving Average Convergence Divergence)
+//! - Keltner Channels and Bollinger/Keltner squeeze detection
+//! - Average True Range (streaming or as part of `IndicatorResult`)
+//! - Slow Stochastic Oscillator (%K/%D)
+//! - Session VWAP, accumulated from `update_with_volume`
+//! - Donchian Channels (rolling highest high / lowest low)
+//! - Close-based Keltner Channels (EMA of close +/- ATR multiple)
+//!
+//! `IndicatorCalculator` keeps its streaming state in local mirrors of the
+//! `ta` indicators (same arithmetic, so outputs are identical) whose
+//! internals can be checkpointed with `serialize_state` and resumed with
+//! `restore_state`.
 
+use std::collections::VecDeque;
 use std::panic::catch_unwind;
 
 use ta::indicators::{
-    BollingerBands, ExponentialMovingAverage, MovingAverageConvergenceDivergence,
+    AverageTrueRange, BollingerBands, ExponentialMovingAverage, KeltnerChannel, MovingAverageConvergenceDivergence,
     SimpleMovingAverage,
 };
-use ta::Next;
+use ta::{Close, High, Low, Next};
 
-use crate::ffi::{ERR_INTERNAL_PANIC, ERR_NULL_POINTER, ERR_SUCCESS};
+use crate::error::{EngineError, EngineResult};
+use crate::ffi::{ERR_INTERNAL_PANIC, ERR_INVALID_DATA, ERR_INVALID_PARAM, ERR_NULL_POINTER, ERR_SUCCESS};
 use crate::precision::Price;
 
 /// Result of indicator calculations for a single price point.
@@ -43,27 +56,100 @@ pub struct IndicatorResult {
     pub macd_dea: f64,
     /// MACD histogram (DIF - DEA)
     pub macd_histogram: f64,
+    /// 14-period Average True Range
+    pub atr14: f64,
+    /// Slow stochastic %K (14, 3)
+    pub slow_k: f64,
+    /// Slow stochastic %D (3-period SMA of slow %K)
+    pub slow_d: f64,
+    /// Session volume-weighted average price (0 until volume is fed)
+    pub vwap: f64,
+    /// Donchian upper channel (highest high over the lookback)
+    pub donchian_upper: f64,
+    /// Donchian lower channel (lowest low over the lookback)
+    pub donchian_lower: f64,
+    /// Donchian mid line ((upper + lower) / 2)
+    pub donchian_mid: f64,
+    /// Keltner upper channel (EMA + multiplier * ATR)
+    pub keltner_upper: f64,
+    /// Keltner middle line (EMA of close)
+    pub keltner_middle: f64,
+    /// Keltner lower channel (EMA - multiplier * ATR)
+    pub keltner_lower: f64,
+}
+
+/// Handling of a non-finite (NaN/Inf) price in `IndicatorCalculator::update`
+/// or `IndicatorCalculator::update_ohlc`.
+///
+/// Either way the price is never fed to the indicators, so one bad tick
+/// cannot poison later outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    /// Return the previous valid result
+    #[default]
+    CarryForward,
+    /// Return the previous valid result and flag the input as rejected
+    Reject,
+}
+
+/// Middle line of `calculate_keltner_channels`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeltnerBasis {
+    /// EMA of the typical price (high + low + close) / 3
+    #[default]
+    TypicalPrice,
+    /// EMA of the close, matching `IndicatorResult::keltner_middle`; the
+    /// bands equal the middle line until `period` bars have been seen
+    Close,
+}
+
+impl From<i32> for NonFinitePolicy {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => NonFinitePolicy::Reject,
+            _ => NonFinitePolicy::CarryForward,
+        }
+    }
 }
 
 /// Calculator for technical indicators.
 ///
 /// Maintains internal state for streaming indicator calculations.
 /// Each call to `update()` processes a new price and returns the current indicator values.
+/// The state can be checkpointed with `serialize_state` and resumed with
+/// `restore_state`.
+#[derive(Debug, Clone)]
 pub struct IndicatorCalculator {
     /// 5-period Simple Moving Average
-    ma5: SimpleMovingAverage,
+    ma5: RollingSma,
     /// 10-period Simple Moving Average
-    ma10: SimpleMovingAverage,
+    ma10: RollingSma,
     /// 20-period Simple Moving Average
-    ma20: SimpleMovingAverage,
+    ma20: RollingSma,
     /// 60-period Simple Moving Average
-    ma60: SimpleMovingAverage,
+    ma60: RollingSma,
     /// Bollinger Bands (20-period, 2 standard deviations)
-    boll: BollingerBands,
+    boll: RollingBollinger,
     /// MACD (12, 26, 9)
-    macd: MovingAverageConvergenceDivergence,
+    macd: RollingMacd,
+    /// 14-period Average True Range
+    atr14: RollingAtr,
+    /// Slow Stochastic Oscillator (14, 3)
+    stoch: StochasticOscillator,
+    /// Session price * volume accumulators
+    vwap: SessionVwap,
+    /// Donchian Channel (20-period unless configured)
+    donchian: DonchianChannel,
+    /// Keltner Channel (20-period, 2 ATRs unless configured)
+    keltner: RollingKeltner,
     /// Count of prices processed
     count: usize,
+    /// Handling of non-finite close prices
+    non_finite_policy: NonFinitePolicy,
+    /// Result of the last finite close price
+    last_result: IndicatorResult,
+    /// Whether the last update was rejected under `NonFinitePolicy::Reject`
+    last_input_rejected: bool,
 }
 
 impl IndicatorCalculator {
@@ -73,49 +159,134 @@ impl IndicatorCalculator {
     /// - MA periods: 5, 10, 20, 60
     /// - Bollinger Bands: 20-period, 2 standard deviations
     /// - MACD: 12, 26, 9 (fast, slow, signal)
+    /// - ATR: 14-period
+    /// - Stochastic: 14-period %K, 3-period smoothing
+    /// - Donchian Channel: 20-period
+    /// - Keltner Channel: 20-period EMA and ATR, 2 ATRs
     pub fn new() -> Self {
         Self {
-            ma5: SimpleMovingAverage::new(5).expect("Invalid MA5 period"),
-            ma10: SimpleMovingAverage::new(10).expect("Invalid MA10 period"),
-            ma20: SimpleMovingAverage::new(20).expect("Invalid MA20 period"),
-            ma60: SimpleMovingAverage::new(60).expect("Invalid MA60 period"),
-            boll: BollingerBands::new(20, 2.0).expect("Invalid Bollinger Bands params"),
-            macd: MovingAverageConvergenceDivergence::new(12, 26, 9)
-                .expect("Invalid MACD params"),
+            ma5: RollingSma::new(5).expect("Invalid MA5 period"),
+            ma10: RollingSma::new(10).expect("Invalid MA10 period"),
+            ma20: RollingSma::new(20).expect("Invalid MA20 period"),
+            ma60: RollingSma::new(60).expect("Invalid MA60 period"),
+            boll: RollingBollinger::new(20, 2.0).expect("Invalid Bollinger Bands params"),
+            macd: RollingMacd::new(12, 26, 9).expect("Invalid MACD params"),
+            atr14: RollingAtr::new(14).expect("Invalid ATR period"),
+            stoch: StochasticOscillator::new(14, 3).expect("Invalid stochastic params"),
+            vwap: SessionVwap::default(),
+            donchian: DonchianChannel::new(20).expect("Invalid Donchian period"),
+            keltner: RollingKeltner::new(20, 2.0).expect("Invalid Keltner params"),
             count: 0,
+            non_finite_policy: NonFinitePolicy::default(),
+            last_result: IndicatorResult::default(),
+            last_input_rejected: false,
         }
     }
 
     /// Create a new IndicatorCalculator with custom MA periods.
     pub fn with_ma_periods(ma5: usize, ma10: usize, ma20: usize, ma60: usize) -> Option<Self> {
         Some(Self {
-            ma5: SimpleMovingAverage::new(ma5).ok()?,
-            ma10: SimpleMovingAverage::new(ma10).ok()?,
-            ma20: SimpleMovingAverage::new(ma20).ok()?,
-            ma60: SimpleMovingAverage::new(ma60).ok()?,
-            boll: BollingerBands::new(20, 2.0).ok()?,
-            macd: MovingAverageConvergenceDivergence::new(12, 26, 9).ok()?,
+            ma5: RollingSma::new(ma5)?,
+            ma10: RollingSma::new(ma10)?,
+            ma20: RollingSma::new(ma20)?,
+            ma60: RollingSma::new(ma60)?,
+            boll: RollingBollinger::new(20, 2.0)?,
+            macd: RollingMacd::new(12, 26, 9)?,
+            atr14: RollingAtr::new(14)?,
+            stoch: StochasticOscillator::new(14, 3)?,
+            vwap: SessionVwap::default(),
+            donchian: DonchianChannel::new(20)?,
+            keltner: RollingKeltner::new(20, 2.0)?,
             count: 0,
+            non_finite_policy: NonFinitePolicy::default(),
+            last_result: IndicatorResult::default(),
+            last_input_rejected: false,
         })
     }
 
     /// Create a new IndicatorCalculator with custom MACD parameters.
     pub fn with_macd_params(fast: usize, slow: usize, signal: usize) -> Option<Self> {
         Some(Self {
-            ma5: SimpleMovingAverage::new(5).ok()?,
-            ma10: SimpleMovingAverage::new(10).ok()?,
-            ma20: SimpleMovingAverage::new(20).ok()?,
-            ma60: SimpleMovingAverage::new(60).ok()?,
-            boll: BollingerBands::new(20, 2.0).ok()?,
-            macd: MovingAverageConvergenceDivergence::new(fast, slow, signal).ok()?,
+            ma5: RollingSma::new(5)?,
+            ma10: RollingSma::new(10)?,
+            ma20: RollingSma::new(20)?,
+            ma60: RollingSma::new(60)?,
+            boll: RollingBollinger::new(20, 2.0)?,
+            macd: RollingMacd::new(fast, slow, signal)?,
+            atr14: RollingAtr::new(14)?,
+            stoch: StochasticOscillator::new(14, 3)?,
+            vwap: SessionVwap::default(),
+            donchian: DonchianChannel::new(20)?,
+            keltner: RollingKeltner::new(20, 2.0)?,
             count: 0,
+            non_finite_policy: NonFinitePolicy::default(),
+            last_result: IndicatorResult::default(),
+            last_input_rejected: false,
         })
     }
 
+    /// Set the Donchian Channel lookback, or None if `period` is zero.
+    pub fn with_donchian_period(mut self, period: usize) -> Option<Self> {
+        self.donchian = DonchianChannel::new(period)?;
+        Some(self)
+    }
+
+    /// Set the Keltner Channel period and ATR multiplier.
+    ///
+    /// Returns None if `period` is zero or `multiplier` is negative or
+    /// non-finite.
+    pub fn with_keltner_params(mut self, period: usize, multiplier: f64) -> Option<Self> {
+        self.keltner = RollingKeltner::new(period, multiplier)?;
+        Some(self)
+    }
+
+    /// Set how non-finite close prices are handled.
+    pub fn with_non_finite_policy(mut self, policy: NonFinitePolicy) -> Self {
+        self.non_finite_policy = policy;
+        self
+    }
+
+    /// Get the non-finite price policy.
+    pub fn non_finite_policy(&self) -> NonFinitePolicy {
+        self.non_finite_policy
+    }
+
+    /// Set how non-finite close prices are handled.
+    pub fn set_non_finite_policy(&mut self, policy: NonFinitePolicy) {
+        self.non_finite_policy = policy;
+    }
+
+    /// Check whether the last update was rejected as non-finite.
+    ///
+    /// Only set under `NonFinitePolicy::Reject`.
+    pub fn last_input_rejected(&self) -> bool {
+        self.last_input_rejected
+    }
+
     /// Update indicators with a new close price.
     ///
     /// Returns the current indicator values after processing the new price.
+    /// The bar is treated as high = low = close, so `atr14` only reflects
+    /// close-to-close moves and the stochastic window spans closes only.
+    /// VWAP is left unchanged; use `update_with_volume` to accumulate it. A
+    /// non-finite price is skipped and the previous valid result returned;
+    /// see `NonFinitePolicy`.
     pub fn update(&mut self, close: Price) -> IndicatorResult {
+        self.update_ohlc(close, close, close)
+    }
+
+    /// Update indicators with a new high/low/close bar.
+    ///
+    /// Close-based indicators use `close`; `atr14`, the stochastic, the
+    /// Donchian Channel and the Keltner ATR use the full bar. If any
+    /// of the prices is non-finite the bar is skipped and the previous valid
+    /// result returned; see `NonFinitePolicy`.
+    pub fn update_ohlc(&mut self, high: Price, low: Price, close: Price) -> IndicatorResult {
+        if !(high.is_finite() && low.is_finite() && close.is_finite()) {
+            self.last_input_rejected = self.non_finite_policy == NonFinitePolicy::Reject;
+            return self.last_result;
+        }
+        self.last_input_rejected = false;
         self.count += 1;
 
         // Calculate moving averages
@@ -125,23 +296,68 @@ impl IndicatorCalculator {
         let ma60_val = self.ma60.next(close);
 
         // Calculate Bollinger Bands
-        let boll_output = self.boll.next(close);
+        let (boll_upper, boll_middle, boll_lower) = self.boll.next(close);
 
         // Calculate MACD
-        let macd_output = self.macd.next(close);
+        let (macd_dif, macd_dea, macd_histogram) = self.macd.next(close);
+
+        // Calculate ATR
+        let atr14_val = self.atr14.next(high, low, close);
+
+        // Calculate Stochastic
+        let (slow_k, slow_d) = self.stoch.next(close, high, low);
+
+        // Calculate Donchian Channel
+        let (donchian_upper, donchian_mid, donchian_lower) = self.donchian.next(high, low);
 
-        IndicatorResult {
+        // Calculate Keltner Channel
+        let (keltner_upper, keltner_middle, keltner_lower) = self.keltner.next(high, low, close);
+
+        self.last_result = IndicatorResult {
             ma5: ma5_val,
             ma10: ma10_val,
             ma20: ma20_val,
             ma60: ma60_val,
-            boll_upper: boll_output.upper,
-            boll_middle: boll_output.average,
-            boll_lower: boll_output.lower,
-            macd_dif: macd_output.macd,
-            macd_dea: macd_output.signal,
-            macd_histogram: macd_output.histogram,
+            boll_upper,
+            boll_middle,
+            boll_lower,
+            macd_dif,
+            macd_dea,
+            macd_histogram,
+            atr14: atr14_val,
+            slow_k,
+            slow_d,
+            vwap: self.vwap.value(),
+            donchian_upper,
+            donchian_lower,
+            donchian_mid,
+            keltner_upper,
+            keltner_middle,
+            keltner_lower,
+        };
+        self.last_result
+    }
+
+    /// Update indicators with a new price and its traded volume.
+    ///
+    /// Updates every indicator as `update` does, then adds `price * volume`
+    /// to the session VWAP. Non-positive or non-finite volume leaves VWAP
+    /// unchanged, as does a skipped non-finite price.
+    pub fn update_with_volume(&mut self, price: Price, volume: f64) -> IndicatorResult {
+        self.update(price);
+        if price.is_finite() && volume.is_finite() && volume > 0.0 {
+            self.vwap.add(price, volume);
+            self.last_result.vwap = self.vwap.value();
         }
+        self.last_result
+    }
+
+    /// Start a new VWAP session, zeroing the price * volume accumulators.
+    ///
+    /// Other indicators keep their state.
+    pub fn reset_session(&mut self) {
+        self.vwap = SessionVwap::default();
+        self.last_result.vwap = 0.0;
     }
 
     /// Get the number of prices processed.
@@ -149,9 +365,69 @@ impl IndicatorCalculator {
         self.count
     }
 
+    /// Checkpoint the streaming state of every indicator.
+    ///
+    /// The state holds each indicator's window of recent inputs, running
+    /// sums and counts, and the last result; restoring it with
+    /// `restore_state` resumes with bit-identical outputs. The non-finite
+    /// price policy is configuration and is not included.
+    pub fn serialize_state(&self) -> Vec<f64> {
+        let mut out = vec![STATE_VERSION, self.count as f64];
+        self.ma5.save(&mut out);
+        self.ma10.save(&mut out);
+        self.ma20.save(&mut out);
+        self.ma60.save(&mut out);
+        self.boll.save(&mut out);
+        self.macd.save(&mut out);
+        self.atr14.save(&mut out);
+        self.stoch.save(&mut out);
+        self.vwap.save(&mut out);
+        self.donchian.save(&mut out);
+        self.keltner.save(&mut out);
+        out.extend(result_fields(&self.last_result));
+        out
+    }
+
+    /// Resume from a state produced by `serialize_state`.
+    ///
+    /// The calculator must have the same periods as the one that was
+    /// checkpointed. On error the calculator is left unchanged.
+    pub fn restore_state(&mut self, state: &[f64]) -> EngineResult<()> {
+        let mut restored = self.clone();
+        restored.load(&mut StateReader { data: state }).ok_or_else(|| {
+            EngineError::validation("Indicator state is malformed or does not match the calculator's periods")
+        })?;
+        restored.last_input_rejected = false;
+        *self = restored;
+        Ok(())
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        if reader.value()? != STATE_VERSION {
+            return None;
+        }
+        self.count = reader.count()?;
+        self.ma5.load(reader)?;
+        self.ma10.load(reader)?;
+        self.ma20.load(reader)?;
+        self.ma60.load(reader)?;
+        self.boll.load(reader)?;
+        self.macd.load(reader)?;
+        self.atr14.load(reader)?;
+        self.stoch.load(reader)?;
+        self.vwap.load(reader)?;
+        self.donchian.load(reader)?;
+        self.keltner.load(reader)?;
+        self.last_result = result_from_fields(reader.values(RESULT_FIELDS)?);
+        reader.data.is_empty().then_some(())
+    }
+
     /// Reset the calculator to initial state.
+    ///
+    /// The non-finite price policy is kept.
     pub fn reset(&mut self) {
-        *self = Self::new();
+        let policy = self.non_finite_policy;
+        *self = Self::new().with_non_finite_policy(policy);
     }
 }
 
@@ -211,6 +487,8 @@ pub unsafe extern "C" fn free_indicator_calculator(calc: *mut IndicatorCalculato
 /// # Returns
 /// - ERR_SUCCESS on success
 /// - ERR_NULL_POINTER if calc or result is null
+/// - ERR_INVALID_DATA if `close` was rejected as non-finite (the previous
+///   result is still written)
 #[no_mangle]
 pub unsafe extern "C" fn calculate_indicators(
     calc: *mut IndicatorCalculator,
@@ -225,7 +503,90 @@ pub unsafe extern "C" fn calculate_indicators(
         let calc_ref = &mut *calc;
         let indicator_result = calc_ref.update(close);
         *result = indicator_result;
-        ERR_SUCCESS
+        if calc_ref.last_input_rejected() {
+            ERR_INVALID_DATA
+        } else {
+            ERR_SUCCESS
+        }
+    });
+
+    match outcome {
+        Ok(code) => code,
+        Err(_) => ERR_INTERNAL_PANIC,
+    }
+}
+
+/// Update indicators with a new high/low/close bar.
+///
+/// # Safety
+/// - `calc` must be a valid pointer from `create_indicator_calculator`
+/// - `result` must be a valid pointer to write IndicatorResult
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if calc or result is null
+/// - ERR_INVALID_DATA if the bar was rejected as non-finite (the previous
+///   result is still written)
+#[no_mangle]
+pub unsafe extern "C" fn calculate_indicators_ohlc(
+    calc: *mut IndicatorCalculator,
+    high: f64,
+    low: f64,
+    close: f64,
+    result: *mut IndicatorResult,
+) -> i32 {
+    if calc.is_null() || result.is_null() {
+        return ERR_NULL_POINTER;
+    }
+
+    let outcome = catch_unwind(|| {
+        let calc_ref = &mut *calc;
+        let indicator_result = calc_ref.update_ohlc(high, low, close);
+        *result = indicator_result;
+        if calc_ref.last_input_rejected() {
+            ERR_INVALID_DATA
+        } else {
+            ERR_SUCCESS
+        }
+    });
+
+    match outcome {
+        Ok(code) => code,
+        Err(_) => ERR_INTERNAL_PANIC,
+    }
+}
+
+/// Update indicators with a new price and its traded volume.
+///
+/// # Safety
+/// - `calc` must be a valid pointer from `create_indicator_calculator`
+/// - `result` must be a valid pointer to write IndicatorResult
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if calc or result is null
+/// - ERR_INVALID_DATA if the price was rejected as non-finite (the previous
+///   result is still written)
+#[no_mangle]
+pub unsafe extern "C" fn calculate_indicators_with_volume(
+    calc: *mut IndicatorCalculator,
+    price: f64,
+    volume: f64,
+    result: *mut IndicatorResult,
+) -> i32 {
+    if calc.is_null() || result.is_null() {
+        return ERR_NULL_POINTER;
+    }
+
+    let outcome = catch_unwind(|| {
+        let calc_ref = &mut *calc;
+        let indicator_result = calc_ref.update_with_volume(price, volume);
+        *result = indicator_result;
+        if calc_ref.last_input_rejected() {
+            ERR_INVALID_DATA
+        } else {
+            ERR_SUCCESS
+        }
     });
 
     match outcome {
@@ -244,6 +605,8 @@ pub unsafe extern "C" fn calculate_indicators(
 /// # Returns
 /// - ERR_SUCCESS on success
 /// - ERR_NULL_POINTER if any pointer is null
+/// - ERR_INVALID_DATA if any price was rejected as non-finite (all results
+///   are still written)
 #[no_mangle]
 pub unsafe extern "C" fn calculate_indicators_batch(
     calc: *mut IndicatorCalculator,
@@ -264,11 +627,15 @@ pub unsafe extern "C" fn calculate_indicators_batch(
         let prices_slice = std::slice::from_raw_parts(prices, count as usize);
         let results_slice = std::slice::from_raw_parts_mut(results, count as usize);
 
+        let mut code = ERR_SUCCESS;
         for (i, &price) in prices_slice.iter().enumerate() {
             results_slice[i] = calc_ref.update(price);
+            if calc_ref.last_input_rejected() {
+                code = ERR_INVALID_DATA;
+            }
         }
 
-        ERR_SUCCESS
+        code
     });
 
     match outcome {
@@ -303,101 +670,1062 @@ pub unsafe extern "C" fn reset_indicator_calculator(calc: *mut IndicatorCalculat
     }
 }
 
-// ============================================================================
-// Standalone calculation functions for verification
-// ============================================================================
-
-/// Calculate Simple Moving Average for a price series.
+/// Start a new VWAP session on the indicator calculator.
 ///
-/// Returns the SMA values for each price point.
-pub fn calculate_sma(prices: &[f64], period: usize) -> Vec<f64> {
-    if period == 0 || prices.is_empty() {
-        return vec![];
+/// # Safety
+/// - `calc` must be a valid pointer from `create_indicator_calculator`
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if calc is null
+#[no_mangle]
+pub unsafe extern "C" fn reset_indicator_session(calc: *mut IndicatorCalculator) -> i32 {
+    if calc.is_null() {
+        return ERR_NULL_POINTER;
     }
 
-    let mut sma = SimpleMovingAverage::new(period).expect("Invalid period");
-    prices.iter().map(|&p| sma.next(p)).collect()
+    let outcome = catch_unwind(|| {
+        let calc_ref = &mut *calc;
+        calc_ref.reset_session();
+        ERR_SUCCESS
+    });
+
+    match outcome {
+        Ok(code) => code,
+        Err(_) => ERR_INTERNAL_PANIC,
+    }
 }
 
-/// Calculate Exponential Moving Average for a price series.
+/// Callback receiving a serialized indicator state as `len` doubles.
 ///
-/// Returns the EMA values for each price point.
-pub fn calculate_ema(prices: &[f64], period: usize) -> Vec<f64> {
-    if period == 0 || prices.is_empty() {
-        return vec![];
+/// The buffer is only valid during the call; copy it before returning.
+pub type IndicatorStateCallback = extern "C" fn(data: *const f64, len: usize);
+
+/// Checkpoint the indicator calculator's state.
+///
+/// # Safety
+/// - `calc` must be a valid pointer from `create_indicator_calculator`
+/// - `callback` must be a valid function pointer
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if calc is null
+#[no_mangle]
+pub unsafe extern "C" fn serialize_indicator_state(
+    calc: *const IndicatorCalculator,
+    callback: IndicatorStateCallback,
+) -> i32 {
+    if calc.is_null() {
+        return ERR_NULL_POINTER;
     }
 
-    let mut ema = ExponentialMovingAverage::new(period).expect("Invalid period");
-    prices.iter().map(|&p| ema.next(p)).collect()
+    let outcome = catch_unwind(|| {
+        let state = (*calc).serialize_state();
+        callback(state.as_ptr(), state.len());
+        ERR_SUCCESS
+    });
+
+    match outcome {
+        Ok(code) => code,
+        Err(_) => ERR_INTERNAL_PANIC,
+    }
 }
 
-/// Calculate Bollinger Bands for a price series.
+/// Restore the indicator calculator from a checkpointed state.
 ///
-/// Returns (upper, middle, lower) bands for each price point.
-pub fn calculate_bollinger_bands(
-    prices: &[f64],
-    period: usize,
-    std_dev: f64,
-) -> Vec<(f64, f64, f64)> {
-    if period == 0 || prices.is_empty() {
-        return vec![];
+/// # Safety
+/// - `calc` must be a valid pointer from `create_indicator_calculator`
+/// - `data` must be a valid pointer to at least `len` doubles
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if calc or data is null
+/// - ERR_INVALID_PARAM if the state is malformed or was taken from a
+///   calculator with different periods (the calculator is unchanged)
+#[no_mangle]
+pub unsafe extern "C" fn restore_indicator_state(
+    calc: *mut IndicatorCalculator,
+    data: *const f64,
+    len: usize,
+) -> i32 {
+    if calc.is_null() || data.is_null() {
+        return ERR_NULL_POINTER;
     }
 
-    let mut boll = BollingerBands::new(period, std_dev).expect("Invalid params");
-    prices
-        .iter()
-        .map(|&p| {
-            let output = boll.next(p);
-            (output.upper, output.average, output.lower)
-        })
-        .collect()
+    let outcome = catch_unwind(|| {
+        let calc_ref = &mut *calc;
+        let state = std::slice::from_raw_parts(data, len);
+        match calc_ref.restore_state(state) {
+            Ok(()) => ERR_SUCCESS,
+            Err(_) => ERR_INVALID_PARAM,
+        }
+    });
+
+    match outcome {
+        Ok(code) => code,
+        Err(_) => ERR_INTERNAL_PANIC,
+    }
 }
 
-/// Calculate MACD for a price series.
+/// Set how the calculator handles non-finite close prices.
 ///
-/// Returns (dif, dea, histogram) for each price point.
-pub fn calculate_macd(
-    prices: &[f64],
-    fast: usize,
-    slow: usize,
-    signal: usize,
-) -> Vec<(f64, f64, f64)> {
-    if prices.is_empty() {
-        return vec![];
+/// # Safety
+/// - `calc` must be a valid pointer from `create_indicator_calculator`
+///
+/// # Arguments
+/// - `policy`: 0 = carry forward, 1 = reject
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if calc is null
+/// - ERR_INVALID_PARAM if policy is unknown
+#[no_mangle]
+pub unsafe extern "C" fn set_indicator_non_finite_policy(
+    calc: *mut IndicatorCalculator,
+    policy: i32,
+) -> i32 {
+    if calc.is_null() {
+        return ERR_NULL_POINTER;
+    }
+    if !(0..=1).contains(&policy) {
+        return ERR_INVALID_PARAM;
     }
 
-    let mut macd =
-        MovingAverageConvergenceDivergence::new(fast, slow, signal).expect("Invalid params");
-    prices
-        .iter()
-        .map(|&p| {
-            let output = macd.next(p);
-            (output.macd, output.signal, output.histogram)
-        })
-        .collect()
+    // SAFETY: calc validated above
+    (*calc).set_non_finite_policy(NonFinitePolicy::from(policy));
+    ERR_SUCCESS
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+// ============================================================================
+// Checkpointable indicator state
+// ============================================================================
 
-    #[test]
-    fn test_indicator_calculator_creation() {
-        let calc = IndicatorCalculator::new();
-        assert_eq!(calc.count(), 0);
+/// Version tag at the start of a serialized `IndicatorCalculator` state.
+const STATE_VERSION: f64 = 3.0;
+
+/// Number of `IndicatorResult` fields in a serialized state.
+const RESULT_FIELDS: usize = 20;
+
+/// Cursor over a serialized state, read front to back.
+struct StateReader<'a> {
+    data: &'a [f64],
+}
+
+impl<'a> StateReader<'a> {
+    fn value(&mut self) -> Option<f64> {
+        let (&first, rest) = self.data.split_first()?;
+        self.data = rest;
+        Some(first)
     }
 
-    #[test]
-    fn test_indicator_calculator_update() {
-        let mut calc = IndicatorCalculator::new();
+    fn count(&mut self) -> Option<usize> {
+        let value = self.value()?;
+        (value >= 0.0 && value.fract() == 0.0 && value <= u32::MAX as f64).then_some(value as usize)
+    }
 
-        // Feed some prices
-        for i in 1..=10 {
-            let result = calc.update(100.0 + i as f64);
-            assert!(result.ma5 > 0.0);
+    fn flag(&mut self) -> Option<bool> {
+        match self.count()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
         }
+    }
 
-        assert_eq!(calc.count(), 10);
+    /// Read a count that must equal this side's configured `period`.
+    fn period(&mut self, period: usize) -> Option<()> {
+        (self.count()? == period).then_some(())
+    }
+
+    fn values(&mut self, len: usize) -> Option<&'a [f64]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (values, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(values)
+    }
+}
+
+/// `ta::indicators::SimpleMovingAverage` with checkpointable state.
+#[derive(Debug, Clone)]
+struct RollingSma {
+    period: usize,
+    index: usize,
+    count: usize,
+    sum: f64,
+    window: Vec<f64>,
+}
+
+impl RollingSma {
+    fn new(period: usize) -> Option<Self> {
+        (period > 0).then(|| Self {
+            period,
+            index: 0,
+            count: 0,
+            sum: 0.0,
+            window: vec![0.0; period],
+        })
+    }
+
+    fn next(&mut self, input: f64) -> f64 {
+        let old_val = self.window[self.index];
+        self.window[self.index] = input;
+        self.index = (self.index + 1) % self.period;
+        if self.count < self.period {
+            self.count += 1;
+        }
+        self.sum = self.sum - old_val + input;
+        self.sum / self.count as f64
+    }
+
+    fn save(&self, out: &mut Vec<f64>) {
+        out.extend([self.period as f64, self.index as f64, self.count as f64, self.sum]);
+        out.extend_from_slice(&self.window);
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        reader.period(self.period)?;
+        self.index = reader.count().filter(|&i| i < self.period)?;
+        self.count = reader.count().filter(|&c| c <= self.period)?;
+        self.sum = reader.value()?;
+        self.window.copy_from_slice(reader.values(self.period)?);
+        Some(())
+    }
+}
+
+/// `ta::indicators::BollingerBands` with checkpointable state.
+#[derive(Debug, Clone)]
+struct RollingBollinger {
+    multiplier: f64,
+    period: usize,
+    index: usize,
+    count: usize,
+    /// Running mean
+    m: f64,
+    /// Running sum of squared deviations
+    m2: f64,
+    window: Vec<f64>,
+}
+
+impl RollingBollinger {
+    fn new(period: usize, multiplier: f64) -> Option<Self> {
+        (period > 0).then(|| Self {
+            multiplier,
+            period,
+            index: 0,
+            count: 0,
+            m: 0.0,
+            m2: 0.0,
+            window: vec![0.0; period],
+        })
+    }
+
+    /// Feed one close and return (upper, middle, lower).
+    fn next(&mut self, input: f64) -> (f64, f64, f64) {
+        let old_val = self.window[self.index];
+        self.window[self.index] = input;
+        self.index = (self.index + 1) % self.period;
+
+        if self.count < self.period {
+            self.count += 1;
+            let delta = input - self.m;
+            self.m += delta / self.count as f64;
+            let delta2 = input - self.m;
+            self.m2 += delta * delta2;
+        } else {
+            let delta = input - old_val;
+            let old_m = self.m;
+            self.m += delta / self.period as f64;
+            let delta2 = input - self.m + old_val - old_m;
+            self.m2 += delta * delta2;
+        }
+        if self.m2 < 0.0 {
+            self.m2 = 0.0;
+        }
+
+        let sd = (self.m2 / self.count as f64).sqrt();
+        (self.m + sd * self.multiplier, self.m, self.m - sd * self.multiplier)
+    }
+
+    fn save(&self, out: &mut Vec<f64>) {
+        out.extend([self.period as f64, self.index as f64, self.count as f64, self.m, self.m2]);
+        out.extend_from_slice(&self.window);
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        reader.period(self.period)?;
+        self.index = reader.count().filter(|&i| i < self.period)?;
+        self.count = reader.count().filter(|&c| c <= self.period)?;
+        self.m = reader.value()?;
+        self.m2 = reader.value()?;
+        self.window.copy_from_slice(reader.values(self.period)?);
+        Some(())
+    }
+}
+
+/// `ta::indicators::ExponentialMovingAverage` with checkpointable state.
+#[derive(Debug, Clone)]
+struct RollingEma {
+    period: usize,
+    k: f64,
+    current: f64,
+    is_new: bool,
+}
+
+impl RollingEma {
+    fn new(period: usize) -> Option<Self> {
+        (period > 0).then(|| Self {
+            period,
+            k: 2.0 / (period + 1) as f64,
+            current: 0.0,
+            is_new: true,
+        })
+    }
+
+    fn next(&mut self, input: f64) -> f64 {
+        if self.is_new {
+            self.is_new = false;
+            self.current = input;
+        } else {
+            self.current = self.k * input + (1.0 - self.k) * self.current;
+        }
+        self.current
+    }
+
+    fn save(&self, out: &mut Vec<f64>) {
+        out.extend([self.period as f64, self.is_new as u8 as f64, self.current]);
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        reader.period(self.period)?;
+        self.is_new = reader.flag()?;
+        self.current = reader.value()?;
+        Some(())
+    }
+}
+
+/// `ta::indicators::MovingAverageConvergenceDivergence` with checkpointable state.
+#[derive(Debug, Clone)]
+struct RollingMacd {
+    fast: RollingEma,
+    slow: RollingEma,
+    signal: RollingEma,
+}
+
+impl RollingMacd {
+    fn new(fast: usize, slow: usize, signal: usize) -> Option<Self> {
+        Some(Self {
+            fast: RollingEma::new(fast)?,
+            slow: RollingEma::new(slow)?,
+            signal: RollingEma::new(signal)?,
+        })
+    }
+
+    /// Feed one close and return (dif, dea, histogram).
+    fn next(&mut self, input: f64) -> (f64, f64, f64) {
+        let dif = self.fast.next(input) - self.slow.next(input);
+        let dea = self.signal.next(dif);
+        (dif, dea, dif - dea)
+    }
+
+    fn save(&self, out: &mut Vec<f64>) {
+        self.fast.save(out);
+        self.slow.save(out);
+        self.signal.save(out);
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        self.fast.load(reader)?;
+        self.slow.load(reader)?;
+        self.signal.load(reader)
+    }
+}
+
+/// `ta::indicators::AverageTrueRange` with checkpointable state.
+#[derive(Debug, Clone)]
+struct RollingAtr {
+    prev_close: Option<f64>,
+    ema: RollingEma,
+}
+
+impl RollingAtr {
+    fn new(period: usize) -> Option<Self> {
+        Some(Self {
+            prev_close: None,
+            ema: RollingEma::new(period)?,
+        })
+    }
+
+    fn next(&mut self, high: f64, low: f64, close: f64) -> f64 {
+        let true_range = match self.prev_close {
+            Some(prev_close) => (high - low)
+                .max((high - prev_close).abs())
+                .max((low - prev_close).abs()),
+            None => high - low,
+        };
+        self.prev_close = Some(close);
+        self.ema.next(true_range)
+    }
+
+    fn save(&self, out: &mut Vec<f64>) {
+        out.extend([self.prev_close.is_some() as u8 as f64, self.prev_close.unwrap_or(0.0)]);
+        self.ema.save(out);
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        let has_prev_close = reader.flag()?;
+        let prev_close = reader.value()?;
+        self.prev_close = has_prev_close.then_some(prev_close);
+        self.ema.load(reader)
+    }
+}
+
+/// Keltner Channel around the EMA of close with an ATR band offset.
+///
+/// Until `period` bars have been seen the ATR is not yet available and the
+/// bands collapse onto the middle line.
+#[derive(Debug, Clone)]
+struct RollingKeltner {
+    ema: RollingEma,
+    atr: RollingAtr,
+    multiplier: f64,
+    period: usize,
+    count: usize,
+}
+
+impl RollingKeltner {
+    fn new(period: usize, multiplier: f64) -> Option<Self> {
+        if !multiplier.is_finite() || multiplier < 0.0 {
+            return None;
+        }
+        Some(Self {
+            ema: RollingEma::new(period)?,
+            atr: RollingAtr::new(period)?,
+            multiplier,
+            period,
+            count: 0,
+        })
+    }
+
+    /// Feed one bar and return (upper, middle, lower).
+    fn next(&mut self, high: f64, low: f64, close: f64) -> (f64, f64, f64) {
+        self.count += 1;
+        let middle = self.ema.next(close);
+        let atr = self.atr.next(high, low, close);
+        let offset = if self.count >= self.period { self.multiplier * atr } else { 0.0 };
+        (middle + offset, middle, middle - offset)
+    }
+
+    fn save(&self, out: &mut Vec<f64>) {
+        out.push(self.count as f64);
+        self.ema.save(out);
+        self.atr.save(out);
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        self.count = reader.count()?;
+        self.ema.load(reader)?;
+        self.atr.load(reader)
+    }
+}
+
+/// Flatten an `IndicatorResult` in field order.
+fn result_fields(result: &IndicatorResult) -> [f64; RESULT_FIELDS] {
+    [
+        result.ma5,
+        result.ma10,
+        result.ma20,
+        result.ma60,
+        result.boll_upper,
+        result.boll_middle,
+        result.boll_lower,
+        result.macd_dif,
+        result.macd_dea,
+        result.macd_histogram,
+        result.atr14,
+        result.slow_k,
+        result.slow_d,
+        result.vwap,
+        result.donchian_upper,
+        result.donchian_lower,
+        result.donchian_mid,
+        result.keltner_upper,
+        result.keltner_middle,
+        result.keltner_lower,
+    ]
+}
+
+/// Rebuild an `IndicatorResult` from `result_fields` output.
+fn result_from_fields(f: &[f64]) -> IndicatorResult {
+    IndicatorResult {
+        ma5: f[0],
+        ma10: f[1],
+        ma20: f[2],
+        ma60: f[3],
+        boll_upper: f[4],
+        boll_middle: f[5],
+        boll_lower: f[6],
+        macd_dif: f[7],
+        macd_dea: f[8],
+        macd_histogram: f[9],
+        atr14: f[10],
+        slow_k: f[11],
+        slow_d: f[12],
+        vwap: f[13],
+        donchian_upper: f[14],
+        donchian_lower: f[15],
+        donchian_mid: f[16],
+        keltner_upper: f[17],
+        keltner_middle: f[18],
+        keltner_lower: f[19],
+    }
+}
+
+// ============================================================================
+// Squeeze detection
+// ============================================================================
+
+/// High/low/close bar fed to range-based `ta` indicators.
+#[derive(Debug, Clone, Copy)]
+struct HlcBar {
+    high: f64,
+    low: f64,
+    close: f64,
+}
+
+impl Close for HlcBar {
+    fn close(&self) -> f64 {
+        self.close
+    }
+}
+
+impl High for HlcBar {
+    fn high(&self) -> f64 {
+        self.high
+    }
+}
+
+impl Low for HlcBar {
+    fn low(&self) -> f64 {
+        self.low
+    }
+}
+
+/// Streaming Bollinger-inside-Keltner squeeze detector.
+///
+/// A squeeze is "on" when the Bollinger band width is narrower than the
+/// Keltner channel width. Bars before both indicators have a full period of
+/// data report false.
+#[derive(Debug, Clone)]
+pub struct SqueezeDetector {
+    boll: BollingerBands,
+    keltner: KeltnerChannel,
+    warmup: usize,
+    count: usize,
+}
+
+impl SqueezeDetector {
+    /// Create a detector, or None if either period is zero.
+    pub fn new(bb_period: usize, bb_std: f64, kc_period: usize, kc_mult: f64) -> Option<Self> {
+        Some(Self {
+            boll: BollingerBands::new(bb_period, bb_std).ok()?,
+            keltner: KeltnerChannel::new(kc_period, kc_mult).ok()?,
+            warmup: bb_period.max(kc_period),
+            count: 0,
+        })
+    }
+
+    /// Feed one bar and return whether the squeeze is on.
+    pub fn next(&mut self, close: f64, high: f64, low: f64) -> bool {
+        self.count += 1;
+        let bb = self.boll.next(close);
+        let kc = self.keltner.next(&HlcBar { high, low, close });
+
+        self.count >= self.warmup && (bb.upper - bb.lower) < (kc.upper - kc.lower)
+    }
+
+    /// Get the number of bars processed.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Streaming Average True Range over high/low/close bars.
+///
+/// Bars before `period` have been seen report `None`.
+#[derive(Debug, Clone)]
+pub struct AtrIndicator {
+    atr: AverageTrueRange,
+    period: usize,
+    count: usize,
+}
+
+impl AtrIndicator {
+    /// Create an ATR indicator, or None if the period is zero.
+    pub fn new(period: usize) -> Option<Self> {
+        Some(Self {
+            atr: AverageTrueRange::new(period).ok()?,
+            period,
+            count: 0,
+        })
+    }
+
+    /// Feed one bar and return the ATR once a full period has been seen.
+    pub fn next(&mut self, close: f64, high: f64, low: f64) -> Option<f64> {
+        self.count += 1;
+        let atr = self.atr.next(&HlcBar { high, low, close });
+        (self.count >= self.period).then_some(atr)
+    }
+
+    /// Get the number of bars processed.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Session VWAP accumulators.
+#[derive(Debug, Clone, Copy, Default)]
+struct SessionVwap {
+    /// Sum of price * volume
+    price_volume: f64,
+    /// Sum of volume
+    volume: f64,
+}
+
+impl SessionVwap {
+    fn save(&self, out: &mut Vec<f64>) {
+        out.extend([self.price_volume, self.volume]);
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        self.price_volume = reader.value()?;
+        self.volume = reader.value()?;
+        Some(())
+    }
+
+    fn add(&mut self, price: f64, volume: f64) {
+        self.price_volume += price * volume;
+        self.volume += volume;
+    }
+
+    /// Volume-weighted average price, or 0 before any volume.
+    fn value(&self) -> f64 {
+        if self.volume > 0.0 {
+            self.price_volume / self.volume
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Streaming slow Stochastic Oscillator.
+///
+/// Fast %K is `100 * (close - lowest low) / (highest high - lowest low)`
+/// over the last `k_period` bars, or 50 when the window is flat. Slow %K is
+/// the `d_period` SMA of fast %K and slow %D the `d_period` SMA of slow %K.
+/// Until the windows fill, each uses the bars seen so far.
+#[derive(Debug, Clone)]
+pub struct StochasticOscillator {
+    k_period: usize,
+    /// (high, low) of the last `k_period` bars
+    window: VecDeque<(f64, f64)>,
+    slow_k: RollingSma,
+    slow_d: RollingSma,
+    count: usize,
+}
+
+impl StochasticOscillator {
+    /// Create a stochastic oscillator, or None if either period is zero.
+    pub fn new(k_period: usize, d_period: usize) -> Option<Self> {
+        if k_period == 0 {
+            return None;
+        }
+        Some(Self {
+            k_period,
+            window: VecDeque::with_capacity(k_period),
+            slow_k: RollingSma::new(d_period)?,
+            slow_d: RollingSma::new(d_period)?,
+            count: 0,
+        })
+    }
+
+    /// Feed one bar and return (slow %K, slow %D).
+    pub fn next(&mut self, close: f64, high: f64, low: f64) -> (f64, f64) {
+        self.count += 1;
+        if self.window.len() == self.k_period {
+            self.window.pop_front();
+        }
+        self.window.push_back((high, low));
+
+        let highest = self.window.iter().map(|&(h, _)| h).fold(f64::NEG_INFINITY, f64::max);
+        let lowest = self.window.iter().map(|&(_, l)| l).fold(f64::INFINITY, f64::min);
+        let range = highest - lowest;
+        let fast_k = if range > 0.0 { 100.0 * (close - lowest) / range } else { 50.0 };
+
+        let slow_k = self.slow_k.next(fast_k);
+        (slow_k, self.slow_d.next(slow_k))
+    }
+
+    /// Get the number of bars processed.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    fn save(&self, out: &mut Vec<f64>) {
+        out.extend([self.k_period as f64, self.count as f64, self.window.len() as f64]);
+        for &(high, low) in &self.window {
+            out.extend([high, low]);
+        }
+        self.slow_k.save(out);
+        self.slow_d.save(out);
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        reader.period(self.k_period)?;
+        self.count = reader.count()?;
+        let len = reader.count().filter(|&len| len <= self.k_period)?;
+        let bars = reader.values(len * 2)?;
+        self.window = bars.chunks_exact(2).map(|bar| (bar[0], bar[1])).collect();
+        self.slow_k.load(reader)?;
+        self.slow_d.load(reader)
+    }
+}
+
+/// Donchian Channel over high/low bars.
+///
+/// The upper channel is the highest high and the lower channel the lowest
+/// low of the last `period` bars; the mid line is their average. Until
+/// `period` bars have been seen, the running extremes of all bars so far
+/// are used.
+#[derive(Debug, Clone)]
+pub struct DonchianChannel {
+    period: usize,
+    /// (high, low) of the last `period` bars
+    window: VecDeque<(f64, f64)>,
+    count: usize,
+}
+
+impl DonchianChannel {
+    /// Create a Donchian Channel, or None if `period` is zero.
+    pub fn new(period: usize) -> Option<Self> {
+        if period == 0 {
+            return None;
+        }
+        Some(Self {
+            period,
+            window: VecDeque::with_capacity(period),
+            count: 0,
+        })
+    }
+
+    /// Feed one bar and return (upper, mid, lower).
+    pub fn next(&mut self, high: f64, low: f64) -> (f64, f64, f64) {
+        self.count += 1;
+        if self.window.len() == self.period {
+            self.window.pop_front();
+        }
+        self.window.push_back((high, low));
+
+        let upper = self.window.iter().map(|&(h, _)| h).fold(f64::NEG_INFINITY, f64::max);
+        let lower = self.window.iter().map(|&(_, l)| l).fold(f64::INFINITY, f64::min);
+        (upper, (upper + lower) / 2.0, lower)
+    }
+
+    /// Get the number of bars processed.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    fn save(&self, out: &mut Vec<f64>) {
+        out.extend([self.period as f64, self.count as f64, self.window.len() as f64]);
+        for &(high, low) in &self.window {
+            out.extend([high, low]);
+        }
+    }
+
+    fn load(&mut self, reader: &mut StateReader) -> Option<()> {
+        reader.period(self.period)?;
+        self.count = reader.count()?;
+        let len = reader.count().filter(|&len| len <= self.period)?;
+        let bars = reader.values(len * 2)?;
+        self.window = bars.chunks_exact(2).map(|bar| (bar[0], bar[1])).collect();
+        Some(())
+    }
+}
+
+// ============================================================================
+// Standalone calculation functions for verification
+// ============================================================================
+
+/// Calculate Simple Moving Average for a price series.
+///
+/// Returns the SMA values for each price point.
+pub fn calculate_sma(prices: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 || prices.is_empty() {
+        return vec![];
+    }
+
+    let mut sma = SimpleMovingAverage::new(period).expect("Invalid period");
+    prices.iter().map(|&p| sma.next(p)).collect()
+}
+
+/// Calculate Exponential Moving Average for a price series.
+///
+/// Returns the EMA values for each price point.
+pub fn calculate_ema(prices: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 || prices.is_empty() {
+        return vec![];
+    }
+
+    let mut ema = ExponentialMovingAverage::new(period).expect("Invalid period");
+    prices.iter().map(|&p| ema.next(p)).collect()
+}
+
+/// Calculate Simple Moving Average in a single pass over a slice.
+///
+/// Uses a sliding window sum (O(1) per element) instead of a per-element
+/// indicator. Matches `calculate_sma`, including averaging over the prices
+/// seen so far until the window is full.
+pub fn calculate_sma_fast(prices: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 || prices.is_empty() {
+        return vec![];
+    }
+
+    let mut sma = Vec::with_capacity(prices.len());
+    let mut sum = 0.0;
+    let head = period.min(prices.len());
+
+    // Partial windows while the first `period` prices arrive
+    sma.extend(prices[..head].iter().enumerate().map(|(i, &price)| {
+        sum += price;
+        sum / (i + 1) as f64
+    }));
+
+    // Full windows: add the new price, drop the one leaving the window
+    let window = period as f64;
+    sma.extend(prices[head..].iter().zip(prices).map(|(&price, &old)| {
+        sum = sum - old + price;
+        sum / window
+    }));
+    sma
+}
+
+/// Calculate Exponential Moving Average in a single pass over a slice.
+///
+/// Applies the EMA recurrence directly, seeded with the first price.
+/// Matches `calculate_ema`.
+pub fn calculate_ema_fast(prices: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 || prices.is_empty() {
+        return vec![];
+    }
+
+    let k = 2.0 / (period + 1) as f64;
+    let decay = 1.0 - k;
+    // Seeding with the first price starts the average there, as ta does
+    let mut current = prices[0];
+    prices
+        .iter()
+        .map(|&price| {
+            current = k * price + decay * current;
+            current
+        })
+        .collect()
+}
+
+/// Calculate Bollinger Bands for a price series.
+///
+/// Returns (upper, middle, lower) bands for each price point.
+pub fn calculate_bollinger_bands(
+    prices: &[f64],
+    period: usize,
+    std_dev: f64,
+) -> Vec<(f64, f64, f64)> {
+    if period == 0 || prices.is_empty() {
+        return vec![];
+    }
+
+    let mut boll = BollingerBands::new(period, std_dev).expect("Invalid params");
+    prices
+        .iter()
+        .map(|&p| {
+            let output = boll.next(p);
+            (output.upper, output.average, output.lower)
+        })
+        .collect()
+}
+
+/// Calculate Keltner Channels for a bar series.
+///
+/// The middle line is the EMA of the `basis` price and the band offset is
+/// `multiplier` times the ATR. Returns (upper, middle, lower) for each bar,
+/// or an empty vector if the lengths differ, `period` is 0 or `multiplier`
+/// is negative or non-finite.
+pub fn calculate_keltner_channels(
+    closes: &[f64],
+    highs: &[f64],
+    lows: &[f64],
+    period: usize,
+    multiplier: f64,
+    basis: KeltnerBasis,
+) -> Vec<(f64, f64, f64)> {
+    if closes.len() != highs.len() || closes.len() != lows.len() {
+        return vec![];
+    }
+
+    match basis {
+        KeltnerBasis::TypicalPrice => {
+            if !multiplier.is_finite() || multiplier < 0.0 {
+                return vec![];
+            }
+            let Ok(mut keltner) = KeltnerChannel::new(period, multiplier) else {
+                return vec![];
+            };
+            (0..closes.len())
+                .map(|i| {
+                    let bar = HlcBar {
+                        high: highs[i],
+                        low: lows[i],
+                        close: closes[i],
+                    };
+                    let output = keltner.next(&bar);
+                    (output.upper, output.average, output.lower)
+                })
+                .collect()
+        }
+        KeltnerBasis::Close => {
+            let Some(mut keltner) = RollingKeltner::new(period, multiplier) else {
+                return vec![];
+            };
+            (0..closes.len())
+                .map(|i| keltner.next(highs[i], lows[i], closes[i]))
+                .collect()
+        }
+    }
+}
+
+/// Calculate the slow Stochastic Oscillator for a bar series.
+///
+/// Returns (slow %K, slow %D) for each bar; see `StochasticOscillator`.
+/// Returns an empty vector if the lengths differ or either period is 0.
+pub fn calculate_stochastic(
+    highs: &[f64],
+    lows: &[f64],
+    closes: &[f64],
+    k_period: usize,
+    d_period: usize,
+) -> Vec<(f64, f64)> {
+    if closes.len() != highs.len() || closes.len() != lows.len() {
+        return vec![];
+    }
+    let Some(mut stoch) = StochasticOscillator::new(k_period, d_period) else {
+        return vec![];
+    };
+
+    (0..closes.len())
+        .map(|i| stoch.next(closes[i], highs[i], lows[i]))
+        .collect()
+}
+
+/// Calculate the Donchian Channel for a bar series.
+///
+/// Returns (upper, mid, lower) for each bar; see `DonchianChannel`.
+/// Returns an empty vector if the lengths differ or `period` is 0.
+pub fn calculate_donchian(highs: &[f64], lows: &[f64], period: usize) -> Vec<(f64, f64, f64)> {
+    if highs.len() != lows.len() {
+        return vec![];
+    }
+    let Some(mut donchian) = DonchianChannel::new(period) else {
+        return vec![];
+    };
+
+    highs
+        .iter()
+        .zip(lows)
+        .map(|(&high, &low)| donchian.next(high, low))
+        .collect()
+}
+
+/// Flag bars where the Bollinger bands sit inside the Keltner channels.
+///
+/// Both channels are computed over the full series so index `i` of each
+/// refers to the same bar. The first `max(bb_period, kc_period) - 1` bars
+/// report false. Returns an empty vector if the lengths differ or either
+/// period is 0.
+pub fn detect_squeeze(
+    closes: &[f64],
+    highs: &[f64],
+    lows: &[f64],
+    bb_period: usize,
+    bb_std: f64,
+    kc_period: usize,
+    kc_mult: f64,
+) -> Vec<bool> {
+    if bb_period == 0 || kc_period == 0 {
+        return vec![];
+    }
+
+    let bollinger = calculate_bollinger_bands(closes, bb_period, bb_std);
+    let keltner = calculate_keltner_channels(closes, highs, lows, kc_period, kc_mult, KeltnerBasis::TypicalPrice);
+    if bollinger.len() != closes.len() || keltner.len() != closes.len() {
+        return vec![];
+    }
+
+    let warmup = bb_period.max(kc_period);
+    bollinger
+        .iter()
+        .zip(&keltner)
+        .enumerate()
+        .map(|(i, (&(bb_upper, _, bb_lower), &(kc_upper, _, kc_lower)))| {
+            i + 1 >= warmup && (bb_upper - bb_lower) < (kc_upper - kc_lower)
+        })
+        .collect()
+}
+
+/// Calculate MACD for a price series.
+///
+/// Returns (dif, dea, histogram) for each price point.
+pub fn calculate_macd(
+    prices: &[f64],
+    fast: usize,
+    slow: usize,
+    signal: usize,
+) -> Vec<(f64, f64, f64)> {
+    if prices.is_empty() {
+        return vec![];
+    }
+
+    let mut macd =
+        MovingAverageConvergenceDivergence::new(fast, slow, signal).expect("Invalid params");
+    prices
+        .iter()
+        .map(|&p| {
+            let output = macd.next(p);
+            (output.macd, output.signal, output.histogram)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indicator_calculator_creation() {
+        let calc = IndicatorCalculator::new();
+        assert_eq!(calc.count(), 0);
+    }
+
+    #[test]
+    fn test_indicator_calculator_update() {
+        let mut calc = IndicatorCalculator::new();
+
+        // Feed some prices
+        for i in 1..=10 {
+            let result = calc.update(100.0 + i as f64);
+            assert!(result.ma5 > 0.0);
+        }
+
+        assert_eq!(calc.count(), 10);
     }
 
     #[test]
@@ -447,6 +1775,389 @@ mod tests {
         assert!((result.macd_histogram - (result.macd_dif - result.macd_dea)).abs() < 0.0001);
     }
 
+    #[test]
+    fn test_atr14_known_ohlc_series() {
+        let mut calc = IndicatorCalculator::new();
+
+        // First true range is high - low = 2.0 and seeds the average
+        assert!((calc.update_ohlc(10.0, 8.0, 9.0).atr14 - 2.0).abs() < 1e-12);
+        // TR = max(11 - 9, |11 - 9|, |9 - 9|) = 2.0
+        assert!((calc.update_ohlc(11.0, 9.0, 10.5).atr14 - 2.0).abs() < 1e-12);
+        // Gap up: TR = max(13 - 12, |13 - 10.5|, |12 - 10.5|) = 2.5
+        // ATR = 2.0 + (2.5 - 2.0) * 2 / 15
+        let result = calc.update_ohlc(13.0, 12.0, 12.5);
+        assert!((result.atr14 - (2.0 + 0.5 * 2.0 / 15.0)).abs() < 1e-12);
+        // Close-based indicators only see the closes
+        assert!((result.ma5 - (9.0 + 10.5 + 12.5) / 3.0).abs() < 1e-12);
+    }
+
+    /// Bars with some intrabar range and volume for checkpoint tests.
+    fn checkpoint_bar(i: usize) -> (f64, f64, f64, f64) {
+        let close = 100.0 + (i as f64 * 0.37).sin() * 5.0 + i as f64 * 0.05;
+        (close + 0.8, close - 0.6, close, 10.0 + (i % 7) as f64)
+    }
+
+    /// Random walk from a fixed xorshift seed.
+    fn random_walk(len: usize) -> Vec<f64> {
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut price = 100.0;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let step = (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5;
+                price = (price + step * 2.0).max(1.0);
+                price
+            })
+            .collect()
+    }
+
+    fn assert_close(a: f64, b: f64, what: &str, i: usize) {
+        assert!((a - b).abs() <= 1e-9 * b.abs().max(1.0), "{} mismatch at {}: {} vs {}", what, i, a, b);
+    }
+
+    #[test]
+    fn test_rolling_mirrors_match_ta() {
+        let prices = random_walk(20_000);
+
+        for period in [1, 5, 20, 60, 250] {
+            let mut sma = RollingSma::new(period).unwrap();
+            let mut ema = RollingEma::new(period).unwrap();
+            let expected_sma = calculate_sma(&prices, period);
+            let expected_ema = calculate_ema(&prices, period);
+            for (i, &p) in prices.iter().enumerate() {
+                assert_close(sma.next(p), expected_sma[i], "SMA", i);
+                assert_close(ema.next(p), expected_ema[i], "EMA", i);
+            }
+        }
+
+        for (period, multiplier) in [(2, 1.0), (20, 2.0), (100, 2.5)] {
+            let mut boll = RollingBollinger::new(period, multiplier).unwrap();
+            let expected = calculate_bollinger_bands(&prices, period, multiplier);
+            for (i, &p) in prices.iter().enumerate() {
+                let (upper, middle, lower) = boll.next(p);
+                assert_close(upper, expected[i].0, "Bollinger upper", i);
+                assert_close(middle, expected[i].1, "Bollinger middle", i);
+                assert_close(lower, expected[i].2, "Bollinger lower", i);
+            }
+        }
+
+        for (fast, slow, signal) in [(12, 26, 9), (3, 10, 16)] {
+            let mut macd = RollingMacd::new(fast, slow, signal).unwrap();
+            let expected = calculate_macd(&prices, fast, slow, signal);
+            for (i, &p) in prices.iter().enumerate() {
+                let (dif, dea, histogram) = macd.next(p);
+                assert_close(dif, expected[i].0, "MACD dif", i);
+                assert_close(dea, expected[i].1, "MACD dea", i);
+                assert_close(histogram, expected[i].2, "MACD histogram", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_restore_matches_uninterrupted_run() {
+        let mut original = IndicatorCalculator::new();
+        let mut checkpoint = Vec::new();
+        for i in 0..100 {
+            let (high, low, close, volume) = checkpoint_bar(i);
+            original.update_ohlc(high, low, close);
+            original.update_with_volume(close, volume);
+            if i == 49 {
+                checkpoint = original.serialize_state();
+            }
+        }
+
+        // Resume a fresh calculator from the mid-stream checkpoint
+        let mut resumed = IndicatorCalculator::new();
+        resumed.restore_state(&checkpoint).unwrap();
+        assert_eq!(resumed.count(), 100);
+
+        let mut replay = IndicatorCalculator::new();
+        for i in 0..50 {
+            let (high, low, close, volume) = checkpoint_bar(i);
+            replay.update_ohlc(high, low, close);
+            replay.update_with_volume(close, volume);
+        }
+        for i in 50..100 {
+            let (high, low, close, volume) = checkpoint_bar(i);
+            assert_eq!(resumed.update_ohlc(high, low, close), replay.update_ohlc(high, low, close));
+            assert_eq!(
+                resumed.update_with_volume(close, volume),
+                replay.update_with_volume(close, volume)
+            );
+        }
+        assert_eq!(resumed.serialize_state(), original.serialize_state());
+    }
+
+    #[test]
+    fn test_restore_rejects_mismatched_state() {
+        let mut calc = IndicatorCalculator::new();
+        for i in 0..30 {
+            calc.update(100.0 + i as f64);
+        }
+        let state = calc.serialize_state();
+        let before = calc.update(130.0);
+
+        // Different MA periods, a truncated buffer and a bad version all fail
+        let mut other = IndicatorCalculator::with_ma_periods(3, 10, 20, 60).unwrap();
+        assert!(other.restore_state(&state).is_err());
+        assert!(calc.restore_state(&state[..state.len() - 1]).is_err());
+        let mut bad_version = state.clone();
+        bad_version[0] = 99.0;
+        assert!(calc.restore_state(&bad_version).is_err());
+
+        // Failed restores leave the calculator untouched
+        assert_eq!(calc.count(), 31);
+        calc.restore_state(&state).unwrap();
+        assert_eq!(calc.update(130.0), before);
+    }
+
+    thread_local! {
+        static SAVED_STATE: std::cell::RefCell<Vec<f64>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    extern "C" fn capture_state(data: *const f64, len: usize) {
+        let state = unsafe { std::slice::from_raw_parts(data, len) }.to_vec();
+        SAVED_STATE.with(|cell| *cell.borrow_mut() = state);
+    }
+
+    #[test]
+    fn test_indicator_state_ffi_round_trip() {
+        let mut calc = IndicatorCalculator::new();
+        for i in 0..25 {
+            calc.update(100.0 + i as f64);
+        }
+        assert_eq!(unsafe { serialize_indicator_state(&calc, capture_state) }, ERR_SUCCESS);
+        let state = SAVED_STATE.with(|cell| cell.borrow().clone());
+        assert_eq!(state, calc.serialize_state());
+
+        let mut resumed = IndicatorCalculator::new();
+        let code = unsafe { restore_indicator_state(&mut resumed, state.as_ptr(), state.len()) };
+        assert_eq!(code, ERR_SUCCESS);
+        assert_eq!(resumed.update(125.0), calc.update(125.0));
+
+        let code = unsafe { restore_indicator_state(&mut resumed, state.as_ptr(), 3) };
+        assert_eq!(code, ERR_INVALID_PARAM);
+        assert_eq!(
+            unsafe { restore_indicator_state(std::ptr::null_mut(), state.as_ptr(), state.len()) },
+            ERR_NULL_POINTER
+        );
+    }
+
+    #[test]
+    fn test_vwap_is_volume_weighted_mean() {
+        let bars = [(100.0, 10.0), (102.0, 30.0), (101.0, 0.0), (99.0, 60.0)];
+        let mut calc = IndicatorCalculator::new();
+        assert_eq!(calc.update(100.0).vwap, 0.0);
+
+        let mut price_volume = 0.0;
+        let mut volume = 0.0;
+        for &(p, v) in &bars {
+            price_volume += p * v;
+            volume += v;
+            let result = calc.update_with_volume(p, v);
+            assert!((result.vwap - price_volume / volume).abs() < 1e-12);
+        }
+        // (1000 + 3060 + 5940) / 100
+        assert!((calc.update(150.0).vwap - 100.0).abs() < 1e-12);
+
+        // Bad volume and skipped prices leave VWAP alone
+        assert!((calc.update_with_volume(200.0, -5.0).vwap - 100.0).abs() < 1e-12);
+        assert!((calc.update_with_volume(f64::NAN, 5.0).vwap - 100.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_reset_session_restarts_vwap() {
+        let mut calc = IndicatorCalculator::new();
+        calc.update_with_volume(100.0, 10.0);
+        calc.update_with_volume(110.0, 10.0);
+        assert!((calc.update(105.0).vwap - 105.0).abs() < 1e-12);
+
+        calc.reset_session();
+        assert_eq!(calc.update(106.0).vwap, 0.0);
+        assert_eq!(calc.count(), 4);
+        assert!((calc.update_with_volume(120.0, 5.0).vwap - 120.0).abs() < 1e-12);
+
+        let mut result = IndicatorResult::default();
+        let code = unsafe { calculate_indicators_with_volume(&mut calc, 130.0, 5.0, &mut result) };
+        assert_eq!(code, ERR_SUCCESS);
+        assert!((result.vwap - 125.0).abs() < 1e-12);
+        assert_eq!(unsafe { reset_indicator_session(&mut calc) }, ERR_SUCCESS);
+        assert_eq!(calc.update(130.0).vwap, 0.0);
+    }
+
+    #[test]
+    fn test_stochastic_known_series() {
+        let highs = [10.0, 11.0, 12.0, 12.0];
+        let lows = [8.0, 9.0, 10.0, 11.0];
+        let closes = [9.0, 10.5, 12.0, 11.0];
+
+        // Fast %K over 3 bars: 50, 83.33, 100, 66.67; smoothed over 2
+        let expected_k = [50.0, 200.0 / 3.0, 275.0 / 3.0, 250.0 / 3.0];
+        let expected_d = [50.0, 175.0 / 3.0, 475.0 / 6.0, 87.5];
+
+        let result = calculate_stochastic(&highs, &lows, &closes, 3, 2);
+        assert_eq!(result.len(), 4);
+        for (i, &(slow_k, slow_d)) in result.iter().enumerate() {
+            assert!((slow_k - expected_k[i]).abs() < 1e-9, "slow_k[{}] = {}", i, slow_k);
+            assert!((slow_d - expected_d[i]).abs() < 1e-9, "slow_d[{}] = {}", i, slow_d);
+        }
+
+        assert!(calculate_stochastic(&highs, &lows[..3], &closes, 3, 2).is_empty());
+        assert!(calculate_stochastic(&highs, &lows, &closes, 0, 2).is_empty());
+    }
+
+    #[test]
+    fn test_stochastic_flat_window_is_fifty() {
+        let mut calc = IndicatorCalculator::new();
+        for _ in 0..20 {
+            let result = calc.update(100.0);
+            assert_eq!(result.slow_k, 50.0);
+            assert_eq!(result.slow_d, 50.0);
+        }
+
+        // A close at the top of the window drives %K up
+        let result = calc.update_ohlc(102.0, 99.0, 102.0);
+        assert!(result.slow_k > 50.0 && result.slow_k <= 100.0);
+    }
+
+    #[test]
+    fn test_stochastic_matches_standalone() {
+        let highs: Vec<f64> = (0..40).map(|i| 101.0 + (i as f64 * 0.7).sin() * 3.0).collect();
+        let lows: Vec<f64> = highs.iter().map(|h| h - 2.0).collect();
+        let closes: Vec<f64> = highs.iter().zip(&lows).map(|(h, l)| (h + l) / 2.0 + 0.3).collect();
+
+        let expected = calculate_stochastic(&highs, &lows, &closes, 14, 3);
+        let mut calc = IndicatorCalculator::new();
+        for i in 0..closes.len() {
+            let result = calc.update_ohlc(highs[i], lows[i], closes[i]);
+            assert!((result.slow_k - expected[i].0).abs() < 1e-12);
+            assert!((result.slow_d - expected[i].1).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_donchian_upper_is_window_max() {
+        let highs: Vec<f64> = (0..40).map(|i| 101.0 + (i as f64 * 0.7).sin() * 3.0).collect();
+        let lows: Vec<f64> = highs.iter().map(|h| h - 2.0).collect();
+        let period = 10;
+
+        let channel = calculate_donchian(&highs, &lows, period);
+        assert_eq!(channel.len(), highs.len());
+        for (i, &(upper, mid, lower)) in channel.iter().enumerate() {
+            // Before the window fills, the extremes run from the first bar
+            let start = (i + 1).saturating_sub(period);
+            let max = highs[start..=i].iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let min = lows[start..=i].iter().cloned().fold(f64::INFINITY, f64::min);
+            assert_eq!(upper, max);
+            assert_eq!(lower, min);
+            assert!((mid - (max + min) / 2.0).abs() < 1e-12);
+        }
+
+        assert!(calculate_donchian(&highs, &lows, 0).is_empty());
+        assert!(calculate_donchian(&highs, &lows[1..], period).is_empty());
+    }
+
+    #[test]
+    fn test_donchian_in_calculator() {
+        let highs = [10.0, 12.0, 11.0, 9.0, 8.0];
+        let lows = [9.0, 10.0, 8.0, 7.0, 7.5];
+        let mut calc = IndicatorCalculator::new().with_donchian_period(3).unwrap();
+        let mut results = Vec::new();
+        for i in 0..highs.len() {
+            results.push(calc.update_ohlc(highs[i], lows[i], (highs[i] + lows[i]) / 2.0));
+        }
+
+        // Running extremes until three bars accumulate
+        assert_eq!(results[0].donchian_upper, 10.0);
+        assert_eq!(results[0].donchian_lower, 9.0);
+        assert_eq!(results[1].donchian_upper, 12.0);
+        // Window [12, 11, 9] / [10, 8, 7]
+        assert_eq!(results[3].donchian_upper, 12.0);
+        assert_eq!(results[3].donchian_lower, 7.0);
+        // 12 rolls out: window [11, 9, 8] / [8, 7, 7.5]
+        assert_eq!(results[4].donchian_upper, 11.0);
+        assert_eq!(results[4].donchian_mid, 9.0);
+
+        assert!(IndicatorCalculator::new().with_donchian_period(0).is_none());
+    }
+
+    #[test]
+    fn test_keltner_hand_computed() {
+        let highs = [11.0, 12.0, 13.0, 12.0];
+        let lows = [9.0, 10.0, 10.0, 11.0];
+        let closes = [10.0, 11.0, 12.0, 11.0];
+
+        // period 3: EMA k = 0.5, true ranges 2, 2, 3, 1
+        let channel = calculate_keltner_channels(&closes, &highs, &lows, 3, 2.0, KeltnerBasis::Close);
+        assert_eq!(channel.len(), 4);
+        // ATR not available yet: bands sit on the EMA
+        assert_eq!(channel[0], (10.0, 10.0, 10.0));
+        assert_eq!(channel[1], (10.5, 10.5, 10.5));
+        // EMA 11.25, ATR 2.5
+        assert_eq!(channel[2], (16.25, 11.25, 6.25));
+        // EMA 11.125, ATR 1.75
+        assert_eq!(channel[3], (14.625, 11.125, 7.625));
+
+        for basis in [KeltnerBasis::Close, KeltnerBasis::TypicalPrice] {
+            assert!(calculate_keltner_channels(&closes, &highs, &lows, 0, 2.0, basis).is_empty());
+            assert!(calculate_keltner_channels(&closes, &highs, &lows, 3, -1.0, basis).is_empty());
+            assert!(calculate_keltner_channels(&closes, &highs, &lows[1..], 3, 2.0, basis).is_empty());
+        }
+
+        // Typical price basis: EMA seeded at (11 + 9 + 10) / 3
+        let typical = calculate_keltner_channels(&closes, &highs, &lows, 3, 2.0, KeltnerBasis::TypicalPrice);
+        assert_eq!(typical.len(), 4);
+        assert_eq!(typical[0].1, 10.0);
+        assert!(typical[2].1 != channel[2].1);
+    }
+
+    #[test]
+    fn test_keltner_in_calculator() {
+        let (closes, highs, lows) = squeeze_series();
+        let expected = calculate_keltner_channels(&closes, &highs, &lows, 10, 1.5, KeltnerBasis::Close);
+        let mut calc = IndicatorCalculator::new().with_keltner_params(10, 1.5).unwrap();
+
+        for i in 0..closes.len() {
+            let result = calc.update_ohlc(highs[i], lows[i], closes[i]);
+            assert_eq!(result.keltner_upper, expected[i].0);
+            assert_eq!(result.keltner_middle, expected[i].1);
+            assert_eq!(result.keltner_lower, expected[i].2);
+        }
+
+        assert!(IndicatorCalculator::new().with_keltner_params(0, 2.0).is_none());
+        assert!(IndicatorCalculator::new().with_keltner_params(20, f64::NAN).is_none());
+    }
+
+    #[test]
+    fn test_atr14_matches_atr_indicator() {
+        let (closes, highs, lows) = squeeze_series();
+        let mut calc = IndicatorCalculator::new();
+        let mut atr = AtrIndicator::new(14).unwrap();
+
+        for i in 0..closes.len() {
+            let result = calc.update_ohlc(highs[i], lows[i], closes[i]);
+            if let Some(expected) = atr.next(closes[i], highs[i], lows[i]) {
+                assert!((result.atr14 - expected).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_treats_close_as_bar() {
+        let mut calc = IndicatorCalculator::new();
+
+        // H = L = C: the first bar has no range, then only close-to-close moves
+        assert_eq!(calc.update(100.0).atr14, 0.0);
+        let result = calc.update(103.0);
+        assert!((result.atr14 - 3.0 * 2.0 / 15.0).abs() < 1e-12);
+
+        let mut ohlc = IndicatorCalculator::new();
+        ohlc.update_ohlc(100.0, 100.0, 100.0);
+        assert_eq!(ohlc.update_ohlc(103.0, 103.0, 103.0), result);
+    }
+
     #[test]
     fn test_reset() {
         let mut calc = IndicatorCalculator::new();
@@ -473,6 +2184,32 @@ mod tests {
         assert!((sma[4] - 4.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_fast_moving_averages_match_ta() {
+        let prices: Vec<f64> = (0..5000)
+            .map(|i| 100.0 + (i as f64 * 0.07).sin() * 5.0 + (i % 13) as f64 * 0.1)
+            .collect();
+
+        for period in [1, 3, 20, 200, 6000] {
+            let sma = calculate_sma(&prices, period);
+            let sma_fast = calculate_sma_fast(&prices, period);
+            assert_eq!(sma.len(), sma_fast.len());
+            for (a, b) in sma.iter().zip(&sma_fast) {
+                assert!((a - b).abs() < 1e-9, "SMA({}) mismatch: {} vs {}", period, a, b);
+            }
+
+            let ema = calculate_ema(&prices, period);
+            let ema_fast = calculate_ema_fast(&prices, period);
+            assert_eq!(ema.len(), ema_fast.len());
+            for (a, b) in ema.iter().zip(&ema_fast) {
+                assert!((a - b).abs() < 1e-9, "EMA({}) mismatch: {} vs {}", period, a, b);
+            }
+        }
+
+        assert!(calculate_sma_fast(&prices, 0).is_empty());
+        assert!(calculate_ema_fast(&[], 5).is_empty());
+    }
+
     #[test]
     fn test_calculate_bollinger_bands() {
         let prices: Vec<f64> = (0..30).map(|i| 100.0 + (i % 5) as f64).collect();
@@ -500,6 +2237,66 @@ mod tests {
         }
     }
 
+    /// Closes swing ±3 on a 10-bar cycle with narrow bars for 40 bars, hold
+    /// within ±0.05 with wide bars for 40 bars, then swing ±3 again.
+    fn squeeze_series() -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        let mut closes = Vec::new();
+        let mut highs = Vec::new();
+        let mut lows = Vec::new();
+        for i in 0..120 {
+            let (close, range) = if (40..80).contains(&i) {
+                (100.0 + if i % 2 == 0 { 0.05 } else { -0.05 }, 1.0)
+            } else {
+                (100.0 + (i as f64 * std::f64::consts::PI / 5.0).sin() * 3.0, 0.1)
+            };
+            closes.push(close);
+            highs.push(close + range);
+            lows.push(close - range);
+        }
+        (closes, highs, lows)
+    }
+
+    #[test]
+    fn test_detect_squeeze() {
+        let (closes, highs, lows) = squeeze_series();
+        let squeeze = detect_squeeze(&closes, &highs, &lows, 20, 2.0, 20, 1.5);
+
+        assert_eq!(squeeze.len(), 120);
+        assert!(squeeze[..19].iter().all(|&on| !on));
+        assert!(squeeze[20..40].iter().all(|&on| !on));
+        assert!(squeeze[60..80].iter().all(|&on| on));
+        assert!(squeeze[100..].iter().all(|&on| !on));
+    }
+
+    #[test]
+    fn test_squeeze_warmup_reports_false() {
+        // Tight closes would be "on" immediately without the warmup guard
+        let closes = vec![100.0; 30];
+        let highs = vec![101.0; 30];
+        let lows = vec![99.0; 30];
+        let squeeze = detect_squeeze(&closes, &highs, &lows, 10, 2.0, 20, 1.5);
+
+        assert!(squeeze[..19].iter().all(|&on| !on));
+        assert!(squeeze[19..].iter().all(|&on| on));
+        assert!(detect_squeeze(&closes, &highs[..29], &lows, 10, 2.0, 20, 1.5).is_empty());
+        assert!(detect_squeeze(&closes, &highs, &lows, 0, 2.0, 20, 1.5).is_empty());
+    }
+
+    #[test]
+    fn test_streaming_squeeze_matches_batch() {
+        let (closes, highs, lows) = squeeze_series();
+        let batch = detect_squeeze(&closes, &highs, &lows, 20, 2.0, 14, 1.5);
+
+        let mut detector = SqueezeDetector::new(20, 2.0, 14, 1.5).unwrap();
+        let streamed: Vec<bool> = (0..closes.len())
+            .map(|i| detector.next(closes[i], highs[i], lows[i]))
+            .collect();
+
+        assert_eq!(streamed, batch);
+        assert_eq!(detector.count(), 120);
+        assert!(SqueezeDetector::new(0, 2.0, 14, 1.5).is_none());
+    }
+
     #[test]
     fn test_ffi_create_and_free() {
         unsafe {
@@ -541,6 +2338,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ffi_calculate_indicators_ohlc() {
+        unsafe {
+            let calc = create_indicator_calculator();
+            let mut result = IndicatorResult::default();
+
+            let code = calculate_indicators_ohlc(calc, 10.0, 8.0, 9.0, &mut result);
+            assert_eq!(code, ERR_SUCCESS);
+            assert!((result.atr14 - 2.0).abs() < 1e-12);
+            assert_eq!(result.ma5, 9.0);
+
+            assert_eq!(set_indicator_non_finite_policy(calc, 1), ERR_SUCCESS);
+            let code = calculate_indicators_ohlc(calc, f64::NAN, 8.0, 9.0, &mut result);
+            assert_eq!(code, ERR_INVALID_DATA);
+            assert!((result.atr14 - 2.0).abs() < 1e-12);
+            assert_eq!(
+                calculate_indicators_ohlc(calc, 10.0, 8.0, 9.0, std::ptr::null_mut()),
+                ERR_NULL_POINTER
+            );
+
+            free_indicator_calculator(calc);
+        }
+    }
+
     #[test]
     fn test_ffi_batch_calculation() {
         unsafe {
@@ -564,4 +2385,52 @@ mod tests {
             free_indicator_calculator(calc);
         }
     }
+
+    #[test]
+    fn test_nan_input_does_not_poison_outputs() {
+        let mut calc = IndicatorCalculator::new();
+        for i in 0..30 {
+            calc.update(100.0 + (i % 5) as f64);
+        }
+        let before = calc.update(101.0);
+
+        // Bad tick is carried forward without being counted
+        assert_eq!(calc.update(f64::NAN), before);
+        assert_eq!(calc.update(f64::INFINITY), before);
+        assert!(!calc.last_input_rejected());
+        assert_eq!(calc.count(), 31);
+
+        for i in 0..70 {
+            let result = calc.update(100.0 + (i % 7) as f64);
+            assert!(result.ma5.is_finite());
+            assert!(result.ma60.is_finite());
+            assert!(result.boll_upper.is_finite());
+            assert!(result.macd_histogram.is_finite());
+        }
+    }
+
+    #[test]
+    fn test_nan_input_rejected() {
+        let mut calc = IndicatorCalculator::new().with_non_finite_policy(NonFinitePolicy::Reject);
+        let before = calc.update(100.0);
+
+        assert_eq!(calc.update(f64::NAN), before);
+        assert!(calc.last_input_rejected());
+        assert!(calc.update(101.0).ma5.is_finite());
+        assert!(!calc.last_input_rejected());
+
+        calc.reset();
+        assert_eq!(calc.non_finite_policy(), NonFinitePolicy::Reject);
+
+        unsafe {
+            let calc = create_indicator_calculator();
+            let mut result = IndicatorResult::default();
+            assert_eq!(set_indicator_non_finite_policy(calc, 1), ERR_SUCCESS);
+            assert_eq!(set_indicator_non_finite_policy(calc, 7), ERR_INVALID_PARAM);
+            assert_eq!(calculate_indicators(calc, 100.0, &mut result), ERR_SUCCESS);
+            assert_eq!(calculate_indicators(calc, f64::NAN, &mut result), ERR_INVALID_DATA);
+            assert_eq!(result.ma5, 100.0);
+            free_indicator_calculator(calc);
+        }
+    }
 }
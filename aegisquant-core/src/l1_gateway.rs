@@ -1,15 +1,24 @@
 //! L1 Simulated Gateway for realistic order execution.
 //!
 //! Provides order execution simulation based on L1 order book depth,
-//! including partial fills and slippage modeling.
+//! including partial fills and slippage modeling. Orders can be filled
+//! aggressively in one pass or sliced VWAP-style across book updates, and
+//! non-marketable limit orders rest in the book behind the queue ahead of them.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::ffi::{c_char, CStr};
+use std::panic::catch_unwind;
 use std::sync::atomic::{AtomicI32, Ordering};
 
-use crate::gateway::{Fill, Gateway, GatewayError, OrderId};
-use crate::orderbook::{OrderBookLevel, OrderBookSnapshot};
-use crate::precision::{Price, Quantity};
-use crate::types::{AccountStatus, OrderRequest, Position, DIRECTION_BUY, DIRECTION_SELL};
+use crate::gateway::{
+    fok_rejected, validate_tif, CostBasisMethod, EntryLots, Fill, Gateway, GatewayError, OrderId,
+};
+use crate::orderbook::{FfiOrderBookSnapshot, OrderBookLevel, OrderBookSnapshot};
+use crate::precision::{price_eq, Price, Quantity, QUANTITY_EPSILON};
+use crate::types::{
+    AccountStatus, OrderRequest, Position, DIRECTION_BUY, DIRECTION_SELL, ORDER_TYPE_LIMIT,
+    TIF_FOK, TIF_GTC,
+};
 
 /// Gateway mode for order execution.
 #[repr(i32)]
@@ -82,8 +91,241 @@ impl SlippageModel {
     }
 }
 
-/// Fill result from L1 order execution.
+/// Slippage model scaled by recent price volatility.
+///
+/// The base model's slippage is multiplied by `volatility / reference_volatility`,
+/// clamped to `[min_multiplier, max_multiplier]`, and then capped at the base
+/// model's `max_slippage`. Volatility is the standard deviation of the last
+/// `window` simple returns seen through `update_price`.
+#[derive(Debug, Clone, Copy)]
+pub struct VolatilityScaledSlippage {
+    /// Base slippage before volatility scaling
+    pub base: SlippageModel,
+    /// Number of returns in the rolling volatility window
+    pub window: usize,
+    /// Per-update return volatility at which the multiplier is 1.0
+    pub reference_volatility: f64,
+    /// Lower bound on the volatility multiplier
+    pub min_multiplier: f64,
+    /// Upper bound on the volatility multiplier
+    pub max_multiplier: f64,
+}
+
+impl Default for VolatilityScaledSlippage {
+    fn default() -> Self {
+        Self {
+            base: SlippageModel::default(),
+            window: 20,
+            reference_volatility: 0.001, // 10 bps per update
+            min_multiplier: 0.5,
+            max_multiplier: 5.0,
+        }
+    }
+}
+
+impl VolatilityScaledSlippage {
+    /// Create a volatility-scaled model with default multiplier bounds.
+    pub fn new(base: SlippageModel, window: usize, reference_volatility: f64) -> Self {
+        Self {
+            base,
+            window,
+            reference_volatility,
+            ..Self::default()
+        }
+    }
+
+    /// Multiplier applied to the base slippage (1.0 without a volatility estimate).
+    pub fn multiplier(&self, volatility: Option<f64>) -> f64 {
+        match volatility {
+            Some(vol) if self.reference_volatility > 0.0 => {
+                (vol / self.reference_volatility).clamp(self.min_multiplier, self.max_multiplier)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Calculate slippage for an order quantity at the given volatility.
+    pub fn calculate(&self, quantity: Quantity, volatility: Option<f64>) -> f64 {
+        let slippage = (self.base.base_slippage + self.base.impact_factor * quantity)
+            * self.multiplier(volatility);
+        slippage.min(self.base.max_slippage)
+    }
+}
+
+/// Rolling standard deviation of simple returns, updated incrementally.
+#[derive(Debug, Clone)]
+struct RollingVolatility {
+    window: usize,
+    last_price: Option<f64>,
+    returns: VecDeque<f64>,
+    mean: f64,
+    /// Sum of squared deviations from the mean
+    m2: f64,
+}
+
+impl RollingVolatility {
+    fn new(window: usize) -> Self {
+        Self {
+            window: window.max(2),
+            last_price: None,
+            returns: VecDeque::with_capacity(window.max(2) + 1),
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    fn update(&mut self, price: f64) {
+        if let Some(last) = self.last_price {
+            if last > 0.0 && price.is_finite() {
+                self.push((price - last) / last);
+                if self.returns.len() > self.window {
+                    if let Some(oldest) = self.returns.pop_front() {
+                        self.pop(oldest);
+                    }
+                }
+            }
+        }
+        if price.is_finite() {
+            self.last_price = Some(price);
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        self.returns.push_back(value);
+        let n = self.returns.len() as f64;
+        let delta = value - self.mean;
+        self.mean += delta / n;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn pop(&mut self, value: f64) {
+        let n = self.returns.len() as f64;
+        if n == 0.0 {
+            self.mean = 0.0;
+            self.m2 = 0.0;
+            return;
+        }
+        let delta = value - self.mean;
+        self.mean -= delta / n;
+        self.m2 -= delta * (value - self.mean);
+    }
+
+    /// Sample standard deviation, or None with fewer than two returns.
+    fn volatility(&self) -> Option<f64> {
+        let n = self.returns.len();
+        if n < 2 {
+            return None;
+        }
+        Some((self.m2.max(0.0) / (n - 1) as f64).sqrt())
+    }
+}
+
+/// Maker/taker commission schedule.
+///
+/// Fees are in basis points of trade value; a negative maker fee is a
+/// rebate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeModel {
+    /// Fee for fills that add liquidity (resting orders)
+    pub maker_bps: f64,
+    /// Fee for fills that take liquidity (orders crossing the book)
+    pub taker_bps: f64,
+}
+
+impl FeeModel {
+    /// Create a fee model from maker and taker fees in basis points.
+    pub fn new(maker_bps: f64, taker_bps: f64) -> Self {
+        Self { maker_bps, taker_bps }
+    }
+
+    /// Commission for a fill of `trade_value` on the given side.
+    pub fn commission(&self, trade_value: f64, liquidity: Liquidity) -> f64 {
+        let bps = match liquidity {
+            Liquidity::Maker => self.maker_bps,
+            Liquidity::Taker => self.taker_bps,
+        };
+        trade_value * bps / 10_000.0
+    }
+}
+
+/// Whether a fill added or removed book liquidity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liquidity {
+    /// Resting order filled by an incoming one
+    Maker,
+    /// Incoming order crossing the book
+    Taker,
+}
+
+/// How `submit_order` works an order against the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionStyle {
+    /// Fill the whole order against the current book at once
+    #[default]
+    Aggressive,
+    /// Split the order into `slices` equal child orders, one sent on submit
+    /// and one every `interval_ticks` calls to `update_orderbook`. Fewer
+    /// than two slices fills aggressively.
+    Vwap { slices: usize, interval_ticks: usize },
+}
+
+/// Parent order being worked in slices under `ExecutionStyle::Vwap`.
 #[derive(Debug, Clone)]
+struct SlicedOrder {
+    order_id: OrderId,
+    order: OrderRequest,
+    /// Child orders still to send, including the next one
+    slices_left: usize,
+    interval_ticks: usize,
+    /// Book updates since the last child order
+    ticks_since_slice: usize,
+    /// Fills of all children so far
+    result: FillResult,
+}
+
+impl SlicedOrder {
+    fn is_complete(&self) -> bool {
+        self.slices_left == 0
+    }
+
+    /// Quantity for the next child: an equal share of what is still unfilled,
+    /// so quantity a child could not fill carries over to later ones.
+    fn next_slice_quantity(&self) -> Quantity {
+        self.result.unfilled / self.slices_left as f64
+    }
+
+    /// Fold a child's fills into the parent result.
+    fn absorb(&mut self, child: FillResult) {
+        let total_cost = self.result.average_price * self.result.filled_quantity
+            + child.average_price * child.filled_quantity;
+        self.result.filled_quantity += child.filled_quantity;
+        self.result.unfilled = (self.order.quantity - self.result.filled_quantity).max(0.0);
+        self.result.commission += child.commission;
+        self.result.average_price = if self.result.filled_quantity > 0.0 {
+            total_cost / self.result.filled_quantity
+        } else {
+            0.0
+        };
+        self.result.fills.extend(child.fills);
+    }
+}
+
+/// Limit order resting in the book at its limit price.
+///
+/// It only fills once the `queue_ahead` volume that was already resting at
+/// that price when it arrived has traded.
+#[derive(Debug, Clone, Copy)]
+pub struct RestingOrder {
+    pub order_id: OrderId,
+    pub order: OrderRequest,
+    /// Volume still ahead of this order at its price level
+    pub queue_ahead: Quantity,
+    /// Quantity not yet filled
+    pub remaining: Quantity,
+}
+
+/// Fill result from L1 order execution.
+#[derive(Debug, Clone, Default)]
 pub struct FillResult {
     /// Individual fills at each price level
     pub fills: Vec<LevelFill>,
@@ -93,6 +335,8 @@ pub struct FillResult {
     pub average_price: Price,
     /// Total filled quantity
     pub filled_quantity: Quantity,
+    /// Total commission, the sum of the per-level commissions
+    pub commission: f64,
 }
 
 /// Fill at a single price level.
@@ -104,6 +348,10 @@ pub struct LevelFill {
     pub quantity: Quantity,
     /// Level index (0 = best price)
     pub level: usize,
+    /// Liquidity side; fills that cross the book are always taker
+    pub liquidity: Liquidity,
+    /// Commission charged on this fill
+    pub commission: f64,
 }
 
 /// L1 Simulated Gateway for realistic order execution.
@@ -118,12 +366,14 @@ pub struct L1SimulatedGateway {
     slippage_model: SlippageModel,
     /// Commission rate as a fraction
     commission_rate: f64,
+    /// Maker/taker fees replacing `commission_rate` (None = flat rate)
+    fee_model: Option<FeeModel>,
     /// Maximum fill ratio (e.g., 0.5 = can only fill 50% of available liquidity)
     fill_ratio: f64,
     /// Current market prices by symbol
     current_prices: HashMap<String, Price>,
-    /// Positions by symbol
-    positions: HashMap<String, PositionInternal>,
+    /// Positions by symbol, ordered so PnL sums are deterministic
+    positions: BTreeMap<String, PositionInternal>,
     /// Account balance
     balance: f64,
     /// Initial balance (kept for potential future reporting)
@@ -135,6 +385,18 @@ pub struct L1SimulatedGateway {
     pending_fills: Vec<Fill>,
     /// Current timestamp
     current_timestamp: i64,
+    /// Volatility-scaled slippage replacing `slippage_model` (None = disabled)
+    volatility_slippage: Option<VolatilityScaledSlippage>,
+    /// Rolling return volatility by symbol, fed by `update_price`
+    volatility: HashMap<String, RollingVolatility>,
+    /// How `submit_order` works orders
+    execution_style: ExecutionStyle,
+    /// Orders submitted under `ExecutionStyle::Vwap`, active and completed
+    sliced_orders: Vec<SlicedOrder>,
+    /// Resting limit orders, in submission order
+    resting_orders: Vec<RestingOrder>,
+    /// Lot matching used for realized PnL
+    cost_basis_method: CostBasisMethod,
 }
 
 /// Internal position representation.
@@ -144,6 +406,7 @@ struct PositionInternal {
     quantity: f64,
     average_price: f64,
     realized_pnl: f64,
+    lots: EntryLots,
 }
 
 impl L1SimulatedGateway {
@@ -153,17 +416,37 @@ impl L1SimulatedGateway {
             orderbook: OrderBookSnapshot::default(),
             slippage_model,
             commission_rate,
+            fee_model: None,
             fill_ratio: 0.5, // Default: can fill up to 50% of available liquidity
             current_prices: HashMap::new(),
-            positions: HashMap::new(),
+            positions: BTreeMap::new(),
             balance: initial_balance,
             initial_balance,
             next_order_id: 1,
             pending_fills: Vec::new(),
             current_timestamp: 0,
+            volatility_slippage: None,
+            volatility: HashMap::new(),
+            execution_style: ExecutionStyle::Aggressive,
+            sliced_orders: Vec::new(),
+            resting_orders: Vec::new(),
+            cost_basis_method: CostBasisMethod::Average,
         }
     }
 
+    /// Set how realized PnL matches closes against entries.
+    ///
+    /// Change this only while flat: lots are tracked from the first fill
+    /// after switching to FIFO or LIFO.
+    pub fn set_cost_basis_method(&mut self, method: CostBasisMethod) {
+        self.cost_basis_method = method;
+    }
+
+    /// Get the cost basis method.
+    pub fn cost_basis_method(&self) -> CostBasisMethod {
+        self.cost_basis_method
+    }
+
     /// Set the fill ratio (maximum percentage of available liquidity that can be filled).
     pub fn set_fill_ratio(&mut self, ratio: f64) {
         self.fill_ratio = ratio.clamp(0.0, 1.0);
@@ -174,9 +457,84 @@ impl L1SimulatedGateway {
         self.fill_ratio
     }
 
+    /// Set the slippage model. Applies to all subsequent fills.
+    pub fn set_slippage_model(&mut self, slippage_model: SlippageModel) {
+        self.slippage_model = slippage_model;
+    }
+
+    /// Get the current slippage model.
+    pub fn slippage_model(&self) -> &SlippageModel {
+        &self.slippage_model
+    }
+
+    /// Scale slippage with recent volatility instead of using the flat model.
+    ///
+    /// `None` restores `slippage_model`. Changing the model clears the
+    /// rolling volatility estimates.
+    pub fn set_volatility_slippage(&mut self, model: Option<VolatilityScaledSlippage>) {
+        self.volatility_slippage = model;
+        self.volatility.clear();
+    }
+
+    /// Get the volatility-scaled slippage model, if any.
+    pub fn volatility_slippage(&self) -> Option<&VolatilityScaledSlippage> {
+        self.volatility_slippage.as_ref()
+    }
+
+    /// Get the rolling return volatility for a symbol, if enough prices were seen.
+    pub fn recent_volatility(&self, symbol: &str) -> Option<f64> {
+        self.volatility.get(symbol).and_then(RollingVolatility::volatility)
+    }
+
+    /// Slippage for an order quantity on a symbol under the active model.
+    fn slippage_for(&self, symbol: &str, quantity: Quantity) -> f64 {
+        match &self.volatility_slippage {
+            Some(model) => model.calculate(quantity, self.recent_volatility(symbol)),
+            None => self.slippage_model.calculate(quantity),
+        }
+    }
+
+    /// Set the commission rate fraction. Applies to all subsequent fills.
+    pub fn set_commission_rate(&mut self, commission_rate: f64) {
+        self.commission_rate = commission_rate.max(0.0);
+    }
+
+    /// Get the commission rate.
+    pub fn commission_rate(&self) -> f64 {
+        self.commission_rate
+    }
+
+    /// Charge maker/taker fees instead of the flat commission rate.
+    ///
+    /// `None` restores `commission_rate`. L1 fills cross the book and pay
+    /// the taker fee; the maker fee is reserved for resting orders.
+    pub fn set_fee_model(&mut self, fee_model: Option<FeeModel>) {
+        self.fee_model = fee_model;
+    }
+
+    /// Get the maker/taker fee model, if any.
+    pub fn fee_model(&self) -> Option<&FeeModel> {
+        self.fee_model.as_ref()
+    }
+
+    /// Set how subsequent orders are executed.
+    ///
+    /// Orders already being sliced keep their original schedule.
+    pub fn set_execution_style(&mut self, style: ExecutionStyle) {
+        self.execution_style = style;
+    }
+
+    /// Get the execution style.
+    pub fn execution_style(&self) -> ExecutionStyle {
+        self.execution_style
+    }
+
     /// Update the order book snapshot.
+    ///
+    /// Sends the next child of every sliced order that is due.
     pub fn update_orderbook(&mut self, orderbook: OrderBookSnapshot) {
         self.orderbook = orderbook;
+        self.advance_sliced_orders();
     }
 
     /// Get the current order book snapshot.
@@ -191,11 +549,13 @@ impl L1SimulatedGateway {
 
     /// Execute an order against the order book.
     ///
-    /// Returns a FillResult containing individual fills at each price level,
-    /// the unfilled quantity, and the volume-weighted average price.
+    /// Returns a FillResult containing individual fills at each price level
+    /// with their taker commission, the unfilled quantity, and the
+    /// volume-weighted average price.
     pub fn execute_order(&self, order: &OrderRequest) -> FillResult {
         let mut remaining = order.quantity;
         let mut total_cost = 0.0;
+        let mut total_commission = 0.0;
         let mut fills = Vec::new();
         
         // Select the appropriate side of the order book
@@ -211,26 +571,37 @@ impl L1SimulatedGateway {
             if remaining <= 0.0 || level.is_empty() {
                 break;
             }
+            // Limit orders only take levels at or better than their limit
+            if order.order_type == ORDER_TYPE_LIMIT
+                && (order.direction == DIRECTION_BUY) == (level.price > order.limit_price)
+                && !price_eq(level.price, order.limit_price)
+            {
+                break;
+            }
             
             // Calculate available quantity at this level (limited by fill_ratio)
             let available = level.quantity * self.fill_ratio;
             let fill_qty = remaining.min(available);
             
             // Calculate fill price with slippage
-            let slippage = self.slippage_model.calculate(fill_qty);
+            let slippage = self.slippage_for(order.symbol_str(), fill_qty);
             let fill_price = if order.direction == DIRECTION_BUY {
                 level.price * (1.0 + slippage) // Buy at higher price
             } else {
                 level.price * (1.0 - slippage) // Sell at lower price
             };
             
+            let commission = self.calculate_commission(fill_price * fill_qty, Liquidity::Taker);
             fills.push(LevelFill {
                 price: fill_price,
                 quantity: fill_qty,
                 level: level_idx,
+                liquidity: Liquidity::Taker,
+                commission,
             });
             
             total_cost += fill_price * fill_qty;
+            total_commission += commission;
             remaining -= fill_qty;
         }
         
@@ -246,74 +617,214 @@ impl L1SimulatedGateway {
             unfilled: remaining,
             average_price,
             filled_quantity,
+            commission: total_commission,
         }
     }
 
-    /// Calculate commission for a trade.
-    fn calculate_commission(&self, trade_value: f64) -> f64 {
-        trade_value * self.commission_rate
+    /// Accumulated fills of a sliced order, whether active or complete.
+    pub fn sliced_order_result(&self, order_id: OrderId) -> Option<&FillResult> {
+        self.sliced_orders
+            .iter()
+            .find(|sliced| sliced.order_id == order_id)
+            .map(|sliced| &sliced.result)
     }
 
-    /// Calculate unrealized PnL for a position.
-    fn calculate_unrealized_pnl(&self, position: &PositionInternal) -> f64 {
-        if let Some(&current_price) = self.current_prices.get(&position.symbol) {
-            (current_price - position.average_price) * position.quantity
-        } else {
-            0.0
+    /// Number of sliced orders with child orders still to send.
+    pub fn active_sliced_orders(&self) -> usize {
+        self.sliced_orders.iter().filter(|sliced| !sliced.is_complete()).count()
+    }
+
+    /// Remove and return the results of all completed sliced orders.
+    pub fn take_completed_sliced_orders(&mut self) -> Vec<(OrderId, FillResult)> {
+        let (completed, active) = std::mem::take(&mut self.sliced_orders)
+            .into_iter()
+            .partition(SlicedOrder::is_complete);
+        self.sliced_orders = active;
+        completed
+            .into_iter()
+            .map(|sliced: SlicedOrder| (sliced.order_id, sliced.result))
+            .collect()
+    }
+
+    /// Send one child of a sliced order against the current book and book
+    /// its fill. A child that runs out of funds ends the parent early.
+    fn send_slice(&mut self, sliced: &mut SlicedOrder) -> Result<(), GatewayError> {
+        let mut child = sliced.order;
+        child.quantity = sliced.next_slice_quantity();
+        sliced.slices_left -= 1;
+        sliced.ticks_since_slice = 0;
+
+        let result = self.execute_order(&child);
+        if result.filled_quantity > 0.0 {
+            let booked = self.apply_fill(
+                &sliced.order,
+                sliced.order_id,
+                result.average_price,
+                result.filled_quantity,
+                result.commission,
+            );
+            if let Err(e) = booked {
+                sliced.slices_left = 0;
+                return Err(e);
+            }
+            sliced.absorb(result);
         }
+        Ok(())
     }
 
-    /// Get total unrealized PnL.
-    fn total_unrealized_pnl(&self) -> f64 {
-        self.positions
-            .values()
-            .map(|p| self.calculate_unrealized_pnl(p))
-            .sum()
+    /// Send the children of sliced orders whose interval has elapsed.
+    fn advance_sliced_orders(&mut self) {
+        let mut sliced_orders = std::mem::take(&mut self.sliced_orders);
+        for sliced in sliced_orders.iter_mut().filter(|sliced| !sliced.is_complete()) {
+            sliced.ticks_since_slice += 1;
+            if sliced.ticks_since_slice >= sliced.interval_ticks {
+                // A failed child has already ended its parent order
+                let _ = self.send_slice(sliced);
+            }
+        }
+        self.sliced_orders = sliced_orders;
     }
 
-    /// Get total realized PnL.
-    fn total_realized_pnl(&self) -> f64 {
-        self.positions.values().map(|p| p.realized_pnl).sum()
+    /// Start working an order in `slices` children, sending the first now.
+    fn submit_sliced(
+        &mut self,
+        order: &OrderRequest,
+        current_price: f64,
+        slices: usize,
+        interval_ticks: usize,
+    ) -> Result<OrderId, GatewayError> {
+        let order_id = self.next_order_id;
+        let mut sliced = SlicedOrder {
+            order_id,
+            order: *order,
+            slices_left: slices,
+            interval_ticks: interval_ticks.max(1),
+            ticks_since_slice: 0,
+            result: FillResult {
+                unfilled: order.quantity,
+                ..FillResult::default()
+            },
+        };
+
+        self.send_slice(&mut sliced)?;
+
+        self.next_order_id += 1;
+        self.current_prices.insert(order.symbol_str().to_string(), current_price);
+        self.sliced_orders.push(sliced);
+        Ok(order_id)
     }
-}
 
-impl Default for L1SimulatedGateway {
-    fn default() -> Self {
-        Self::new(100_000.0, SlippageModel::default(), 0.0001)
+    /// Get the resting limit orders, in submission order.
+    pub fn resting_orders(&self) -> &[RestingOrder] {
+        &self.resting_orders
     }
-}
 
-impl Gateway for L1SimulatedGateway {
-    fn submit_order(&mut self, order: &OrderRequest, current_price: f64) -> Result<OrderId, GatewayError> {
-        // Validate order
-        if order.quantity <= 0.0 {
-            return Err(GatewayError::InvalidOrder("Quantity must be positive".to_string()));
-        }
-        if order.direction != DIRECTION_BUY && order.direction != DIRECTION_SELL {
-            return Err(GatewayError::InvalidOrder("Invalid direction".to_string()));
+    /// Whether a limit order crosses the current book.
+    fn is_marketable(&self, order: &OrderRequest) -> bool {
+        if order.direction == DIRECTION_BUY {
+            self.orderbook.best_ask().is_some_and(|ask| ask <= order.limit_price)
+        } else {
+            self.orderbook.best_bid().is_some_and(|bid| bid >= order.limit_price)
         }
+    }
 
-        let symbol = order.symbol_str().to_string();
-        
-        // Execute order against order book
-        let fill_result = self.execute_order(order);
-        
-        // If no fills, check if we can do a simple fill at current price
-        let (fill_price, fill_quantity) = if fill_result.filled_quantity > 0.0 {
-            (fill_result.average_price, fill_result.filled_quantity)
+    /// Quantity already queued on the order's side at its limit price.
+    fn queue_ahead(&self, order: &OrderRequest) -> Quantity {
+        let (levels, count) = if order.direction == DIRECTION_BUY {
+            (&self.orderbook.bids, self.orderbook.bid_count)
         } else {
-            // Fallback to simple execution at current price with slippage
-            let slippage = self.slippage_model.calculate(order.quantity);
-            let price = if order.direction == DIRECTION_BUY {
-                current_price * (1.0 + slippage)
+            (&self.orderbook.asks, self.orderbook.ask_count)
+        };
+        levels[..count as usize]
+            .iter()
+            .find(|level| price_eq(level.price, order.limit_price))
+            .map_or(0.0, |level| level.quantity)
+    }
+
+    /// Rest a limit order at the back of the queue at its price.
+    fn rest_order(&mut self, order: &OrderRequest) -> OrderId {
+        let order_id = self.next_order_id;
+        self.next_order_id += 1;
+        self.resting_orders.push(RestingOrder {
+            order_id,
+            order: *order,
+            queue_ahead: self.queue_ahead(order),
+            remaining: order.quantity,
+        });
+        order_id
+    }
+
+    /// Assign an ID to an IOC order that was cancelled without filling.
+    fn cancelled_order_id(&mut self) -> OrderId {
+        let order_id = self.next_order_id;
+        self.next_order_id += 1;
+        order_id
+    }
+
+    /// Report a trade print of `quantity` at `price` for `symbol`.
+    ///
+    /// A print at a resting order's price first consumes the volume queued
+    /// ahead of it and fills the order from what is left; a print through
+    /// its price means the whole level traded and fills it completely.
+    /// Resting fills are at the limit price and pay the maker fee. Orders at
+    /// the same price share one print in FIFO order: the volume queued ahead
+    /// of each order is consumed first, and whatever an earlier order fills
+    /// is no longer available to the ones behind it. An order whose fill the
+    /// balance can't cover is cancelled.
+    pub fn on_trade(&mut self, symbol: &str, price: Price, quantity: Quantity) {
+        // Print volume not yet consumed by queued volume or earlier orders
+        let mut left = quantity;
+        // Queued book volume this print has traded through so far
+        let mut queue_traded = 0.0;
+        let mut resting = std::mem::take(&mut self.resting_orders);
+
+        for rest in resting.iter_mut().filter(|rest| rest.order.symbol_str() == symbol) {
+            let limit = rest.order.limit_price;
+            let fill_qty = if price_eq(price, limit) {
+                let through_queue = (rest.queue_ahead - queue_traded).max(0.0).min(left);
+                left -= through_queue;
+                queue_traded += through_queue;
+                rest.queue_ahead = (rest.queue_ahead - queue_traded).max(0.0);
+                rest.remaining.min(left)
+            } else if (rest.order.direction == DIRECTION_BUY) == (price < limit) {
+                rest.queue_ahead = 0.0;
+                rest.remaining
             } else {
-                current_price * (1.0 - slippage)
+                continue;
             };
-            (price, order.quantity)
-        };
-        
+            if fill_qty <= QUANTITY_EPSILON {
+                continue;
+            }
+
+            let commission = self.calculate_commission(limit * fill_qty, Liquidity::Maker);
+            match self.apply_fill(&rest.order, rest.order_id, limit, fill_qty, commission) {
+                Ok(()) => {
+                    rest.remaining -= fill_qty;
+                    if price_eq(price, limit) {
+                        left -= fill_qty;
+                    }
+                }
+                Err(_) => rest.remaining = 0.0,
+            }
+        }
+
+        resting.retain(|rest| rest.remaining > QUANTITY_EPSILON);
+        self.resting_orders = resting;
+    }
+
+    /// Book a fill: check funds, update the position and balance, and record
+    /// the fill for `get_fills`.
+    fn apply_fill(
+        &mut self,
+        order: &OrderRequest,
+        order_id: OrderId,
+        fill_price: Price,
+        fill_quantity: Quantity,
+        commission: f64,
+    ) -> Result<(), GatewayError> {
+        let symbol = order.symbol_str().to_string();
         let trade_value = fill_quantity * fill_price;
-        let commission = self.calculate_commission(trade_value);
+        let method = self.cost_basis_method;
 
         // Check funds for buy orders
         if order.direction == DIRECTION_BUY {
@@ -326,31 +837,34 @@ impl Gateway for L1SimulatedGateway {
             }
         }
 
-        // Generate order ID
-        let order_id = self.next_order_id;
-        self.next_order_id += 1;
-
         // Update position
         let position = self.positions.entry(symbol.clone()).or_insert(PositionInternal {
-            symbol: symbol.clone(),
+            symbol,
             quantity: 0.0,
             average_price: 0.0,
             realized_pnl: 0.0,
+            lots: EntryLots::default(),
         });
 
         if order.direction == DIRECTION_BUY {
             let new_quantity = position.quantity + fill_quantity;
             if position.quantity > 0.0 {
                 position.average_price = (position.average_price * position.quantity + fill_price * fill_quantity) / new_quantity;
+                position.lots.open(method, fill_quantity, fill_price);
             } else if position.quantity < 0.0 {
                 let cover_quantity = fill_quantity.min(-position.quantity);
-                let pnl = (position.average_price - fill_price) * cover_quantity;
+                let entry_price = position.lots.close(method, cover_quantity, position.average_price);
+                let pnl = (entry_price - fill_price) * cover_quantity;
                 position.realized_pnl += pnl;
                 if fill_quantity > -position.quantity {
                     position.average_price = fill_price;
+                    position.lots.reset(method, fill_quantity - cover_quantity, fill_price);
+                } else if let Some(average_price) = position.lots.average_price() {
+                    position.average_price = average_price;
                 }
             } else {
                 position.average_price = fill_price;
+                position.lots.reset(method, fill_quantity, fill_price);
             }
             position.quantity = new_quantity;
             self.balance -= trade_value + commission;
@@ -358,23 +872,26 @@ impl Gateway for L1SimulatedGateway {
             let new_quantity = position.quantity - fill_quantity;
             if position.quantity > 0.0 {
                 let close_quantity = fill_quantity.min(position.quantity);
-                let pnl = (fill_price - position.average_price) * close_quantity;
+                let entry_price = position.lots.close(method, close_quantity, position.average_price);
+                let pnl = (fill_price - entry_price) * close_quantity;
                 position.realized_pnl += pnl;
                 if fill_quantity > position.quantity {
                     position.average_price = fill_price;
+                    position.lots.reset(method, fill_quantity - close_quantity, fill_price);
+                } else if let Some(average_price) = position.lots.average_price() {
+                    position.average_price = average_price;
                 }
             } else if position.quantity < 0.0 {
                 position.average_price = (position.average_price * (-position.quantity) + fill_price * fill_quantity) / (-new_quantity);
+                position.lots.open(method, fill_quantity, fill_price);
             } else {
                 position.average_price = fill_price;
+                position.lots.reset(method, fill_quantity, fill_price);
             }
             position.quantity = new_quantity;
             self.balance += trade_value - commission;
         }
 
-        // Update current price
-        self.current_prices.insert(symbol, current_price);
-
         // Record fill
         let fill = Fill {
             order_id,
@@ -384,14 +901,156 @@ impl Gateway for L1SimulatedGateway {
             commission,
             direction: order.direction,
             timestamp: self.current_timestamp,
+            client_id: order.client_id,
         };
         self.pending_fills.push(fill);
 
+        Ok(())
+    }
+
+    /// Calculate commission for a trade on the given liquidity side.
+    fn calculate_commission(&self, trade_value: f64, liquidity: Liquidity) -> f64 {
+        match &self.fee_model {
+            Some(model) => model.commission(trade_value, liquidity),
+            None => trade_value * self.commission_rate,
+        }
+    }
+
+    /// Calculate unrealized PnL for a position.
+    fn calculate_unrealized_pnl(&self, position: &PositionInternal) -> f64 {
+        if let Some(&current_price) = self.current_prices.get(&position.symbol) {
+            (current_price - position.average_price) * position.quantity
+        } else {
+            0.0
+        }
+    }
+
+    /// Get total unrealized PnL.
+    fn total_unrealized_pnl(&self) -> f64 {
+        self.positions
+            .values()
+            .map(|p| self.calculate_unrealized_pnl(p))
+            .sum()
+    }
+
+    /// Get total realized PnL.
+    fn total_realized_pnl(&self) -> f64 {
+        self.positions.values().map(|p| p.realized_pnl).sum()
+    }
+}
+
+impl Default for L1SimulatedGateway {
+    fn default() -> Self {
+        Self::new(100_000.0, SlippageModel::default(), 0.0001)
+    }
+}
+
+impl Gateway for L1SimulatedGateway {
+    fn submit_order(&mut self, order: &OrderRequest, current_price: f64) -> Result<OrderId, GatewayError> {
+        // Validate order
+        if order.quantity <= 0.0 {
+            return Err(GatewayError::InvalidOrder("Quantity must be positive".to_string()));
+        }
+        if order.direction != DIRECTION_BUY && order.direction != DIRECTION_SELL {
+            return Err(GatewayError::InvalidOrder("Invalid direction".to_string()));
+        }
+        validate_tif(order)?;
+
+        let is_limit = order.order_type == ORDER_TYPE_LIMIT;
+        if is_limit {
+            if !order.limit_price.is_finite() || order.limit_price <= 0.0 {
+                return Err(GatewayError::InvalidOrder("Limit price must be positive".to_string()));
+            }
+            if !self.is_marketable(order) {
+                return match order.tif {
+                    TIF_GTC => Ok(self.rest_order(order)),
+                    TIF_FOK => Err(fok_rejected(order.quantity, 0.0)),
+                    _ => Ok(self.cancelled_order_id()),
+                };
+            }
+        }
+
+        if let ExecutionStyle::Vwap { slices, interval_ticks } = self.execution_style {
+            if slices > 1 && order.tif == TIF_GTC {
+                return self.submit_sliced(order, current_price, slices, interval_ticks);
+            }
+        }
+
+        let symbol = order.symbol_str().to_string();
+        
+        // Execute order against order book
+        let fill_result = self.execute_order(order);
+        if order.tif == TIF_FOK && fill_result.unfilled > QUANTITY_EPSILON {
+            return Err(fok_rejected(order.quantity, fill_result.filled_quantity));
+        }
+
+        // IOC orders and limit orders without book liquidity cancel or rest
+        // what did not fill; GTC market orders fall back to the current price
+        if fill_result.filled_quantity <= 0.0 && (is_limit || order.tif != TIF_GTC) {
+            self.current_prices.insert(symbol, current_price);
+            return Ok(if order.tif == TIF_GTC {
+                self.rest_order(order)
+            } else {
+                self.cancelled_order_id()
+            });
+        }
+
+        // If no fills, check if we can do a simple fill at current price
+        let (fill_price, fill_quantity, commission) = if fill_result.filled_quantity > 0.0 {
+            (fill_result.average_price, fill_result.filled_quantity, fill_result.commission)
+        } else {
+            // Fallback to simple execution at current price with slippage
+            let slippage = self.slippage_for(&symbol, order.quantity);
+            let price = if order.direction == DIRECTION_BUY {
+                current_price * (1.0 + slippage)
+            } else {
+                current_price * (1.0 - slippage)
+            };
+            let commission = self.calculate_commission(price * order.quantity, Liquidity::Taker);
+            (price, order.quantity, commission)
+        };
+
+        let order_id = self.next_order_id;
+        self.apply_fill(order, order_id, fill_price, fill_quantity, commission)?;
+        self.next_order_id += 1;
+
+        // GTC limit remainders rest at the limit under the same order ID
+        if is_limit && order.tif == TIF_GTC && fill_result.unfilled > QUANTITY_EPSILON {
+            self.resting_orders.push(RestingOrder {
+                order_id,
+                order: *order,
+                queue_ahead: self.queue_ahead(order),
+                remaining: fill_result.unfilled,
+            });
+        }
+
+        // Update current price
+        self.current_prices.insert(symbol, current_price);
+
         Ok(order_id)
     }
 
+    /// Cancels a resting limit order, or the unsent children of a sliced
+    /// order; fills already booked stand. Aggressive orders fill on submit
+    /// and cannot be cancelled.
     fn cancel_order(&mut self, order_id: OrderId) -> Result<(), GatewayError> {
-        Err(GatewayError::OrderNotFound(order_id))
+        let resting = self.resting_orders.len();
+        self.resting_orders.retain(|rest| rest.order_id != order_id);
+        if self.resting_orders.len() < resting {
+            return Ok(());
+        }
+
+        match self
+            .sliced_orders
+            .iter_mut()
+            .find(|sliced| sliced.order_id == order_id && !sliced.is_complete())
+        {
+            Some(sliced) => {
+                sliced.slices_left = 0;
+                Ok(())
+            }
+            None => Err(GatewayError::OrderNotFound(order_id)),
+        }
     }
 
     fn query_position(&self, symbol: &str) -> Option<Position> {
@@ -405,6 +1064,32 @@ impl Gateway for L1SimulatedGateway {
         })
     }
 
+    fn query_positions(&self) -> Result<Vec<Position>, GatewayError> {
+        Ok(self
+            .positions
+            .keys()
+            .filter_map(|symbol| self.query_position(symbol))
+            .collect())
+    }
+
+    fn restore_position(&mut self, symbol: &str, quantity: f64, average_price: f64) -> Result<(), GatewayError> {
+        if quantity.abs() < QUANTITY_EPSILON {
+            self.positions.remove(symbol);
+            return Ok(());
+        }
+        let position = self.positions.entry(symbol.to_string()).or_insert(PositionInternal {
+            symbol: symbol.to_string(),
+            quantity: 0.0,
+            average_price: 0.0,
+            realized_pnl: 0.0,
+            lots: EntryLots::default(),
+        });
+        position.quantity = quantity;
+        position.average_price = average_price;
+        position.lots.reset(self.cost_basis_method, quantity.abs(), average_price);
+        Ok(())
+    }
+
     fn query_account(&self) -> AccountStatus {
         let unrealized_pnl = self.total_unrealized_pnl();
         let realized_pnl = self.total_realized_pnl();
@@ -425,6 +1110,12 @@ impl Gateway for L1SimulatedGateway {
 
     fn update_price(&mut self, symbol: &str, price: f64) {
         self.current_prices.insert(symbol.to_string(), price);
+        if let Some(model) = &self.volatility_slippage {
+            self.volatility
+                .entry(symbol.to_string())
+                .or_insert_with(|| RollingVolatility::new(model.window))
+                .update(price);
+        }
     }
 }
 
@@ -445,10 +1136,126 @@ pub extern "C" fn get_gateway_mode_ffi() -> i32 {
     get_gateway_mode() as i32
 }
 
+/// Create a new L1 simulated gateway with the default slippage model.
+///
+/// # Safety
+/// - Caller must call `free_l1_gateway` to release the returned pointer
+///
+/// # Returns
+/// - Valid pointer on success
+/// - Null pointer on failure
+#[no_mangle]
+pub extern "C" fn create_l1_gateway(initial_balance: f64, commission_rate: f64) -> *mut L1SimulatedGateway {
+    let result = catch_unwind(|| {
+        let gateway = L1SimulatedGateway::new(initial_balance, SlippageModel::default(), commission_rate);
+        Box::into_raw(Box::new(gateway))
+    });
+
+    result.unwrap_or(std::ptr::null_mut())
+}
+
+/// Free an L1 simulated gateway.
+///
+/// # Safety
+/// - `gateway` must be a valid pointer returned by `create_l1_gateway`
+/// - Must only be called once per gateway
+#[no_mangle]
+pub unsafe extern "C" fn free_l1_gateway(gateway: *mut L1SimulatedGateway) {
+    if gateway.is_null() {
+        return;
+    }
+
+    let _ = catch_unwind(|| {
+        let _ = Box::from_raw(gateway);
+    });
+}
+
+/// Copy an order book snapshot from C# into the gateway.
+///
+/// The snapshot is copied as-is; no per-level conversion is performed.
+///
+/// # Safety
+/// - `gateway` must be a valid pointer from `create_l1_gateway`
+/// - `snapshot` must be a valid pointer to an FfiOrderBookSnapshot
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if either pointer is null
+/// - ERR_INVALID_PARAM if bid_count or ask_count is outside 0..=MAX_LEVELS
+#[no_mangle]
+pub unsafe extern "C" fn update_gateway_orderbook(
+    gateway: *mut L1SimulatedGateway,
+    snapshot: *const FfiOrderBookSnapshot,
+) -> i32 {
+    use crate::ffi::{ERR_INTERNAL_PANIC, ERR_INVALID_PARAM, ERR_NULL_POINTER, ERR_SUCCESS};
+
+    if gateway.is_null() || snapshot.is_null() {
+        return ERR_NULL_POINTER;
+    }
+
+    let outcome = catch_unwind(|| {
+        let snapshot = *snapshot;
+        if !snapshot.has_valid_counts() {
+            return ERR_INVALID_PARAM;
+        }
+        (*gateway).update_orderbook(snapshot);
+        ERR_SUCCESS
+    });
+
+    outcome.unwrap_or(ERR_INTERNAL_PANIC)
+}
+
+/// Query the full position for a symbol.
+///
+/// # Safety
+/// - `gateway` must be a valid pointer from `create_l1_gateway`
+/// - `symbol` must be a valid null-terminated UTF-8 string
+/// - `out_position` must be a valid pointer to write a Position
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_NULL_POINTER if any pointer is null
+/// - ERR_INVALID_PARAM if symbol is not valid UTF-8
+/// - ERR_POSITION_NOT_FOUND if no position is held for the symbol
+///   (`out_position` is left untouched)
+#[no_mangle]
+pub unsafe extern "C" fn query_position_ffi(
+    gateway: *const L1SimulatedGateway,
+    symbol: *const c_char,
+    out_position: *mut Position,
+) -> i32 {
+    use crate::ffi::{
+        ERR_INTERNAL_PANIC, ERR_INVALID_PARAM, ERR_NULL_POINTER, ERR_POSITION_NOT_FOUND,
+        ERR_SUCCESS,
+    };
+
+    if gateway.is_null() || symbol.is_null() || out_position.is_null() {
+        return ERR_NULL_POINTER;
+    }
+
+    let outcome = catch_unwind(|| {
+        // SAFETY: symbol validated above
+        let symbol = match CStr::from_ptr(symbol).to_str() {
+            Ok(s) => s,
+            Err(_) => return ERR_INVALID_PARAM,
+        };
+        match (*gateway).query_position(symbol) {
+            Some(position) => {
+                *out_position = position;
+                ERR_SUCCESS
+            }
+            None => ERR_POSITION_NOT_FOUND,
+        }
+    });
+
+    outcome.unwrap_or(ERR_INTERNAL_PANIC)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::orderbook::OrderBookLevel;
+    use crate::orderbook::{OrderBookLevel, MAX_LEVELS};
+    use crate::types::TIF_IOC;
 
     fn create_test_orderbook() -> OrderBookSnapshot {
         let bids = vec![
@@ -477,6 +1284,55 @@ mod tests {
         assert_eq!(slippage, 0.05); // Capped at max
     }
 
+    #[test]
+    fn test_volatile_path_has_higher_slippage() {
+        let model = VolatilityScaledSlippage::new(SlippageModel::new(0.001, 0.0, 0.004), 10, 0.002);
+
+        // Average buy slippage over identical orders along a price path
+        let average_slippage = |swing: f64| {
+            let mut gateway = L1SimulatedGateway::new(1e9, SlippageModel::default(), 0.0);
+            gateway.set_volatility_slippage(Some(model));
+            let mut order = OrderRequest::with_symbol("BTCUSDT");
+            order.quantity = 1.0;
+            order.direction = DIRECTION_BUY;
+
+            let (mut total, mut max) = (0.0, 0.0f64);
+            for i in 0..40 {
+                let price = 100.0 * (1.0 + if i % 2 == 0 { swing } else { -swing });
+                gateway.update_price("BTCUSDT", price);
+                gateway.submit_order(&order, price).unwrap();
+                let fill = gateway.get_fills()[0];
+                let slippage = fill.price / price - 1.0;
+                total += slippage;
+                max = max.max(slippage);
+            }
+            (total / 40.0, max)
+        };
+
+        let (calm, calm_max) = average_slippage(0.0005);
+        let (volatile, volatile_max) = average_slippage(0.01);
+        assert!(volatile > calm);
+        assert!(calm_max < 0.004);
+        // Extreme volatility is still capped at max_slippage
+        assert!((volatile_max - 0.004).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rolling_volatility_matches_window() {
+        let mut rolling = RollingVolatility::new(3);
+        let prices = [100.0, 101.0, 99.0, 102.0, 100.0, 100.5];
+        for &price in &prices {
+            rolling.update(price);
+        }
+
+        // Direct sample std dev of the last three returns
+        let returns: Vec<f64> = prices.windows(2).map(|w| (w[1] - w[0]) / w[0]).collect();
+        let last = &returns[returns.len() - 3..];
+        let mean = last.iter().sum::<f64>() / 3.0;
+        let var = last.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 2.0;
+        assert!((rolling.volatility().unwrap() - var.sqrt()).abs() < 1e-12);
+    }
+
     #[test]
     fn test_gateway_mode() {
         set_gateway_mode_internal(GatewayMode::L1);
@@ -509,6 +1365,24 @@ mod tests {
         assert!(result.average_price >= 101.0); // At least best ask price
     }
 
+    #[test]
+    fn test_client_id_echoed_on_fill() {
+        let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        gateway.update_orderbook(create_test_orderbook());
+
+        for client_id in [0, 7, u64::MAX] {
+            let mut order = OrderRequest::with_symbol("BTCUSDT");
+            order.quantity = 1.0;
+            order.direction = DIRECTION_BUY;
+            order.client_id = client_id;
+            gateway.submit_order(&order, 101.0).unwrap();
+
+            let fills = gateway.get_fills();
+            assert_eq!(fills.len(), 1);
+            assert_eq!(fills[0].client_id, client_id);
+        }
+    }
+
     #[test]
     fn test_execute_sell_order() {
         let mut gateway = L1SimulatedGateway::default();
@@ -543,6 +1417,290 @@ mod tests {
         assert!(result.filled_quantity > 0.0);
     }
 
+    #[test]
+    fn test_taker_fees_per_level() {
+        let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.001);
+        gateway.set_fee_model(Some(FeeModel::new(-1.0, 5.0)));
+        gateway.update_orderbook(create_test_orderbook());
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 200.0;
+        order.direction = DIRECTION_BUY;
+        let result = gateway.execute_order(&order);
+
+        assert!(result.fills.len() > 1);
+        let mut expected_total = 0.0;
+        for fill in &result.fills {
+            assert_eq!(fill.liquidity, Liquidity::Taker);
+            let expected = fill.price * fill.quantity * 5.0 / 10_000.0;
+            assert!((fill.commission - expected).abs() < 1e-9);
+            expected_total += fill.commission;
+        }
+        assert!((result.commission - expected_total).abs() < 1e-9);
+
+        // The gateway fill charges the same total
+        gateway.submit_order(&order, 101.0).unwrap();
+        let fills = gateway.get_fills();
+        assert!((fills[0].commission - expected_total).abs() < 1e-9);
+    }
+
+    /// One-lot levels a tick apart that refill on every snapshot.
+    fn thin_orderbook() -> OrderBookSnapshot {
+        let bids: Vec<_> = (0..5).map(|i| OrderBookLevel::new(99.0 - i as f64, 10.0, 1)).collect();
+        let asks: Vec<_> = (0..5).map(|i| OrderBookLevel::new(101.0 + i as f64, 10.0, 1)).collect();
+        OrderBookSnapshot::with_levels(&bids, &asks, 100.0, 0)
+    }
+
+    #[test]
+    fn test_vwap_slicing_beats_aggressive_fill_on_thin_book() {
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 20.0;
+        order.direction = DIRECTION_BUY;
+
+        let mut aggressive = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        aggressive.update_orderbook(thin_orderbook());
+        aggressive.submit_order(&order, 100.0).unwrap();
+        let aggressive_fill = aggressive.get_fills()[0];
+
+        let mut sliced = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        sliced.set_execution_style(ExecutionStyle::Vwap { slices: 4, interval_ticks: 2 });
+        sliced.update_orderbook(thin_orderbook());
+        let order_id = sliced.submit_order(&order, 100.0).unwrap();
+        assert_eq!(sliced.active_sliced_orders(), 1);
+
+        // One child on submit, then one every second book update
+        for _ in 0..6 {
+            sliced.update_orderbook(thin_orderbook());
+        }
+        assert_eq!(sliced.active_sliced_orders(), 0);
+        assert_eq!(sliced.get_fills().len(), 4);
+
+        let result = sliced.sliced_order_result(order_id).unwrap().clone();
+        assert!((result.filled_quantity - 20.0).abs() < 1e-9);
+        assert!(result.unfilled.abs() < 1e-9);
+        assert!(result.fills.iter().all(|fill| fill.level == 0));
+        assert!(result.average_price < aggressive_fill.price);
+        assert!((sliced.query_position("BTCUSDT").unwrap().average_price - result.average_price).abs() < 1e-9);
+
+        let completed = sliced.take_completed_sliced_orders();
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].0, order_id);
+        assert!(sliced.sliced_order_result(order_id).is_none());
+    }
+
+    #[test]
+    fn test_vwap_unfilled_quantity_carries_to_later_slices() {
+        let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        gateway.set_execution_style(ExecutionStyle::Vwap { slices: 2, interval_ticks: 1 });
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 8.0;
+        order.direction = DIRECTION_BUY;
+
+        // Empty book: the first child fills nothing
+        let order_id = gateway.submit_order(&order, 100.0).unwrap();
+        assert_eq!(gateway.sliced_order_result(order_id).unwrap().filled_quantity, 0.0);
+
+        // The last child takes the whole remainder
+        gateway.update_orderbook(create_test_orderbook());
+        let result = gateway.sliced_order_result(order_id).unwrap();
+        assert!((result.filled_quantity - 8.0).abs() < 1e-9);
+        assert_eq!(gateway.active_sliced_orders(), 0);
+    }
+
+    #[test]
+    fn test_cancel_sliced_order_keeps_booked_fills() {
+        let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        gateway.set_execution_style(ExecutionStyle::Vwap { slices: 4, interval_ticks: 1 });
+        gateway.update_orderbook(thin_orderbook());
+
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 20.0;
+        order.direction = DIRECTION_BUY;
+        let order_id = gateway.submit_order(&order, 100.0).unwrap();
+
+        assert!(gateway.cancel_order(order_id).is_ok());
+        gateway.update_orderbook(thin_orderbook());
+
+        let result = gateway.sliced_order_result(order_id).unwrap();
+        assert!((result.filled_quantity - 5.0).abs() < 1e-9);
+        assert!((result.unfilled - 15.0).abs() < 1e-9);
+        assert!((gateway.query_position("BTCUSDT").unwrap().quantity - 5.0).abs() < 1e-9);
+        assert!(matches!(gateway.cancel_order(order_id), Err(GatewayError::OrderNotFound(_))));
+    }
+
+    fn buy_limit(quantity: f64, limit_price: f64) -> OrderRequest {
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = quantity;
+        order.direction = DIRECTION_BUY;
+        order.order_type = ORDER_TYPE_LIMIT;
+        order.limit_price = limit_price;
+        order
+    }
+
+    #[test]
+    fn test_fok_rejects_on_insufficient_depth() {
+        let mut gateway = L1SimulatedGateway::new(1_000_000.0, SlippageModel::new(0.0, 0.0, 0.0), 0.0);
+        gateway.update_orderbook(create_test_orderbook());
+
+        // Half of each ask level is fillable: 50 + 100 + 150 = 300
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 301.0;
+        order.direction = DIRECTION_BUY;
+        order.tif = TIF_FOK;
+        let result = gateway.submit_order(&order, 100.0);
+        assert!(matches!(result, Err(GatewayError::InvalidOrder(_))));
+        assert!(gateway.get_fills().is_empty());
+        assert!(gateway.query_position("BTCUSDT").is_none());
+
+        // Depth within the limit counts, levels beyond it do not
+        let mut limit = buy_limit(151.0, 102.0);
+        limit.tif = TIF_FOK;
+        assert!(gateway.submit_order(&limit, 100.0).is_err());
+        limit.quantity = 150.0;
+        gateway.submit_order(&limit, 100.0).unwrap();
+        assert!((gateway.query_position("BTCUSDT").unwrap().quantity - 150.0).abs() < 1e-9);
+
+        order.quantity = 300.0;
+        gateway.submit_order(&order, 100.0).unwrap();
+        assert!((gateway.query_position("BTCUSDT").unwrap().quantity - 450.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ioc_partial_fill_cancels_remainder() {
+        let mut gateway = L1SimulatedGateway::new(1_000_000.0, SlippageModel::new(0.0, 0.0, 0.0), 0.0);
+        gateway.update_orderbook(create_test_orderbook());
+
+        // Only the 101 level is within the limit, and half of it is fillable
+        let mut order = buy_limit(200.0, 101.0);
+        order.tif = TIF_IOC;
+        let order_id = gateway.submit_order(&order, 100.0).unwrap();
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert!((fills[0].quantity - 50.0).abs() < 1e-9);
+        assert!((fills[0].price - 101.0).abs() < 1e-9);
+        assert!(gateway.resting_orders().is_empty());
+        assert!(gateway.cancel_order(order_id).is_err());
+
+        // A GTC limit rests the same remainder under its order ID
+        order.tif = TIF_GTC;
+        let order_id = gateway.submit_order(&order, 100.0).unwrap();
+        assert_eq!(gateway.resting_orders().len(), 1);
+        assert_eq!(gateway.resting_orders()[0].order_id, order_id);
+        assert!((gateway.resting_orders()[0].remaining - 150.0).abs() < 1e-9);
+
+        gateway.get_fills();
+
+        // A non-marketable IOC limit is cancelled without filling
+        order.tif = TIF_IOC;
+        order.limit_price = 95.0;
+        gateway.submit_order(&order, 100.0).unwrap();
+        assert!(gateway.get_fills().is_empty());
+        assert_eq!(gateway.resting_orders().len(), 1);
+    }
+
+    #[test]
+    fn test_resting_order_fills_after_queue_ahead_trades() {
+        let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        gateway.set_fee_model(Some(FeeModel::new(-1.0, 5.0)));
+        gateway.update_orderbook(create_test_orderbook());
+
+        // 100 lots already rest at the best bid
+        gateway.submit_order(&buy_limit(10.0, 99.0), 100.0).unwrap();
+        assert_eq!(gateway.resting_orders().len(), 1);
+        assert_eq!(gateway.resting_orders()[0].queue_ahead, 100.0);
+
+        // The first print only works through the queue
+        gateway.on_trade("BTCUSDT", 99.0, 60.0);
+        assert!(gateway.get_fills().is_empty());
+        assert_eq!(gateway.resting_orders()[0].queue_ahead, 40.0);
+
+        // The second clears the queue and 4 lots of the order
+        gateway.on_trade("BTCUSDT", 99.0, 44.0);
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert!((fills[0].quantity - 4.0).abs() < 1e-9);
+        assert_eq!(fills[0].price, 99.0);
+        assert!((fills[0].commission - (-99.0 * 4.0 / 10_000.0)).abs() < 1e-9);
+        assert!((gateway.resting_orders()[0].remaining - 6.0).abs() < 1e-9);
+
+        // Prints at other symbols or worse prices don't touch it
+        gateway.on_trade("ETHUSDT", 99.0, 50.0);
+        gateway.on_trade("BTCUSDT", 100.0, 50.0);
+        assert!(gateway.get_fills().is_empty());
+
+        gateway.on_trade("BTCUSDT", 99.0, 6.0);
+        assert!(gateway.resting_orders().is_empty());
+        assert!((gateway.query_position("BTCUSDT").unwrap().quantity - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_same_price_orders_share_print_behind_queue() {
+        let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        gateway.update_orderbook(create_test_orderbook());
+
+        // Both orders rest behind the 100 lots at the best bid
+        let first = gateway.submit_order(&buy_limit(10.0, 99.0), 100.0).unwrap();
+        let second = gateway.submit_order(&buy_limit(10.0, 99.0), 100.0).unwrap();
+        assert_eq!(gateway.resting_orders()[1].queue_ahead, 100.0);
+
+        // 115 lots clear the queue, fill the first order and 5 of the second
+        gateway.on_trade("BTCUSDT", 99.0, 115.0);
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].order_id, first);
+        assert!((fills[0].quantity - 10.0).abs() < 1e-9);
+        assert_eq!(fills[1].order_id, second);
+        assert!((fills[1].quantity - 5.0).abs() < 1e-9);
+        assert!((gateway.query_position("BTCUSDT").unwrap().quantity - 15.0).abs() < 1e-9);
+
+        assert_eq!(gateway.resting_orders().len(), 1);
+        assert_eq!(gateway.resting_orders()[0].queue_ahead, 0.0);
+        assert!((gateway.resting_orders()[0].remaining - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trade_through_fills_resting_order() {
+        let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        gateway.update_orderbook(create_test_orderbook());
+
+        gateway.submit_order(&buy_limit(10.0, 98.0), 100.0).unwrap();
+        assert_eq!(gateway.resting_orders()[0].queue_ahead, 200.0);
+
+        gateway.on_trade("BTCUSDT", 97.5, 1.0);
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert!((fills[0].quantity - 10.0).abs() < 1e-9);
+        assert_eq!(fills[0].price, 98.0);
+    }
+
+    #[test]
+    fn test_limit_order_at_new_level_and_cancel() {
+        let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        gateway.update_orderbook(create_test_orderbook());
+
+        // Improving the bid starts a new level with nothing ahead
+        let first = gateway.submit_order(&buy_limit(5.0, 99.5), 100.0).unwrap();
+        let second = gateway.submit_order(&buy_limit(5.0, 99.5), 100.0).unwrap();
+        assert_eq!(gateway.resting_orders()[0].queue_ahead, 0.0);
+
+        // The earlier order takes the print first
+        gateway.on_trade("BTCUSDT", 99.5, 7.0);
+        let fills = gateway.get_fills();
+        assert_eq!(fills.len(), 2);
+        assert_eq!((fills[0].order_id, fills[0].quantity), (first, 5.0));
+        assert!((fills[1].quantity - 2.0).abs() < 1e-9);
+
+        assert!(gateway.cancel_order(second).is_ok());
+        assert!(gateway.resting_orders().is_empty());
+        assert!(matches!(gateway.cancel_order(second), Err(GatewayError::OrderNotFound(_))));
+
+        // A marketable limit still crosses the book immediately
+        gateway.submit_order(&buy_limit(5.0, 101.0), 100.0).unwrap();
+        assert_eq!(gateway.get_fills().len(), 1);
+        assert!(gateway.resting_orders().is_empty());
+    }
+
     #[test]
     fn test_submit_order_updates_position() {
         let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
@@ -587,6 +1745,29 @@ mod tests {
         assert_eq!(gateway.fill_ratio(), 0.0);
     }
 
+    #[test]
+    fn test_fifo_and_lifo_realize_different_pnl() {
+        let realized = |method: CostBasisMethod| {
+            let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::new(0.0, 0.0, 0.0), 0.0);
+            gateway.set_cost_basis_method(method);
+            gateway.set_fill_ratio(1.0);
+
+            for (direction, price) in [(DIRECTION_BUY, 100.0), (DIRECTION_BUY, 110.0), (DIRECTION_SELL, 120.0)] {
+                let level = [OrderBookLevel::new(price, 1_000.0, 1)];
+                gateway.update_orderbook(OrderBookSnapshot::with_levels(&level, &level, price, 0));
+                let mut order = OrderRequest::with_symbol("BTCUSDT");
+                order.quantity = 10.0;
+                order.direction = direction;
+                gateway.submit_order(&order, price).unwrap();
+            }
+            gateway.query_position("BTCUSDT").unwrap().realized_pnl
+        };
+
+        assert!((realized(CostBasisMethod::Average) - 150.0).abs() < 1e-9);
+        assert!((realized(CostBasisMethod::Fifo) - 200.0).abs() < 1e-9);
+        assert!((realized(CostBasisMethod::Lifo) - 100.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_account_status() {
         let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
@@ -603,4 +1784,102 @@ mod tests {
         assert!(status.balance < 100_000.0); // Spent money
         assert_eq!(status.position_count, 1);
     }
+
+    #[test]
+    fn test_ffi_update_gateway_orderbook() {
+        let mut ffi_snapshot = FfiOrderBookSnapshot::default();
+        ffi_snapshot.bids[0] = OrderBookLevel::new(99.5, 10.0, 2);
+        ffi_snapshot.bids[1] = OrderBookLevel::new(99.0, 20.0, 3);
+        ffi_snapshot.asks[0] = OrderBookLevel::new(100.5, 15.0, 1);
+        ffi_snapshot.bid_count = 2;
+        ffi_snapshot.ask_count = 1;
+        ffi_snapshot.last_price = 100.0;
+        ffi_snapshot.timestamp = 42;
+
+        unsafe {
+            let gateway = create_l1_gateway(100_000.0, 0.0001);
+            assert!(!gateway.is_null());
+
+            let code = update_gateway_orderbook(gateway, &ffi_snapshot);
+            assert_eq!(code, crate::ffi::ERR_SUCCESS);
+
+            let book = (*gateway).orderbook();
+            assert_eq!(book.bid_count, 2);
+            assert_eq!(book.ask_count, 1);
+            assert_eq!(book.bids[..2], ffi_snapshot.bids[..2]);
+            assert_eq!(book.asks[0], ffi_snapshot.asks[0]);
+            assert_eq!(book.last_price, 100.0);
+            assert_eq!(book.timestamp, 42);
+            assert_eq!(book.best_bid(), Some(99.5));
+            assert_eq!(book.best_ask(), Some(100.5));
+
+            free_l1_gateway(gateway);
+        }
+    }
+
+    #[test]
+    fn test_ffi_update_gateway_orderbook_rejects_bad_counts() {
+        unsafe {
+            let gateway = create_l1_gateway(100_000.0, 0.0001);
+            let good = create_test_orderbook();
+            assert_eq!(update_gateway_orderbook(gateway, &good), crate::ffi::ERR_SUCCESS);
+
+            let mut too_many = good;
+            too_many.bid_count = MAX_LEVELS as i32 + 1;
+            assert_eq!(update_gateway_orderbook(gateway, &too_many), crate::ffi::ERR_INVALID_PARAM);
+
+            let mut negative = good;
+            negative.ask_count = -1;
+            assert_eq!(update_gateway_orderbook(gateway, &negative), crate::ffi::ERR_INVALID_PARAM);
+
+            // Rejected snapshots leave the previous book in place
+            assert_eq!((*gateway).orderbook().bid_count, 3);
+
+            assert_eq!(
+                update_gateway_orderbook(std::ptr::null_mut(), &good),
+                crate::ffi::ERR_NULL_POINTER
+            );
+            assert_eq!(
+                update_gateway_orderbook(gateway, std::ptr::null()),
+                crate::ffi::ERR_NULL_POINTER
+            );
+
+            free_l1_gateway(gateway);
+        }
+    }
+
+    #[test]
+    fn test_query_position_ffi() {
+        use crate::ffi::{ERR_INVALID_PARAM, ERR_POSITION_NOT_FOUND, ERR_SUCCESS};
+
+        let mut gateway = L1SimulatedGateway::new(100_000.0, SlippageModel::default(), 0.0);
+        gateway.restore_position("BTCUSDT", 2.0, 100.0).unwrap();
+        gateway.update_price("BTCUSDT", 110.0);
+
+        let mut position = Position::default();
+        unsafe {
+            let code = query_position_ffi(&gateway, c"BTCUSDT".as_ptr(), &mut position);
+            assert_eq!(code, ERR_SUCCESS);
+        }
+        assert_eq!(position.symbol_str(), "BTCUSDT");
+        assert_eq!(position.quantity, 2.0);
+        assert_eq!(position.average_price, 100.0);
+        assert_eq!(position.unrealized_pnl, 20.0);
+
+        // A missing symbol leaves the previous contents in place
+        unsafe {
+            let code = query_position_ffi(&gateway, c"ETHUSDT".as_ptr(), &mut position);
+            assert_eq!(code, ERR_POSITION_NOT_FOUND);
+            assert_eq!(
+                query_position_ffi(&gateway, [0xffu8, 0].as_ptr() as *const c_char, &mut position),
+                ERR_INVALID_PARAM
+            );
+            assert_eq!(
+                query_position_ffi(std::ptr::null(), c"BTCUSDT".as_ptr(), &mut position),
+                crate::ffi::ERR_NULL_POINTER
+            );
+        }
+        assert_eq!(position.symbol_str(), "BTCUSDT");
+        assert_eq!(position.quantity, 2.0);
+    }
 }
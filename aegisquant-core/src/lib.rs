@@ -24,6 +24,12 @@ pub mod indicators;
 pub mod persistence;
 pub mod emergency;
 pub mod latency;
+pub mod pairs;
+pub mod portfolio;
+pub mod compare;
+pub mod validation;
+pub mod lookahead;
+pub mod metrics;
 
 pub use types::*;
 pub use ffi::*;
@@ -36,6 +42,8 @@ pub use error::{EngineError, EngineResult, set_last_error};
 pub use precision::{
     PRICE_EPSILON, QUANTITY_EPSILON, Price, Quantity,
     approx_eq, price_eq, quantity_eq, spread_bps, AccountBalance,
+    round_to_tick, round_to_lot, round_to_decimals,
+    round_price_ffi, round_quantity_ffi, price_eq_ffi, quantity_eq_ffi,
 };
 pub use risk::*;
 pub use gateway::*;
@@ -51,22 +59,25 @@ pub use logger::{
 };
 pub use optimizer::*;
 pub use orderbook::{
-    OrderBookLevel, OrderBookSnapshot, OrderBookStats, MAX_LEVELS,
+    OrderBookLevel, OrderBookSnapshot, OrderBookStats, FfiOrderBookSnapshot, MAX_LEVELS,
+    BookSide, OrderBookDelta,
     get_orderbook, get_orderbook_stats,
 };
 pub use l1_gateway::{
-    GatewayMode, SlippageModel, FillResult, LevelFill, L1SimulatedGateway,
+    GatewayMode, SlippageModel, VolatilityScaledSlippage, FillResult, LevelFill, L1SimulatedGateway,
+    FeeModel, Liquidity, ExecutionStyle, RestingOrder,
     set_gateway_mode_internal, get_gateway_mode, set_gateway_mode, get_gateway_mode_ffi,
+    create_l1_gateway, free_l1_gateway, update_gateway_orderbook, query_position_ffi,
 };
 pub use event_bus::{
-    Event, EventBus, EventFilter, EventBusStats, EventDrivenStrategy,
-    OrderStatus, Subscription, SubscriptionId, SharedEventBus,
+    Event, EventBus, EventFilter, EventBusStats, EventTypeStats, EventCapacities, EventDrivenStrategy,
+    OrderStatus, Subscription, SubscriptionId, SharedEventBus, EventHandler, SequencedEvent,
     create_shared_event_bus, set_event_callback, clear_event_callback,
-    subscribe_event, unsubscribe_event, get_event_bus_stats,
+    subscribe_event, unsubscribe_event, get_event_bus_stats, get_event_bus_stats_by_type,
     EVENT_TYPE_TICK, EVENT_TYPE_TIMER, EVENT_TYPE_ORDER_UPDATE,
-    EVENT_TYPE_ACCOUNT_UPDATE, EVENT_TYPE_SIGNAL, EVENT_TYPE_CUSTOM,
+    EVENT_TYPE_ACCOUNT_UPDATE, EVENT_TYPE_SIGNAL, EVENT_TYPE_CUSTOM, EVENT_TYPE_COUNT,
     // Timer management
-    TimerId, TimerEntry, TimerManager, OrderUpdateSender,
+    TimerId, TimerEntry, TimerManager, CronSchedule, OrderUpdateSender,
     create_timer_manager, destroy_timer_manager,
     schedule_timer_once, schedule_timer_repeating, cancel_timer, process_timers,
 };
@@ -75,21 +86,32 @@ pub use warmup::{
     is_warmup_complete, get_warmup_current_bar, get_warmup_remaining_bars,
 };
 pub use indicators::{
-    IndicatorResult, IndicatorCalculator,
-    create_indicator_calculator, free_indicator_calculator,
-    calculate_indicators, calculate_indicators_batch, reset_indicator_calculator,
-    calculate_sma, calculate_ema, calculate_bollinger_bands, calculate_macd,
+    IndicatorResult, IndicatorCalculator, NonFinitePolicy,
+    create_indicator_calculator, free_indicator_calculator, set_indicator_non_finite_policy,
+    calculate_indicators, calculate_indicators_ohlc, calculate_indicators_batch,
+    calculate_indicators_with_volume, reset_indicator_calculator, reset_indicator_session,
+    serialize_indicator_state, restore_indicator_state, IndicatorStateCallback,
+    calculate_sma, calculate_ema, calculate_sma_fast, calculate_ema_fast,
+    calculate_bollinger_bands, calculate_macd,
+    calculate_keltner_channels, KeltnerBasis, detect_squeeze, SqueezeDetector, AtrIndicator,
+    StochasticOscillator, calculate_stochastic, DonchianChannel, calculate_donchian,
 };
 pub use persistence::{
-    PersistenceManager, TradeRecord, AccountSnapshot, PositionRecord, RecoveredState,
+    PersistenceManager, TradeRecord, AccountSnapshot, PositionRecord, RecoveredState, RetryPolicy,
+    PersistenceConfig, Discrepancy, reconcile, reconcile_and_restore, EntryReason, ExitReason, EXIT_REASON_COUNT,
     FfiTradeRecord, FfiAccountSnapshot, ERR_DB_ERROR,
     create_persistence_manager, free_persistence_manager,
     save_trade_ffi, save_account_snapshot_ffi, save_position_ffi, load_state_ffi,
+    get_exit_reason_counts_ffi,
 };
 pub use emergency::{
-    is_halted, activate_emergency_stop, reset_emergency_stop,
+    HaltReason, HaltCallback,
+    is_halted, activate_emergency_stop, reset_emergency_stop, get_halt_reason,
+    halt_symbol, reset_symbol, is_symbol_halted, get_symbol_halt_reason,
     generate_close_all_orders, check_halt,
     emergency_stop, reset_emergency_stop_ffi, is_emergency_halted, close_all_positions,
+    emergency_stop_with_reason, get_halt_reason_ffi, set_halt_callback, clear_halt_callback,
+    halt_symbol_ffi, reset_symbol_ffi, is_symbol_halted_ffi,
 };
 pub use latency::{
     LatencyStats, LatencyTracker, LatencyGuard,
@@ -99,6 +121,15 @@ pub use latency::{
     set_latency_sample_rate_ffi, set_latency_enabled_ffi,
 };
 pub use data_pipeline::{
-    DataPipeline, PipelineConfig, MarketDataStore,
+    DataPipeline, PipelineConfig, OutlierMethod, OutlierAction, OutlierAudit, MarketDataStore,
     TimescaleDbStore, CsvFileStore, ParquetFileStore,
 };
+pub use pairs::{rolling_hedge_ratio, spread_zscore};
+pub use portfolio::MultiSymbolEngine;
+pub use compare::{ResultDiff, CurveDiff, compare_results, compare_runs, compare_equity_curves};
+pub use validation::{
+    ValidationSeverity, ValidationWarning, validate_config, warnings_to_json,
+    validate_engine_config_ffi,
+};
+pub use lookahead::{LookaheadViolation, check_lookahead, assert_no_lookahead};
+pub use metrics::{render_prometheus, export_prometheus, export_metrics_prometheus};
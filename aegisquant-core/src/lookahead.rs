@@ -0,0 +1,183 @@
+//! Look-ahead bias detection for strategies.
+//!
+//! A testing utility, not used on the backtest hot path. A strategy is built
+//! from the dataset it will trade, run once over the full data, and then
+//! rebuilt for every bar with all later ticks replaced by garbage (NaN
+//! prices and volumes). A strategy that only uses past data produces the
+//! same signal at each bar either way; the first bar where the signals
+//! differ is reported.
+//!
+//! Checking `n` ticks replays the strategy `n` times, so keep datasets small.
+
+use thiserror::Error;
+
+use crate::strategy::{Signal, Strategy};
+use crate::types::Tick;
+
+/// First bar at which a strategy's signal depended on future data.
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+#[error("look-ahead detected at bar {bar}: {expected:?} with full data, {actual:?} with future data hidden")]
+pub struct LookaheadViolation {
+    /// Bar index of the first divergent signal
+    pub bar: usize,
+    /// Signal produced with the full dataset available
+    pub expected: Signal,
+    /// Signal produced with every later tick replaced by garbage
+    pub actual: Signal,
+}
+
+/// Check a strategy for look-ahead bias.
+///
+/// `make` builds a fresh strategy from the dataset it is given. Strategies
+/// that capture the dataset (e.g. to index into it) must take it from this
+/// argument so the harness can hide the future from them.
+pub fn check_lookahead<S, F>(make: F, ticks: &[Tick]) -> Result<(), LookaheadViolation>
+where
+    S: Strategy,
+    F: Fn(&[Tick]) -> S,
+{
+    let mut reference = make(ticks);
+    let expected: Vec<Signal> = ticks.iter().map(|tick| reference.on_tick(tick)).collect();
+
+    for bar in 0..ticks.len() {
+        let visible = hide_future(ticks, bar);
+        let mut strategy = make(&visible);
+        let mut actual = Signal::None;
+        for tick in &visible[..=bar] {
+            actual = strategy.on_tick(tick);
+        }
+
+        if actual != expected[bar] {
+            return Err(LookaheadViolation {
+                bar,
+                expected: expected[bar],
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Panic if `check_lookahead` finds a divergent bar.
+///
+/// The panic message names the first divergent bar and both signals.
+pub fn assert_no_lookahead<S, F>(make: F, ticks: &[Tick])
+where
+    S: Strategy,
+    F: Fn(&[Tick]) -> S,
+{
+    if let Err(violation) = check_lookahead(make, ticks) {
+        panic!("{}", violation);
+    }
+}
+
+/// Copy `ticks`, replacing every tick after `bar` with garbage.
+fn hide_future(ticks: &[Tick], bar: usize) -> Vec<Tick> {
+    ticks
+        .iter()
+        .enumerate()
+        .map(|(i, tick)| {
+            if i <= bar {
+                *tick
+            } else {
+                Tick {
+                    timestamp: tick.timestamp,
+                    price: f64::NAN,
+                    volume: f64::NAN,
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::DualMAStrategy;
+    use crate::types::{OrderRequest, StrategyParams};
+
+    /// Trades on the next tick's price, which it should never see.
+    struct PeekingStrategy {
+        data: Vec<Tick>,
+        index: usize,
+    }
+
+    impl Strategy for PeekingStrategy {
+        fn on_tick(&mut self, tick: &Tick) -> Signal {
+            let next = self.data.get(self.index + 1).map(|t| t.price);
+            self.index += 1;
+            match next {
+                Some(next) if next > tick.price => Signal::Buy,
+                Some(next) if next < tick.price => Signal::Sell,
+                _ => Signal::None,
+            }
+        }
+
+        fn generate_order(&self, _signal: Signal, _symbol: &str, _price: f64) -> Option<OrderRequest> {
+            None
+        }
+
+        fn reset(&mut self) {
+            self.index = 0;
+        }
+
+        fn name(&self) -> &str {
+            "Peeking"
+        }
+    }
+
+    fn ticks() -> Vec<Tick> {
+        [100.0, 100.0, 100.0, 101.0, 99.0, 102.0, 104.0, 103.0, 100.0, 98.0, 99.0, 103.0, 106.0, 105.0, 101.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &price)| Tick {
+                timestamp: i as i64,
+                price,
+                volume: 1000.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cheating_strategy_is_caught() {
+        let make = |data: &[Tick]| PeekingStrategy {
+            data: data.to_vec(),
+            index: 0,
+        };
+
+        // Bars 0 and 1 are flat next-tick moves; bar 2 peeks at the rise to 101
+        let violation = check_lookahead(make, &ticks()).unwrap_err();
+        assert_eq!(
+            violation,
+            LookaheadViolation {
+                bar: 2,
+                expected: Signal::Buy,
+                actual: Signal::None,
+            }
+        );
+        assert!(violation.to_string().contains("bar 2"));
+    }
+
+    #[test]
+    #[should_panic(expected = "look-ahead detected at bar 2")]
+    fn test_assert_no_lookahead_panics() {
+        assert_no_lookahead(
+            |data: &[Tick]| PeekingStrategy {
+                data: data.to_vec(),
+                index: 0,
+            },
+            &ticks(),
+        );
+    }
+
+    #[test]
+    fn test_dual_ma_has_no_lookahead() {
+        let params = StrategyParams {
+            short_ma_period: 2,
+            long_ma_period: 4,
+            ..Default::default()
+        };
+        assert_no_lookahead(|_: &[Tick]| DualMAStrategy::new(params), &ticks());
+    }
+}
@@ -0,0 +1,276 @@
+//! Prometheus text-format export of engine metrics.
+//!
+//! Renders the global `LatencyStats` and, when a bus is given, its
+//! `EventBusStats` in the Prometheus exposition format so operators can
+//! scrape the engine. `export_metrics_prometheus` hands the text to C#,
+//! which serves it from its own HTTP endpoint.
+
+use std::fmt::Write;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::event_bus::{EventBus, EventBusStats};
+use crate::ffi::{ERR_INTERNAL_PANIC, ERR_SUCCESS};
+use crate::ffi_string::{with_string_callback, StringCallback};
+use crate::latency::{get_latency_stats, LatencyStats};
+
+/// Prefix shared by every exported metric name.
+const METRIC_PREFIX: &str = "aegisquant";
+
+/// Append one metric family: its `# HELP` and `# TYPE` lines followed by a
+/// sample per `(labels, value)` pair. `labels` is empty or `{k="v",...}`.
+fn write_family(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(&str, u64)]) {
+    let _ = writeln!(out, "# HELP {}_{} {}", METRIC_PREFIX, name, help);
+    let _ = writeln!(out, "# TYPE {}_{} {}", METRIC_PREFIX, name, kind);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}_{}{} {}", METRIC_PREFIX, name, labels, value);
+    }
+}
+
+/// Render latency and event bus statistics as Prometheus exposition text.
+///
+/// Event bus metrics are omitted when `event_bus` is `None`.
+pub fn render_prometheus(latency: &LatencyStats, event_bus: Option<&EventBusStats>) -> String {
+    let mut out = String::new();
+
+    write_family(
+        &mut out,
+        "latency_nanoseconds",
+        "gauge",
+        "Tick processing latency percentiles in nanoseconds.",
+        &[
+            ("{quantile=\"0.5\"}", latency.p50_ns),
+            ("{quantile=\"0.95\"}", latency.p95_ns),
+            ("{quantile=\"0.99\"}", latency.p99_ns),
+        ],
+    );
+    write_family(
+        &mut out,
+        "latency_min_nanoseconds",
+        "gauge",
+        "Minimum tick processing latency in nanoseconds.",
+        &[("", latency.min_ns)],
+    );
+    write_family(
+        &mut out,
+        "latency_max_nanoseconds",
+        "gauge",
+        "Maximum tick processing latency in nanoseconds.",
+        &[("", latency.max_ns)],
+    );
+    write_family(
+        &mut out,
+        "latency_avg_nanoseconds",
+        "gauge",
+        "Average tick processing latency in nanoseconds.",
+        &[("", latency.avg_ns)],
+    );
+    write_family(
+        &mut out,
+        "latency_last_nanoseconds",
+        "gauge",
+        "Most recent tick processing latency in nanoseconds.",
+        &[("", latency.last_ns)],
+    );
+    write_family(
+        &mut out,
+        "latency_samples_total",
+        "counter",
+        "Number of latency samples recorded.",
+        &[("", latency.sample_count)],
+    );
+
+    if let Some(bus) = event_bus {
+        write_family(
+            &mut out,
+            "event_bus_subscribers",
+            "gauge",
+            "Active event bus subscribers.",
+            &[("", bus.subscriber_count as u64)],
+        );
+        write_family(
+            &mut out,
+            "event_bus_events_published_total",
+            "counter",
+            "Events published to the event bus.",
+            &[("", bus.events_published)],
+        );
+        write_family(
+            &mut out,
+            "event_bus_events_delivered_total",
+            "counter",
+            "Events delivered to subscribers.",
+            &[("", bus.events_delivered)],
+        );
+        write_family(
+            &mut out,
+            "event_bus_events_dropped_total",
+            "counter",
+            "Events dropped because a subscriber channel was full.",
+            &[("", bus.events_dropped)],
+        );
+        write_family(
+            &mut out,
+            "event_bus_last_sequence",
+            "gauge",
+            "Sequence number of the last published event.",
+            &[("", bus.last_seq)],
+        );
+    }
+
+    out
+}
+
+/// Export the global latency statistics and, optionally, an event bus's
+/// statistics in Prometheus exposition format.
+pub fn export_prometheus(event_bus: Option<&EventBus>) -> String {
+    let bus_stats = event_bus.map(EventBus::stats);
+    render_prometheus(&get_latency_stats(), bus_stats.as_ref())
+}
+
+// ============================================================================
+// FFI Functions
+// ============================================================================
+
+/// Export engine metrics in Prometheus exposition format.
+///
+/// The text is passed to `callback`; copy it before returning.
+///
+/// # Safety
+/// - `event_bus` must be a valid pointer or null; when null only latency
+///   metrics are exported
+/// - `callback` must be a valid function pointer
+///
+/// # Returns
+/// - ERR_SUCCESS on success
+/// - ERR_INTERNAL_PANIC if rendering panicked
+#[no_mangle]
+pub unsafe extern "C" fn export_metrics_prometheus(
+    event_bus: *const EventBus,
+    callback: StringCallback,
+) -> i32 {
+    let outcome = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: Caller guarantees the pointer is valid or null
+        let bus = if event_bus.is_null() { None } else { Some(&*event_bus) };
+        with_string_callback(&export_prometheus(bus), callback);
+        ERR_SUCCESS
+    }));
+
+    outcome.unwrap_or(ERR_INTERNAL_PANIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::{Event, EventFilter};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::ffi::{c_char, CStr};
+
+    /// Parse exposition text into `name{labels}` -> value, checking that every
+    /// sample line is well formed and belongs to a family declared by `# TYPE`.
+    fn parse_exposition(text: &str) -> HashMap<String, f64> {
+        let mut declared = Vec::new();
+        let mut samples = HashMap::new();
+
+        for line in text.lines() {
+            if let Some(comment) = line.strip_prefix("# ") {
+                let mut parts = comment.splitn(3, ' ');
+                let keyword = parts.next().unwrap();
+                let name = parts.next().unwrap();
+                let rest = parts.next().unwrap();
+                assert!(keyword == "HELP" || keyword == "TYPE", "bad comment: {}", line);
+                if keyword == "TYPE" {
+                    assert!(["gauge", "counter"].contains(&rest), "bad type: {}", line);
+                    declared.push(name.to_string());
+                }
+                continue;
+            }
+
+            let (series, value) = line.rsplit_once(' ').expect("sample without value");
+            let name = series.split('{').next().unwrap();
+            assert!(
+                name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':'),
+                "bad metric name: {}",
+                line
+            );
+            if let Some(labels) = series.strip_prefix(name).filter(|l| !l.is_empty()) {
+                let inner = labels.strip_prefix('{').and_then(|l| l.strip_suffix('}'));
+                let inner = inner.unwrap_or_else(|| panic!("bad labels: {}", line));
+                for pair in inner.split(',') {
+                    let (key, quoted) = pair.split_once('=').expect("label without value");
+                    assert!(!key.is_empty() && quoted.starts_with('"') && quoted.ends_with('"'));
+                }
+            }
+            assert_eq!(declared.last().map(String::as_str), Some(name), "undeclared: {}", line);
+            let value: f64 = value.parse().unwrap_or_else(|_| panic!("bad value: {}", line));
+            samples.insert(series.to_string(), value);
+        }
+
+        samples
+    }
+
+    fn sample_latency() -> LatencyStats {
+        LatencyStats {
+            min_ns: 100,
+            max_ns: 9000,
+            avg_ns: 450,
+            p50_ns: 400,
+            p95_ns: 1200,
+            p99_ns: 3000,
+            sample_count: 250,
+            last_ns: 380,
+        }
+    }
+
+    #[test]
+    fn test_render_parses_as_metric_lines() {
+        let bus = EventBusStats {
+            subscriber_count: 2,
+            events_published: 10,
+            events_delivered: 18,
+            events_dropped: 2,
+            last_seq: 10,
+        };
+        let samples = parse_exposition(&render_prometheus(&sample_latency(), Some(&bus)));
+
+        assert_eq!(samples["aegisquant_latency_nanoseconds{quantile=\"0.99\"}"], 3000.0);
+        assert_eq!(samples["aegisquant_latency_samples_total"], 250.0);
+        assert_eq!(samples["aegisquant_event_bus_subscribers"], 2.0);
+        assert_eq!(samples["aegisquant_event_bus_events_dropped_total"], 2.0);
+        assert_eq!(samples["aegisquant_event_bus_last_sequence"], 10.0);
+    }
+
+    #[test]
+    fn test_render_without_event_bus() {
+        let text = render_prometheus(&sample_latency(), None);
+
+        let samples = parse_exposition(&text);
+        assert_eq!(samples.len(), 8);
+        assert!(!text.contains("event_bus"));
+    }
+
+    thread_local! {
+        static RECEIVED: RefCell<Option<String>> = const { RefCell::new(None) };
+    }
+
+    extern "C" fn capture(s: *const c_char) {
+        let text = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        RECEIVED.with(|cell| *cell.borrow_mut() = Some(text));
+    }
+
+    #[test]
+    fn test_export_metrics_prometheus_ffi() {
+        let mut bus = EventBus::new(4);
+        let _subscription = bus.subscribe(EventFilter::all());
+        bus.publish(Event::Custom { event_type: "ping".to_string(), payload: String::new() });
+
+        let code = unsafe { export_metrics_prometheus(&bus, capture) };
+        assert_eq!(code, ERR_SUCCESS);
+
+        let text = RECEIVED.with(|cell| cell.borrow_mut().take()).unwrap();
+        let samples = parse_exposition(&text);
+        assert_eq!(samples["aegisquant_event_bus_events_published_total"], 1.0);
+        assert_eq!(samples["aegisquant_event_bus_subscribers"], 1.0);
+    }
+}
@@ -1,13 +1,85 @@
 //! Parameter optimization module using Rayon for parallel execution.
 //!
 //! Provides parameter sweep functionality to find optimal strategy parameters
-//! by running multiple backtests in parallel.
+//! by running multiple backtests in parallel. Without the `parallel` feature
+//! (on by default) sweeps run sequentially on the calling thread.
 
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
 use crate::engine::BacktestEngine;
-use crate::types::{BacktestResult, RiskConfig, StrategyParams, Tick};
+use crate::ffi::{ERR_INVALID_PARAM, ERR_NULL_POINTER, ERR_SUCCESS};
+use crate::types::{BacktestResult, RiskConfig, StrategyParams, Tick, MA_TYPE_SMA};
+
+/// Worker threads for parameter sweeps (0 = all cores).
+static OPTIMIZER_THREADS: AtomicUsize = AtomicUsize::new(0);
+/// Combinations completed in the current sweep.
+static OPTIMIZER_COMPLETED: AtomicU64 = AtomicU64::new(0);
+/// Combinations in the current sweep.
+static OPTIMIZER_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Set the number of worker threads used by parameter sweeps (0 = all cores).
+///
+/// Has no effect without the `parallel` feature.
+pub fn set_optimizer_threads_internal(threads: usize) {
+    OPTIMIZER_THREADS.store(threads, Ordering::SeqCst);
+}
+
+/// Get the configured number of optimizer worker threads (0 = all cores).
+pub fn get_optimizer_threads() -> usize {
+    OPTIMIZER_THREADS.load(Ordering::SeqCst)
+}
+
+/// Get progress of the most recent sweep as (completed, total).
+///
+/// Progress is shared by all sweeps in the process; when sweeps overlap,
+/// the last one started owns the counters.
+pub fn get_optimizer_progress() -> (u64, u64) {
+    (
+        OPTIMIZER_COMPLETED.load(Ordering::SeqCst),
+        OPTIMIZER_TOTAL.load(Ordering::SeqCst),
+    )
+}
+
+/// Build a dedicated pool sized by `set_optimizer_threads`.
+///
+/// Sweeps run inside this pool rather than the global Rayon pool, so the
+/// thread count is enforced even if the global pool was already built.
+#[cfg(feature = "parallel")]
+fn build_optimizer_pool() -> Option<rayon::ThreadPool> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(get_optimizer_threads())
+        .build()
+        .ok()
+}
+
+/// Map `items` through `f` in the optimizer pool, keeping input order.
+///
+/// Returns None if the pool cannot be built.
+#[cfg(feature = "parallel")]
+fn map_in_pool<T: Sync, R: Send>(
+    items: &[T],
+    f: impl Fn(&T) -> Option<R> + Sync + Send,
+) -> Option<Vec<R>> {
+    let pool = build_optimizer_pool()?;
+    Some(pool.install(|| items.par_iter().filter_map(f).collect()))
+}
+
+/// Map `items` through `f` sequentially on the calling thread.
+#[cfg(not(feature = "parallel"))]
+fn map_in_pool<T: Sync, R: Send>(
+    items: &[T],
+    f: impl Fn(&T) -> Option<R> + Sync + Send,
+) -> Option<Vec<R>> {
+    Some(items.iter().filter_map(f).collect())
+}
+
+/// Reset the shared progress counters for a sweep of `total` combinations.
+fn start_progress(total: usize) {
+    OPTIMIZER_COMPLETED.store(0, Ordering::SeqCst);
+    OPTIMIZER_TOTAL.store(total as u64, Ordering::SeqCst);
+}
 
 /// Result of a single parameter combination test.
 #[derive(Debug, Clone)]
@@ -111,6 +183,8 @@ impl Optimizer {
                             stop_loss_pct: 0.02,
                             take_profit_pct: 0.05,
                             warmup_bars: 0,
+                            short_ma_type: MA_TYPE_SMA,
+                            long_ma_type: MA_TYPE_SMA,
                         });
                     }
                 }
@@ -122,9 +196,28 @@ impl Optimizer {
         combinations
     }
 
+    /// Generate combinations that take MA periods (and position size, if
+    /// ranged) from `range` and every other field from `base`.
+    fn combinations_from_base(&self, range: &ParameterRange, base: &StrategyParams) -> Vec<StrategyParams> {
+        self.generate_combinations(range)
+            .into_iter()
+            .map(|combination| StrategyParams {
+                short_ma_period: combination.short_ma_period,
+                long_ma_period: combination.long_ma_period,
+                position_size: if range.position_size_range.is_some() {
+                    combination.position_size
+                } else {
+                    base.position_size
+                },
+                ..*base
+            })
+            .collect()
+    }
+
     /// Run parameter sweep with the given tick data.
     ///
-    /// Uses Rayon to parallelize across CPU cores.
+    /// Uses a Rayon pool sized by `set_optimizer_threads`. Returns no results
+    /// if the pool cannot be built.
     pub fn run_parameter_sweep(
         &mut self,
         ticks: &[Tick],
@@ -133,6 +226,7 @@ impl Optimizer {
         let combinations = self.generate_combinations(range);
         self.total_combinations = combinations.len();
         self.progress.store(0, Ordering::SeqCst);
+        start_progress(combinations.len());
 
         // Convert ticks to vectors for engine loading
         let timestamps: Vec<i64> = ticks.iter().map(|t| t.timestamp).collect();
@@ -140,27 +234,25 @@ impl Optimizer {
         let volumes: Vec<f64> = ticks.iter().map(|t| t.volume).collect();
 
         // Run backtests in parallel
-        let results: Vec<OptimizationResult> = combinations
-            .par_iter()
-            .filter_map(|params| {
-                let result = self.run_single_backtest(
-                    params,
-                    &timestamps,
-                    &prices,
-                    &volumes,
-                );
-                
-                // Update progress
-                self.progress.fetch_add(1, Ordering::SeqCst);
-                
-                result.map(|r| OptimizationResult {
-                    params: *params,
-                    result: r,
-                })
+        let this = &*self;
+        map_in_pool(&combinations, |params| {
+            let result = this.run_single_backtest(
+                params,
+                &timestamps,
+                &prices,
+                &volumes,
+            );
+
+            // Update progress
+            this.progress.fetch_add(1, Ordering::SeqCst);
+            OPTIMIZER_COMPLETED.fetch_add(1, Ordering::SeqCst);
+
+            result.map(|r| OptimizationResult {
+                params: *params,
+                result: r,
             })
-            .collect();
-
-        results
+        })
+        .unwrap_or_default()
     }
 
     /// Run a single backtest with the given parameters.
@@ -191,6 +283,71 @@ impl Optimizer {
         engine.run().ok()
     }
 
+    /// Find the best parameters by Sharpe ratio on a window of ticks.
+    ///
+    /// Runs sequentially on the calling thread and leaves the global sweep
+    /// progress untouched, so it can be called from inside a running engine.
+    /// MA periods (and position size, if ranged) come from `range`; every
+    /// other field is taken from `base`. Ties keep the earliest combination.
+    pub fn optimize_window(
+        &self,
+        ticks: &[Tick],
+        range: &ParameterRange,
+        base: &StrategyParams,
+    ) -> Option<OptimizationResult> {
+        let timestamps: Vec<i64> = ticks.iter().map(|t| t.timestamp).collect();
+        let prices: Vec<f64> = ticks.iter().map(|t| t.price).collect();
+        let volumes: Vec<f64> = ticks.iter().map(|t| t.volume).collect();
+
+        let mut best: Option<OptimizationResult> = None;
+        for params in self.combinations_from_base(range, base) {
+            let Some(result) = self.run_single_backtest(&params, &timestamps, &prices, &volumes) else {
+                continue;
+            };
+            if best.as_ref().is_none_or(|b| result.sharpe_ratio > b.result.sharpe_ratio) {
+                best = Some(OptimizationResult { params, result });
+            }
+        }
+        best
+    }
+
+    /// Re-run one parameter set across a grid of slippage and commission values.
+    ///
+    /// Returns `(slippage, commission_rate, result)` for every grid cell,
+    /// ordered by slippage and then commission. Cells whose backtest fails
+    /// are omitted.
+    pub fn cost_sensitivity(
+        &self,
+        params: &StrategyParams,
+        ticks: &[Tick],
+        slippage_grid: &[f64],
+        commission_grid: &[f64],
+    ) -> Vec<(f64, f64, BacktestResult)> {
+        let mut engine = BacktestEngine::new(*params, self.risk_config)
+            .with_initial_balance(self.initial_balance)
+            .with_symbol(&self.symbol);
+
+        let timestamps: Vec<i64> = ticks.iter().map(|t| t.timestamp).collect();
+        let prices: Vec<f64> = ticks.iter().map(|t| t.price).collect();
+        let volumes: Vec<f64> = ticks.iter().map(|t| t.volume).collect();
+        if engine.load_data_from_vectors(timestamps, prices, volumes).is_err() {
+            return Vec::new();
+        }
+
+        let mut grid = Vec::with_capacity(slippage_grid.len() * commission_grid.len());
+        for &slippage in slippage_grid {
+            for &commission in commission_grid {
+                engine.set_slippage(slippage);
+                engine.set_commission(commission);
+                if let Ok(result) = engine.run() {
+                    grid.push((slippage, commission, result));
+                }
+            }
+        }
+
+        grid
+    }
+
     /// Get current progress (completed / total).
     pub fn progress(&self) -> (usize, usize) {
         (self.progress.load(Ordering::SeqCst), self.total_combinations)
@@ -247,44 +404,252 @@ impl Default for Optimizer {
     }
 }
 
+/// Score a grid search maximizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Objective {
+    /// Total return percentage
+    #[default]
+    TotalReturn,
+    /// Sharpe ratio
+    Sharpe,
+    /// Total return divided by max drawdown
+    Calmar,
+}
+
+impl Objective {
+    /// Score a backtest result (higher is better).
+    ///
+    /// Calmar is infinite for a positive return with no drawdown and 0 for
+    /// a flat run.
+    pub fn score(&self, result: &BacktestResult) -> f64 {
+        match self {
+            Objective::TotalReturn => result.total_return_pct,
+            Objective::Sharpe => result.sharpe_ratio,
+            Objective::Calmar => {
+                if result.max_drawdown_pct > 0.0 {
+                    result.total_return_pct / result.max_drawdown_pct
+                } else if result.total_return_pct > 0.0 {
+                    f64::INFINITY
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Outcome of a grid search.
+///
+/// Named `GridSearchResult` rather than `OptimizationResult`, which is
+/// already the per-combination result above.
+#[derive(Debug, Clone)]
+pub struct GridSearchResult {
+    /// Parameters with the highest score
+    pub best_params: StrategyParams,
+    /// Score of `best_params`
+    pub best_score: f64,
+    /// Score of every combination that ran, in grid order
+    pub all_scores: Vec<(StrategyParams, f64)>,
+}
+
+/// Exhaustive search over short/long MA periods and position size.
+///
+/// Every combination runs a fresh `BacktestEngine` over the same ticks.
+/// Fields outside the grid come from the base parameters.
+#[derive(Debug)]
+pub struct GridSearchOptimizer {
+    optimizer: Optimizer,
+    range: ParameterRange,
+    objective: Objective,
+    base: StrategyParams,
+}
+
+impl GridSearchOptimizer {
+    /// Create a grid search over `range` maximizing `objective`.
+    pub fn new(risk_config: RiskConfig, range: ParameterRange, objective: Objective) -> Self {
+        Self {
+            optimizer: Optimizer::new(risk_config),
+            range,
+            objective,
+            base: StrategyParams::default(),
+        }
+    }
+
+    /// Set the initial balance for backtests.
+    pub fn with_initial_balance(mut self, balance: f64) -> Self {
+        self.optimizer = self.optimizer.with_initial_balance(balance);
+        self
+    }
+
+    /// Set the trading symbol.
+    pub fn with_symbol(mut self, symbol: &str) -> Self {
+        self.optimizer = self.optimizer.with_symbol(symbol);
+        self
+    }
+
+    /// Set the parameters used for fields outside the grid.
+    pub fn with_base_params(mut self, base: StrategyParams) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// The objective being maximized.
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    /// All parameter sets in the grid.
+    pub fn combinations(&self) -> Vec<StrategyParams> {
+        self.optimizer.combinations_from_base(&self.range, &self.base)
+    }
+
+    /// Run every combination sequentially on the calling thread.
+    ///
+    /// Returns None if no combination ran. Ties keep the earliest combination.
+    pub fn run(&self, ticks: &[Tick]) -> Option<GridSearchResult> {
+        let timestamps: Vec<i64> = ticks.iter().map(|t| t.timestamp).collect();
+        let prices: Vec<f64> = ticks.iter().map(|t| t.price).collect();
+        let volumes: Vec<f64> = ticks.iter().map(|t| t.volume).collect();
+
+        let all_scores: Vec<(StrategyParams, f64)> = self
+            .combinations()
+            .into_iter()
+            .filter_map(|params| {
+                self.optimizer
+                    .run_single_backtest(&params, &timestamps, &prices, &volumes)
+                    .map(|result| (params, self.objective.score(&result)))
+            })
+            .collect();
+
+        Self::select_best(all_scores)
+    }
+
+    /// Run every combination across the optimizer pool.
+    ///
+    /// Each worker gets a fresh `BacktestEngine` with its own copy of the
+    /// ticks; results are gathered in grid order, so the outcome matches
+    /// `run`. Uses the pool sized by `set_optimizer_threads` and reports
+    /// through `get_optimizer_progress`. Returns None if no combination ran
+    /// or the pool cannot be built.
+    ///
+    /// # Thread Safety
+    /// Engines share no state with each other, but process-wide settings are
+    /// seen by every worker: do not change the gateway mode, latency tracker
+    /// or emergency halt while a search runs, and any warmup-complete
+    /// callback must tolerate calls from worker threads.
+    #[cfg(feature = "parallel")]
+    pub fn par_run(&self, ticks: &[Tick]) -> Option<GridSearchResult> {
+        let combinations = self.combinations();
+        start_progress(combinations.len());
+
+        let timestamps: Vec<i64> = ticks.iter().map(|t| t.timestamp).collect();
+        let prices: Vec<f64> = ticks.iter().map(|t| t.price).collect();
+        let volumes: Vec<f64> = ticks.iter().map(|t| t.volume).collect();
+
+        let all_scores = map_in_pool(&combinations, |params| {
+            let result = self
+                .optimizer
+                .run_single_backtest(params, &timestamps, &prices, &volumes);
+            OPTIMIZER_COMPLETED.fetch_add(1, Ordering::SeqCst);
+            result.map(|result| (*params, self.objective.score(&result)))
+        })?;
+
+        Self::select_best(all_scores)
+    }
+
+    /// Pick the highest score; NaN scores never win.
+    fn select_best(all_scores: Vec<(StrategyParams, f64)>) -> Option<GridSearchResult> {
+        let mut best: Option<(StrategyParams, f64)> = None;
+        for &(params, score) in &all_scores {
+            if best.is_none_or(|(_, b)| score > b || b.is_nan()) {
+                best = Some((params, score));
+            }
+        }
+        best.map(|(best_params, best_score)| GridSearchResult {
+            best_params,
+            best_score,
+            all_scores,
+        })
+    }
+}
+
 /// Run parameter sweep from FFI.
 ///
-/// This function is designed to be called from C# via FFI.
+/// This function is designed to be called from C# via FFI. Runs in the
+/// pool sized by `set_optimizer_threads` and reports progress through
+/// `get_optimizer_progress`.
 pub fn run_parameter_sweep_ffi(
     params_list: &[StrategyParams],
     risk_config: &RiskConfig,
     ticks: &[Tick],
     initial_balance: f64,
 ) -> Vec<BacktestResult> {
+    start_progress(params_list.len());
+
     let timestamps: Vec<i64> = ticks.iter().map(|t| t.timestamp).collect();
     let prices: Vec<f64> = ticks.iter().map(|t| t.price).collect();
     let volumes: Vec<f64> = ticks.iter().map(|t| t.volume).collect();
 
-    params_list
-        .par_iter()
-        .filter_map(|params| {
-            let mut engine = BacktestEngine::new(*params, *risk_config)
-                .with_initial_balance(initial_balance);
+    map_in_pool(params_list, |params| {
+        let mut engine = BacktestEngine::new(*params, *risk_config)
+            .with_initial_balance(initial_balance);
 
-            if engine
-                .load_data_from_vectors(
-                    timestamps.clone(),
-                    prices.clone(),
-                    volumes.clone(),
-                )
-                .is_err()
-            {
-                return None;
-            }
+        let result = engine
+            .load_data_from_vectors(
+                timestamps.clone(),
+                prices.clone(),
+                volumes.clone(),
+            )
+            .ok()
+            .and_then(|_| engine.run().ok());
 
-            engine.run().ok()
-        })
-        .collect()
+        OPTIMIZER_COMPLETED.fetch_add(1, Ordering::SeqCst);
+        result
+    })
+    .unwrap_or_default()
+}
+
+/// Set the number of worker threads used by parameter sweeps.
+///
+/// # Arguments
+/// - `n`: Thread count (0 = all cores)
+///
+/// # Returns
+/// - 0 on success
+/// - ERR_INVALID_PARAM if `n` is negative
+#[no_mangle]
+pub extern "C" fn set_optimizer_threads(n: i32) -> i32 {
+    if n < 0 {
+        return ERR_INVALID_PARAM;
+    }
+    set_optimizer_threads_internal(n as usize);
+    ERR_SUCCESS
+}
+
+/// Get progress of the most recent parameter sweep.
+///
+/// # Safety
+/// - `completed` and `total` must be valid pointers to write u64 values
+///
+/// # Returns
+/// - 0 on success
+/// - ERR_NULL_POINTER if either pointer is null
+#[no_mangle]
+pub unsafe extern "C" fn get_optimizer_progress_ffi(completed: *mut u64, total: *mut u64) -> i32 {
+    if completed.is_null() || total.is_null() {
+        return ERR_NULL_POINTER;
+    }
+
+    let (done, all) = get_optimizer_progress();
+    *completed = done;
+    *total = all;
+    ERR_SUCCESS
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
 
     fn create_test_ticks() -> Vec<Tick> {
         (0..100)
@@ -325,6 +690,90 @@ mod tests {
     }
 
     #[test]
+    fn test_grid_search_picks_best_of_3x3() {
+        let ticks = create_test_ticks();
+        let range = ParameterRange {
+            short_ma_range: (3, 7, 2),
+            long_ma_range: (10, 20, 5),
+            position_size_range: None,
+        };
+        let base = StrategyParams {
+            position_size: 10.0,
+            ..Default::default()
+        };
+
+        for objective in [Objective::TotalReturn, Objective::Sharpe, Objective::Calmar] {
+            let search = GridSearchOptimizer::new(RiskConfig::default(), range.clone(), objective)
+                .with_base_params(base);
+            let result = search.run(&ticks).unwrap();
+            assert_eq!(result.all_scores.len(), 9);
+            assert!(result.all_scores.iter().all(|(p, _)| p.position_size == 10.0));
+
+            let max = result
+                .all_scores
+                .iter()
+                .map(|(_, s)| *s)
+                .fold(f64::NEG_INFINITY, f64::max);
+            assert_eq!(result.best_score, max);
+
+            // The best params reproduce the best score in a standalone run
+            let mut engine = BacktestEngine::new(result.best_params, RiskConfig::default());
+            engine
+                .load_data_from_vectors(
+                    ticks.iter().map(|t| t.timestamp).collect(),
+                    ticks.iter().map(|t| t.price).collect(),
+                    ticks.iter().map(|t| t.volume).collect(),
+                )
+                .unwrap();
+            assert_eq!(objective.score(&engine.run().unwrap()), result.best_score);
+        }
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "parallel")]
+    fn test_par_run_matches_run() {
+        let ticks = create_test_ticks();
+        let range = ParameterRange {
+            short_ma_range: (3, 7, 2),
+            long_ma_range: (10, 20, 5),
+            position_size_range: Some((5.0, 10.0, 5.0)),
+        };
+        set_optimizer_threads(4);
+
+        for objective in [Objective::TotalReturn, Objective::Sharpe, Objective::Calmar] {
+            let search = GridSearchOptimizer::new(RiskConfig::default(), range.clone(), objective);
+            let sequential = search.run(&ticks).unwrap();
+            let parallel = search.par_run(&ticks).unwrap();
+
+            assert_eq!(parallel.best_params, sequential.best_params);
+            assert_eq!(parallel.best_score, sequential.best_score);
+            assert_eq!(parallel.all_scores, sequential.all_scores);
+            assert_eq!(get_optimizer_progress(), (18, 18));
+        }
+        set_optimizer_threads(0);
+    }
+
+    #[test]
+    fn test_objective_calmar() {
+        let result = BacktestResult {
+            total_return_pct: 12.0,
+            max_drawdown_pct: 4.0,
+            ..Default::default()
+        };
+        assert_eq!(Objective::Calmar.score(&result), 3.0);
+        assert_eq!(Objective::TotalReturn.score(&result), 12.0);
+
+        let no_drawdown = BacktestResult {
+            total_return_pct: 5.0,
+            ..Default::default()
+        };
+        assert_eq!(Objective::Calmar.score(&no_drawdown), f64::INFINITY);
+        assert_eq!(Objective::Calmar.score(&BacktestResult::default()), 0.0);
+    }
+
+    #[test]
+    #[serial]
     fn test_parameter_sweep() {
         let mut optimizer = Optimizer::default()
             .with_initial_balance(100_000.0);
@@ -392,6 +841,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_parallel_execution_consistency() {
         // Run the same optimization twice and verify results are consistent
         let ticks = create_test_ticks();
@@ -420,4 +870,103 @@ mod tests {
             assert!((r1.result.final_equity - r2.result.final_equity).abs() < 0.01);
         }
     }
+
+    #[test]
+    fn test_cost_sensitivity_marginal_strategy() {
+        let optimizer = Optimizer::new(RiskConfig {
+            max_order_rate: 1000,
+            ..Default::default()
+        });
+        let params = StrategyParams {
+            short_ma_period: 3,
+            long_ma_period: 8,
+            position_size: 10.0,
+            ..Default::default()
+        };
+        // Shallow swings leave only a thin edge over costs
+        let ticks: Vec<Tick> = create_test_ticks()
+            .into_iter()
+            .take(90)
+            .map(|t| Tick {
+                price: 100.0 + (t.price - 100.0) * 0.1,
+                ..t
+            })
+            .collect();
+
+        let slippages = [0.0, 0.005, 0.02];
+        let commissions = [0.0, 0.001];
+        let grid = optimizer.cost_sensitivity(&params, &ticks, &slippages, &commissions);
+
+        assert_eq!(grid.len(), 6);
+        assert_eq!((grid[0].0, grid[0].1), (0.0, 0.0));
+        assert_eq!((grid[5].0, grid[5].1), (0.02, 0.001));
+
+        let frictionless = &grid[0].2;
+        let worst = &grid[5].2;
+        assert!(frictionless.total_trades > 0);
+        assert!(frictionless.total_return_pct > 0.0);
+        assert!(worst.total_return_pct < 0.0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_thread_count_does_not_change_results() {
+        let ticks = create_test_ticks();
+        let range = ParameterRange {
+            short_ma_range: (3, 5, 1),
+            long_ma_range: (8, 12, 2),
+            position_size_range: None,
+        };
+
+        let run = |threads: i32| {
+            assert_eq!(set_optimizer_threads(threads), ERR_SUCCESS);
+            let mut optimizer = Optimizer::default();
+            let mut results = optimizer.run_parameter_sweep(&ticks, &range);
+            results.sort_by_key(|r| (r.params.short_ma_period, r.params.long_ma_period));
+
+            let total = optimizer.total_combinations as u64;
+            assert_eq!(get_optimizer_progress(), (total, total));
+            results
+        };
+
+        let single = run(1);
+        let multi = run(4);
+        set_optimizer_threads(0);
+
+        assert!(!single.is_empty());
+        assert_eq!(single.len(), multi.len());
+        for (a, b) in single.iter().zip(&multi) {
+            assert_eq!(a.params.short_ma_period, b.params.short_ma_period);
+            assert_eq!(a.params.long_ma_period, b.params.long_ma_period);
+            assert_eq!(a.result.final_equity, b.result.final_equity);
+            assert_eq!(a.result.total_trades, b.result.total_trades);
+        }
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "parallel")]
+    fn test_optimizer_thread_setting_is_enforced() {
+        assert_eq!(set_optimizer_threads(-1), ERR_INVALID_PARAM);
+        assert_eq!(set_optimizer_threads(2), ERR_SUCCESS);
+        let pool = build_optimizer_pool().unwrap();
+        assert_eq!(pool.install(rayon::current_num_threads), 2);
+        set_optimizer_threads(0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_optimizer_progress_ffi() {
+        let ticks = create_test_ticks();
+        let params_list = vec![StrategyParams::default(); 3];
+        let results = run_parameter_sweep_ffi(&params_list, &RiskConfig::default(), &ticks, 100_000.0);
+        assert_eq!(results.len(), 3);
+
+        let (mut completed, mut total) = (0u64, 0u64);
+        unsafe {
+            assert_eq!(get_optimizer_progress_ffi(&mut completed, &mut total), ERR_SUCCESS);
+            assert_eq!(get_optimizer_progress_ffi(std::ptr::null_mut(), &mut total), ERR_NULL_POINTER);
+        }
+        assert_eq!((completed, total), (3, 3));
+    }
 }
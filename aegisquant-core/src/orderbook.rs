@@ -3,7 +3,7 @@
 //! Provides order book data structures and statistics for simulating
 //! realistic market depth and liquidity conditions.
 
-use crate::precision::{Price, Quantity, spread_bps};
+use crate::precision::{price_eq, Price, Quantity, spread_bps};
 
 /// Maximum number of price levels in the order book.
 pub const MAX_LEVELS: usize = 10;
@@ -39,6 +39,30 @@ impl OrderBookLevel {
     }
 }
 
+/// Side of the order book a delta applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSide {
+    /// Buy side
+    Bid,
+    /// Sell side
+    Ask,
+}
+
+/// Incremental (L2) update to a single price level.
+///
+/// `new_quantity` replaces the level's quantity; 0 removes the level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderBookDelta {
+    /// Side of the book
+    pub side: BookSide,
+    /// Price of the level
+    pub price: Price,
+    /// Quantity at the level after the update
+    pub new_quantity: Quantity,
+    /// Number of orders at the level after the update
+    pub new_order_count: i32,
+}
+
 /// Order book snapshot containing bid and ask levels.
 ///
 /// # FFI Safety
@@ -60,6 +84,12 @@ pub struct OrderBookSnapshot {
     pub timestamp: i64,
 }
 
+/// FFI layout for pushing order book snapshots from C#.
+///
+/// `OrderBookSnapshot` is already `repr(C)` with fixed-size level arrays, so
+/// the FFI layout is the snapshot itself and can be copied without conversion.
+pub type FfiOrderBookSnapshot = OrderBookSnapshot;
+
 impl Default for OrderBookSnapshot {
     fn default() -> Self {
         Self {
@@ -106,6 +136,12 @@ impl OrderBookSnapshot {
         snapshot
     }
 
+    /// Check that `bid_count` and `ask_count` are within `0..=MAX_LEVELS`.
+    pub fn has_valid_counts(&self) -> bool {
+        (0..=MAX_LEVELS as i32).contains(&self.bid_count)
+            && (0..=MAX_LEVELS as i32).contains(&self.ask_count)
+    }
+
     /// Get the best bid price (highest buy price).
     pub fn best_bid(&self) -> Option<Price> {
         if self.bid_count > 0 {
@@ -175,6 +211,27 @@ impl OrderBookSnapshot {
             .map(|l| l.order_count)
             .sum();
         
+        let total_volume = total_bid_volume + total_ask_volume;
+        let imbalance = if total_volume > 0.0 {
+            (total_bid_volume - total_ask_volume) / total_volume
+        } else {
+            0.0
+        };
+        
+        // Weight each side's price by the opposite side's size, so the
+        // microprice leans towards the side likely to be hit next
+        let microprice = if self.bid_count > 0 && self.ask_count > 0 {
+            let (bid, ask) = (self.bids[0], self.asks[0]);
+            let top_qty = bid.quantity + ask.quantity;
+            if top_qty > 0.0 {
+                (bid.price * ask.quantity + ask.price * bid.quantity) / top_qty
+            } else {
+                self.last_price
+            }
+        } else {
+            self.last_price
+        };
+        
         OrderBookStats {
             total_bid_volume,
             total_ask_volume,
@@ -185,9 +242,61 @@ impl OrderBookSnapshot {
             total_ask_orders,
             bid_levels: self.bid_count,
             ask_levels: self.ask_count,
+            imbalance,
+            microprice,
         }
     }
 
+    /// Apply an incremental level update.
+    ///
+    /// Updates the level at `delta.price`, inserts it if missing, or removes
+    /// it when `new_quantity` is 0. Bids stay sorted descending and asks
+    /// ascending. When a side already holds `MAX_LEVELS` levels, an insert
+    /// drops the lowest-priority level, and an update worse than every
+    /// level is ignored.
+    pub fn apply_delta(&mut self, delta: &OrderBookDelta) {
+        let (levels, count) = match delta.side {
+            BookSide::Bid => (&mut self.bids, &mut self.bid_count),
+            BookSide::Ask => (&mut self.asks, &mut self.ask_count),
+        };
+        let len = (*count).clamp(0, MAX_LEVELS as i32) as usize;
+
+        // First level that does not have priority over the delta's price
+        let ahead = |level: &OrderBookLevel| {
+            !price_eq(level.price, delta.price)
+                && match delta.side {
+                    BookSide::Bid => level.price > delta.price,
+                    BookSide::Ask => level.price < delta.price,
+                }
+        };
+        let pos = levels[..len].iter().position(|l| !ahead(l)).unwrap_or(len);
+        let matched = pos < len && price_eq(levels[pos].price, delta.price);
+
+        if delta.new_quantity <= 0.0 {
+            if matched {
+                levels.copy_within(pos + 1..len, pos);
+                levels[len - 1] = OrderBookLevel::default();
+                *count = (len - 1) as i32;
+            }
+            return;
+        }
+
+        let level = OrderBookLevel::new(delta.price, delta.new_quantity, delta.new_order_count);
+        if matched {
+            levels[pos] = level;
+            return;
+        }
+        if pos >= MAX_LEVELS {
+            return;
+        }
+
+        // Shift worse levels back, dropping the last one if the side is full
+        let end = len.min(MAX_LEVELS - 1);
+        levels.copy_within(pos..end, pos + 1);
+        levels[pos] = level;
+        *count = (end + 1) as i32;
+    }
+
     /// Set a bid level at the given index.
     pub fn set_bid(&mut self, index: usize, level: OrderBookLevel) {
         if index < MAX_LEVELS {
@@ -234,6 +343,10 @@ pub struct OrderBookStats {
     pub bid_levels: i32,
     /// Number of ask levels
     pub ask_levels: i32,
+    /// Volume imbalance (bid - ask) / (bid + ask), in [-1, 1]; 0 if the book is empty
+    pub imbalance: f64,
+    /// Best bid/ask weighted by the opposite side's size; last price if a side is empty
+    pub microprice: Price,
 }
 
 /// FFI function to get order book snapshot.
@@ -387,6 +500,25 @@ mod tests {
         
         // bid_ask_ratio = 300 / 400 = 0.75
         assert!((stats.bid_ask_ratio - 0.75).abs() < 0.001);
+        
+        // imbalance = (300 - 400) / 700
+        assert!((stats.imbalance - (-100.0 / 700.0)).abs() < 1e-12);
+        // microprice = (99 * 150 + 101 * 100) / 250 = 99.8
+        assert!((stats.microprice - 99.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_one_sided_orderbook_stats() {
+        let bids = vec![OrderBookLevel::new(99.0, 100.0, 10)];
+        let snapshot = OrderBookSnapshot::with_levels(&bids, &[], 100.5, 0);
+        let stats = snapshot.get_stats();
+        
+        assert_eq!(stats.imbalance, 1.0);
+        assert_eq!(stats.microprice, 100.5);
+        
+        let stats = OrderBookSnapshot::default().get_stats();
+        assert_eq!(stats.imbalance, 0.0);
+        assert_eq!(stats.microprice, 0.0);
     }
 
     #[test]
@@ -412,4 +544,97 @@ mod tests {
         assert_eq!(snapshot.bids[0].price, 99.0);
         assert_eq!(snapshot.asks[0].price, 101.0);
     }
+
+    fn delta(side: BookSide, price: Price, new_quantity: Quantity) -> OrderBookDelta {
+        OrderBookDelta {
+            side,
+            price,
+            new_quantity,
+            new_order_count: 1,
+        }
+    }
+
+    fn prices(levels: &[OrderBookLevel], count: i32) -> Vec<Price> {
+        levels[..count as usize].iter().map(|l| l.price).collect()
+    }
+
+    #[test]
+    fn test_apply_delta_inserts_in_middle() {
+        let bids = vec![
+            OrderBookLevel::new(99.0, 100.0, 10),
+            OrderBookLevel::new(97.0, 300.0, 30),
+        ];
+        let asks = vec![
+            OrderBookLevel::new(101.0, 150.0, 15),
+            OrderBookLevel::new(103.0, 250.0, 25),
+        ];
+        let mut snapshot = OrderBookSnapshot::with_levels(&bids, &asks, 100.0, 0);
+
+        snapshot.apply_delta(&delta(BookSide::Bid, 98.0, 200.0));
+        snapshot.apply_delta(&delta(BookSide::Ask, 102.0, 50.0));
+
+        assert_eq!(prices(&snapshot.bids, snapshot.bid_count), vec![99.0, 98.0, 97.0]);
+        assert_eq!(prices(&snapshot.asks, snapshot.ask_count), vec![101.0, 102.0, 103.0]);
+        assert_eq!(snapshot.bids[1].quantity, 200.0);
+
+        // Existing level is updated in place
+        snapshot.apply_delta(&OrderBookDelta {
+            side: BookSide::Ask,
+            price: 101.0,
+            new_quantity: 75.0,
+            new_order_count: 3,
+        });
+        assert_eq!(snapshot.ask_count, 3);
+        assert_eq!(snapshot.asks[0], OrderBookLevel::new(101.0, 75.0, 3));
+    }
+
+    #[test]
+    fn test_apply_delta_deletes_level() {
+        let bids = vec![
+            OrderBookLevel::new(99.0, 100.0, 10),
+            OrderBookLevel::new(98.0, 200.0, 20),
+            OrderBookLevel::new(97.0, 300.0, 30),
+        ];
+        let mut snapshot = OrderBookSnapshot::with_levels(&bids, &[], 100.0, 0);
+
+        snapshot.apply_delta(&delta(BookSide::Bid, 99.0, 0.0));
+        assert_eq!(prices(&snapshot.bids, snapshot.bid_count), vec![98.0, 97.0]);
+        assert_eq!(snapshot.best_bid(), Some(98.0));
+        assert!(snapshot.bids[2].is_empty());
+
+        // Removing a missing level is a no-op
+        snapshot.apply_delta(&delta(BookSide::Bid, 95.0, 0.0));
+        snapshot.apply_delta(&delta(BookSide::Ask, 101.0, 0.0));
+        assert_eq!(snapshot.bid_count, 2);
+        assert_eq!(snapshot.ask_count, 0);
+    }
+
+    #[test]
+    fn test_apply_delta_overflow_drops_lowest_priority() {
+        let asks: Vec<OrderBookLevel> = (0..MAX_LEVELS)
+            .map(|i| OrderBookLevel::new(101.0 + i as f64, 10.0, 1))
+            .collect();
+        let mut snapshot = OrderBookSnapshot::with_levels(&[], &asks, 100.0, 0);
+        let worst = 101.0 + (MAX_LEVELS - 1) as f64;
+
+        // Better than the worst level: inserted, the worst ask falls off
+        snapshot.apply_delta(&delta(BookSide::Ask, 100.5, 10.0));
+        assert_eq!(snapshot.ask_count, MAX_LEVELS as i32);
+        assert_eq!(snapshot.best_ask(), Some(100.5));
+        assert_eq!(snapshot.asks[MAX_LEVELS - 1].price, worst - 1.0);
+
+        // Worse than every level of a full side: ignored
+        let before = snapshot.asks;
+        snapshot.apply_delta(&delta(BookSide::Ask, worst + 5.0, 10.0));
+        assert_eq!(snapshot.asks, before);
+
+        let bids: Vec<OrderBookLevel> = (0..MAX_LEVELS)
+            .map(|i| OrderBookLevel::new(99.0 - i as f64, 10.0, 1))
+            .collect();
+        let mut snapshot = OrderBookSnapshot::with_levels(&bids, &[], 100.0, 0);
+        snapshot.apply_delta(&delta(BookSide::Bid, 95.5, 10.0));
+        assert_eq!(snapshot.bid_count, MAX_LEVELS as i32);
+        assert_eq!(snapshot.bids[4].price, 95.5);
+        assert_eq!(snapshot.bids[MAX_LEVELS - 1].price, 99.0 - (MAX_LEVELS - 2) as f64);
+    }
 }
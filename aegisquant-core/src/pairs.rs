@@ -0,0 +1,249 @@
+//! Pair trading analytics.
+//!
+//! Provides rolling statistics used by statistical-arbitrage strategies:
+//! - Rolling OLS hedge ratio between two price series
+//! - Z-score of the hedged spread
+//!
+//! Rolling moments are updated incrementally (add newest, remove oldest)
+//! using Welford-style centered updates, which avoids recomputing each window
+//! from scratch and the cancellation error of raw sum-of-squares formulas.
+
+/// Relative variance below which a window is treated as degenerate.
+const DEGENERATE_VARIANCE: f64 = 1e-12;
+
+/// Incrementally maintained first and second moments over a sliding window.
+#[derive(Debug, Default, Clone, Copy)]
+struct RollingMoments {
+    count: usize,
+    mean_x: f64,
+    mean_y: f64,
+    /// Sum of squared deviations of x
+    m2_x: f64,
+    /// Sum of squared deviations of y
+    m2_y: f64,
+    /// Sum of co-deviations of x and y
+    c_xy: f64,
+}
+
+impl RollingMoments {
+    fn push(&mut self, x: f64, y: f64) {
+        self.count += 1;
+        let n = self.count as f64;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += dx / n;
+        self.mean_y += dy / n;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    fn pop(&mut self, x: f64, y: f64) {
+        if self.count <= 1 {
+            *self = Self::default();
+            return;
+        }
+        self.count -= 1;
+        let n = self.count as f64;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x -= dx / n;
+        self.mean_y -= dy / n;
+        self.m2_x -= dx * (x - self.mean_x);
+        self.m2_y -= dy * (y - self.mean_y);
+        self.c_xy -= dx * (y - self.mean_y);
+    }
+
+    /// OLS slope of y on x, or None if x has (near) zero variance.
+    fn slope(&self) -> Option<f64> {
+        let scale = self.mean_x * self.mean_x * self.count as f64;
+        if self.m2_x <= DEGENERATE_VARIANCE * scale.max(1.0) {
+            return None;
+        }
+        Some(self.c_xy / self.m2_x)
+    }
+
+    /// Population standard deviation of y.
+    fn std_y(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        (self.m2_y.max(0.0) / self.count as f64).sqrt()
+    }
+}
+
+/// Calculate the rolling OLS hedge ratio of `y` regressed on `x`.
+///
+/// For each index `i`, fits `y = alpha + beta * x` over the trailing `window`
+/// observations ending at `i` and returns `beta`.
+///
+/// # Returns
+/// A vector the same length as the inputs. Entries are `NaN` until `window`
+/// observations are available, and for windows where `x` has zero variance.
+/// Returns an empty vector if the lengths differ or `window < 2`.
+pub fn rolling_hedge_ratio(y: &[f64], x: &[f64], window: usize) -> Vec<f64> {
+    if y.len() != x.len() || window < 2 {
+        return vec![];
+    }
+
+    let mut moments = RollingMoments::default();
+    let mut ratios = Vec::with_capacity(y.len());
+
+    for i in 0..y.len() {
+        moments.push(x[i], y[i]);
+        if i >= window {
+            moments.pop(x[i - window], y[i - window]);
+        }
+
+        if i + 1 < window {
+            ratios.push(f64::NAN);
+        } else {
+            ratios.push(moments.slope().unwrap_or(f64::NAN));
+        }
+    }
+
+    ratios
+}
+
+/// Calculate the rolling z-score of the hedged spread `y - hedge_ratio * x`.
+///
+/// The z-score at index `i` is computed against the mean and standard
+/// deviation of the trailing `window` spreads ending at `i`.
+///
+/// # Returns
+/// A vector the same length as the inputs. Entries are `NaN` until `window`
+/// valid spreads are available or while a `NaN` hedge ratio is in the window.
+/// A zero-variance spread window yields 0.0. Returns an empty vector if the
+/// lengths differ or `window < 2`.
+pub fn spread_zscore(y: &[f64], x: &[f64], hedge_ratios: &[f64], window: usize) -> Vec<f64> {
+    if y.len() != x.len() || y.len() != hedge_ratios.len() || window < 2 {
+        return vec![];
+    }
+
+    let spreads: Vec<f64> = (0..y.len()).map(|i| y[i] - hedge_ratios[i] * x[i]).collect();
+
+    let mut moments = RollingMoments::default();
+    let mut invalid_in_window = 0usize;
+    let mut zscores = Vec::with_capacity(spreads.len());
+
+    for i in 0..spreads.len() {
+        let spread = spreads[i];
+        if spread.is_finite() {
+            moments.push(spread, spread);
+        } else {
+            invalid_in_window += 1;
+        }
+
+        if i >= window {
+            let old = spreads[i - window];
+            if old.is_finite() {
+                moments.pop(old, old);
+            } else {
+                invalid_in_window -= 1;
+            }
+        }
+
+        if i + 1 < window || invalid_in_window > 0 {
+            zscores.push(f64::NAN);
+            continue;
+        }
+
+        let std = moments.std_y();
+        if std <= DEGENERATE_VARIANCE.sqrt() * moments.mean_y.abs().max(1.0) {
+            zscores.push(0.0);
+        } else {
+            zscores.push((spread - moments.mean_y) / std);
+        }
+    }
+
+    zscores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Synthetic cointegrated pair: y = 2x + 5 + small stationary noise.
+    fn cointegrated_pair(len: usize) -> (Vec<f64>, Vec<f64>) {
+        let x: Vec<f64> = (0..len)
+            .map(|i| 100.0 + i as f64 * 0.1 + (i as f64 * 0.3).sin() * 3.0)
+            .collect();
+        let y: Vec<f64> = x
+            .iter()
+            .enumerate()
+            .map(|(i, &xi)| 2.0 * xi + 5.0 + (i as f64 * 1.7).sin() * 0.2)
+            .collect();
+        (y, x)
+    }
+
+    #[test]
+    fn test_hedge_ratio_on_cointegrated_pair() {
+        let (y, x) = cointegrated_pair(300);
+        let ratios = rolling_hedge_ratio(&y, &x, 50);
+
+        assert_eq!(ratios.len(), 300);
+        assert!(ratios[..49].iter().all(|r| r.is_nan()));
+        for &beta in &ratios[49..] {
+            assert!((beta - 2.0).abs() < 0.05, "beta = {}", beta);
+        }
+    }
+
+    #[test]
+    fn test_incremental_matches_direct_regression() {
+        let (y, x) = cointegrated_pair(120);
+        let window = 30;
+        let ratios = rolling_hedge_ratio(&y, &x, window);
+
+        for end in [window - 1, 60, 119] {
+            let xs = &x[end + 1 - window..=end];
+            let ys = &y[end + 1 - window..=end];
+            let mx = xs.iter().sum::<f64>() / window as f64;
+            let my = ys.iter().sum::<f64>() / window as f64;
+            let cov: f64 = xs.iter().zip(ys).map(|(a, b)| (a - mx) * (b - my)).sum();
+            let var: f64 = xs.iter().map(|a| (a - mx).powi(2)).sum();
+            assert!((ratios[end] - cov / var).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_window() {
+        let x = vec![50.0; 20];
+        let y: Vec<f64> = (0..20).map(|i| 100.0 + i as f64).collect();
+        let ratios = rolling_hedge_ratio(&y, &x, 5);
+        assert!(ratios.iter().all(|r| r.is_nan()));
+
+        let zscores = spread_zscore(&y, &x, &ratios, 5);
+        assert!(zscores.iter().all(|z| z.is_nan()));
+    }
+
+    #[test]
+    fn test_spread_zscore_detects_dislocation() {
+        let (mut y, x) = cointegrated_pair(300);
+        y[250] += 10.0;
+
+        let ratios = vec![2.0; 300];
+        let zscores = spread_zscore(&y, &x, &ratios, 50);
+
+        assert_eq!(zscores.len(), 300);
+        assert!(zscores[..49].iter().all(|z| z.is_nan()));
+        assert!(zscores[250] > 3.0);
+        for &z in &zscores[60..250] {
+            assert!(z.abs() < 3.0);
+        }
+    }
+
+    #[test]
+    fn test_constant_spread_zscore_is_zero() {
+        let x: Vec<f64> = (0..20).map(|i| 10.0 + i as f64).collect();
+        let y: Vec<f64> = x.iter().map(|v| 2.0 * v + 1.0).collect();
+        let zscores = spread_zscore(&y, &x, &[2.0; 20], 5);
+        assert!(zscores[4..].iter().all(|&z| z == 0.0));
+    }
+
+    #[test]
+    fn test_mismatched_inputs() {
+        assert!(rolling_hedge_ratio(&[1.0, 2.0], &[1.0], 2).is_empty());
+        assert!(rolling_hedge_ratio(&[1.0, 2.0], &[1.0, 2.0], 1).is_empty());
+        assert!(spread_zscore(&[1.0], &[1.0], &[], 2).is_empty());
+    }
+}
@@ -5,25 +5,105 @@
 //! - Save account snapshots
 //! - Save position data
 //! - Recover state from database
+//! - Reconcile recovered positions against a gateway
+//! - Retry writes that hit transient `SQLITE_BUSY` / `SQLITE_LOCKED` errors
+//! - Open file databases in WAL mode so readers and writers don't block each other
 //!
 //! Requirements: 15.1, 15.2, 15.3, 15.4, 15.5
 
+use std::collections::BTreeMap;
 use std::ffi::{c_char, CStr};
 use std::path::Path;
 use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
 
 use rust_decimal::Decimal;
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension};
 
 use crate::error::{EngineError, EngineResult};
-use crate::ffi::{ERR_NULL_POINTER, ERR_SUCCESS};
+use crate::ffi::{ERR_BUFFER_TOO_SMALL, ERR_NULL_POINTER, ERR_SUCCESS};
+use crate::gateway::{Gateway, GatewayError};
+use crate::precision::{round_to_decimals, QUANTITY_EPSILON};
 use crate::types::Position;
 
 /// Database error code
 pub const ERR_DB_ERROR: i32 = -13;
 
+/// Why a position was opened.
+///
+/// Stored as its `i32` code; `None` marks opening trades and rows written
+/// before the reason columns existed.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EntryReason {
+    #[default]
+    None = 0,
+    /// Strategy signal
+    Signal = 1,
+}
+
+impl EntryReason {
+    /// Reason for a stored code. Unknown codes map to `None`.
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            1 => EntryReason::Signal,
+            _ => EntryReason::None,
+        }
+    }
+}
+
+/// Why a position was closed.
+///
+/// Stored as its `i32` code, which also indexes `get_exit_reason_counts`.
+/// `None` marks opening trades and rows written before the reason columns
+/// existed; every close recorded by the engine carries one of the others.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ExitReason {
+    #[default]
+    None = 0,
+    /// Opposite strategy signal
+    Signal = 1,
+    /// Stop loss hit
+    Stop = 2,
+    /// Take profit hit
+    Target = 3,
+    /// Maximum holding time reached (`BacktestEngine::with_max_holding_bars`)
+    Time = 4,
+    /// Session end flatten
+    Session = 5,
+    /// Forced liquidation (equity floor or emergency stop)
+    Emergency = 6,
+}
+
+/// Number of `ExitReason` codes, including `None`.
+pub const EXIT_REASON_COUNT: usize = 7;
+
+impl ExitReason {
+    /// Reason for a stored code. Unknown codes map to `None`.
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            1 => ExitReason::Signal,
+            2 => ExitReason::Stop,
+            3 => ExitReason::Target,
+            4 => ExitReason::Time,
+            5 => ExitReason::Session,
+            6 => ExitReason::Emergency,
+            _ => ExitReason::None,
+        }
+    }
+}
+
 /// Trade record for persistence.
-#[derive(Debug, Clone)]
+///
+/// `pnl` is the raw PnL. For closing trades, `gross_pnl`, `commission`
+/// (entry and exit commission of the closed quantity) and
+/// `net_pnl = gross_pnl - commission` break it down, and `entry_reason` /
+/// `exit_reason` record why the closed position was opened and closed. All
+/// of these are 0 / `None` for opening trades and rows written before these
+/// columns existed.
+#[derive(Debug, Clone, Default)]
 pub struct TradeRecord {
     pub timestamp: i64,
     pub symbol: String,
@@ -31,8 +111,69 @@ pub struct TradeRecord {
     pub quantity: f64,
     pub price: f64,
     pub pnl: f64,
+    pub gross_pnl: f64,
+    pub commission: f64,
+    pub net_pnl: f64,
+    pub entry_reason: EntryReason,
+    pub exit_reason: ExitReason,
+}
+
+impl TradeRecord {
+    /// Record for a closing trade, deriving `net_pnl` from the gross PnL and
+    /// total commission. `pnl` is set to the gross PnL.
+    pub fn closing(
+        timestamp: i64,
+        symbol: &str,
+        direction: i32,
+        quantity: f64,
+        price: f64,
+        gross_pnl: f64,
+        commission: f64,
+    ) -> Self {
+        Self {
+            timestamp,
+            symbol: symbol.to_string(),
+            direction,
+            quantity,
+            price,
+            pnl: gross_pnl,
+            gross_pnl,
+            commission,
+            net_pnl: gross_pnl - commission,
+            ..Default::default()
+        }
+    }
+
+    /// Copy of this record with entry and exit reasons set.
+    pub fn with_reasons(mut self, entry: EntryReason, exit: ExitReason) -> Self {
+        self.entry_reason = entry;
+        self.exit_reason = exit;
+        self
+    }
+
+    /// Copy of this record with price and PnL rounded to `decimals` places
+    /// for display. Quantity is left unchanged.
+    pub fn rounded(&self, decimals: u32) -> Self {
+        Self {
+            price: round_to_decimals(self.price, decimals),
+            pnl: round_to_decimals(self.pnl, decimals),
+            gross_pnl: round_to_decimals(self.gross_pnl, decimals),
+            commission: round_to_decimals(self.commission, decimals),
+            net_pnl: round_to_decimals(self.net_pnl, decimals),
+            ..self.clone()
+        }
+    }
 }
 
+/// Columns added to `trades` after its first release, as (name, type).
+const TRADE_ADDED_COLUMNS: [(&str, &str); 5] = [
+    ("gross_pnl", "REAL"),
+    ("commission", "REAL"),
+    ("net_pnl", "REAL"),
+    ("entry_reason", "INTEGER"),
+    ("exit_reason", "INTEGER"),
+];
+
 /// Account snapshot for persistence.
 #[derive(Debug, Clone)]
 pub struct AccountSnapshot {
@@ -59,20 +200,170 @@ pub struct RecoveredState {
     pub trades: Vec<TradeRecord>,
 }
 
+/// Position mismatch between persisted state and a gateway.
+///
+/// A symbol missing on one side is reported with a quantity of 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discrepancy {
+    pub symbol: String,
+    pub persisted_quantity: f64,
+    pub live_quantity: f64,
+}
+
+/// Retry configuration for writes that hit a locked database.
+///
+/// `busy_timeout_ms` is SQLite's own wait before returning `SQLITE_BUSY`.
+/// On top of that, `save_*` methods retry transient lock errors up to
+/// `max_retries` times, doubling the backoff from `initial_backoff_ms` up to
+/// `max_backoff_ms`. Any other error is returned immediately.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// SQLite busy timeout in milliseconds
+    pub busy_timeout_ms: u64,
+    /// Number of retries after the first failed attempt
+    pub max_retries: u32,
+    /// Backoff before the first retry in milliseconds
+    pub initial_backoff_ms: u64,
+    /// Upper bound on the backoff in milliseconds
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            busy_timeout_ms: 5000,
+            max_retries: 3,
+            initial_backoff_ms: 50,
+            max_backoff_ms: 1000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that fails on the first error without retrying.
+    pub fn no_retry() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before the given retry (0-based).
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64.checked_shl(retry).unwrap_or(u64::MAX);
+        let ms = self.initial_backoff_ms.saturating_mul(factor).min(self.max_backoff_ms);
+        Duration::from_millis(ms)
+    }
+}
+
+/// Connection settings applied when a database file is opened.
+///
+/// With `wal` enabled the database uses SQLite's write-ahead log, which lets
+/// readers proceed while another connection writes. WAL keeps two sidecar
+/// files next to the database, `<db>-wal` and `<db>-shm`; copy or delete them
+/// together with the database file. The mode is stored in the file itself, so
+/// `wal: false` leaves whatever journal mode the database already has.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PersistenceConfig {
+    /// Switch the database to `journal_mode=WAL` on open
+    pub wal: bool,
+    /// SQLite busy timeout in milliseconds
+    pub busy_timeout_ms: u64,
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            wal: true,
+            busy_timeout_ms: 5000,
+        }
+    }
+}
+
+/// Insert statement shared by `save_trade` and `save_trades_batch`.
+const INSERT_TRADE_SQL: &str =
+    "INSERT INTO trades (timestamp, symbol, direction, quantity, price, pnl, session_date,
+                         gross_pnl, commission, net_pnl, entry_reason, exit_reason)
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)";
+
+/// Bind a trade record to the parameters of `INSERT_TRADE_SQL`.
+fn trade_params<'a>(trade: &'a TradeRecord, session_date: &'a str) -> impl rusqlite::Params + 'a {
+    (
+        trade.timestamp,
+        trade.symbol.as_str(),
+        trade.direction,
+        trade.quantity,
+        trade.price,
+        trade.pnl,
+        session_date,
+        trade.gross_pnl,
+        trade.commission,
+        trade.net_pnl,
+        trade.entry_reason as i32,
+        trade.exit_reason as i32,
+    )
+}
+
+/// Run `op` inside a savepoint, rolling its writes back if it fails.
+///
+/// Outside a transaction the savepoint starts one; inside `transaction()`
+/// it nests, so the writes commit or roll back with the enclosing work.
+fn in_savepoint<T>(conn: &Connection, op: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+    conn.execute_batch("SAVEPOINT write_batch")?;
+    match op(conn) {
+        Ok(value) => {
+            conn.execute_batch("RELEASE write_batch")?;
+            Ok(value)
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK TO write_batch; RELEASE write_batch");
+            Err(e)
+        }
+    }
+}
+
+/// Whether an error is a transient lock that is worth retrying.
+fn is_transient(err: &rusqlite::Error) -> bool {
+    matches!(
+        err.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy) | Some(ErrorCode::DatabaseLocked)
+    )
+}
+
 /// Persistence manager for SQLite database operations.
 pub struct PersistenceManager {
     conn: Connection,
+    retry_policy: RetryPolicy,
 }
 
 impl PersistenceManager {
     /// Create a new PersistenceManager with the given database path.
     ///
-    /// Creates the database file and tables if they don't exist.
+    /// Creates the database file and tables if they don't exist. Uses the
+    /// default [`PersistenceConfig`], so the database is opened in WAL mode.
     pub fn new<P: AsRef<Path>>(db_path: P) -> EngineResult<Self> {
+        Self::with_config(db_path, PersistenceConfig::default())
+    }
+
+    /// Create a new PersistenceManager with explicit connection settings.
+    ///
+    /// `config.busy_timeout_ms` replaces the busy timeout of the default
+    /// retry policy.
+    pub fn with_config<P: AsRef<Path>>(db_path: P, config: PersistenceConfig) -> EngineResult<Self> {
         let conn = Connection::open(db_path)
             .map_err(|e| EngineError::database(format!("Failed to open database: {}", e)))?;
 
-        let manager = Self { conn };
+        let manager = Self {
+            conn,
+            retry_policy: RetryPolicy {
+                busy_timeout_ms: config.busy_timeout_ms,
+                ..RetryPolicy::default()
+            },
+        };
+        manager.apply_busy_timeout()?;
+        if config.wal {
+            manager.enable_wal()?;
+        }
         manager.create_tables()?;
         Ok(manager)
     }
@@ -82,11 +373,84 @@ impl PersistenceManager {
         let conn = Connection::open_in_memory()
             .map_err(|e| EngineError::database(format!("Failed to open in-memory db: {}", e)))?;
 
-        let manager = Self { conn };
+        let manager = Self {
+            conn,
+            retry_policy: RetryPolicy::default(),
+        };
+        manager.apply_busy_timeout()?;
         manager.create_tables()?;
         Ok(manager)
     }
 
+    /// Set the retry policy and apply its busy timeout to the connection.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) -> EngineResult<()> {
+        self.retry_policy = policy;
+        self.apply_busy_timeout()
+    }
+
+    /// Get the current retry policy.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    fn apply_busy_timeout(&self) -> EngineResult<()> {
+        self.conn
+            .busy_timeout(Duration::from_millis(self.retry_policy.busy_timeout_ms))
+            .map_err(|e| EngineError::database(format!("Failed to set busy timeout: {}", e)))
+    }
+
+    /// Switch the database to write-ahead logging.
+    fn enable_wal(&self) -> EngineResult<()> {
+        let mode: String = self
+            .conn
+            .query_row("PRAGMA journal_mode=WAL", [], |row| row.get(0))
+            .map_err(|e| EngineError::database(format!("Failed to enable WAL: {}", e)))?;
+        if !mode.eq_ignore_ascii_case("wal") {
+            return Err(EngineError::database(format!(
+                "Failed to enable WAL: journal mode is {}",
+                mode
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run a write, retrying transient lock errors according to the retry policy.
+    fn with_retry<T>(&self, mut op: impl FnMut(&Connection) -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+        let mut retry = 0;
+        loop {
+            match op(&self.conn) {
+                Err(e) if is_transient(&e) && retry < self.retry_policy.max_retries => {
+                    thread::sleep(self.retry_policy.backoff(retry));
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Run `f` as one atomic unit of writes.
+    ///
+    /// Every write `f` makes through the manager, including the batch
+    /// methods, commits together when it returns `Ok`; an error rolls all
+    /// of them back and is returned to the caller.
+    pub fn transaction<T>(&self, f: impl FnOnce(&Self) -> EngineResult<T>) -> EngineResult<T> {
+        self.conn
+            .execute_batch("SAVEPOINT manager_tx")
+            .map_err(|e| EngineError::database(format!("Failed to begin transaction: {}", e)))?;
+        match f(self) {
+            Ok(value) => {
+                self.conn
+                    .execute_batch("RELEASE manager_tx")
+                    .map_err(|e| EngineError::database(format!("Failed to commit transaction: {}", e)))?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK TO manager_tx; RELEASE manager_tx");
+                Err(e)
+            }
+        }
+    }
+
     /// Create required database tables.
     fn create_tables(&self) -> EngineResult<()> {
         self.conn
@@ -99,11 +463,17 @@ impl PersistenceManager {
                     quantity REAL NOT NULL,
                     price REAL NOT NULL,
                     pnl REAL,
-                    session_date TEXT NOT NULL
+                    session_date TEXT NOT NULL,
+                    gross_pnl REAL NOT NULL DEFAULT 0,
+                    commission REAL NOT NULL DEFAULT 0,
+                    net_pnl REAL NOT NULL DEFAULT 0,
+                    entry_reason INTEGER NOT NULL DEFAULT 0,
+                    exit_reason INTEGER NOT NULL DEFAULT 0
                 )",
                 [],
             )
             .map_err(|e| EngineError::database(format!("Failed to create trades table: {}", e)))?;
+        self.migrate_trade_columns()?;
 
         self.conn
             .execute(
@@ -137,6 +507,20 @@ impl PersistenceManager {
                 EngineError::database(format!("Failed to create positions table: {}", e))
             })?;
 
+        self.conn
+            .execute(
+                "CREATE TABLE IF NOT EXISTS equity_points (
+                    id INTEGER PRIMARY KEY,
+                    timestamp INTEGER NOT NULL,
+                    equity REAL NOT NULL,
+                    session_date TEXT NOT NULL
+                )",
+                [],
+            )
+            .map_err(|e| {
+                EngineError::database(format!("Failed to create equity_points table: {}", e))
+            })?;
+
         // Create indexes for faster queries
         self.conn
             .execute(
@@ -150,38 +534,86 @@ impl PersistenceManager {
                 [],
             )
             .ok();
+        self.conn
+            .execute(
+                "CREATE INDEX IF NOT EXISTS idx_equity_session ON equity_points(session_date)",
+                [],
+            )
+            .ok();
+
+        Ok(())
+    }
+
+    /// Add the cost attribution and reason columns to a `trades` table that
+    /// predates them.
+    ///
+    /// Existing rows get 0 for the new columns.
+    fn migrate_trade_columns(&self) -> EngineResult<()> {
+        let existing: Vec<String> = {
+            let mut stmt = self
+                .conn
+                .prepare("PRAGMA table_info(trades)")
+                .map_err(|e| EngineError::database(format!("Failed to read trades schema: {}", e)))?;
+            let names = stmt
+                .query_map([], |row| row.get::<_, String>(1))
+                .map_err(|e| EngineError::database(format!("Failed to read trades schema: {}", e)))?
+                .filter_map(|r| r.ok())
+                .collect();
+            names
+        };
 
+        for (column, sql_type) in TRADE_ADDED_COLUMNS {
+            if existing.iter().any(|name| name == column) {
+                continue;
+            }
+            self.conn
+                .execute(
+                    &format!(
+                        "ALTER TABLE trades ADD COLUMN {} {} NOT NULL DEFAULT 0",
+                        column, sql_type
+                    ),
+                    [],
+                )
+                .map_err(|e| {
+                    EngineError::database(format!("Failed to add trades.{} column: {}", column, e))
+                })?;
+        }
         Ok(())
     }
 
     /// Save a trade record to the database.
     pub fn save_trade(&self, trade: &TradeRecord, session_date: &str) -> EngineResult<()> {
-        self.conn
-            .execute(
-                "INSERT INTO trades (timestamp, symbol, direction, quantity, price, pnl, session_date)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                params![
-                    trade.timestamp,
-                    trade.symbol,
-                    trade.direction,
-                    trade.quantity,
-                    trade.price,
-                    trade.pnl,
-                    session_date
-                ],
-            )
+        self.with_retry(|conn| conn.execute(INSERT_TRADE_SQL, trade_params(trade, session_date)))
             .map_err(|e| EngineError::database(format!("Failed to save trade: {}", e)))?;
         Ok(())
     }
 
+    /// Save many trade records in one transaction.
+    ///
+    /// A single prepared statement is reused for every row. If any row
+    /// fails, the whole batch is rolled back and nothing is written.
+    pub fn save_trades_batch(&self, trades: &[TradeRecord], session_date: &str) -> EngineResult<()> {
+        self.with_retry(|conn| {
+            in_savepoint(conn, |conn| {
+                let mut stmt = conn.prepare_cached(INSERT_TRADE_SQL)?;
+                for trade in trades {
+                    stmt.execute(trade_params(trade, session_date))?;
+                }
+                Ok(())
+            })
+        })
+        .map_err(|e| EngineError::database(format!("Failed to save trade batch: {}", e)))?;
+        Ok(())
+    }
+
     /// Save an account snapshot to the database.
     pub fn save_account_snapshot(
         &self,
         snapshot: &AccountSnapshot,
         session_date: &str,
     ) -> EngineResult<()> {
-        self.conn
-            .execute(
+        self.with_retry(|conn| {
+            conn.execute(
                 "INSERT INTO account_snapshots (timestamp, balance, equity, position_count, session_date)
                  VALUES (?1, ?2, ?3, ?4, ?5)",
                 params![
@@ -192,14 +624,15 @@ impl PersistenceManager {
                     session_date
                 ],
             )
-            .map_err(|e| EngineError::database(format!("Failed to save snapshot: {}", e)))?;
+        })
+        .map_err(|e| EngineError::database(format!("Failed to save snapshot: {}", e)))?;
         Ok(())
     }
 
     /// Save or update a position in the database.
     pub fn save_position(&self, position: &PositionRecord, session_date: &str) -> EngineResult<()> {
-        self.conn
-            .execute(
+        self.with_retry(|conn| {
+            conn.execute(
                 "INSERT OR REPLACE INTO positions (symbol, quantity, average_price, unrealized_pnl, session_date)
                  VALUES (?1, ?2, ?3, ?4, ?5)",
                 params![
@@ -210,7 +643,8 @@ impl PersistenceManager {
                     session_date
                 ],
             )
-            .map_err(|e| EngineError::database(format!("Failed to save position: {}", e)))?;
+        })
+        .map_err(|e| EngineError::database(format!("Failed to save position: {}", e)))?;
         Ok(())
     }
 
@@ -226,6 +660,47 @@ impl PersistenceManager {
         self.save_position(&record, session_date)
     }
 
+    /// Save an equity curve as `(timestamp, equity)` points.
+    ///
+    /// All points are inserted in a single transaction, so either the whole
+    /// curve is written or none of it is.
+    pub fn save_equity_curve(&self, session_date: &str, points: &[(i64, f64)]) -> EngineResult<()> {
+        self.with_retry(|conn| {
+            in_savepoint(conn, |conn| {
+                let mut stmt = conn.prepare_cached(
+                    "INSERT INTO equity_points (timestamp, equity, session_date) VALUES (?1, ?2, ?3)",
+                )?;
+                for &(timestamp, equity) in points {
+                    stmt.execute(params![timestamp, equity, session_date])?;
+                }
+                Ok(())
+            })
+        })
+        .map_err(|e| EngineError::database(format!("Failed to save equity curve: {}", e)))?;
+        Ok(())
+    }
+
+    /// Load a session's equity curve, ordered by timestamp.
+    ///
+    /// Points sharing a timestamp keep their insertion order.
+    pub fn load_equity_curve(&self, session_date: &str) -> EngineResult<Vec<(i64, f64)>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT timestamp, equity FROM equity_points
+                 WHERE session_date = ?1 ORDER BY timestamp, id",
+            )
+            .map_err(|e| EngineError::database(format!("Failed to prepare query: {}", e)))?;
+
+        let points = stmt
+            .query_map(params![session_date], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| EngineError::database(format!("Failed to query equity curve: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(points)
+    }
+
     /// Recover state from the database for a given session date.
     pub fn recover_state(&self, session_date: &str) -> EngineResult<RecoveredState> {
         // 1. Recover latest account snapshot
@@ -302,8 +777,9 @@ impl PersistenceManager {
         let mut stmt = self
             .conn
             .prepare(
-                "SELECT timestamp, symbol, direction, quantity, price, pnl FROM trades 
-                 WHERE session_date = ?1 ORDER BY timestamp",
+                "SELECT timestamp, symbol, direction, quantity, price, pnl, gross_pnl, commission, net_pnl,
+                        entry_reason, exit_reason
+                 FROM trades WHERE session_date = ?1 ORDER BY timestamp, id",
             )
             .map_err(|e| EngineError::database(format!("Failed to prepare query: {}", e)))?;
 
@@ -316,6 +792,11 @@ impl PersistenceManager {
                     quantity: row.get(3)?,
                     price: row.get(4)?,
                     pnl: row.get(5)?,
+                    gross_pnl: row.get(6)?,
+                    commission: row.get(7)?,
+                    net_pnl: row.get(8)?,
+                    entry_reason: EntryReason::from_code(row.get(9)?),
+                    exit_reason: ExitReason::from_code(row.get(10)?),
                 })
             })
             .map_err(|e| EngineError::database(format!("Failed to query trades: {}", e)))?
@@ -343,6 +824,31 @@ impl PersistenceManager {
         Ok(count)
     }
 
+    /// Count a session's closing trades by exit reason.
+    ///
+    /// The result is indexed by `ExitReason` code; slot 0 counts trades with
+    /// no exit reason (opening trades and legacy rows).
+    pub fn get_exit_reason_counts(&self, session_date: &str) -> EngineResult<[i64; EXIT_REASON_COUNT]> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT exit_reason, COUNT(*) FROM trades WHERE session_date = ?1 GROUP BY exit_reason",
+            )
+            .map_err(|e| EngineError::database(format!("Failed to prepare query: {}", e)))?;
+
+        let rows: Vec<(i32, i64)> = stmt
+            .query_map(params![session_date], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| EngineError::database(format!("Failed to count exit reasons: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut counts = [0i64; EXIT_REASON_COUNT];
+        for (code, count) in rows {
+            counts[ExitReason::from_code(code) as usize] += count;
+        }
+        Ok(counts)
+    }
+
     /// Clear all data for a session (for testing).
     pub fn clear_session(&self, session_date: &str) -> EngineResult<()> {
         self.conn
@@ -357,10 +863,80 @@ impl PersistenceManager {
         self.conn
             .execute("DELETE FROM positions WHERE session_date = ?1", params![session_date])
             .map_err(|e| EngineError::database(format!("Failed to clear positions: {}", e)))?;
+        self.conn
+            .execute(
+                "DELETE FROM equity_points WHERE session_date = ?1",
+                params![session_date],
+            )
+            .map_err(|e| EngineError::database(format!("Failed to clear equity curve: {}", e)))?;
         Ok(())
     }
 }
 
+/// Compare recovered positions to the gateway's live positions.
+///
+/// Returns one `Discrepancy` per symbol whose quantities differ by more than
+/// `QUANTITY_EPSILON`, sorted by symbol. The list is empty on a clean match.
+/// The gateway is not modified; see `reconcile_and_restore`. Fails with the
+/// gateway's error if it cannot list its positions.
+pub fn reconcile(
+    gateway: &mut dyn Gateway,
+    recovered: &RecoveredState,
+) -> Result<Vec<Discrepancy>, GatewayError> {
+    reconcile_positions(gateway, recovered, false)
+}
+
+/// Reconcile positions and restore persisted state as the source of truth.
+///
+/// Every mismatched symbol is overwritten on the gateway with the persisted
+/// quantity and average price; symbols absent from persistence are closed.
+/// Returns the discrepancies found before restoring, or the gateway's error
+/// if it cannot list or overwrite positions.
+pub fn reconcile_and_restore(
+    gateway: &mut dyn Gateway,
+    recovered: &RecoveredState,
+) -> Result<Vec<Discrepancy>, GatewayError> {
+    reconcile_positions(gateway, recovered, true)
+}
+
+fn reconcile_positions(
+    gateway: &mut dyn Gateway,
+    recovered: &RecoveredState,
+    apply: bool,
+) -> Result<Vec<Discrepancy>, GatewayError> {
+    // symbol -> (persisted record, live quantity)
+    let mut by_symbol: BTreeMap<String, (Option<&PositionRecord>, f64)> = BTreeMap::new();
+    for record in &recovered.positions {
+        by_symbol.entry(record.symbol.clone()).or_insert((None, 0.0)).0 = Some(record);
+    }
+    for position in gateway.query_positions()? {
+        by_symbol
+            .entry(position.symbol_str().to_string())
+            .or_insert((None, 0.0))
+            .1 = position.quantity;
+    }
+
+    let mut discrepancies = Vec::new();
+    for (symbol, (record, live_quantity)) in by_symbol {
+        let persisted_quantity = record.map(|r| r.quantity).unwrap_or(0.0);
+        if (persisted_quantity - live_quantity).abs() <= QUANTITY_EPSILON {
+            continue;
+        }
+
+        if apply {
+            let average_price = record.map(|r| r.average_price).unwrap_or(0.0);
+            gateway.restore_position(&symbol, persisted_quantity, average_price)?;
+        }
+        discrepancies.push(Discrepancy {
+            symbol,
+            persisted_quantity,
+            live_quantity,
+        });
+    }
+
+    Ok(discrepancies)
+}
+
 // ============================================================================
 // FFI Functions
 // ============================================================================
@@ -409,6 +985,7 @@ pub unsafe extern "C" fn free_persistence_manager(manager: *mut PersistenceManag
 
 /// FFI-compatible trade record for saving.
 #[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct FfiTradeRecord {
     pub timestamp: i64,
     pub symbol: [u8; 16],
@@ -416,6 +993,35 @@ pub struct FfiTradeRecord {
     pub quantity: f64,
     pub price: f64,
     pub pnl: f64,
+    pub gross_pnl: f64,
+    pub commission: f64,
+    pub net_pnl: f64,
+    /// `EntryReason` code
+    pub entry_reason: i32,
+    /// `ExitReason` code
+    pub exit_reason: i32,
+}
+
+impl From<&TradeRecord> for FfiTradeRecord {
+    fn from(trade: &TradeRecord) -> Self {
+        let mut symbol = [0u8; 16];
+        let bytes = trade.symbol.as_bytes();
+        let len = bytes.len().min(15); // Leave room for null terminator
+        symbol[..len].copy_from_slice(&bytes[..len]);
+        Self {
+            timestamp: trade.timestamp,
+            symbol,
+            direction: trade.direction,
+            quantity: trade.quantity,
+            price: trade.price,
+            pnl: trade.pnl,
+            gross_pnl: trade.gross_pnl,
+            commission: trade.commission,
+            net_pnl: trade.net_pnl,
+            entry_reason: trade.entry_reason as i32,
+            exit_reason: trade.exit_reason as i32,
+        }
+    }
 }
 
 /// Save a trade record via FFI.
@@ -453,6 +1059,11 @@ pub unsafe extern "C" fn save_trade_ffi(
         quantity: trade_ref.quantity,
         price: trade_ref.price,
         pnl: trade_ref.pnl,
+        gross_pnl: trade_ref.gross_pnl,
+        commission: trade_ref.commission,
+        net_pnl: trade_ref.net_pnl,
+        entry_reason: EntryReason::from_code(trade_ref.entry_reason),
+        exit_reason: ExitReason::from_code(trade_ref.exit_reason),
     };
 
     match manager_ref.save_trade(&record, session) {
@@ -577,9 +1188,53 @@ pub unsafe extern "C" fn load_state_ffi(
     }
 }
 
+/// Count a session's closing trades by exit reason via FFI.
+///
+/// # Safety
+/// - `manager` must be a valid pointer from `create_persistence_manager`
+/// - `session_date` must be a valid null-terminated UTF-8 string
+/// - `out_counts` must be valid for `len` writes of i64
+///
+/// # Returns
+/// - ERR_SUCCESS on success, with `out_counts[code]` set for each `ExitReason` code
+/// - ERR_NULL_POINTER if any pointer is null
+/// - ERR_BUFFER_TOO_SMALL if `len < EXIT_REASON_COUNT`
+/// - ERR_DB_ERROR on database error
+#[no_mangle]
+pub unsafe extern "C" fn get_exit_reason_counts_ffi(
+    manager: *mut PersistenceManager,
+    session_date: *const c_char,
+    out_counts: *mut i64,
+    len: usize,
+) -> i32 {
+    if manager.is_null() || session_date.is_null() || out_counts.is_null() {
+        return ERR_NULL_POINTER;
+    }
+    if len < EXIT_REASON_COUNT {
+        return ERR_BUFFER_TOO_SMALL;
+    }
+
+    // SAFETY: Pointers validated above
+    let manager_ref = &*manager;
+
+    let session = match CStr::from_ptr(session_date).to_str() {
+        Ok(s) => s,
+        Err(_) => return ERR_DB_ERROR,
+    };
+
+    match manager_ref.get_exit_reason_counts(session) {
+        Ok(counts) => {
+            std::ptr::copy_nonoverlapping(counts.as_ptr(), out_counts, EXIT_REASON_COUNT);
+            ERR_SUCCESS
+        }
+        Err(_) => ERR_DB_ERROR,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gateway::SimulatedGateway;
     use rust_decimal_macros::dec;
 
     #[test]
@@ -600,6 +1255,7 @@ mod tests {
             quantity: 0.5,
             price: 42000.0,
             pnl: 100.0,
+            ..Default::default()
         };
 
         manager.save_trade(&trade, session).unwrap();
@@ -610,6 +1266,79 @@ mod tests {
         assert_eq!(trades[0].quantity, 0.5);
     }
 
+    #[test]
+    fn test_trade_costs_round_trip() {
+        let manager = PersistenceManager::in_memory().unwrap();
+        let trade = TradeRecord::closing(1704067200, "BTCUSDT", -1, 10.0, 110.0, 100.0, 2.1);
+        assert_eq!(trade.net_pnl, trade.gross_pnl - trade.commission);
+
+        manager.save_trade(&trade, "2024-01-01").unwrap();
+        let recovered = &manager.get_trades("2024-01-01").unwrap()[0];
+        assert_eq!(recovered.gross_pnl, 100.0);
+        assert_eq!(recovered.commission, 2.1);
+        assert_eq!(recovered.net_pnl, 100.0 - 2.1);
+    }
+
+    #[test]
+    fn test_legacy_trades_table_is_migrated() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("legacy.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute(
+                "CREATE TABLE trades (
+                    id INTEGER PRIMARY KEY,
+                    timestamp INTEGER NOT NULL,
+                    symbol TEXT NOT NULL,
+                    direction INTEGER NOT NULL,
+                    quantity REAL NOT NULL,
+                    price REAL NOT NULL,
+                    pnl REAL,
+                    session_date TEXT NOT NULL
+                )",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO trades (timestamp, symbol, direction, quantity, price, pnl, session_date)
+                 VALUES (1, 'BTCUSDT', -1, 1.0, 42000.0, 50.0, '2024-01-01')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let manager = PersistenceManager::new(&path).unwrap();
+        let trades = manager.get_trades("2024-01-01").unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].pnl, 50.0);
+        assert_eq!((trades[0].gross_pnl, trades[0].commission, trades[0].net_pnl), (0.0, 0.0, 0.0));
+
+        // Reopening an already migrated database is a no-op
+        drop(manager);
+        let manager = PersistenceManager::new(&path).unwrap();
+        manager.save_trade(&TradeRecord::closing(2, "BTCUSDT", -1, 1.0, 42100.0, 100.0, 8.4), "2024-01-01").unwrap();
+        assert_eq!(manager.get_trades("2024-01-01").unwrap()[1].net_pnl, 91.6);
+    }
+
+    #[test]
+    fn test_trade_record_rounded_for_display() {
+        let trade = TradeRecord {
+            timestamp: 1,
+            symbol: "BTCUSDT".to_string(),
+            direction: 1,
+            quantity: 0.123456,
+            price: 100.10000000000001,
+            pnl: 49.99999999999997,
+            ..Default::default()
+        };
+
+        let rounded = trade.rounded(2);
+        assert_eq!(rounded.price, 100.1);
+        assert_eq!(rounded.pnl, 50.0);
+        assert_eq!(rounded.quantity, 0.123456);
+        assert_eq!(trade.pnl, 49.99999999999997);
+    }
+
     #[test]
     fn test_save_and_recover_snapshot() {
         let manager = PersistenceManager::in_memory().unwrap();
@@ -703,6 +1432,7 @@ mod tests {
             quantity: 0.5,
             price: 42000.0,
             pnl: 100.0,
+            ..Default::default()
         };
 
         manager.save_trade(&trade, session).unwrap();
@@ -723,6 +1453,7 @@ mod tests {
             quantity: 1.0,
             price: 42000.0,
             pnl: 0.0,
+            ..Default::default()
         };
 
         let trade2 = TradeRecord {
@@ -732,6 +1463,7 @@ mod tests {
             quantity: 5.0,
             price: 2500.0,
             pnl: 100.0,
+            ..Default::default()
         };
 
         manager.save_trade(&trade1, "2024-01-01").unwrap();
@@ -746,4 +1478,305 @@ mod tests {
         let state2 = manager.recover_state("2024-01-02").unwrap();
         assert_eq!(state2.trades[0].symbol, "ETHUSDT");
     }
+
+    #[test]
+    fn test_equity_curve_round_trip() {
+        let manager = PersistenceManager::in_memory().unwrap();
+        let points: Vec<(i64, f64)> = (0..1000)
+            .map(|i| (1704067200 + i * 60, 100_000.0 + (i as f64 * 0.37).sin() * 500.0))
+            .collect();
+
+        manager.save_equity_curve("2024-01-01", &points).unwrap();
+        manager
+            .save_equity_curve("2024-01-02", &[(1704153600, 1.0)])
+            .unwrap();
+
+        assert_eq!(manager.load_equity_curve("2024-01-01").unwrap(), points);
+        assert_eq!(manager.load_equity_curve("2024-01-02").unwrap().len(), 1);
+        assert!(manager.load_equity_curve("2024-01-03").unwrap().is_empty());
+
+        manager.clear_session("2024-01-01").unwrap();
+        assert!(manager.load_equity_curve("2024-01-01").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_equity_curve_loads_in_timestamp_order() {
+        let manager = PersistenceManager::in_memory().unwrap();
+        manager
+            .save_equity_curve("2024-01-01", &[(30, 3.0), (10, 1.0), (20, 2.0)])
+            .unwrap();
+
+        assert_eq!(
+            manager.load_equity_curve("2024-01-01").unwrap(),
+            vec![(10, 1.0), (20, 2.0), (30, 3.0)]
+        );
+    }
+
+    #[test]
+    fn test_save_trades_batch() {
+        let manager = PersistenceManager::in_memory().unwrap();
+        let trades: Vec<TradeRecord> = (0..5000)
+            .map(|i| TradeRecord {
+                timestamp: 1704067200 + i,
+                direction: if i % 2 == 0 { 1 } else { -1 },
+                ..sample_trade()
+            })
+            .collect();
+
+        manager.save_trades_batch(&trades, "2024-01-01").unwrap();
+
+        assert_eq!(manager.get_trade_count("2024-01-01").unwrap(), 5000);
+        let recovered = manager.get_trades("2024-01-01").unwrap();
+        assert_eq!(recovered[4999].timestamp, 1704067200 + 4999);
+    }
+
+    #[test]
+    fn test_save_trades_batch_rolls_back_on_bad_row() {
+        let manager = PersistenceManager::in_memory().unwrap();
+        let mut trades = vec![sample_trade(); 10];
+        // SQLite stores NaN as NULL, violating `quantity NOT NULL`
+        trades[7].quantity = f64::NAN;
+
+        assert!(manager.save_trades_batch(&trades, "2024-01-01").is_err());
+        assert_eq!(manager.get_trade_count("2024-01-01").unwrap(), 0);
+
+        // The connection is usable again after the rollback
+        manager.save_trades_batch(&trades[..7], "2024-01-01").unwrap();
+        assert_eq!(manager.get_trade_count("2024-01-01").unwrap(), 7);
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_every_write() {
+        let manager = PersistenceManager::in_memory().unwrap();
+        manager.save_trade(&sample_trade(), "2024-01-01").unwrap();
+
+        let result = manager.transaction(|pm| {
+            pm.clear_session("2024-01-01")?;
+            pm.save_equity_curve("2024-01-01", &[(1, 100.0), (2, 101.0)])?;
+            pm.save_trades_batch(&vec![sample_trade(); 3], "2024-01-01")?;
+            Err::<(), _>(EngineError::database("checkpoint aborted"))
+        });
+        assert!(result.is_err());
+        assert_eq!(manager.get_trade_count("2024-01-01").unwrap(), 1);
+        assert!(manager.load_equity_curve("2024-01-01").unwrap().is_empty());
+
+        manager
+            .transaction(|pm| {
+                pm.clear_session("2024-01-01")?;
+                pm.save_trades_batch(&vec![sample_trade(); 3], "2024-01-01")
+            })
+            .unwrap();
+        assert_eq!(manager.get_trade_count("2024-01-01").unwrap(), 3);
+    }
+
+    /// Hold an exclusive lock on `path` from another connection, releasing it
+    /// after `hold`. Returns once the lock is acquired.
+    fn hold_exclusive_lock(path: &Path, hold: Duration) -> thread::JoinHandle<()> {
+        let path = path.to_path_buf();
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let handle = thread::spawn(move || {
+            let conn = Connection::open(path).unwrap();
+            conn.execute_batch("BEGIN EXCLUSIVE").unwrap();
+            locked_tx.send(()).unwrap();
+            thread::sleep(hold);
+            conn.execute_batch("COMMIT").unwrap();
+        });
+        locked_rx.recv().unwrap();
+        handle
+    }
+
+    fn sample_trade() -> TradeRecord {
+        TradeRecord {
+            timestamp: 1704067200,
+            symbol: "BTCUSDT".to_string(),
+            direction: 1,
+            quantity: 1.0,
+            price: 42000.0,
+            pnl: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_contended_write_succeeds_after_retry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("contended.db");
+        let mut manager = PersistenceManager::new(&path).unwrap();
+        manager
+            .set_retry_policy(RetryPolicy {
+                busy_timeout_ms: 0,
+                max_retries: 10,
+                initial_backoff_ms: 20,
+                max_backoff_ms: 100,
+            })
+            .unwrap();
+
+        let locker = hold_exclusive_lock(&path, Duration::from_millis(150));
+        manager.save_trade(&sample_trade(), "2024-01-01").unwrap();
+        locker.join().unwrap();
+
+        assert_eq!(manager.get_trade_count("2024-01-01").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_contended_write_fails_without_retry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("contended.db");
+        let mut manager = PersistenceManager::new(&path).unwrap();
+        manager
+            .set_retry_policy(RetryPolicy {
+                busy_timeout_ms: 0,
+                ..RetryPolicy::no_retry()
+            })
+            .unwrap();
+
+        let locker = hold_exclusive_lock(&path, Duration::from_millis(150));
+        let result = manager.save_trade(&sample_trade(), "2024-01-01");
+        locker.join().unwrap();
+
+        assert!(matches!(result, Err(EngineError::DatabaseError(_))));
+    }
+
+    #[test]
+    fn test_fatal_error_is_not_retried() {
+        let mut manager = PersistenceManager::in_memory().unwrap();
+        manager
+            .set_retry_policy(RetryPolicy {
+                busy_timeout_ms: 0,
+                max_retries: 5,
+                initial_backoff_ms: 1000,
+                max_backoff_ms: 1000,
+            })
+            .unwrap();
+        manager.conn.execute_batch("DROP TABLE trades").unwrap();
+
+        let start = std::time::Instant::now();
+        assert!(manager.save_trade(&sample_trade(), "2024-01-01").is_err());
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+
+    fn journal_mode(manager: &PersistenceManager) -> String {
+        manager
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_file_database_uses_wal_by_default() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("wal.db");
+        let manager = PersistenceManager::new(&path).unwrap();
+
+        assert_eq!(journal_mode(&manager), "wal");
+        assert_eq!(manager.retry_policy().busy_timeout_ms, 5000);
+        manager.save_trade(&sample_trade(), "2024-01-01").unwrap();
+        assert!(dir.path().join("wal.db-wal").exists());
+    }
+
+    #[test]
+    fn test_wal_can_be_disabled() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = PersistenceConfig {
+            wal: false,
+            busy_timeout_ms: 250,
+        };
+        let manager = PersistenceManager::with_config(dir.path().join("rollback.db"), config).unwrap();
+
+        assert_eq!(journal_mode(&manager), "delete");
+        assert_eq!(manager.retry_policy().busy_timeout_ms, 250);
+    }
+
+    #[test]
+    fn test_concurrent_writers_on_same_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("shared.db");
+        // Create the schema up front so both writers start from the same file.
+        PersistenceManager::new(&path).unwrap();
+
+        let writers: Vec<_> = (0..2)
+            .map(|_| {
+                let path = path.clone();
+                thread::spawn(move || {
+                    let manager = PersistenceManager::new(&path).unwrap();
+                    for _ in 0..200 {
+                        manager.save_trade(&sample_trade(), "2024-01-01").unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let manager = PersistenceManager::new(&path).unwrap();
+        assert_eq!(manager.get_trade_count("2024-01-01").unwrap(), 400);
+    }
+
+    #[test]
+    fn test_retry_backoff_is_capped() {
+        let policy = RetryPolicy {
+            busy_timeout_ms: 0,
+            max_retries: 10,
+            initial_backoff_ms: 10,
+            max_backoff_ms: 50,
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(10));
+        assert_eq!(policy.backoff(2), Duration::from_millis(40));
+        assert_eq!(policy.backoff(3), Duration::from_millis(50));
+        assert_eq!(policy.backoff(80), Duration::from_millis(50));
+    }
+
+    fn position_record(symbol: &str, quantity: f64) -> PositionRecord {
+        PositionRecord {
+            symbol: symbol.to_string(),
+            quantity,
+            average_price: 100.0,
+            unrealized_pnl: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_clean_match() {
+        let manager = PersistenceManager::in_memory().unwrap();
+        let session = "2024-01-01";
+        manager.save_position(&position_record("BTCUSDT", 2.0), session).unwrap();
+        manager.save_position(&position_record("ETHUSDT", -5.0), session).unwrap();
+        let recovered = manager.recover_state(session).unwrap();
+
+        let mut gateway = SimulatedGateway::default();
+        gateway.restore_position("BTCUSDT", 2.0, 100.0).unwrap();
+        gateway.restore_position("ETHUSDT", -5.0, 100.0).unwrap();
+
+        assert!(reconcile(&mut gateway, &recovered).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_reports_and_restores_mismatch() {
+        let manager = PersistenceManager::in_memory().unwrap();
+        let session = "2024-01-01";
+        manager.save_position(&position_record("BTCUSDT", 2.0), session).unwrap();
+        manager.save_position(&position_record("ETHUSDT", 3.0), session).unwrap();
+        let recovered = manager.recover_state(session).unwrap();
+
+        // Crash mid-write: BTC fill not persisted, ETH missing live, SOL never persisted
+        let mut gateway = SimulatedGateway::default();
+        gateway.restore_position("BTCUSDT", 3.0, 101.0).unwrap();
+        gateway.restore_position("SOLUSDT", 10.0, 20.0).unwrap();
+
+        let expected = vec![
+            Discrepancy { symbol: "BTCUSDT".to_string(), persisted_quantity: 2.0, live_quantity: 3.0 },
+            Discrepancy { symbol: "ETHUSDT".to_string(), persisted_quantity: 3.0, live_quantity: 0.0 },
+            Discrepancy { symbol: "SOLUSDT".to_string(), persisted_quantity: 0.0, live_quantity: 10.0 },
+        ];
+        assert_eq!(reconcile(&mut gateway, &recovered).unwrap(), expected);
+        // Report-only leaves the gateway untouched
+        assert_eq!(gateway.query_position("BTCUSDT").unwrap().quantity, 3.0);
+
+        assert_eq!(reconcile_and_restore(&mut gateway, &recovered).unwrap(), expected);
+        assert!(reconcile(&mut gateway, &recovered).unwrap().is_empty());
+        let btc = gateway.query_position("BTCUSDT").unwrap();
+        assert_eq!((btc.quantity, btc.average_price), (2.0, 100.0));
+        assert!(gateway.query_position("SOLUSDT").is_none());
+    }
 }
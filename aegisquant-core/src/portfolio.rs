@@ -0,0 +1,543 @@
+//! Multi-symbol backtesting.
+//!
+//! `MultiSymbolEngine` replays one tick stream per symbol against a single
+//! strategy, gateway and risk manager, so orders on every symbol draw on
+//! the same balance and pass the same capital checks.
+
+use std::collections::HashMap;
+
+use crate::engine::{annualized_sharpe, max_drawdown_pct};
+use crate::error::{EngineError, EngineResult};
+use crate::gateway::{Gateway, SimulatedGateway};
+use crate::risk::RiskManager;
+use crate::strategy::{sequence_signals, Signal, SignalOrdering, Strategy, SymbolSignal};
+use crate::types::{AccountStatus, BacktestResult, RiskConfig, Tick};
+
+/// Backtest engine for portfolio strategies trading several symbols.
+///
+/// The streams are merged by timestamp. All ticks sharing a timestamp reach
+/// the gateway and strategy before any order is placed, and the resulting
+/// signals are submitted in `SignalOrdering` order so runs are
+/// reproducible. The result covers the whole portfolio, with one equity
+/// point per timestamp.
+#[derive(Debug)]
+pub struct MultiSymbolEngine {
+    /// Strategy receiving every symbol's ticks
+    strategy: Box<dyn Strategy>,
+    /// Risk manager shared across symbols
+    risk_manager: RiskManager,
+    /// Gateway holding the shared balance and all positions
+    gateway: SimulatedGateway,
+    /// Tick stream per symbol
+    streams: HashMap<String, Vec<Tick>>,
+    /// Initial balance for PnL calculation
+    initial_balance: f64,
+    /// Submission order for signals sharing a timestamp
+    signal_ordering: SignalOrdering,
+    /// Portfolio equity after each timestamp
+    equity_curve: Vec<f64>,
+    /// Orders accepted by the gateway in the current run
+    total_trades: i32,
+    /// Orders whose closing fills realized a profit
+    winning_trades: i32,
+    /// Orders whose closing fills realized a loss
+    losing_trades: i32,
+    /// Timestamp of the first order accepted in the current run
+    first_trade_timestamp: Option<i64>,
+}
+
+impl MultiSymbolEngine {
+    /// Create an engine running `strategy` under `risk_config`.
+    pub fn new(strategy: Box<dyn Strategy>, risk_config: RiskConfig) -> Self {
+        Self {
+            strategy,
+            risk_manager: RiskManager::new(risk_config),
+            gateway: SimulatedGateway::new(100_000.0, 0.001, 0.0001),
+            streams: HashMap::new(),
+            initial_balance: 100_000.0,
+            signal_ordering: SignalOrdering::default(),
+            equity_curve: Vec::new(),
+            total_trades: 0,
+            winning_trades: 0,
+            losing_trades: 0,
+            first_trade_timestamp: None,
+        }
+    }
+
+    /// Create engine with custom initial balance.
+    pub fn with_initial_balance(mut self, balance: f64) -> Self {
+        self.initial_balance = balance;
+        self.gateway = SimulatedGateway::new(
+            balance,
+            self.gateway.slippage(),
+            self.gateway.commission_rate(),
+        );
+        self
+    }
+
+    /// Set the slippage rate applied to fills.
+    pub fn set_slippage(&mut self, slippage: f64) {
+        self.gateway.set_slippage(slippage);
+    }
+
+    /// Set the commission rate applied to fills.
+    pub fn set_commission(&mut self, commission_rate: f64) {
+        self.gateway.set_commission_rate(commission_rate);
+    }
+
+    /// Set the submission order for signals sharing a timestamp.
+    ///
+    /// Earlier orders draw on the shared balance and risk limits first.
+    /// Defaults to `SignalOrdering::Alphabetical`.
+    pub fn set_signal_ordering(&mut self, ordering: SignalOrdering) {
+        self.signal_ordering = ordering;
+    }
+
+    /// Set or replace the position limit for `symbol`.
+    ///
+    /// Overrides `max_position_size` for that symbol; see
+    /// `RiskManager::set_symbol_limit`.
+    pub fn set_symbol_limit(&mut self, symbol: &str, max_position: f64) {
+        self.risk_manager.set_symbol_limit(symbol, max_position);
+    }
+
+    /// Add (or replace) the tick stream for `symbol`.
+    ///
+    /// Ticks must be in timestamp order.
+    pub fn add_symbol(&mut self, symbol: &str, ticks: Vec<Tick>) -> EngineResult<()> {
+        if symbol.is_empty() {
+            return Err(EngineError::validation("Symbol must not be empty"));
+        }
+        if ticks.windows(2).any(|pair| pair[1].timestamp < pair[0].timestamp) {
+            return Err(EngineError::validation(format!(
+                "Ticks for {} are not in timestamp order",
+                symbol
+            )));
+        }
+        self.streams.insert(symbol.to_string(), ticks);
+        Ok(())
+    }
+
+    /// Symbols with a loaded tick stream, sorted.
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self.streams.keys().map(|s| s.as_str()).collect();
+        symbols.sort_unstable();
+        symbols
+    }
+
+    /// All loaded ticks as (symbol, tick), merged by timestamp.
+    ///
+    /// Ticks with equal timestamps are listed by symbol; the orders they
+    /// trigger are sequenced separately by the signal ordering.
+    pub fn merged_ticks(&self) -> Vec<(&str, Tick)> {
+        let mut merged: Vec<(&str, Tick)> = self
+            .symbols()
+            .into_iter()
+            .flat_map(|symbol| self.streams[symbol].iter().map(move |tick| (symbol, *tick)))
+            .collect();
+        // Stable sort keeps symbol order within a timestamp
+        merged.sort_by_key(|(_, tick)| tick.timestamp);
+        merged
+    }
+
+    /// Run the backtest over every loaded symbol.
+    pub fn run(&mut self) -> EngineResult<BacktestResult> {
+        if self.streams.values().all(|ticks| ticks.is_empty()) {
+            return Err(EngineError::validation("No data loaded"));
+        }
+        self.begin_run();
+
+        let merged: Vec<(String, Tick)> = self
+            .merged_ticks()
+            .into_iter()
+            .map(|(symbol, tick)| (symbol.to_string(), tick))
+            .collect();
+        for group in merged.chunk_by(|a, b| a.1.timestamp == b.1.timestamp) {
+            self.process_timestamp(group);
+        }
+
+        Ok(self.finish_run())
+    }
+
+    /// Get current account status.
+    pub fn get_account_status(&self) -> AccountStatus {
+        self.gateway.query_account()
+    }
+
+    /// Get the shared gateway.
+    pub fn gateway(&self) -> &SimulatedGateway {
+        &self.gateway
+    }
+
+    /// Portfolio equity after each timestamp of the last run.
+    pub fn equity_curve(&self) -> &[f64] {
+        &self.equity_curve
+    }
+
+    /// Reset run state before processing ticks.
+    fn begin_run(&mut self) {
+        self.strategy.reset();
+        self.gateway.reset();
+        self.risk_manager.initialize(self.initial_balance);
+        self.risk_manager.clear_throttle_history();
+        self.equity_curve.clear();
+        self.total_trades = 0;
+        self.winning_trades = 0;
+        self.losing_trades = 0;
+        self.first_trade_timestamp = None;
+    }
+
+    /// Dispatch the ticks sharing one timestamp and execute the resulting orders.
+    ///
+    /// Every tick updates the gateway and reaches the strategy first; the
+    /// signals are then sequenced with `sequence_signals` and submitted in
+    /// that order.
+    fn process_timestamp(&mut self, ticks: &[(String, Tick)]) {
+        let mut signals = Vec::new();
+        for (symbol, tick) in ticks {
+            self.gateway.update_tick(symbol, tick);
+            let signal = self.strategy.on_symbol_tick(symbol, tick);
+            if signal != Signal::None {
+                let strength = self.strategy.signal_strength(symbol);
+                signals.push(SymbolSignal::new(symbol.as_str(), signal, strength));
+            }
+        }
+        sequence_signals(&mut signals, self.signal_ordering);
+
+        for signal in &signals {
+            // The symbol's latest tick at this timestamp sets the price
+            if let Some((_, tick)) = ticks.iter().rev().find(|(symbol, _)| *symbol == signal.symbol) {
+                self.execute_signal(&signal.symbol, signal.signal, tick);
+            }
+        }
+
+        let account = self.gateway.query_account();
+        self.equity_curve.push(account.equity);
+        self.risk_manager.update_equity(account.equity);
+    }
+
+    /// Submit the order for one signal if it passes the risk checks.
+    fn execute_signal(&mut self, symbol: &str, signal: Signal, tick: &Tick) {
+        let Some(order) = self.strategy.generate_order(signal, symbol, tick.price) else {
+            return;
+        };
+        // Capital checks see the balance and positions of every symbol
+        let account = self.gateway.query_account();
+        let positions = self.gateway.query_positions().unwrap_or_default();
+        let current_qty = positions
+            .iter()
+            .find(|p| p.symbol_str() == order.symbol_str())
+            .map_or(0.0, |p| p.quantity);
+        let risk_check = self
+            .risk_manager
+            .check_with_position(&order, &account, tick.price, current_qty)
+            .and_then(|()| self.risk_manager.check_max_positions(&order, &positions));
+        let realized_before = self.realized_pnl(symbol);
+        if risk_check.is_ok() && self.gateway.submit_order(&order, tick.price).is_ok() {
+            self.total_trades += 1;
+            self.first_trade_timestamp.get_or_insert(tick.timestamp);
+            self.gateway.get_fills();
+
+            // The order's fills are all on this symbol, so the change in its
+            // realized PnL is what the order's closing fills made
+            let closed_pnl = self.realized_pnl(symbol) - realized_before;
+            if closed_pnl > 0.0 {
+                self.winning_trades += 1;
+            } else if closed_pnl < 0.0 {
+                self.losing_trades += 1;
+            }
+        }
+    }
+
+    /// Realized PnL of the position in `symbol` so far this run.
+    fn realized_pnl(&self, symbol: &str) -> f64 {
+        self.gateway.query_position(symbol).map_or(0.0, |p| p.realized_pnl)
+    }
+
+    /// Compute the portfolio result of the last run.
+    fn finish_run(&self) -> BacktestResult {
+        let final_equity = self.gateway.query_account().equity;
+        let returns: Vec<f64> = self
+            .equity_curve
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]) / pair[0])
+            .collect();
+
+        let total_return_pct = (final_equity - self.initial_balance) / self.initial_balance * 100.0;
+        let buy_and_hold_return_pct = self.calculate_buy_and_hold_return();
+
+        BacktestResult {
+            final_equity,
+            total_return_pct,
+            max_drawdown_pct: max_drawdown_pct(&self.equity_curve),
+            sharpe_ratio: annualized_sharpe(&returns),
+            total_trades: self.total_trades,
+            winning_trades: self.winning_trades,
+            losing_trades: self.losing_trades,
+            actual_start_bar: 0,
+            first_trade_timestamp: self.first_trade_timestamp.unwrap_or(0),
+            blown_up: 0,
+            stopped_at_bar: -1,
+            buy_and_hold_return_pct,
+            // There is no warmup, so the benchmark is the basket itself
+            benchmark_return_pct: buy_and_hold_return_pct,
+            alpha_pct: total_return_pct - buy_and_hold_return_pct,
+        }
+    }
+
+    /// Buy-and-hold return of an equal-weight basket of the symbols, as a percentage.
+    ///
+    /// Each symbol gets an equal share of the initial balance at its first
+    /// tick and is sold at its last, paying the commission on both legs.
+    fn calculate_buy_and_hold_return(&self) -> f64 {
+        let commission = self.gateway.commission_rate();
+        let growths: Vec<f64> = self
+            .streams
+            .values()
+            .filter_map(|ticks| match (ticks.first(), ticks.last()) {
+                (Some(first), Some(last)) if first.price > 0.0 => {
+                    Some((last.price / first.price) * (1.0 - commission) / (1.0 + commission))
+                }
+                _ => None,
+            })
+            .collect();
+        if growths.is_empty() {
+            return 0.0;
+        }
+        (growths.iter().sum::<f64>() / growths.len() as f64 - 1.0) * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OrderRequest, DIRECTION_BUY, DIRECTION_SELL};
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+
+    /// (symbol, timestamp) of each tick the strategy saw, in order.
+    type Dispatches = Arc<Mutex<Vec<(String, i64)>>>;
+
+    /// Buys `quantity` of each symbol on its first tick and records dispatch order.
+    struct BuyEachOnce {
+        quantity: f64,
+        bought: HashSet<String>,
+        seen: Dispatches,
+        strengths: HashMap<String, f64>,
+    }
+
+    impl Strategy for BuyEachOnce {
+        fn on_tick(&mut self, _tick: &Tick) -> Signal {
+            Signal::None
+        }
+
+        fn on_symbol_tick(&mut self, symbol: &str, tick: &Tick) -> Signal {
+            self.seen.lock().unwrap().push((symbol.to_string(), tick.timestamp));
+            if self.bought.insert(symbol.to_string()) {
+                Signal::Buy
+            } else {
+                Signal::None
+            }
+        }
+
+        fn signal_strength(&self, symbol: &str) -> f64 {
+            self.strengths.get(symbol).copied().unwrap_or(0.0)
+        }
+
+        fn generate_order(&self, signal: Signal, symbol: &str, _price: f64) -> Option<OrderRequest> {
+            (signal == Signal::Buy).then(|| {
+                let mut order = OrderRequest::with_symbol(symbol);
+                order.quantity = self.quantity;
+                order.direction = DIRECTION_BUY;
+                order
+            })
+        }
+
+        fn reset(&mut self) {
+            self.bought.clear();
+            self.seen.lock().unwrap().clear();
+        }
+
+        fn name(&self) -> &str {
+            "BuyEachOnce"
+        }
+    }
+
+    fn ticks(start: i64, prices: &[f64]) -> Vec<Tick> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, &price)| Tick { timestamp: start + i as i64 * 10, price, volume: 100.0 })
+            .collect()
+    }
+
+    fn engine(quantity: f64, balance: f64) -> (MultiSymbolEngine, Dispatches) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let strategy = BuyEachOnce {
+            quantity,
+            bought: HashSet::new(),
+            seen: Arc::clone(&seen),
+            strengths: HashMap::from([("ETHUSDT".to_string(), 2.0), ("BTCUSDT".to_string(), 1.0)]),
+        };
+        let risk = RiskConfig {
+            max_order_value: 1e9,
+            max_position_size: 1e6,
+            max_drawdown_pct: 1.0,
+            ..Default::default()
+        };
+        let mut engine = MultiSymbolEngine::new(Box::new(strategy), risk).with_initial_balance(balance);
+        engine.set_slippage(0.0);
+        engine.set_commission(0.0);
+        // Correlated streams: ETH moves with BTC at a tenth of the price
+        engine.add_symbol("BTCUSDT", ticks(0, &[1000.0, 1010.0, 1020.0])).unwrap();
+        engine.add_symbol("ETHUSDT", ticks(5, &[100.0, 101.0, 102.0])).unwrap();
+        (engine, seen)
+    }
+
+    #[test]
+    fn test_streams_are_merged_by_timestamp() {
+        let (mut engine, seen) = engine(1.0, 100_000.0);
+        engine.add_symbol("AAA", ticks(0, &[50.0])).unwrap();
+        engine.run().unwrap();
+
+        let seen = seen.lock().unwrap().clone();
+        let expected: Vec<(String, i64)> = [
+            ("AAA", 0), ("BTCUSDT", 0), ("ETHUSDT", 5), ("BTCUSDT", 10),
+            ("ETHUSDT", 15), ("BTCUSDT", 20), ("ETHUSDT", 25),
+        ]
+        .iter()
+        .map(|&(s, t)| (s.to_string(), t))
+        .collect();
+        assert_eq!(seen, expected);
+        // AAA and BTCUSDT share timestamp 0
+        assert_eq!(engine.equity_curve().len(), 6);
+    }
+
+    #[test]
+    fn test_shared_balance_across_symbols() {
+        let (mut engine, _) = engine(10.0, 20_000.0);
+        let result = engine.run().unwrap();
+
+        // Both buys draw on the one balance: 10 * 1000 + 10 * 100
+        assert_eq!(result.total_trades, 2);
+        let account = engine.get_account_status();
+        assert!((account.balance - 9_000.0).abs() < 1e-9);
+        assert_eq!(account.position_count, 2);
+
+        // Unrealized PnL of both positions: 10 * 20 + 10 * 2
+        assert!((account.total_pnl - 220.0).abs() < 1e-9);
+        assert!((result.final_equity - (account.balance + 220.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_capital_check_spans_symbols() {
+        // BTC's buy leaves too little balance for ETH's
+        let (mut engine, _) = engine(10.0, 10_500.0);
+        let result = engine.run().unwrap();
+
+        assert_eq!(result.total_trades, 1);
+        let positions = engine.gateway().query_positions().unwrap();
+        let held: Vec<&str> = positions
+            .iter()
+            .filter(|p| p.quantity > 0.0)
+            .map(|p| p.symbol_str())
+            .collect();
+        assert_eq!(held, vec!["BTCUSDT"]);
+        assert!((engine.get_account_status().balance - 500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_signal_ordering_decides_simultaneous_orders() {
+        // Both symbols signal at timestamp 0, but the balance covers only one buy
+        let held = |ordering: SignalOrdering| {
+            let (mut engine, _) = engine(10.0, 10_500.0);
+            engine.add_symbol("ETHUSDT", ticks(0, &[100.0, 101.0, 102.0])).unwrap();
+            engine.set_signal_ordering(ordering);
+            assert_eq!(engine.run().unwrap().total_trades, 1);
+            let positions = engine.gateway().query_positions().unwrap();
+            positions
+                .iter()
+                .filter(|p| p.quantity > 0.0)
+                .map(|p| p.symbol_str().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(held(SignalOrdering::Alphabetical), vec!["BTCUSDT"]);
+        assert_eq!(held(SignalOrdering::ByStrength), vec!["ETHUSDT"]);
+    }
+
+    #[test]
+    fn test_symbol_limit_uses_real_position() {
+        // Far below max_position_size, but over BTC's own limit
+        let (mut engine, _) = engine(10.0, 100_000.0);
+        engine.set_symbol_limit("BTCUSDT", 5.0);
+        let result = engine.run().unwrap();
+
+        assert_eq!(result.total_trades, 1);
+        let btc = engine.gateway().query_position("BTCUSDT");
+        assert!(btc.is_none_or(|p| p.quantity == 0.0));
+        assert!((engine.gateway().query_position("ETHUSDT").unwrap().quantity - 10.0).abs() < 1e-9);
+    }
+
+    /// Trades one unit of each symbol on scripted (symbol, timestamp) signals.
+    struct Scripted {
+        signals: HashMap<(String, i64), Signal>,
+    }
+
+    impl Strategy for Scripted {
+        fn on_tick(&mut self, _tick: &Tick) -> Signal {
+            Signal::None
+        }
+
+        fn on_symbol_tick(&mut self, symbol: &str, tick: &Tick) -> Signal {
+            self.signals
+                .get(&(symbol.to_string(), tick.timestamp))
+                .copied()
+                .unwrap_or(Signal::None)
+        }
+
+        fn generate_order(&self, signal: Signal, symbol: &str, _price: f64) -> Option<OrderRequest> {
+            let mut order = OrderRequest::with_symbol(symbol);
+            order.quantity = 1.0;
+            order.direction = match signal {
+                Signal::Buy => DIRECTION_BUY,
+                Signal::Sell => DIRECTION_SELL,
+                _ => return None,
+            };
+            Some(order)
+        }
+
+        fn reset(&mut self) {}
+
+        fn name(&self) -> &str {
+            "Scripted"
+        }
+    }
+
+    #[test]
+    fn test_wins_and_losses_use_each_closing_order() {
+        // +10 on the first round trip, then -5: the second close still
+        // leaves the symbol's cumulative realized PnL positive
+        let script = [(0, Signal::Buy), (10, Signal::Sell), (20, Signal::Buy), (30, Signal::Sell)];
+        let strategy = Scripted {
+            signals: script.iter().map(|&(t, signal)| (("BTCUSDT".to_string(), t), signal)).collect(),
+        };
+        let risk = RiskConfig { max_order_value: 1e9, max_order_rate: 100, ..Default::default() };
+        let mut engine = MultiSymbolEngine::new(Box::new(strategy), risk);
+        engine.set_slippage(0.0);
+        engine.set_commission(0.0);
+        engine.add_symbol("BTCUSDT", ticks(0, &[100.0, 110.0, 110.0, 105.0])).unwrap();
+        let result = engine.run().unwrap();
+
+        assert_eq!(result.total_trades, 4);
+        assert_eq!(result.winning_trades, 1);
+        assert_eq!(result.losing_trades, 1);
+    }
+
+    #[test]
+    fn test_run_without_data_fails() {
+        let (mut engine, _) = engine(1.0, 1_000.0);
+        engine.streams.clear();
+        assert!(engine.run().is_err());
+    }
+}
@@ -6,6 +6,8 @@
 //! - `AccountBalance` struct using rust_decimal for precise accounting
 //! - `Price` and `Quantity` type aliases for L1 OrderBook
 //! - `spread_bps()` function for calculating bid-ask spread in basis points
+//! - `round_to_tick()` / `round_to_lot()` banker's rounding, also exposed via FFI
+//! - `round_to_decimals()` for rounding reported values to a display precision
 //!
 //! Requirements: 3.1, 3.2, 3.3, 3.4, 3.5
 
@@ -78,6 +80,67 @@ pub fn spread_bps(bid: Price, ask: Price) -> f64 {
     spread / mid_price * 10000.0
 }
 
+/// Round a value to the nearest multiple of `increment` using banker's rounding.
+///
+/// The value is converted to `Decimal` via its shortest decimal representation,
+/// so ties such as 100.125 with increment 0.01 resolve to the even multiple (100.12).
+/// Non-finite values and non-positive increments are returned unchanged.
+fn round_half_even_to(value: f64, increment: f64) -> f64 {
+    if !value.is_finite() || !increment.is_finite() || increment <= 0.0 {
+        return value;
+    }
+
+    let (Some(v), Some(inc)) = (Decimal::from_f64(value), Decimal::from_f64(increment)) else {
+        return value;
+    };
+    if inc.is_zero() {
+        return value;
+    }
+
+    let steps = (v / inc).round_dp_with_strategy(0, RoundingStrategy::MidpointNearestEven);
+    (steps * inc).to_f64().unwrap_or(value)
+}
+
+/// Round a price to the nearest tick using banker's rounding (half-even).
+///
+/// # Arguments
+/// * `price` - Price to round
+/// * `tick_size` - Minimum price increment (values <= 0 disable rounding)
+#[inline]
+pub fn round_to_tick(price: Price, tick_size: f64) -> Price {
+    round_half_even_to(price, tick_size)
+}
+
+/// Round a quantity to the nearest lot using banker's rounding (half-even).
+///
+/// # Arguments
+/// * `quantity` - Quantity to round
+/// * `lot_size` - Minimum quantity increment (values <= 0 disable rounding)
+#[inline]
+pub fn round_to_lot(quantity: Quantity, lot_size: f64) -> Quantity {
+    round_half_even_to(quantity, lot_size)
+}
+
+/// Round a value to a number of decimal places using banker's rounding (half-even).
+///
+/// Intended for reported values only; accounting should keep full precision.
+/// The value is converted to `Decimal` via its shortest decimal representation,
+/// so float noise such as 50000.00000000007 rounds to 50000.0. Non-finite
+/// values are returned unchanged.
+pub fn round_to_decimals(value: f64, decimals: u32) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+
+    match Decimal::from_f64(value) {
+        Some(v) => v
+            .round_dp_with_strategy(decimals, RoundingStrategy::MidpointNearestEven)
+            .to_f64()
+            .unwrap_or(value),
+        None => value,
+    }
+}
+
 /// Account balance using rust_decimal for precise financial calculations.
 ///
 /// This struct ensures no cumulative floating-point errors in account balance
@@ -214,6 +277,47 @@ impl AccountBalance {
     }
 }
 
+// ============================================================================
+// FFI Functions
+// ============================================================================
+
+/// Round a price to the nearest tick (FFI).
+///
+/// Uses the same banker's rounding as `round_to_tick`, so C# and the engine
+/// agree at the last decimal.
+#[no_mangle]
+pub extern "C" fn round_price_ffi(value: f64, tick_size: f64) -> f64 {
+    round_to_tick(value, tick_size)
+}
+
+/// Round a quantity to the nearest lot (FFI).
+///
+/// Uses the same banker's rounding as `round_to_lot`.
+#[no_mangle]
+pub extern "C" fn round_quantity_ffi(value: f64, lot_size: f64) -> f64 {
+    round_to_lot(value, lot_size)
+}
+
+/// Compare two prices using PRICE_EPSILON (FFI).
+///
+/// # Returns
+/// - 1 if equal within PRICE_EPSILON
+/// - 0 otherwise
+#[no_mangle]
+pub extern "C" fn price_eq_ffi(a: f64, b: f64) -> i32 {
+    price_eq(a, b) as i32
+}
+
+/// Compare two quantities using QUANTITY_EPSILON (FFI).
+///
+/// # Returns
+/// - 1 if equal within QUANTITY_EPSILON
+/// - 0 otherwise
+#[no_mangle]
+pub extern "C" fn quantity_eq_ffi(a: f64, b: f64) -> i32 {
+    quantity_eq(a, b) as i32
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,6 +354,67 @@ mod tests {
         assert_eq!(spread_bps(-1.0, 1.0), 0.0);
     }
 
+    #[test]
+    fn test_round_to_tick_half_even() {
+        assert_eq!(round_to_tick(100.125, 0.01), 100.12);
+        assert_eq!(round_to_tick(100.135, 0.01), 100.14);
+        assert_eq!(round_to_tick(100.025, 0.05), 100.0);
+        assert_eq!(round_to_tick(100.075, 0.05), 100.1);
+        assert_eq!(round_to_tick(100.13, 0.25), 100.25);
+        assert_eq!(round_to_tick(-2.5, 1.0), -2.0);
+    }
+
+    #[test]
+    fn test_round_to_lot_half_even() {
+        assert_eq!(round_to_lot(0.5, 1.0), 0.0);
+        assert_eq!(round_to_lot(1.5, 1.0), 2.0);
+        assert_eq!(round_to_lot(150.0, 100.0), 200.0);
+        assert_eq!(round_to_lot(250.0, 100.0), 200.0);
+        assert_eq!(round_to_lot(0.0015, 0.001), 0.002);
+    }
+
+    #[test]
+    fn test_round_to_decimals() {
+        assert_eq!(round_to_decimals(50000.00000000007, 2), 50000.0);
+        assert_eq!(round_to_decimals(0.1 + 0.2, 2), 0.3);
+        assert_eq!(round_to_decimals(1.005, 2), 1.0);
+        assert_eq!(round_to_decimals(-12.3456, 3), -12.346);
+        assert_eq!(round_to_decimals(2.5, 0), 2.0);
+        assert!(round_to_decimals(f64::INFINITY, 2).is_infinite());
+    }
+
+    #[test]
+    fn test_round_invalid_increment() {
+        assert_eq!(round_to_tick(100.123, 0.0), 100.123);
+        assert_eq!(round_to_lot(3.7, -1.0), 3.7);
+        assert!(round_to_tick(f64::NAN, 0.01).is_nan());
+    }
+
+    #[test]
+    fn test_ffi_rounding_matches_internal() {
+        let cases = [
+            (100.125, 0.01),
+            (100.135, 0.01),
+            (100.025, 0.05),
+            (0.5, 1.0),
+            (2.5, 1.0),
+            (99.995, 0.01),
+            (1e-9, 0.01),
+        ];
+        for (value, increment) in cases {
+            assert_eq!(round_price_ffi(value, increment), round_to_tick(value, increment));
+            assert_eq!(round_quantity_ffi(value, increment), round_to_lot(value, increment));
+        }
+    }
+
+    #[test]
+    fn test_ffi_equality() {
+        assert_eq!(price_eq_ffi(100.0, 100.0 + 1e-11), 1);
+        assert_eq!(price_eq_ffi(100.0, 100.1), 0);
+        assert_eq!(quantity_eq_ffi(1000.0, 1000.0 + 1e-9), 1);
+        assert_eq!(quantity_eq_ffi(1000.0, 1000.1), 0);
+    }
+
     #[test]
     fn test_account_balance_new() {
         let balance = AccountBalance::new(Decimal::from(1000000));
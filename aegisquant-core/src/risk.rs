@@ -2,15 +2,20 @@
 //!
 //! Implements pre-trade risk checks including:
 //! - Capital adequacy check
-//! - Order rate throttling
-//! - Position limit enforcement
+//! - Single-order notional cap (fat-finger protection)
+//! - Order rate and notional-per-second throttling
+//! - Position limit enforcement (global or per symbol)
+//! - Open position count cap
 //! - Maximum drawdown protection
+//! - Daily loss kill-switch (trips the emergency stop)
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::Instant;
 use thiserror::Error;
 
-use crate::types::{AccountStatus, OrderRequest, RiskConfig};
+use crate::emergency::{activate_emergency_stop, HaltReason};
+use crate::precision::QUANTITY_EPSILON;
+use crate::types::{AccountStatus, OrderRequest, Position, RiskConfig};
 
 /// Risk check error types with specific rejection reasons.
 #[derive(Debug, Error, Clone, PartialEq)]
@@ -18,14 +23,26 @@ pub enum RiskError {
     #[error("Insufficient capital: required {required:.2}, available {available:.2}")]
     InsufficientCapital { required: f64, available: f64 },
 
+    #[error("Max order value exceeded: order value {value:.2} > max {max:.2}")]
+    MaxOrderValueExceeded { value: f64, max: f64 },
+
     #[error("Order rate exceeded: {current} orders/sec, max {max}")]
     ThrottleExceeded { current: i32, max: i32 },
 
+    #[error("Notional rate exceeded: {current:.2} traded in the last second + order {order:.2} > max {max:.2}")]
+    NotionalThrottleExceeded { current: f64, order: f64, max: f64 },
+
     #[error("Position limit exceeded: current {current:.2} + order {order:.2} > max {max:.2}")]
     PositionLimitExceeded { current: f64, order: f64, max: f64 },
 
     #[error("Max drawdown exceeded: current {current:.2}% > max {max:.2}%")]
     MaxDrawdownExceeded { current: f64, max: f64 },
+
+    #[error("Max open positions reached: {current} open, max {max}")]
+    MaxOpenPositionsExceeded { current: i32, max: i32 },
+
+    #[error("Daily loss exceeded: lost {loss:.2} since session start, max {max:.2}")]
+    DailyLossExceeded { loss: f64, max: f64 },
 }
 
 impl RiskError {
@@ -33,9 +50,13 @@ impl RiskError {
     pub fn to_error_code(&self) -> i32 {
         match self {
             RiskError::InsufficientCapital { .. } => crate::ffi::ERR_INSUFFICIENT_CAPITAL,
+            RiskError::MaxOrderValueExceeded { .. } => crate::ffi::ERR_MAX_ORDER_VALUE,
             RiskError::ThrottleExceeded { .. } => crate::ffi::ERR_THROTTLE_EXCEEDED,
+            RiskError::NotionalThrottleExceeded { .. } => crate::ffi::ERR_THROTTLE_EXCEEDED,
             RiskError::PositionLimitExceeded { .. } => crate::ffi::ERR_POSITION_LIMIT,
             RiskError::MaxDrawdownExceeded { .. } => crate::ffi::ERR_RISK_REJECTED,
+            RiskError::MaxOpenPositionsExceeded { .. } => crate::ffi::ERR_POSITION_LIMIT,
+            RiskError::DailyLossExceeded { .. } => crate::ffi::ERR_RISK_REJECTED,
         }
     }
 }
@@ -44,19 +65,31 @@ impl RiskError {
 ///
 /// Performs multiple risk checks before allowing order execution:
 /// 1. Capital check - ensures sufficient funds
-/// 2. Throttle check - rate limits orders per second
-/// 3. Position limit check - prevents over-concentration
-/// 4. Drawdown check - stops trading on excessive losses
+/// 2. Max order value check - caps single-order notional
+/// 3. Throttle check - rate limits orders and notional per second
+/// 4. Position limit check - prevents over-concentration
+/// 5. Drawdown check - stops trading on excessive losses
+/// 6. Daily loss check - halts all trading once the session loss limit is hit
 #[derive(Debug)]
 pub struct RiskManager {
     /// Risk configuration parameters
     config: RiskConfig,
-    /// Timestamps of recent orders for throttle calculation
-    order_timestamps: VecDeque<Instant>,
+    /// Timestamp and notional of recent orders for throttle calculation
+    order_window: VecDeque<(Instant, f64)>,
+    /// Sum of the notionals in `order_window`
+    window_notional: f64,
     /// Peak equity value for drawdown calculation
     peak_equity: f64,
     /// Initial equity for drawdown calculation
     initial_equity: f64,
+    /// Per-symbol position limits overriding `max_position_size`
+    symbol_limits: HashMap<String, f64>,
+    /// Equity at the start of the current session
+    session_start_equity: f64,
+    /// Whether the daily loss limit has tripped the emergency stop this session
+    daily_loss_tripped: bool,
+    /// Account leverage; `check_capital` requires `notional / leverage`
+    leverage: f64,
 }
 
 impl RiskManager {
@@ -64,16 +97,74 @@ impl RiskManager {
     pub fn new(config: RiskConfig) -> Self {
         Self {
             config,
-            order_timestamps: VecDeque::with_capacity(config.max_order_rate as usize + 1),
+            order_window: VecDeque::with_capacity(config.max_order_rate as usize + 1),
+            window_notional: 0.0,
             peak_equity: 0.0,
             initial_equity: 0.0,
+            symbol_limits: HashMap::new(),
+            session_start_equity: 0.0,
+            daily_loss_tripped: false,
+            leverage: 1.0,
         }
     }
 
+    /// Check capital against a leveraged (margin) account.
+    ///
+    /// Match the gateway's leverage. Values below 1 are treated as 1.
+    pub fn with_leverage(mut self, leverage: f64) -> Self {
+        self.set_leverage(leverage);
+        self
+    }
+
+    /// Set the leverage used by `check_capital`. Values below 1 are treated as 1.
+    pub fn set_leverage(&mut self, leverage: f64) {
+        self.leverage = if leverage.is_finite() { leverage.max(1.0) } else { 1.0 };
+    }
+
+    /// Get the leverage used by `check_capital`.
+    pub fn leverage(&self) -> f64 {
+        self.leverage
+    }
+
+    /// Limit the absolute position in `symbol`, overriding `max_position_size`.
+    pub fn with_symbol_limit(mut self, symbol: &str, max_position: f64) -> Self {
+        self.set_symbol_limit(symbol, max_position);
+        self
+    }
+
+    /// Set or replace the position limit for `symbol`.
+    pub fn set_symbol_limit(&mut self, symbol: &str, max_position: f64) {
+        self.symbol_limits.insert(symbol.to_string(), max_position);
+    }
+
+    /// Position limit for `symbol`: its own limit, else `max_position_size`.
+    pub fn position_limit_for(&self, symbol: &str) -> f64 {
+        self.symbol_limits
+            .get(symbol)
+            .copied()
+            .unwrap_or(self.config.max_position_size)
+    }
+
     /// Initialize the risk manager with starting equity.
     pub fn initialize(&mut self, initial_equity: f64) {
         self.initial_equity = initial_equity;
         self.peak_equity = initial_equity;
+        self.reset_session(initial_equity);
+    }
+
+    /// Start a new session with `equity` as the daily loss baseline.
+    ///
+    /// `BacktestEngine` calls this at each configured session end. Re-arms the
+    /// daily loss kill-switch. The emergency stop itself stays
+    /// active until `emergency::reset_emergency_stop` is called.
+    pub fn reset_session(&mut self, equity: f64) {
+        self.session_start_equity = equity;
+        self.daily_loss_tripped = false;
+    }
+
+    /// Get the equity at the start of the current session.
+    pub fn session_start_equity(&self) -> f64 {
+        self.session_start_equity
     }
 
     /// Update peak equity for drawdown tracking.
@@ -100,15 +191,39 @@ impl RiskManager {
         current_price: f64,
     ) -> Result<(), RiskError> {
         self.check_capital(order, account, current_price)?;
-        self.check_throttle()?;
+        self.check_max_order_value(order, current_price)?;
+        self.check_throttle_notional(order.quantity.abs() * current_price)?;
         self.check_position_limit(order, account)?;
         self.check_drawdown(account)?;
+        self.check_daily_loss(account, self.session_start_equity)?;
+        Ok(())
+    }
+
+    /// Perform all risk checks on an order against its symbol's position.
+    ///
+    /// Same as `check`, except the position limit is checked with
+    /// `check_position_limit_for_symbol` against `current_qty`, the signed
+    /// position in the order's symbol before the order, instead of being
+    /// estimated from the position count.
+    pub fn check_with_position(
+        &mut self,
+        order: &OrderRequest,
+        account: &AccountStatus,
+        current_price: f64,
+        current_qty: f64,
+    ) -> Result<(), RiskError> {
+        self.check_capital(order, account, current_price)?;
+        self.check_max_order_value(order, current_price)?;
+        self.check_throttle_notional(order.quantity.abs() * current_price)?;
+        self.check_position_limit_for_symbol(order, current_qty, order.symbol_str())?;
+        self.check_drawdown(account)?;
+        self.check_daily_loss(account, self.session_start_equity)?;
         Ok(())
     }
 
     /// Check if account has sufficient capital for the order.
     ///
-    /// Calculates order value as: quantity * price
+    /// Calculates order value as: quantity * price / leverage
     /// Rejects if order_value > available_balance
     pub fn check_capital(
         &self,
@@ -116,7 +231,7 @@ impl RiskManager {
         account: &AccountStatus,
         current_price: f64,
     ) -> Result<(), RiskError> {
-        let order_value = order.quantity.abs() * current_price;
+        let order_value = order.quantity.abs() * current_price / self.leverage;
 
         if order_value > account.available {
             return Err(RiskError::InsufficientCapital {
@@ -125,11 +240,24 @@ impl RiskManager {
             });
         }
 
-        // Also check against max_order_value config
+        Ok(())
+    }
+
+    /// Check the order's notional against the single-order cap.
+    ///
+    /// Rejects if quantity * price > max_order_value, regardless of
+    /// available funds.
+    pub fn check_max_order_value(
+        &self,
+        order: &OrderRequest,
+        current_price: f64,
+    ) -> Result<(), RiskError> {
+        let order_value = order.quantity.abs() * current_price;
+
         if order_value > self.config.max_order_value {
-            return Err(RiskError::InsufficientCapital {
-                required: order_value,
-                available: self.config.max_order_value,
+            return Err(RiskError::MaxOrderValueExceeded {
+                value: order_value,
+                max: self.config.max_order_value,
             });
         }
 
@@ -140,20 +268,37 @@ impl RiskManager {
     ///
     /// Uses a sliding window of 1 second to count recent orders.
     /// Rejects if orders in the last second >= max_order_rate.
+    /// The order is recorded with zero notional.
     pub fn check_throttle(&mut self) -> Result<(), RiskError> {
+        self.check_throttle_notional(0.0)
+    }
+
+    /// Check order rate and notional-per-second throttling.
+    ///
+    /// Uses the same 1-second sliding window as `check_throttle`. Rejects if
+    /// orders in the last second >= max_order_rate, or if the notional traded
+    /// in the last second plus `notional` would exceed
+    /// max_notional_per_second (disabled when 0). Only accepted orders are
+    /// recorded.
+    pub fn check_throttle_notional(&mut self, notional: f64) -> Result<(), RiskError> {
         let now = Instant::now();
         let one_second_ago = now - std::time::Duration::from_secs(1);
 
-        // Remove timestamps older than 1 second
-        while let Some(&front) = self.order_timestamps.front() {
+        // Remove orders older than 1 second, keeping the notional sum in step
+        while let Some(&(front, front_notional)) = self.order_window.front() {
             if front < one_second_ago {
-                self.order_timestamps.pop_front();
+                self.order_window.pop_front();
+                self.window_notional -= front_notional;
             } else {
                 break;
             }
         }
+        if self.order_window.is_empty() {
+            // Drop accumulated rounding error
+            self.window_notional = 0.0;
+        }
 
-        let current_rate = self.order_timestamps.len() as i32;
+        let current_rate = self.order_window.len() as i32;
 
         if current_rate >= self.config.max_order_rate {
             return Err(RiskError::ThrottleExceeded {
@@ -162,8 +307,18 @@ impl RiskManager {
             });
         }
 
-        // Record this order timestamp
-        self.order_timestamps.push_back(now);
+        let max_notional = self.config.max_notional_per_second;
+        if max_notional > 0.0 && self.window_notional + notional > max_notional {
+            return Err(RiskError::NotionalThrottleExceeded {
+                current: self.window_notional,
+                order: notional,
+                max: max_notional,
+            });
+        }
+
+        // Record this order
+        self.order_window.push_back((now, notional));
+        self.window_notional += notional;
 
         Ok(())
     }
@@ -196,6 +351,65 @@ impl RiskManager {
         Ok(())
     }
 
+    /// Check the post-order position in one symbol against its limit.
+    ///
+    /// `current_qty` is the signed position in `symbol` before the order.
+    /// Rejects if the absolute post-order quantity exceeds
+    /// `position_limit_for(symbol)`; orders that shrink the position always
+    /// pass, so an over-limit position can still be reduced.
+    pub fn check_position_limit_for_symbol(
+        &self,
+        order: &OrderRequest,
+        current_qty: f64,
+        symbol: &str,
+    ) -> Result<(), RiskError> {
+        let order_quantity = order.quantity.abs();
+        let post_qty = current_qty + order_quantity * order.direction.signum() as f64;
+        let max = self.position_limit_for(symbol);
+
+        if post_qty.abs() > max + QUANTITY_EPSILON && post_qty.abs() > current_qty.abs() {
+            return Err(RiskError::PositionLimitExceeded {
+                current: current_qty,
+                order: order_quantity,
+                max,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Check the cap on the number of open positions.
+    ///
+    /// Only orders that would open a flat symbol count against the cap;
+    /// trades in symbols already open (adding, reducing or closing) always
+    /// pass, so closing a position frees a slot. Disabled when
+    /// max_open_positions is 0.
+    pub fn check_max_positions(
+        &self,
+        order: &OrderRequest,
+        positions: &[Position],
+    ) -> Result<(), RiskError> {
+        let max = self.config.max_open_positions;
+        if max <= 0 || order.quantity.abs() < QUANTITY_EPSILON {
+            return Ok(());
+        }
+
+        let symbol = order.symbol_str();
+        let mut open = 0;
+        for position in positions.iter().filter(|p| p.quantity.abs() >= QUANTITY_EPSILON) {
+            if position.symbol_str() == symbol {
+                return Ok(());
+            }
+            open += 1;
+        }
+
+        if open >= max {
+            return Err(RiskError::MaxOpenPositionsExceeded { current: open, max });
+        }
+
+        Ok(())
+    }
+
     /// Check maximum drawdown.
     ///
     /// Calculates current drawdown from peak equity.
@@ -217,6 +431,40 @@ impl RiskManager {
         Ok(())
     }
 
+    /// Check the session loss against max_daily_loss.
+    ///
+    /// Rejects once equity has fallen more than max_daily_loss below
+    /// `session_start_equity`. The first rejection in a session activates the
+    /// global emergency stop; later ones only reject. Disabled when
+    /// max_daily_loss is 0.
+    ///
+    /// The emergency stop is process-wide: it halts every engine and gateway
+    /// in the process, not just the one whose limit tripped, and stays set
+    /// until `emergency::reset_emergency_stop`. `BacktestEngine` does not read
+    /// it; its own orders are blocked by the rejection. Leave max_daily_loss
+    /// at 0 for optimizer runs that share a process.
+    pub fn check_daily_loss(
+        &mut self,
+        account: &AccountStatus,
+        session_start_equity: f64,
+    ) -> Result<(), RiskError> {
+        let max = self.config.max_daily_loss;
+        if max <= 0.0 {
+            return Ok(());
+        }
+
+        let loss = session_start_equity - account.equity;
+        if loss > max {
+            if !self.daily_loss_tripped {
+                self.daily_loss_tripped = true;
+                activate_emergency_stop(HaltReason::DailyLoss);
+            }
+            return Err(RiskError::DailyLossExceeded { loss, max });
+        }
+
+        Ok(())
+    }
+
     /// Get the current configuration.
     pub fn config(&self) -> &RiskConfig {
         &self.config
@@ -229,12 +477,18 @@ impl RiskManager {
 
     /// Get the number of orders in the current throttle window.
     pub fn current_order_rate(&self) -> i32 {
-        self.order_timestamps.len() as i32
+        self.order_window.len() as i32
+    }
+
+    /// Get the notional traded in the current throttle window.
+    pub fn current_notional_rate(&self) -> f64 {
+        self.window_notional
     }
 
     /// Clear throttle history (useful for testing).
     pub fn clear_throttle_history(&mut self) {
-        self.order_timestamps.clear();
+        self.order_window.clear();
+        self.window_notional = 0.0;
     }
 }
 
@@ -247,6 +501,7 @@ impl Default for RiskManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
 
     fn create_test_account(available: f64, equity: f64) -> AccountStatus {
         AccountStatus {
@@ -292,6 +547,44 @@ mod tests {
         assert!(matches!(result, Err(RiskError::InsufficientCapital { .. })));
     }
 
+    #[test]
+    fn test_capital_check_with_leverage() {
+        let rm = RiskManager::new(RiskConfig::default()).with_leverage(5.0);
+        let account = create_test_account(200.0, 200.0);
+
+        assert!(rm.check_capital(&create_test_order(10.0), &account, 100.0).is_ok());
+        assert_eq!(
+            rm.check_capital(&create_test_order(11.0), &account, 100.0),
+            Err(RiskError::InsufficientCapital { required: 220.0, available: 200.0 })
+        );
+        assert_eq!(RiskManager::new(RiskConfig::default()).with_leverage(0.5).leverage(), 1.0);
+    }
+
+    #[test]
+    fn test_max_order_value_rejects_with_ample_funds() {
+        let rm = RiskManager::new(RiskConfig {
+            max_order_value: 5000.0,
+            ..Default::default()
+        });
+        let account = create_test_account(1_000_000.0, 1_000_000.0);
+        let order = create_test_order(100.0);
+
+        assert!(rm.check_capital(&order, &account, 100.0).is_ok());
+        let result = rm.check_max_order_value(&order, 100.0);
+        assert_eq!(
+            result,
+            Err(RiskError::MaxOrderValueExceeded {
+                value: 10000.0,
+                max: 5000.0
+            })
+        );
+
+        let mut rm = rm;
+        let result = rm.check(&order, &account, 100.0);
+        assert!(matches!(result, Err(RiskError::MaxOrderValueExceeded { .. })));
+        assert!(rm.check_max_order_value(&create_test_order(50.0), 100.0).is_ok());
+    }
+
     #[test]
     fn test_throttle_check_pass() {
         let mut rm = RiskManager::new(RiskConfig {
@@ -322,6 +615,83 @@ mod tests {
         assert!(matches!(result, Err(RiskError::ThrottleExceeded { .. })));
     }
 
+    #[test]
+    fn test_notional_throttle_small_frequent_vs_large_rare() {
+        let config = RiskConfig {
+            max_order_rate: 100,
+            max_notional_per_second: 10_000.0,
+            ..Default::default()
+        };
+
+        // Orders of 1,000 notional stay well under the count limit, but the
+        // 11th would push the window past 10,000
+        let mut rm = RiskManager::new(config);
+        for _ in 0..10 {
+            assert!(rm.check_throttle_notional(1_000.0).is_ok());
+        }
+        let result = rm.check_throttle_notional(1_000.0);
+        assert!(matches!(
+            result,
+            Err(RiskError::NotionalThrottleExceeded { current, .. }) if (current - 10_000.0).abs() < 1e-9
+        ));
+        // Rejected orders are not recorded
+        assert_eq!(rm.current_order_rate(), 10);
+        assert!((rm.current_notional_rate() - 10_000.0).abs() < 1e-9);
+
+        // A single large order fits; a second one in the same second does not
+        let mut rm = RiskManager::new(config);
+        assert!(rm.check_throttle_notional(8_000.0).is_ok());
+        assert!(matches!(
+            rm.check_throttle_notional(8_000.0),
+            Err(RiskError::NotionalThrottleExceeded { .. })
+        ));
+        // Small orders still fit under the remaining headroom
+        assert!(rm.check_throttle_notional(2_000.0).is_ok());
+
+        // Clearing history resets both count and notional
+        rm.clear_throttle_history();
+        assert_eq!(rm.current_order_rate(), 0);
+        assert_eq!(rm.current_notional_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_notional_throttle_window_evicts() {
+        let mut rm = RiskManager::new(RiskConfig {
+            max_order_rate: 100,
+            max_notional_per_second: 10_000.0,
+            ..Default::default()
+        });
+
+        assert!(rm.check_throttle_notional(9_000.0).is_ok());
+        assert!(rm.check_throttle_notional(9_000.0).is_err());
+
+        std::thread::sleep(std::time::Duration::from_millis(1_050));
+        assert!(rm.check_throttle_notional(9_000.0).is_ok());
+        assert_eq!(rm.current_order_rate(), 1);
+        assert!((rm.current_notional_rate() - 9_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_check_applies_notional_throttle() {
+        let mut rm = RiskManager::new(RiskConfig {
+            max_order_rate: 100,
+            max_notional_per_second: 15_000.0,
+            ..Default::default()
+        });
+        rm.initialize(100_000.0);
+        let account = create_test_account(100_000.0, 100_000.0);
+
+        // 100 units at 100.0 = 10,000 notional each
+        let order = create_test_order(100.0);
+        assert!(rm.check(&order, &account, 100.0).is_ok());
+        let result = rm.check(&order, &account, 100.0);
+        assert!(matches!(result, Err(RiskError::NotionalThrottleExceeded { .. })));
+        assert_eq!(
+            result.unwrap_err().to_error_code(),
+            crate::ffi::ERR_THROTTLE_EXCEEDED
+        );
+    }
+
     #[test]
     fn test_position_limit_pass() {
         let rm = RiskManager::new(RiskConfig {
@@ -348,6 +718,108 @@ mod tests {
         assert!(matches!(result, Err(RiskError::PositionLimitExceeded { .. })));
     }
 
+    #[test]
+    fn test_symbol_limits_are_independent() {
+        let rm = RiskManager::new(RiskConfig {
+            max_position_size: 1000.0,
+            ..Default::default()
+        })
+        .with_symbol_limit("BTCUSDT", 2.0)
+        .with_symbol_limit("ETHUSDT", 50.0);
+        let order = |symbol: &str, quantity: f64, direction: i32| OrderRequest {
+            quantity,
+            direction,
+            ..OrderRequest::with_symbol(symbol)
+        };
+        let buy = crate::types::DIRECTION_BUY;
+        let sell = crate::types::DIRECTION_SELL;
+
+        // Same holding and order size: only BTC breaches its limit
+        assert_eq!(
+            rm.check_position_limit_for_symbol(&order("BTCUSDT", 2.0, buy), 1.5, "BTCUSDT"),
+            Err(RiskError::PositionLimitExceeded { current: 1.5, order: 2.0, max: 2.0 })
+        );
+        assert!(rm.check_position_limit_for_symbol(&order("ETHUSDT", 2.0, buy), 1.5, "ETHUSDT").is_ok());
+
+        // Shorts count by absolute size; reducing an over-limit position passes
+        assert!(rm.check_position_limit_for_symbol(&order("BTCUSDT", 3.0, sell), 0.0, "BTCUSDT").is_err());
+        assert!(rm.check_position_limit_for_symbol(&order("BTCUSDT", 1.0, sell), 5.0, "BTCUSDT").is_ok());
+
+        // Unlisted symbols fall back to max_position_size
+        assert_eq!(rm.position_limit_for("SOLUSDT"), 1000.0);
+        assert!(rm.check_position_limit_for_symbol(&order("SOLUSDT", 900.0, buy), 0.0, "SOLUSDT").is_ok());
+        assert!(rm.check_position_limit_for_symbol(&order("SOLUSDT", 900.0, buy), 200.0, "SOLUSDT").is_err());
+    }
+
+    #[test]
+    fn test_check_with_position_uses_symbol_quantity() {
+        let mut rm = RiskManager::new(RiskConfig {
+            max_position_size: 1000.0,
+            max_order_value: 1e9,
+            ..Default::default()
+        })
+        .with_symbol_limit("BTCUSDT", 5.0);
+        rm.initialize(100_000.0);
+        let account = AccountStatus {
+            balance: 100_000.0,
+            equity: 100_000.0,
+            available: 100_000.0,
+            position_count: 20,
+            total_pnl: 0.0,
+        };
+        let mut order = OrderRequest::with_symbol("BTCUSDT");
+        order.quantity = 2.0;
+        order.direction = crate::types::DIRECTION_BUY;
+
+        // The position count would estimate 2000 units held; the real 1 passes
+        assert!(rm.check(&order, &account, 100.0).is_err());
+        assert!(rm.check_with_position(&order, &account, 100.0, 1.0).is_ok());
+        assert_eq!(
+            rm.check_with_position(&order, &account, 100.0, 4.0),
+            Err(RiskError::PositionLimitExceeded { current: 4.0, order: 2.0, max: 5.0 })
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_daily_loss_trips_emergency_stop_once() {
+        use crate::emergency::{is_halted, reset_emergency_stop};
+
+        reset_emergency_stop();
+        let mut rm = RiskManager::new(RiskConfig {
+            max_daily_loss: 500.0,
+            ..Default::default()
+        });
+        rm.initialize(10_000.0);
+        assert_eq!(rm.session_start_equity(), 10_000.0);
+
+        // A loss of exactly the limit passes
+        let start = rm.session_start_equity();
+        assert!(rm.check_daily_loss(&create_test_account(9_500.0, 9_500.0), start).is_ok());
+        assert!(!is_halted());
+
+        assert_eq!(
+            rm.check_daily_loss(&create_test_account(9_400.0, 9_400.0), start),
+            Err(RiskError::DailyLossExceeded { loss: 600.0, max: 500.0 })
+        );
+        assert!(is_halted());
+        assert_eq!(crate::emergency::get_halt_reason(), HaltReason::DailyLoss);
+
+        // Still rejected, but the stop is not re-activated after a manual reset
+        reset_emergency_stop();
+        assert!(rm.check_daily_loss(&create_test_account(9_300.0, 9_300.0), start).is_err());
+        assert!(!is_halted());
+
+        // A new session re-baselines and re-arms the switch
+        rm.reset_session(9_300.0);
+        let start = rm.session_start_equity();
+        assert!(rm.check_daily_loss(&create_test_account(9_300.0, 9_300.0), start).is_ok());
+        assert!(rm.check_daily_loss(&create_test_account(8_700.0, 8_700.0), start).is_err());
+        assert!(is_halted());
+
+        reset_emergency_stop();
+    }
+
     #[test]
     fn test_drawdown_check_pass() {
         let mut rm = RiskManager::new(RiskConfig {
@@ -381,6 +853,9 @@ mod tests {
             max_position_size: 1000.0,
             max_order_value: 100000.0,
             max_drawdown_pct: 0.1,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         });
         rm.initialize(10000.0);
 
@@ -391,6 +866,45 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_max_open_positions() {
+        let rm = RiskManager::new(RiskConfig {
+            max_open_positions: 2,
+            ..Default::default()
+        });
+        let open = |symbol: &str| Position {
+            quantity: 10.0,
+            ..Position::with_symbol(symbol)
+        };
+        let order = |symbol: &str| OrderRequest {
+            quantity: 10.0,
+            ..OrderRequest::with_symbol(symbol)
+        };
+
+        // Open up to the cap
+        let mut positions = Vec::new();
+        assert!(rm.check_max_positions(&order("AAA"), &positions).is_ok());
+        positions.push(open("AAA"));
+        assert!(rm.check_max_positions(&order("BBB"), &positions).is_ok());
+        positions.push(open("BBB"));
+
+        // A third symbol is rejected; adding to an open one is not
+        assert_eq!(
+            rm.check_max_positions(&order("CCC"), &positions),
+            Err(RiskError::MaxOpenPositionsExceeded { current: 2, max: 2 })
+        );
+        assert!(rm.check_max_positions(&order("AAA"), &positions).is_ok());
+
+        // Closing a position frees its slot
+        positions[0].quantity = 0.0;
+        assert!(rm.check_max_positions(&order("CCC"), &positions).is_ok());
+
+        // 0 disables the cap
+        let unlimited = RiskManager::default();
+        positions[0].quantity = 10.0;
+        assert!(unlimited.check_max_positions(&order("CCC"), &positions).is_ok());
+    }
+
     #[test]
     fn test_error_codes() {
         assert_eq!(
@@ -402,6 +916,15 @@ mod tests {
             crate::ffi::ERR_INSUFFICIENT_CAPITAL
         );
 
+        assert_eq!(
+            RiskError::MaxOrderValueExceeded {
+                value: 200.0,
+                max: 100.0
+            }
+            .to_error_code(),
+            crate::ffi::ERR_MAX_ORDER_VALUE
+        );
+
         assert_eq!(
             RiskError::ThrottleExceeded { current: 11, max: 10 }.to_error_code(),
             crate::ffi::ERR_THROTTLE_EXCEEDED
@@ -425,5 +948,10 @@ mod tests {
             .to_error_code(),
             crate::ffi::ERR_RISK_REJECTED
         );
+
+        assert_eq!(
+            RiskError::MaxOpenPositionsExceeded { current: 5, max: 5 }.to_error_code(),
+            crate::ffi::ERR_POSITION_LIMIT
+        );
     }
 }
@@ -3,7 +3,18 @@
 //! Provides the Strategy trait and implementations including
 //! the Dual Moving Average (DualMA) crossover strategy.
 
-use crate::types::{OrderRequest, StrategyParams, Tick, DIRECTION_BUY, DIRECTION_SELL, ORDER_TYPE_MARKET};
+use std::any::Any;
+use std::fmt;
+
+use ta::indicators::ExponentialMovingAverage;
+use ta::Next;
+
+use crate::indicators::{IndicatorCalculator, IndicatorResult};
+use crate::persistence::ExitReason;
+use crate::types::{
+    ExtendedTick, OrderRequest, StrategyParams, Tick, DIRECTION_BUY, DIRECTION_SELL, MA_TYPE_EMA, MA_TYPE_SMA,
+    MA_TYPE_WMA, ORDER_TYPE_MARKET,
+};
 
 /// Trading signal generated by a strategy.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -14,19 +25,51 @@ pub enum Signal {
     Buy,
     /// Sell signal
     Sell,
+    /// Close the open position
+    Exit,
 }
 
 /// Strategy trait for generating trading signals.
-pub trait Strategy: Send + Sync {
+///
+/// `BacktestEngine` runs any implementation as a `Box<dyn Strategy>`. The
+/// `Any` bound lets the engine recognize the built-in `DualMAStrategy`.
+pub trait Strategy: Any + Send + Sync {
     /// Process a new tick and potentially generate a signal.
     ///
     /// # Arguments
     /// * `tick` - The new market data tick
     ///
     /// # Returns
-    /// A trading signal (None, Buy, or Sell)
+    /// A trading signal (None, Buy, Sell, or Exit)
     fn on_tick(&mut self, tick: &Tick) -> Signal;
 
+    /// Process a tick carrying quote and open-interest data.
+    ///
+    /// `BacktestEngine` calls this instead of `on_tick` for ticks loaded from
+    /// files with bid, ask or open-interest columns, and from
+    /// `process_extended_tick`. Strategies that use bid/ask or open interest
+    /// override this; the default ignores the extra fields and calls `on_tick`.
+    fn on_extended_tick(&mut self, tick: &ExtendedTick) -> Signal {
+        self.on_tick(&tick.tick)
+    }
+
+    /// Process a tick for `symbol` in a multi-symbol backtest.
+    ///
+    /// Portfolio strategies override this to keep per-symbol state; the
+    /// default ignores the symbol and calls `on_tick`.
+    fn on_symbol_tick(&mut self, _symbol: &str, tick: &Tick) -> Signal {
+        self.on_tick(tick)
+    }
+
+    /// Conviction behind the latest signal for `symbol`.
+    ///
+    /// `MultiSymbolEngine` submits simultaneous signals strongest first under
+    /// `SignalOrdering::ByStrength`. The default of 0 leaves them in symbol
+    /// order.
+    fn signal_strength(&self, _symbol: &str) -> f64 {
+        0.0
+    }
+
     /// Generate an order request from a signal.
     ///
     /// # Arguments
@@ -43,12 +86,204 @@ pub trait Strategy: Send + Sync {
 
     /// Get the strategy name.
     fn name(&self) -> &str;
+
+    /// Set the current position direction (1 = long, -1 = short, 0 = flat).
+    ///
+    /// Called when the engine changes the position outside the strategy's
+    /// own signals, e.g. a session or emergency flatten. The default ignores it.
+    fn set_current_position(&mut self, _position: i32) {}
+
+    /// Why the most recent `Signal::Exit` closed the position.
+    ///
+    /// Recorded on the closing trade. The default reports `ExitReason::Signal`.
+    fn exit_reason(&self) -> ExitReason {
+        ExitReason::Signal
+    }
+}
+
+impl fmt::Debug for dyn Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Strategy").field("name", &self.name()).finish()
+    }
+}
+
+/// Submission order for signals generated on the same timestamp.
+///
+/// When several symbols signal at once, the order in which their orders are
+/// submitted decides which ones pass shared capital and throttle checks.
+/// Sequencing them explicitly keeps multi-symbol backtests reproducible
+/// instead of depending on map iteration order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignalOrdering {
+    /// Sort by normalized (trimmed, upper-case) symbol
+    #[default]
+    Alphabetical,
+    /// Sort by signal strength descending, ties broken by normalized symbol
+    ByStrength,
+}
+
+/// A signal for a specific symbol awaiting submission.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolSignal {
+    /// Trading symbol
+    pub symbol: String,
+    /// Signal direction
+    pub signal: Signal,
+    /// Signal strength (higher = more conviction). NaN sorts last.
+    pub strength: f64,
+}
+
+impl SymbolSignal {
+    /// Create a new symbol signal.
+    pub fn new(symbol: impl Into<String>, signal: Signal, strength: f64) -> Self {
+        Self {
+            symbol: symbol.into(),
+            signal,
+            strength,
+        }
+    }
+}
+
+/// Normalize a symbol for ordering comparisons.
+fn normalized_symbol(symbol: &str) -> String {
+    symbol.trim().to_ascii_uppercase()
+}
+
+/// Sort simultaneous signals into a stable, deterministic submission order.
+///
+/// The same set of signals always produces the same order regardless of how
+/// they were collected. Signals with identical normalized symbols keep their
+/// relative input order.
+pub fn sequence_signals(signals: &mut [SymbolSignal], ordering: SignalOrdering) {
+    let strength_key = |s: &SymbolSignal| {
+        if s.strength.is_nan() {
+            f64::NEG_INFINITY
+        } else {
+            s.strength
+        }
+    };
+
+    match ordering {
+        SignalOrdering::Alphabetical => {
+            signals.sort_by_cached_key(|s| normalized_symbol(&s.symbol));
+        }
+        SignalOrdering::ByStrength => {
+            signals.sort_by(|a, b| {
+                strength_key(b)
+                    .total_cmp(&strength_key(a))
+                    .then_with(|| normalized_symbol(&a.symbol).cmp(&normalized_symbol(&b.symbol)))
+            });
+        }
+    }
+}
+
+/// Moving average type for a DualMA leg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaType {
+    /// Simple moving average
+    #[default]
+    Sma,
+    /// Exponential moving average
+    Ema,
+    /// Linearly weighted moving average (newest price weighted highest)
+    Wma,
+}
+
+impl MaType {
+    /// Convert an `MA_TYPE_*` code to an MA type.
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            MA_TYPE_SMA => Some(MaType::Sma),
+            MA_TYPE_EMA => Some(MaType::Ema),
+            MA_TYPE_WMA => Some(MaType::Wma),
+            _ => None,
+        }
+    }
+
+    /// Get the `MA_TYPE_*` code for this MA type.
+    pub fn code(self) -> i32 {
+        match self {
+            MaType::Sma => MA_TYPE_SMA,
+            MaType::Ema => MA_TYPE_EMA,
+            MaType::Wma => MA_TYPE_WMA,
+        }
+    }
+}
+
+/// Bollinger band regime required for DualMA orders.
+///
+/// The two variants are inverses: every crossover order passes exactly one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegimeFilter {
+    /// Trade only when price is outside the bands (breakout confirmation)
+    Breakout,
+    /// Trade only when price is inside the bands (mean reversion)
+    Reversion,
+}
+
+impl RegimeFilter {
+    /// Check whether a price is in the regime this filter trades.
+    pub fn allows(&self, price: f64, indicators: &IndicatorResult) -> bool {
+        let outside = price > indicators.boll_upper || price < indicators.boll_lower;
+        match self {
+            RegimeFilter::Breakout => outside,
+            RegimeFilter::Reversion => !outside,
+        }
+    }
+}
+
+/// One moving average leg of the DualMA strategy.
+#[derive(Debug, Clone)]
+struct MaLeg {
+    period: usize,
+    ma_type: MaType,
+    /// Incremental EMA state (EMA legs only)
+    ema: Option<ExponentialMovingAverage>,
+    /// Latest EMA output
+    ema_value: f64,
+}
+
+impl MaLeg {
+    /// Create a leg; unknown type codes fall back to SMA.
+    fn new(period: i32, ma_type: i32) -> Self {
+        let period = period.max(1) as usize;
+        let ma_type = MaType::from_code(ma_type).unwrap_or_default();
+        let ema = match ma_type {
+            MaType::Ema => ExponentialMovingAverage::new(period).ok(),
+            _ => None,
+        };
+        Self {
+            period,
+            ma_type,
+            ema,
+            ema_value: 0.0,
+        }
+    }
+
+    fn update(&mut self, price: f64) {
+        if let Some(ema) = self.ema.as_mut() {
+            self.ema_value = ema.next(price);
+        }
+    }
+
+    fn reset(&mut self) {
+        if let Some(ema) = self.ema.as_mut() {
+            ta::Reset::reset(ema);
+        }
+        self.ema_value = 0.0;
+    }
 }
 
 /// Dual Moving Average crossover strategy.
 ///
 /// Generates buy signals when short MA crosses above long MA (golden cross),
 /// and sell signals when short MA crosses below long MA (death cross).
+/// Each leg is an SMA, EMA or WMA as set by `short_ma_type` and
+/// `long_ma_type`; every type waits for a full period before producing values.
+/// With protective exits enabled, an open position is closed with
+/// `Signal::Exit` once it moves past `stop_loss_pct` or `take_profit_pct`
+/// from its entry price. A `BacktestEngine` with its own protective exits
+/// switches these off, so a position is never exited twice.
 #[derive(Debug)]
 pub struct DualMAStrategy {
     /// Strategy parameters
@@ -65,6 +300,24 @@ pub struct DualMAStrategy {
     prev_long_ma: Option<f64>,
     /// Current position direction (1 = long, -1 = short, 0 = flat)
     current_position: i32,
+    /// Short MA leg
+    short_leg: MaLeg,
+    /// Long MA leg
+    long_leg: MaLeg,
+    /// Optional Bollinger regime gate on generated orders
+    regime_filter: Option<RegimeFilter>,
+    /// Indicator state for the regime filter
+    indicators: IndicatorCalculator,
+    /// Indicator values after the latest tick (None until a tick with a filter)
+    last_indicators: Option<IndicatorResult>,
+    /// Whether stop-loss/take-profit exits are emitted
+    protective_exits: bool,
+    /// Entry price of the open position (None when flat or unknown)
+    entry_price: Option<f64>,
+    /// Direction of the order that closes the position on `Signal::Exit`
+    exit_direction: i32,
+    /// Whether the last `Signal::Exit` hit the stop or the target
+    exit_reason: ExitReason,
 }
 
 impl DualMAStrategy {
@@ -79,9 +332,72 @@ impl DualMAStrategy {
             prev_short_ma: None,
             prev_long_ma: None,
             current_position: 0,
+            short_leg: MaLeg::new(params.short_ma_period, params.short_ma_type),
+            long_leg: MaLeg::new(params.long_ma_period, params.long_ma_type),
+            regime_filter: None,
+            indicators: IndicatorCalculator::new(),
+            last_indicators: None,
+            protective_exits: false,
+            entry_price: None,
+            exit_direction: DIRECTION_SELL,
+            exit_reason: ExitReason::Stop,
         }
     }
 
+    /// Emit `Signal::Exit` when the open position breaches its stop or target.
+    ///
+    /// The move is measured from the price of the crossover that opened the
+    /// position; a `stop_loss_pct` or `take_profit_pct` of 0 disables that side.
+    pub fn with_protective_exits(mut self, enabled: bool) -> Self {
+        self.protective_exits = enabled;
+        self
+    }
+
+    /// Enable or disable the stop-loss/take-profit exits in place.
+    pub fn set_protective_exits(&mut self, enabled: bool) {
+        self.protective_exits = enabled;
+    }
+
+    /// Check whether stop-loss/take-profit exits are emitted.
+    pub fn protective_exits(&self) -> bool {
+        self.protective_exits
+    }
+
+    /// Get the entry price of the open position, if known.
+    pub fn entry_price(&self) -> Option<f64> {
+        self.entry_price
+    }
+
+    /// Check whether the open position has hit its stop or target.
+    fn exit_triggered(&self, price: f64) -> Option<ExitReason> {
+        let entry = self.entry_price.filter(|&e| e > 0.0)?;
+        let direction = self.current_position.signum() as f64;
+        let move_pct = (price - entry) / entry * direction;
+
+        if self.params.stop_loss_pct > 0.0 && move_pct <= -self.params.stop_loss_pct {
+            Some(ExitReason::Stop)
+        } else if self.params.take_profit_pct > 0.0 && move_pct >= self.params.take_profit_pct {
+            Some(ExitReason::Target)
+        } else {
+            None
+        }
+    }
+
+    /// Gate generated orders on a Bollinger band regime.
+    ///
+    /// Bands come from an `IndicatorCalculator` (20-period, 2 standard
+    /// deviations) fed every tick. Crossover detection is unchanged; only
+    /// `generate_order` is filtered.
+    pub fn with_regime_filter(mut self, filter: RegimeFilter) -> Self {
+        self.regime_filter = Some(filter);
+        self
+    }
+
+    /// Get the regime filter, if any.
+    pub fn regime_filter(&self) -> Option<RegimeFilter> {
+        self.regime_filter
+    }
+
     /// Calculate simple moving average over the last n prices.
     fn calculate_ma(&self, period: usize) -> Option<f64> {
         if self.price_count < period {
@@ -99,6 +415,44 @@ impl DualMAStrategy {
         Some(sum / period as f64)
     }
 
+    /// Calculate linearly weighted moving average over the last n prices.
+    fn calculate_wma(&self, period: usize) -> Option<f64> {
+        if self.price_count < period {
+            return None;
+        }
+
+        let buffer_size = self.price_buffer.len();
+        let mut weighted_sum = 0.0;
+
+        for i in 0..period {
+            let idx = (self.buffer_pos + buffer_size - 1 - i) % buffer_size;
+            weighted_sum += self.price_buffer[idx] * (period - i) as f64;
+        }
+
+        let weight_total = (period * (period + 1) / 2) as f64;
+        Some(weighted_sum / weight_total)
+    }
+
+    /// Current value of a leg, or None until it has a full period of data.
+    fn leg_value(&self, leg: &MaLeg) -> Option<f64> {
+        match leg.ma_type {
+            MaType::Sma => self.calculate_ma(leg.period),
+            MaType::Wma => self.calculate_wma(leg.period),
+            MaType::Ema if self.price_count >= leg.period => Some(leg.ema_value),
+            MaType::Ema => None,
+        }
+    }
+
+    /// Get the short leg's MA type.
+    pub fn short_ma_type(&self) -> MaType {
+        self.short_leg.ma_type
+    }
+
+    /// Get the long leg's MA type.
+    pub fn long_ma_type(&self) -> MaType {
+        self.long_leg.ma_type
+    }
+
     /// Get the short MA period.
     pub fn short_ma_period(&self) -> i32 {
         self.params.short_ma_period
@@ -111,12 +465,12 @@ impl DualMAStrategy {
 
     /// Get the current short MA value.
     pub fn current_short_ma(&self) -> Option<f64> {
-        self.calculate_ma(self.params.short_ma_period as usize)
+        self.leg_value(&self.short_leg)
     }
 
     /// Get the current long MA value.
     pub fn current_long_ma(&self) -> Option<f64> {
-        self.calculate_ma(self.params.long_ma_period as usize)
+        self.leg_value(&self.long_leg)
     }
 
     /// Get the previous short MA value.
@@ -152,14 +506,19 @@ impl Strategy for DualMAStrategy {
         self.price_buffer[self.buffer_pos] = tick.price;
         self.buffer_pos = (self.buffer_pos + 1) % self.price_buffer.len();
         self.price_count += 1;
+        self.short_leg.update(tick.price);
+        self.long_leg.update(tick.price);
+        if self.regime_filter.is_some() {
+            self.last_indicators = Some(self.indicators.update(tick.price));
+        }
 
         // Calculate current MAs
-        let short_ma = match self.calculate_ma(self.params.short_ma_period as usize) {
+        let short_ma = match self.current_short_ma() {
             Some(ma) => ma,
             None => return Signal::None, // Not enough data
         };
 
-        let long_ma = match self.calculate_ma(self.params.long_ma_period as usize) {
+        let long_ma = match self.current_long_ma() {
             Some(ma) => ma,
             None => return Signal::None, // Not enough data
         };
@@ -189,13 +548,42 @@ impl Strategy for DualMAStrategy {
         match signal {
             Signal::Buy => self.current_position = 1,
             Signal::Sell => self.current_position = -1,
-            Signal::None => {}
+            Signal::None | Signal::Exit => {}
+        }
+
+        if signal != Signal::None {
+            self.entry_price = Some(tick.price);
+            return signal;
+        }
+
+        // Protective exit when no crossover fired this tick
+        let exit = (self.protective_exits && self.current_position != 0)
+            .then(|| self.exit_triggered(tick.price))
+            .flatten();
+        if let Some(reason) = exit {
+            self.exit_reason = reason;
+            self.exit_direction = if self.current_position > 0 {
+                DIRECTION_SELL
+            } else {
+                DIRECTION_BUY
+            };
+            self.current_position = 0;
+            self.entry_price = None;
+            return Signal::Exit;
         }
 
         signal
     }
 
     fn generate_order(&self, signal: Signal, symbol: &str, price: f64) -> Option<OrderRequest> {
+        // Exits close the position regardless of regime
+        if let Some(filter) = self.regime_filter.filter(|_| signal != Signal::Exit) {
+            match &self.last_indicators {
+                Some(indicators) if filter.allows(price, indicators) => {}
+                _ => return None,
+            }
+        }
+
         match signal {
             Signal::None => None,
             Signal::Buy => {
@@ -214,6 +602,14 @@ impl Strategy for DualMAStrategy {
                 order.limit_price = price;
                 Some(order)
             }
+            Signal::Exit => {
+                let mut order = OrderRequest::with_symbol(symbol);
+                order.quantity = self.params.position_size;
+                order.direction = self.exit_direction;
+                order.order_type = ORDER_TYPE_MARKET;
+                order.limit_price = price;
+                Some(order)
+            }
         }
     }
 
@@ -224,11 +620,30 @@ impl Strategy for DualMAStrategy {
         self.prev_short_ma = None;
         self.prev_long_ma = None;
         self.current_position = 0;
+        self.short_leg.reset();
+        self.long_leg.reset();
+        self.indicators.reset();
+        self.last_indicators = None;
+        self.entry_price = None;
+        self.exit_direction = DIRECTION_SELL;
+        self.exit_reason = ExitReason::Stop;
     }
 
     fn name(&self) -> &str {
         "DualMA"
     }
+
+    /// Also used when rebuilding the strategy mid-run so the new instance
+    /// keeps the position state of the one it replaces. The entry price is
+    /// not carried over, so protective exits stay idle until the next entry.
+    fn set_current_position(&mut self, position: i32) {
+        self.current_position = position;
+        self.entry_price = None;
+    }
+
+    fn exit_reason(&self) -> ExitReason {
+        self.exit_reason
+    }
 }
 
 #[cfg(test)]
@@ -252,12 +667,86 @@ mod tests {
             stop_loss_pct: 0.02,
             take_profit_pct: 0.05,
             warmup_bars: 0,
+            short_ma_type: MA_TYPE_SMA,
+            long_ma_type: MA_TYPE_SMA,
         };
         let strategy = DualMAStrategy::new(params);
         assert_eq!(strategy.short_ma_period(), 5);
         assert_eq!(strategy.long_ma_period(), 10);
     }
 
+    /// Flat, then a rally into a golden cross, then `tail`.
+    fn run_after_golden_cross(strategy: &mut DualMAStrategy, tail: &[f64]) -> (f64, Vec<Signal>) {
+        let mut prices = vec![100.0; 10];
+        prices.extend([101.0, 102.0, 103.0]);
+        let mut entry = None;
+        let mut signals = Vec::new();
+        for price in prices.into_iter().chain(tail.iter().copied()) {
+            let signal = strategy.on_tick(&create_tick(price));
+            if signal == Signal::Buy && entry.is_none() {
+                entry = Some(price);
+            }
+            signals.push(signal);
+        }
+        (entry.expect("rally should cross"), signals)
+    }
+
+    #[test]
+    fn test_stop_loss_exits_before_death_cross() {
+        let params = StrategyParams {
+            short_ma_period: 3,
+            long_ma_period: 10,
+            position_size: 10.0,
+            stop_loss_pct: 0.02,
+            take_profit_pct: 0.0,
+            ..Default::default()
+        };
+        let tail = [104.0, 105.0, 98.0, 97.0, 96.0, 95.0, 94.0, 93.0];
+
+        let mut strategy = DualMAStrategy::new(params).with_protective_exits(true);
+        let (entry, signals) = run_after_golden_cross(&mut strategy, &tail);
+        assert_eq!(entry, 101.0);
+
+        // The gap to 98.0 breaches the 2% stop while the short MA is still above the long
+        let exit = signals.iter().position(|&s| s == Signal::Exit).unwrap();
+        assert_eq!(exit, 15);
+        let order = strategy.generate_order(Signal::Exit, "TEST", 98.0).unwrap();
+        assert_eq!(order.direction, DIRECTION_SELL);
+        assert_eq!(order.quantity, 10.0);
+        assert_eq!(strategy.exit_reason(), ExitReason::Stop);
+
+        // Exactly one exit, and the death cross only comes later
+        assert_eq!(signals.iter().filter(|&&s| s == Signal::Exit).count(), 1);
+        let sell = signals.iter().position(|&s| s == Signal::Sell).unwrap();
+        assert!(exit < sell);
+
+        // Disabled by default
+        let mut strategy = DualMAStrategy::new(params);
+        let (_, signals) = run_after_golden_cross(&mut strategy, &tail);
+        assert!(!signals.contains(&Signal::Exit));
+    }
+
+    #[test]
+    fn test_take_profit_exit() {
+        let params = StrategyParams {
+            short_ma_period: 3,
+            long_ma_period: 10,
+            position_size: 10.0,
+            stop_loss_pct: 0.0,
+            take_profit_pct: 0.05,
+            ..Default::default()
+        };
+        let mut strategy = DualMAStrategy::new(params).with_protective_exits(true);
+        let (entry, signals) = run_after_golden_cross(&mut strategy, &[104.0, 105.0, 106.0, 107.0]);
+
+        // 106.0 is below entry * 1.05 = 106.05; 107.0 takes profit
+        assert_eq!(entry, 101.0);
+        assert_eq!(signals.iter().position(|&s| s == Signal::Exit), Some(16));
+        assert_eq!(strategy.exit_reason(), ExitReason::Target);
+        assert_eq!(strategy.current_position(), 0);
+        assert_eq!(strategy.entry_price(), None);
+    }
+
     #[test]
     fn test_no_signal_insufficient_data() {
         let params = StrategyParams {
@@ -382,6 +871,69 @@ mod tests {
         assert!(strategy.prev_long_ma().is_none());
     }
 
+    #[test]
+    fn test_sequence_signals_alphabetical() {
+        let mut signals = vec![
+            SymbolSignal::new("ethusdt", Signal::Buy, 0.9),
+            SymbolSignal::new("BTCUSDT", Signal::Buy, 0.1),
+            SymbolSignal::new(" adausdt ", Signal::Sell, 0.5),
+        ];
+        sequence_signals(&mut signals, SignalOrdering::Alphabetical);
+
+        let symbols: Vec<&str> = signals.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(symbols, vec![" adausdt ", "BTCUSDT", "ethusdt"]);
+    }
+
+    #[test]
+    fn test_sequence_signals_by_strength() {
+        let mut signals = vec![
+            SymbolSignal::new("BTCUSDT", Signal::Buy, 0.5),
+            SymbolSignal::new("SOLUSDT", Signal::Buy, f64::NAN),
+            SymbolSignal::new("ETHUSDT", Signal::Buy, 0.8),
+            SymbolSignal::new("ADAUSDT", Signal::Buy, 0.5),
+        ];
+        sequence_signals(&mut signals, SignalOrdering::ByStrength);
+
+        let symbols: Vec<&str> = signals.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["ETHUSDT", "ADAUSDT", "BTCUSDT", "SOLUSDT"]);
+    }
+
+    #[test]
+    fn test_sequencing_picks_deterministic_winner_for_limited_capital() {
+        use crate::gateway::{Gateway, GatewayError, SimulatedGateway};
+
+        // Capital for only one of the two orders
+        let run = |mut signals: Vec<SymbolSignal>, ordering: SignalOrdering| {
+            let mut gateway = SimulatedGateway::new(15_000.0, 0.0, 0.0);
+            let strategy = DualMAStrategy::new(StrategyParams {
+                position_size: 100.0,
+                ..Default::default()
+            });
+
+            sequence_signals(&mut signals, ordering);
+            let mut winners = Vec::new();
+            for s in &signals {
+                let order = strategy.generate_order(s.signal, &s.symbol, 100.0).unwrap();
+                match gateway.submit_order(&order, 100.0) {
+                    Ok(_) => winners.push(s.symbol.clone()),
+                    Err(e) => assert_eq!(e, GatewayError::InsufficientFunds),
+                }
+            }
+            winners
+        };
+
+        let a = vec![
+            SymbolSignal::new("ETHUSDT", Signal::Buy, 0.9),
+            SymbolSignal::new("BTCUSDT", Signal::Buy, 0.2),
+        ];
+        let b: Vec<SymbolSignal> = a.iter().rev().cloned().collect();
+
+        assert_eq!(run(a.clone(), SignalOrdering::Alphabetical), vec!["BTCUSDT"]);
+        assert_eq!(run(b.clone(), SignalOrdering::Alphabetical), vec!["BTCUSDT"]);
+        assert_eq!(run(a, SignalOrdering::ByStrength), vec!["ETHUSDT"]);
+        assert_eq!(run(b, SignalOrdering::ByStrength), vec!["ETHUSDT"]);
+    }
+
     #[test]
     fn test_ma_calculation() {
         let params = StrategyParams {
@@ -404,4 +956,124 @@ mod tests {
         let long_ma = strategy.current_long_ma().unwrap();
         assert!((long_ma - 104.0).abs() < 0.001);
     }
+
+    /// Bars at which a DualMA strategy with the given leg types signals.
+    fn signal_bars(prices: &[f64], ma_type: i32) -> Vec<(usize, Signal)> {
+        let mut strategy = DualMAStrategy::new(StrategyParams {
+            short_ma_period: 3,
+            long_ma_period: 8,
+            short_ma_type: ma_type,
+            long_ma_type: ma_type,
+            ..Default::default()
+        });
+        prices
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &p)| match strategy.on_tick(&create_tick(p)) {
+                Signal::None => None,
+                signal => Some((i, signal)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ema_legs_cross_at_different_bar_than_sma() {
+        // Downtrend followed by a steady rally
+        let mut prices: Vec<f64> = (0..15).map(|i| 120.0 - i as f64 * 2.0).collect();
+        prices.extend((1..15).map(|i| 92.0 + i as f64 * 1.5));
+
+        let sma = signal_bars(&prices, MA_TYPE_SMA);
+        let ema = signal_bars(&prices, MA_TYPE_EMA);
+
+        assert_eq!(sma.len(), 1);
+        assert_eq!(ema.len(), 1);
+        assert_eq!(sma[0].1, Signal::Buy);
+        assert_eq!(ema[0].1, Signal::Buy);
+        assert_ne!(sma[0].0, ema[0].0);
+    }
+
+    #[test]
+    fn test_ma_type_defaults_and_values() {
+        let strategy = DualMAStrategy::default();
+        assert_eq!(strategy.short_ma_type(), MaType::Sma);
+        assert_eq!(strategy.long_ma_type(), MaType::Sma);
+        assert_eq!(MaType::from_code(MA_TYPE_EMA), Some(MaType::Ema));
+        assert_eq!(MaType::from_code(7), None);
+        assert_eq!(MaType::Wma.code(), MA_TYPE_WMA);
+
+        let mut strategy = DualMAStrategy::new(StrategyParams {
+            short_ma_period: 3,
+            long_ma_period: 3,
+            short_ma_type: MA_TYPE_WMA,
+            long_ma_type: MA_TYPE_EMA,
+            ..Default::default()
+        });
+        for price in [10.0, 20.0] {
+            strategy.on_tick(&create_tick(price));
+            assert!(strategy.current_long_ma().is_none());
+        }
+        strategy.on_tick(&create_tick(30.0));
+
+        // WMA: (10*1 + 20*2 + 30*3) / 6
+        assert!((strategy.current_short_ma().unwrap() - 140.0 / 6.0).abs() < 1e-9);
+        // EMA(3), k = 0.5: 10 -> 15 -> 22.5
+        assert!((strategy.current_long_ma().unwrap() - 22.5).abs() < 1e-9);
+    }
+
+    /// Count orders generated over a price path with an optional regime filter.
+    fn count_orders(prices: &[f64], filter: Option<RegimeFilter>) -> usize {
+        let params = StrategyParams {
+            short_ma_period: 3,
+            long_ma_period: 8,
+            ..Default::default()
+        };
+        let mut strategy = DualMAStrategy::new(params);
+        if let Some(filter) = filter {
+            strategy = strategy.with_regime_filter(filter);
+        }
+        prices
+            .iter()
+            .filter(|&&price| {
+                let signal = strategy.on_tick(&create_tick(price));
+                strategy.generate_order(signal, "TEST", price).is_some()
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_regime_filter_suppresses_crossovers() {
+        // Quiet oscillations followed by sharp swings
+        let prices: Vec<f64> = (0..200)
+            .map(|i| {
+                let amplitude = if i < 100 { 1.0 } else { 8.0 };
+                let period = if i % 50 < 25 { 12.0 } else { 5.0 };
+                100.0 + amplitude * (i as f64 * std::f64::consts::TAU / period).sin()
+            })
+            .collect();
+
+        let plain = count_orders(&prices, None);
+        let breakout = count_orders(&prices, Some(RegimeFilter::Breakout));
+        let reversion = count_orders(&prices, Some(RegimeFilter::Reversion));
+
+        assert!(plain > 0);
+        assert!(breakout > 0 && breakout < plain);
+        assert!(reversion > 0 && reversion < plain);
+        // The filters are inverses, so together they keep every crossover
+        assert_eq!(breakout + reversion, plain);
+    }
+
+    #[test]
+    fn test_regime_filter_allows() {
+        let bands = IndicatorResult {
+            boll_upper: 110.0,
+            boll_middle: 100.0,
+            boll_lower: 90.0,
+            ..Default::default()
+        };
+        assert!(RegimeFilter::Breakout.allows(111.0, &bands));
+        assert!(RegimeFilter::Breakout.allows(89.0, &bands));
+        assert!(!RegimeFilter::Breakout.allows(100.0, &bands));
+        assert!(RegimeFilter::Reversion.allows(110.0, &bands));
+        assert!(!RegimeFilter::Reversion.allows(111.0, &bands));
+    }
 }
@@ -28,12 +28,71 @@ impl Default for Tick {
     }
 }
 
+/// Tick with the optional quote and open-interest columns of its source.
+///
+/// Not FFI-safe. `DataLoader` fills the fields whose columns exist in the
+/// file; absent columns and null cells are `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ExtendedTick {
+    /// Base tick
+    pub tick: Tick,
+    /// Best bid price
+    pub bid: Option<f64>,
+    /// Best ask price
+    pub ask: Option<f64>,
+    /// Open interest
+    pub open_interest: Option<f64>,
+}
+
+/// Top-of-book quote loaded from a file with `bid` and `ask` columns.
+///
+/// `DataLoader` only emits uncrossed quotes (`bid <= ask`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct QuoteTick {
+    /// Unix timestamp in nanoseconds
+    pub timestamp: i64,
+    /// Best bid price
+    pub bid: f64,
+    /// Best ask price
+    pub ask: f64,
+    /// Volume
+    pub volume: f64,
+}
+
+impl From<Tick> for ExtendedTick {
+    fn from(tick: Tick) -> Self {
+        Self {
+            tick,
+            ..Default::default()
+        }
+    }
+}
+
+/// OHLCV bar aggregated from ticks.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Bar {
+    /// Bucket start as Unix timestamp in milliseconds
+    pub timestamp: i64,
+    /// First tick price in the bucket
+    pub open: f64,
+    /// Highest tick price in the bucket
+    pub high: f64,
+    /// Lowest tick price in the bucket
+    pub low: f64,
+    /// Last tick price in the bucket
+    pub close: f64,
+    /// Summed tick volume
+    pub volume: f64,
+}
+
 /// Order request structure for submitting orders.
 /// 
 /// # FFI Safety
 /// - `symbol` is a fixed-size array (null-terminated UTF-8)
 /// - `direction`: 1 = Buy, -1 = Sell
 /// - `order_type`: 0 = Market, 1 = Limit
+/// - `tif`: 0 = GTC, 1 = IOC, 2 = FOK
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct OrderRequest {
@@ -47,6 +106,10 @@ pub struct OrderRequest {
     pub order_type: i32,
     /// Limit price (ignored for Market orders)
     pub limit_price: f64,
+    /// Caller-assigned client order ID, echoed on fills (0 = untagged)
+    pub client_id: u64,
+    /// Time in force: 0 = GTC, 1 = IOC, 2 = FOK
+    pub tif: i32,
 }
 
 impl Default for OrderRequest {
@@ -57,6 +120,8 @@ impl Default for OrderRequest {
             direction: 0,
             order_type: 0,
             limit_price: 0.0,
+            client_id: 0,
+            tif: TIF_GTC,
         }
     }
 }
@@ -176,8 +241,13 @@ pub struct StrategyParams {
     pub stop_loss_pct: f64,
     /// Take profit percentage (e.g., 0.05 = 5%)
     pub take_profit_pct: f64,
-    /// Number of bars to warm up before generating signals
+    /// Number of bars to warm up before generating signals (the engine uses at
+    /// least the longest MA period)
     pub warmup_bars: i32,
+    /// Moving average type of the short leg (MA_TYPE_*)
+    pub short_ma_type: i32,
+    /// Moving average type of the long leg (MA_TYPE_*)
+    pub long_ma_type: i32,
 }
 
 impl Default for StrategyParams {
@@ -189,6 +259,8 @@ impl Default for StrategyParams {
             stop_loss_pct: 0.02,
             take_profit_pct: 0.05,
             warmup_bars: 0,
+            short_ma_type: MA_TYPE_SMA,
+            long_ma_type: MA_TYPE_SMA,
         }
     }
 }
@@ -209,6 +281,12 @@ pub struct RiskConfig {
     pub max_order_value: f64,
     /// Maximum drawdown percentage (e.g., 0.1 = 10%)
     pub max_drawdown_pct: f64,
+    /// Maximum number of symbols with open positions (0 = unlimited)
+    pub max_open_positions: i32,
+    /// Maximum loss from the session's starting equity before trading halts (0 = disabled)
+    pub max_daily_loss: f64,
+    /// Maximum order notional traded in any 1-second window (0 = disabled)
+    pub max_notional_per_second: f64,
 }
 
 impl Default for RiskConfig {
@@ -218,6 +296,46 @@ impl Default for RiskConfig {
             max_position_size: 1000.0,
             max_order_value: 100000.0,
             max_drawdown_pct: 0.1,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
+        }
+    }
+}
+
+/// Account and execution cost settings for a new engine.
+/// 
+/// # FFI Safety
+/// This struct uses `repr(C)` layout matching C# StructLayout.Sequential.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EngineCostConfig {
+    /// Starting account balance
+    pub initial_balance: f64,
+    /// Slippage as a fraction of the fill price
+    pub slippage: f64,
+    /// Commission as a fraction of the trade value
+    pub commission_rate: f64,
+}
+
+impl EngineCostConfig {
+    /// Whether the balance is positive and both cost rates are non-negative.
+    pub fn is_valid(&self) -> bool {
+        self.initial_balance.is_finite()
+            && self.initial_balance > 0.0
+            && self.slippage.is_finite()
+            && self.slippage >= 0.0
+            && self.commission_rate.is_finite()
+            && self.commission_rate >= 0.0
+    }
+}
+
+impl Default for EngineCostConfig {
+    fn default() -> Self {
+        Self {
+            initial_balance: 100_000.0,
+            slippage: 0.001,
+            commission_rate: 0.0001,
         }
     }
 }
@@ -233,7 +351,7 @@ pub struct DataQualityReport {
     pub total_ticks: i64,
     /// Number of valid ticks
     pub valid_ticks: i64,
-    /// Number of invalid ticks (price <= 0 or volume < 0)
+    /// Number of invalid ticks (price <= 0, volume < 0 or below the minimum volume)
     pub invalid_ticks: i64,
     /// Number of anomaly ticks (price jumps > 10%)
     pub anomaly_ticks: i64,
@@ -241,6 +359,10 @@ pub struct DataQualityReport {
     pub first_timestamp: i64,
     /// Last timestamp in the dataset
     pub last_timestamp: i64,
+    /// Downsampling stride applied to the valid ticks (1 = every tick)
+    pub stride: i64,
+    /// Number of quotes with bid above ask
+    pub crossed_quotes: i64,
 }
 
 /// Backtest result structure.
@@ -268,6 +390,19 @@ pub struct BacktestResult {
     pub actual_start_bar: i32,
     /// First trade timestamp (0 if no trades)
     pub first_trade_timestamp: i64,
+    /// Early termination flag (1 = equity fell below min_equity, 0 = ran to completion)
+    pub blown_up: i32,
+    /// Bar index at which the run stopped early (-1 if it ran to completion)
+    pub stopped_at_bar: i32,
+    /// Return percentage of holding full initial equity from the first to the
+    /// last processed tick, net of one round-trip commission
+    pub buy_and_hold_return_pct: f64,
+    /// Return percentage of holding full equity from the first tradable
+    /// (post-warmup) bar to the last processed tick, net of one round-trip
+    /// commission
+    pub benchmark_return_pct: f64,
+    /// Strategy return minus the benchmark return, in percentage points
+    pub alpha_pct: f64,
 }
 
 impl Default for BacktestResult {
@@ -282,6 +417,29 @@ impl Default for BacktestResult {
             losing_trades: 0,
             actual_start_bar: 0,
             first_trade_timestamp: 0,
+            blown_up: 0,
+            stopped_at_bar: -1,
+            buy_and_hold_return_pct: 0.0,
+            benchmark_return_pct: 0.0,
+            alpha_pct: 0.0,
+        }
+    }
+}
+
+impl BacktestResult {
+    /// Copy of this result with every floating-point field rounded to
+    /// `decimals` places for display.
+    pub fn rounded(&self, decimals: u32) -> Self {
+        let round = |value| crate::precision::round_to_decimals(value, decimals);
+        Self {
+            final_equity: round(self.final_equity),
+            total_return_pct: round(self.total_return_pct),
+            max_drawdown_pct: round(self.max_drawdown_pct),
+            sharpe_ratio: round(self.sharpe_ratio),
+            buy_and_hold_return_pct: round(self.buy_and_hold_return_pct),
+            benchmark_return_pct: round(self.benchmark_return_pct),
+            alpha_pct: round(self.alpha_pct),
+            ..*self
         }
     }
 }
@@ -294,6 +452,19 @@ pub const DIRECTION_SELL: i32 = -1;
 pub const ORDER_TYPE_MARKET: i32 = 0;
 pub const ORDER_TYPE_LIMIT: i32 = 1;
 
+// Time-in-force constants
+/// Good till cancelled: unfilled limit quantity rests
+pub const TIF_GTC: i32 = 0;
+/// Immediate or cancel: fill what is available now, cancel the rest
+pub const TIF_IOC: i32 = 1;
+/// Fill or kill: fill the whole quantity now or reject the order
+pub const TIF_FOK: i32 = 2;
+
+// Moving average type constants
+pub const MA_TYPE_SMA: i32 = 0;
+pub const MA_TYPE_EMA: i32 = 1;
+pub const MA_TYPE_WMA: i32 = 2;
+
 #[cfg(test)]
 mod tests {
     use super::*;
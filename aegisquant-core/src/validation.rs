@@ -0,0 +1,332 @@
+//! Dry-run validation of engine configuration.
+//!
+//! Checks that strategy parameters, risk limits and the loaded data are
+//! mutually consistent before a run, reporting every problem found rather
+//! than stopping at the first. `BacktestEngine::validate` adds checks that
+//! need the loaded ticks; `validate_engine_config_ffi` returns the list to
+//! C# as JSON.
+
+use std::fmt::Write;
+use std::panic::catch_unwind;
+
+use crate::ffi::{ERR_INTERNAL_PANIC, ERR_INVALID_PARAM, ERR_SUCCESS};
+use crate::ffi_string::{with_string_callback, StringCallback};
+use crate::strategy::MaType;
+use crate::types::{RiskConfig, StrategyParams};
+
+/// Severity of a validation finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationSeverity {
+    /// The run will work but probably not as intended
+    Warning,
+    /// The run will fail or cannot produce any trades
+    Error,
+}
+
+impl ValidationSeverity {
+    fn as_str(self) -> &'static str {
+        match self {
+            ValidationSeverity::Warning => "warning",
+            ValidationSeverity::Error => "error",
+        }
+    }
+}
+
+/// One configuration problem found by validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationWarning {
+    /// How serious the problem is
+    pub severity: ValidationSeverity,
+    /// Name of the offending field (e.g. `long_ma_period`)
+    pub field: &'static str,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl ValidationWarning {
+    /// Create a warning-level finding.
+    pub fn warning(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: ValidationSeverity::Warning,
+            field,
+            message: message.into(),
+        }
+    }
+
+    /// Create an error-level finding.
+    pub fn error(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: ValidationSeverity::Error,
+            field,
+            message: message.into(),
+        }
+    }
+
+    /// Check whether this finding is an error.
+    pub fn is_error(&self) -> bool {
+        self.severity == ValidationSeverity::Error
+    }
+}
+
+/// Validate strategy parameters and risk limits against a tick count.
+///
+/// Returns an empty list when the configuration is consistent.
+pub fn validate_config(
+    params: &StrategyParams,
+    risk: &RiskConfig,
+    tick_count: usize,
+) -> Vec<ValidationWarning> {
+    let mut findings = Vec::new();
+
+    // Strategy parameters
+    if params.short_ma_period <= 0 {
+        findings.push(ValidationWarning::error("short_ma_period", "must be positive"));
+    }
+    if params.long_ma_period <= 0 {
+        findings.push(ValidationWarning::error("long_ma_period", "must be positive"));
+    }
+    if params.short_ma_period > 0
+        && params.long_ma_period > 0
+        && params.short_ma_period >= params.long_ma_period
+    {
+        findings.push(ValidationWarning::warning(
+            "short_ma_period",
+            format!(
+                "short MA period {} is not below long MA period {}",
+                params.short_ma_period, params.long_ma_period
+            ),
+        ));
+    }
+    if MaType::from_code(params.short_ma_type).is_none() {
+        findings.push(ValidationWarning::error("short_ma_type", "unknown MA type"));
+    }
+    if MaType::from_code(params.long_ma_type).is_none() {
+        findings.push(ValidationWarning::error("long_ma_type", "unknown MA type"));
+    }
+    if params.position_size <= 0.0 || params.position_size.is_nan() {
+        findings.push(ValidationWarning::error(
+            "position_size",
+            "must be positive; no orders would be placed",
+        ));
+    }
+    if params.stop_loss_pct < 0.0 {
+        findings.push(ValidationWarning::error("stop_loss_pct", "must not be negative"));
+    }
+    if params.take_profit_pct < 0.0 {
+        findings.push(ValidationWarning::error("take_profit_pct", "must not be negative"));
+    }
+    if params.warmup_bars < 0 {
+        findings.push(ValidationWarning::error("warmup_bars", "must not be negative"));
+    }
+
+    // Risk limits
+    if risk.max_order_rate <= 0 {
+        findings.push(ValidationWarning::error(
+            "max_order_rate",
+            "must be positive; every order would be throttled",
+        ));
+    }
+    if risk.max_position_size <= 0.0 {
+        findings.push(ValidationWarning::error("max_position_size", "must be positive"));
+    } else if params.position_size > risk.max_position_size {
+        findings.push(ValidationWarning::error(
+            "position_size",
+            format!(
+                "position size {} exceeds max position size {}",
+                params.position_size, risk.max_position_size
+            ),
+        ));
+    }
+    if risk.max_order_value <= 0.0 {
+        findings.push(ValidationWarning::error("max_order_value", "must be positive"));
+    }
+    if !(risk.max_drawdown_pct > 0.0 && risk.max_drawdown_pct <= 1.0) {
+        findings.push(ValidationWarning::warning(
+            "max_drawdown_pct",
+            format!("{} is outside (0, 1]", risk.max_drawdown_pct),
+        ));
+    }
+
+    // Data
+    if tick_count == 0 {
+        findings.push(ValidationWarning::error("ticks", "no data loaded"));
+    } else {
+        if params.long_ma_period > 0 && params.long_ma_period as usize > tick_count {
+            findings.push(ValidationWarning::error(
+                "long_ma_period",
+                format!(
+                    "long MA period {} exceeds the {} loaded ticks",
+                    params.long_ma_period, tick_count
+                ),
+            ));
+        }
+        if params.warmup_bars > 0 && params.warmup_bars as usize >= tick_count {
+            findings.push(ValidationWarning::warning(
+                "warmup_bars",
+                format!(
+                    "warmup of {} bars covers all {} loaded ticks",
+                    params.warmup_bars, tick_count
+                ),
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Serialize findings as a JSON array of
+/// `{"severity": ..., "field": ..., "message": ...}` objects.
+pub fn warnings_to_json(findings: &[ValidationWarning]) -> String {
+    let mut json = String::from("[");
+    for (i, finding) in findings.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str("{\"severity\":");
+        push_json_string(&mut json, finding.severity.as_str());
+        json.push_str(",\"field\":");
+        push_json_string(&mut json, finding.field);
+        json.push_str(",\"message\":");
+        push_json_string(&mut json, &finding.message);
+        json.push('}');
+    }
+    json.push(']');
+    json
+}
+
+/// Append `s` as a quoted, escaped JSON string.
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// ============================================================================
+// FFI Functions
+// ============================================================================
+
+/// Validate an engine configuration and pass the findings as JSON.
+///
+/// The callback receives a JSON array (see `warnings_to_json`); an empty
+/// array means the configuration is consistent.
+///
+/// # Safety
+/// - `params` must be a valid pointer to StrategyParams or null (uses defaults)
+/// - `risk_config` must be a valid pointer to RiskConfig or null (uses defaults)
+/// - `callback` must copy the string before returning
+///
+/// # Returns
+/// - ERR_SUCCESS on success (even if findings were reported)
+/// - ERR_INVALID_PARAM if tick_count is negative
+#[no_mangle]
+pub unsafe extern "C" fn validate_engine_config_ffi(
+    params: *const StrategyParams,
+    risk_config: *const RiskConfig,
+    tick_count: i64,
+    callback: StringCallback,
+) -> i32 {
+    if tick_count < 0 {
+        return ERR_INVALID_PARAM;
+    }
+
+    let outcome = catch_unwind(|| {
+        // SAFETY: Caller guarantees pointers are valid or null
+        let params = if params.is_null() { StrategyParams::default() } else { *params };
+        let risk = if risk_config.is_null() { RiskConfig::default() } else { *risk_config };
+
+        let findings = validate_config(&params, &risk, tick_count as usize);
+        with_string_callback(&warnings_to_json(&findings), callback);
+        ERR_SUCCESS
+    });
+
+    outcome.unwrap_or(ERR_INTERNAL_PANIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has(findings: &[ValidationWarning], field: &str, severity: ValidationSeverity) -> bool {
+        findings.iter().any(|f| f.field == field && f.severity == severity)
+    }
+
+    #[test]
+    fn test_default_config_is_clean() {
+        let findings = validate_config(&StrategyParams::default(), &RiskConfig::default(), 1000);
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[test]
+    fn test_long_ma_exceeds_data_and_zero_position_size() {
+        let params = StrategyParams {
+            long_ma_period: 50,
+            position_size: 0.0,
+            ..Default::default()
+        };
+        let findings = validate_config(&params, &RiskConfig::default(), 30);
+
+        assert!(has(&findings, "long_ma_period", ValidationSeverity::Error));
+        assert!(has(&findings, "position_size", ValidationSeverity::Error));
+        assert_eq!(findings.len(), 2);
+    }
+
+    #[test]
+    fn test_contradictory_limits() {
+        let params = StrategyParams {
+            short_ma_period: 20,
+            long_ma_period: 10,
+            position_size: 500.0,
+            ..Default::default()
+        };
+        let risk = RiskConfig {
+            max_order_rate: 0,
+            max_position_size: 100.0,
+            max_drawdown_pct: 1.5,
+            ..Default::default()
+        };
+        let findings = validate_config(&params, &risk, 1000);
+
+        assert!(has(&findings, "short_ma_period", ValidationSeverity::Warning));
+        assert!(has(&findings, "position_size", ValidationSeverity::Error));
+        assert!(has(&findings, "max_order_rate", ValidationSeverity::Error));
+        assert!(has(&findings, "max_drawdown_pct", ValidationSeverity::Warning));
+    }
+
+    #[test]
+    fn test_no_data_and_bad_ma_type() {
+        let params = StrategyParams {
+            long_ma_type: 9,
+            ..Default::default()
+        };
+        let findings = validate_config(&params, &RiskConfig::default(), 0);
+
+        assert!(has(&findings, "ticks", ValidationSeverity::Error));
+        assert!(has(&findings, "long_ma_type", ValidationSeverity::Error));
+    }
+
+    #[test]
+    fn test_warnings_to_json() {
+        assert_eq!(warnings_to_json(&[]), "[]");
+        let findings = vec![
+            ValidationWarning::error("position_size", "must be \"positive\""),
+            ValidationWarning::warning("max_drawdown_pct", "line\nbreak"),
+        ];
+        assert_eq!(
+            warnings_to_json(&findings),
+            "[{\"severity\":\"error\",\"field\":\"position_size\",\"message\":\"must be \\\"positive\\\"\"},\
+             {\"severity\":\"warning\",\"field\":\"max_drawdown_pct\",\"message\":\"line\\nbreak\"}]"
+        );
+    }
+}
@@ -56,7 +56,9 @@ proptest! {
         quantity in 0.01f64..100_000.0,
         direction in prop_oneof![Just(1i32), Just(-1i32)],
         order_type in 0i32..=1,
-        limit_price in 0.01f64..1_000_000.0
+        limit_price in 0.01f64..1_000_000.0,
+        client_id in any::<u64>(),
+        tif in 0i32..=2
     ) {
         let original = OrderRequest {
             symbol,
@@ -64,6 +66,8 @@ proptest! {
             direction,
             order_type,
             limit_price,
+            client_id,
+            tif,
         };
         
         let bytes: &[u8] = unsafe {
@@ -82,6 +86,8 @@ proptest! {
         prop_assert_eq!(original.direction, reconstructed.direction);
         prop_assert_eq!(original.order_type, reconstructed.order_type);
         prop_assert!((original.limit_price - reconstructed.limit_price).abs() < f64::EPSILON);
+        prop_assert_eq!(original.client_id, reconstructed.client_id);
+        prop_assert_eq!(original.tif, reconstructed.tif);
     }
 
     /// Property 1: Position struct round-trip through raw bytes
@@ -171,6 +177,8 @@ proptest! {
             stop_loss_pct,
             take_profit_pct,
             warmup_bars,
+            short_ma_type: MA_TYPE_SMA,
+            long_ma_type: MA_TYPE_SMA,
         };
         
         let bytes: &[u8] = unsafe {
@@ -205,6 +213,9 @@ proptest! {
             max_position_size,
             max_order_value,
             max_drawdown_pct,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let bytes: &[u8] = unsafe {
@@ -224,6 +235,33 @@ proptest! {
         prop_assert!((original.max_drawdown_pct - reconstructed.max_drawdown_pct).abs() < f64::EPSILON);
     }
 
+    /// Property 1: EngineCostConfig struct round-trip through raw bytes
+    #[test]
+    fn engine_cost_config_memory_layout_roundtrip(
+        initial_balance in 0.01f64..10_000_000.0,
+        slippage in 0.0f64..0.1,
+        commission_rate in 0.0f64..0.1
+    ) {
+        let original = EngineCostConfig {
+            initial_balance,
+            slippage,
+            commission_rate,
+        };
+        
+        let bytes: &[u8] = unsafe {
+            std::slice::from_raw_parts(
+                &original as *const EngineCostConfig as *const u8,
+                std::mem::size_of::<EngineCostConfig>()
+            )
+        };
+        
+        let reconstructed: EngineCostConfig = unsafe {
+            std::ptr::read(bytes.as_ptr() as *const EngineCostConfig)
+        };
+        
+        prop_assert_eq!(original, reconstructed);
+    }
+
     /// Property 1: DataQualityReport struct round-trip through raw bytes
     #[test]
     fn data_quality_report_memory_layout_roundtrip(
@@ -241,6 +279,8 @@ proptest! {
             anomaly_ticks,
             first_timestamp,
             last_timestamp,
+            stride: 1,
+            crossed_quotes: 0,
         };
         
         let bytes: &[u8] = unsafe {
@@ -202,6 +202,8 @@ proptest! {
             stop_loss_pct: stop_loss,
             take_profit_pct: take_profit,
             warmup_bars: 0,
+            short_ma_type: MA_TYPE_SMA,
+            long_ma_type: MA_TYPE_SMA,
         };
         
         let risk = RiskConfig {
@@ -209,6 +211,9 @@ proptest! {
             max_position_size: max_position,
             max_order_value,
             max_drawdown_pct: max_drawdown,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         unsafe {
@@ -217,8 +222,8 @@ proptest! {
             
             // Verify params were stored correctly
             let engine_ref = &*engine;
-            prop_assert_eq!(engine_ref.params.short_ma_period, short_ma);
-            prop_assert_eq!(engine_ref.params.long_ma_period, long_ma);
+            prop_assert_eq!(engine_ref.engine().strategy_params().short_ma_period, short_ma);
+            prop_assert_eq!(engine_ref.engine().strategy_params().long_ma_period, long_ma);
             
             free_engine(engine);
         }
@@ -103,6 +103,34 @@ fn independent_total_ask_orders(snapshot: &OrderBookSnapshot) -> i32 {
         .sum()
 }
 
+/// Generate an orderbook where either side may be empty.
+fn sparse_orderbook_strategy() -> impl Strategy<Value = OrderBookSnapshot> {
+    (
+        orderbook_strategy(),
+        0usize..=MAX_LEVELS,
+        0usize..=MAX_LEVELS,
+    )
+        .prop_map(|(full, bid_count, ask_count)| {
+            OrderBookSnapshot::with_levels(
+                &full.bids[..bid_count.min(full.bid_count as usize)],
+                &full.asks[..ask_count.min(full.ask_count as usize)],
+                full.last_price,
+                full.timestamp,
+            )
+        })
+}
+
+/// Independent calculation of volume imbalance.
+fn independent_imbalance(snapshot: &OrderBookSnapshot) -> f64 {
+    let total_bid = independent_total_bid_volume(snapshot);
+    let total_ask = independent_total_ask_volume(snapshot);
+    if total_bid + total_ask > 0.0 {
+        (total_bid - total_ask) / (total_bid + total_ask)
+    } else {
+        0.0
+    }
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(100))]
 
@@ -261,6 +289,53 @@ proptest! {
         );
     }
 
+    /// Property 9: imbalance stays within [-1, 1].
+    ///
+    /// **Feature: aegisquant-optimizations, Property 9: 盘口统计正确性**
+    /// **Validates: Requirements 20.4**
+    ///
+    /// For any OrderBookSnapshot, including one-sided and empty books,
+    /// imbalance should match (bid - ask) / (bid + ask) and lie in [-1, 1].
+    #[test]
+    fn prop_imbalance_within_bounds(
+        snapshot in sparse_orderbook_strategy()
+    ) {
+        let stats = snapshot.get_stats();
+        let expected = independent_imbalance(&snapshot);
+        
+        prop_assert!(
+            (-1.0..=1.0).contains(&stats.imbalance),
+            "imbalance out of range: {}", stats.imbalance
+        );
+        prop_assert!(
+            (stats.imbalance - expected).abs() < 1e-9,
+            "imbalance mismatch: got={}, expected={}",
+            stats.imbalance, expected
+        );
+    }
+
+    /// Property 9: microprice lies between best bid and best ask.
+    ///
+    /// **Feature: aegisquant-optimizations, Property 9: 盘口统计正确性**
+    /// **Validates: Requirements 20.4**
+    ///
+    /// For any two-sided OrderBookSnapshot, microprice should lie within the
+    /// touch; for a one-sided book it should equal last_price.
+    #[test]
+    fn prop_microprice_within_touch(
+        snapshot in sparse_orderbook_strategy()
+    ) {
+        let stats = snapshot.get_stats();
+        
+        match (snapshot.best_bid(), snapshot.best_ask()) {
+            (Some(bid), Some(ask)) => prop_assert!(
+                stats.microprice >= bid - PRICE_EPSILON && stats.microprice <= ask + PRICE_EPSILON,
+                "microprice {} outside [{}, {}]", stats.microprice, bid, ask
+            ),
+            _ => prop_assert_eq!(stats.microprice, snapshot.last_price),
+        }
+    }
+
     /// Property 9: All stats values are finite and non-negative.
     ///
     /// **Feature: aegisquant-optimizations, Property 9: 盘口统计正确性**
@@ -280,6 +355,8 @@ proptest! {
         prop_assert!(stats.spread.is_finite(), "spread is not finite");
         prop_assert!(stats.spread_bps.is_finite(), "spread_bps is not finite");
         prop_assert!(stats.bid_ask_ratio.is_finite(), "bid_ask_ratio is not finite");
+        prop_assert!(stats.imbalance.is_finite(), "imbalance is not finite");
+        prop_assert!(stats.microprice.is_finite(), "microprice is not finite");
         
         // Volumes should be non-negative
         prop_assert!(stats.total_bid_volume >= 0.0, "total_bid_volume is negative");
@@ -56,7 +56,9 @@ proptest! {
         quantity in 0.01f64..100_000.0,
         direction in prop_oneof![Just(1i32), Just(-1i32)],
         order_type in 0i32..=1,
-        limit_price in 0.01f64..1_000_000.0
+        limit_price in 0.01f64..1_000_000.0,
+        client_id in any::<u64>(),
+        tif in 0i32..=2
     ) {
         let original = OrderRequest {
             symbol,
@@ -64,6 +66,8 @@ proptest! {
             direction,
             order_type,
             limit_price,
+            client_id,
+            tif,
         };
         
         let bytes: &[u8] = unsafe {
@@ -82,6 +86,8 @@ proptest! {
         prop_assert_eq!(original.direction, reconstructed.direction);
         prop_assert_eq!(original.order_type, reconstructed.order_type);
         prop_assert!((original.limit_price - reconstructed.limit_price).abs() < f64::EPSILON);
+        prop_assert_eq!(original.client_id, reconstructed.client_id);
+        prop_assert_eq!(original.tif, reconstructed.tif);
     }
 
     /// Property 1: Position struct round-trip through raw bytes
@@ -169,6 +175,8 @@ proptest! {
             position_size,
             stop_loss_pct,
             take_profit_pct,
+            short_ma_type: MA_TYPE_SMA,
+            long_ma_type: MA_TYPE_SMA,
         };
         
         let bytes: &[u8] = unsafe {
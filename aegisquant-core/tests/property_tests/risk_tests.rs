@@ -34,6 +34,8 @@ fn arb_order_request() -> impl Strategy<Value = OrderRequest> {
             direction,
             order_type,
             limit_price,
+            client_id: 0,
+            tif: 0,
         }
     })
 }
@@ -70,6 +72,9 @@ fn arb_risk_config() -> impl Strategy<Value = RiskConfig> {
             max_position_size,
             max_order_value,
             max_drawdown_pct,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         }
     })
 }
@@ -94,6 +99,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,  // Very high to not interfere
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let rm = RiskManager::new(config);
@@ -134,6 +142,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,  // Very high to not interfere
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let rm = RiskManager::new(config);
@@ -169,6 +180,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
@@ -200,6 +214,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
@@ -231,6 +248,9 @@ proptest! {
             max_position_size: actual_max,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let rm = RiskManager::new(config);
@@ -268,6 +288,9 @@ proptest! {
             max_position_size: max_position,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let rm = RiskManager::new(config);
@@ -304,6 +327,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
@@ -341,6 +367,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
@@ -379,6 +408,9 @@ proptest! {
             max_position_size: 10000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 0.5,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
@@ -27,6 +27,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let rm = RiskManager::new(config);
@@ -66,6 +69,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let rm = RiskManager::new(config);
@@ -100,6 +106,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
@@ -130,6 +139,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
@@ -158,6 +170,9 @@ proptest! {
             max_position_size: actual_max,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let rm = RiskManager::new(config);
@@ -195,6 +210,9 @@ proptest! {
             max_position_size: max_position,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 1.0,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let rm = RiskManager::new(config);
@@ -231,6 +249,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
@@ -268,6 +289,9 @@ proptest! {
             max_position_size: 1_000_000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
@@ -305,6 +329,9 @@ proptest! {
             max_position_size: 10000.0,
             max_order_value: 100_000_000.0,
             max_drawdown_pct: 0.5,
+            max_open_positions: 0,
+            max_daily_loss: 0.0,
+            max_notional_per_second: 0.0,
         };
         
         let mut rm = RiskManager::new(config);
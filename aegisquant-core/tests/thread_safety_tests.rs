@@ -12,7 +12,7 @@ use rayon::prelude::*;
 
 use aegisquant_core::engine::BacktestEngine;
 use aegisquant_core::optimizer::{Optimizer, ParameterRange};
-use aegisquant_core::types::{RiskConfig, StrategyParams, Tick};
+use aegisquant_core::types::{RiskConfig, StrategyParams, Tick, MA_TYPE_SMA};
 
 /// Generate valid tick data for testing.
 fn generate_test_ticks(count: usize, seed: u64) -> Vec<Tick> {
@@ -86,6 +86,8 @@ proptest! {
             stop_loss_pct: 0.02,
             take_profit_pct: 0.05,
             warmup_bars: 0,
+            short_ma_type: MA_TYPE_SMA,
+            long_ma_type: MA_TYPE_SMA,
         };
         let risk_config = RiskConfig::default();
         let initial_balance = 100_000.0;
@@ -0,0 +1,65 @@
+//! Integration tests for the TimescaleDB market data store.
+//!
+//! Requires the `timescale` feature and a reachable database:
+//! `AEGISQUANT_TIMESCALE_URL=postgres://... cargo test --features timescale --test timescale_tests`
+//! Without the env var the test returns early.
+
+#![cfg(feature = "timescale")]
+
+use aegisquant_core::data_pipeline::{MarketDataStore, TimescaleDbStore};
+use polars::prelude::*;
+use sqlx::Connection;
+
+const URL_VAR: &str = "AEGISQUANT_TIMESCALE_URL";
+
+fn drop_table(url: &str, table: &str) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let mut conn = sqlx::PgConnection::connect(url).await.unwrap();
+        sqlx::query(&format!("DROP TABLE IF EXISTS \"{}\"", table))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+    });
+}
+
+#[test]
+fn test_save_and_load_round_trip() {
+    let Ok(url) = std::env::var(URL_VAR) else {
+        eprintln!("{} not set, skipping TimescaleDB test", URL_VAR);
+        return;
+    };
+    let table = format!("ticks_test_{}", std::process::id());
+    let store = TimescaleDbStore::with_table(&url, &table);
+
+    let df = df! {
+        "timestamp" => &[1_000i64, 2_000, 3_000, 4_000],
+        "price" => &[100.0, 101.0, 102.0, 103.0],
+        "volume" => &[10.0, 20.0, 30.0, 40.0]
+    }
+    .unwrap();
+    store.save_ticks("TEST", &df).unwrap();
+    store.save_ticks("OTHER", &df).unwrap();
+
+    assert!(store.has_data("TEST"));
+    assert!(!store.has_data("MISSING"));
+    assert_eq!(store.list_symbols().unwrap(), vec!["OTHER", "TEST"]);
+
+    // Range is inclusive on both ends and filtered by symbol
+    let loaded = store.load_ticks("TEST", 2_000, 3_000).unwrap();
+    assert_eq!(loaded.height(), 2);
+    let prices: Vec<f64> = loaded
+        .column("price")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(prices, vec![101.0, 102.0]);
+
+    drop_table(&url, &table);
+}